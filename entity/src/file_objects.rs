@@ -5,14 +5,14 @@ use sea_orm::entity::prelude::*;
 #[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq)]
 #[sea_orm(table_name = "file_objects")]
 pub struct Model {
-    #[sea_orm(unique)]
+    #[sea_orm(primary_key, auto_increment = false)]
     pub id: Uuid,
-    pub created_on: DateTimeWithTimeZone,
     pub filename: Option<String>,
-    pub size_bytes: Option<i64>,
-    pub upload_id: Option<String>,
-    #[sea_orm(column_type = "JsonBinary", nullable)]
-    pub parts: Option<Json>,
+    pub content_type: String,
+    pub size_bytes: i64,
+    #[sea_orm(column_type = "VarBinary(StringLen::None)", nullable)]
+    pub pending_data: Option<Vec<u8>>,
+    pub created_at: DateTimeWithTimeZone,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -14,6 +14,32 @@ mod m20260422_000000_add_created_at;
 mod m20260428_000000_rename_site_replicates_to_field_records;
 mod m20260716_000000_add_field_record_and_dna_fields;
 mod m20260721_000000_rename_flow_cytometry_add_soil_temperature;
+mod m20261019_000000_add_campaigns;
+mod m20261020_000000_add_custom_parameters;
+mod m20261021_000000_add_measurement_thresholds;
+mod m20261022_000000_add_replicate_groups;
+mod m20261023_000000_add_sample_types;
+mod m20261024_000000_add_sample_lineage;
+mod m20261025_000000_add_storage;
+mod m20261026_000000_add_material_requests;
+mod m20261027_000000_add_withdrawals;
+mod m20261028_000000_add_custody_events;
+mod m20261029_000000_add_freeze_thaw_cycles;
+mod m20261030_000000_add_shipments;
+mod m20261031_000000_add_temperature_readings;
+mod m20261101_000000_add_file_objects;
+mod m20261102_000000_add_isolate_images;
+mod m20261103_000000_add_isolate_lineage;
+mod m20261104_000000_add_taxa;
+mod m20261105_000000_add_growth_tests;
+mod m20261106_000000_add_isolate_assemblies;
+mod m20261107_000000_add_marker_sequences;
+mod m20261108_000000_add_culture_accessions;
+mod m20261109_000000_add_isolate_sample;
+mod m20261110_000000_add_sample_type_measurements;
+mod m20261111_000000_add_storage_position_history;
+mod m20261112_000000_add_taxa_staging;
+mod m20261113_000000_restrict_isolate_field_record_delete;
 
 pub struct Migrator;
 
@@ -35,6 +61,32 @@ impl MigratorTrait for Migrator {
             Box::new(m20260428_000000_rename_site_replicates_to_field_records::Migration),
             Box::new(m20260716_000000_add_field_record_and_dna_fields::Migration),
             Box::new(m20260721_000000_rename_flow_cytometry_add_soil_temperature::Migration),
+            Box::new(m20261019_000000_add_campaigns::Migration),
+            Box::new(m20261020_000000_add_custom_parameters::Migration),
+            Box::new(m20261021_000000_add_measurement_thresholds::Migration),
+            Box::new(m20261022_000000_add_replicate_groups::Migration),
+            Box::new(m20261023_000000_add_sample_types::Migration),
+            Box::new(m20261024_000000_add_sample_lineage::Migration),
+            Box::new(m20261025_000000_add_storage::Migration),
+            Box::new(m20261026_000000_add_material_requests::Migration),
+            Box::new(m20261027_000000_add_withdrawals::Migration),
+            Box::new(m20261028_000000_add_custody_events::Migration),
+            Box::new(m20261029_000000_add_freeze_thaw_cycles::Migration),
+            Box::new(m20261030_000000_add_shipments::Migration),
+            Box::new(m20261031_000000_add_temperature_readings::Migration),
+            Box::new(m20261101_000000_add_file_objects::Migration),
+            Box::new(m20261102_000000_add_isolate_images::Migration),
+            Box::new(m20261103_000000_add_isolate_lineage::Migration),
+            Box::new(m20261104_000000_add_taxa::Migration),
+            Box::new(m20261105_000000_add_growth_tests::Migration),
+            Box::new(m20261106_000000_add_isolate_assemblies::Migration),
+            Box::new(m20261107_000000_add_marker_sequences::Migration),
+            Box::new(m20261108_000000_add_culture_accessions::Migration),
+            Box::new(m20261109_000000_add_isolate_sample::Migration),
+            Box::new(m20261110_000000_add_sample_type_measurements::Migration),
+            Box::new(m20261111_000000_add_storage_position_history::Migration),
+            Box::new(m20261112_000000_add_taxa_staging::Migration),
+            Box::new(m20261113_000000_restrict_isolate_field_record_delete::Migration),
         ]
     }
 }
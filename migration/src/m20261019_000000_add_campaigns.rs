@@ -0,0 +1,100 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // 1. Campaigns table
+        db.execute_unprepared(
+            r#"
+            CREATE TABLE campaigns (
+                id UUID PRIMARY KEY,
+                name TEXT NOT NULL UNIQUE,
+                start_date DATE NULL,
+                end_date DATE NULL,
+                principal_investigator TEXT NULL,
+                description TEXT NULL,
+                is_private BOOLEAN NOT NULL DEFAULT FALSE,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                CONSTRAINT campaigns_date_order_check
+                    CHECK (start_date IS NULL OR end_date IS NULL OR start_date <= end_date)
+            )
+            "#,
+        )
+        .await?;
+
+        db.execute_unprepared(
+            r#"
+            ALTER TABLE field_records ADD COLUMN campaign_id UUID NULL;
+            ALTER TABLE field_records ADD CONSTRAINT fk_field_record_campaign_id
+                FOREIGN KEY (campaign_id) REFERENCES campaigns(id) ON DELETE SET NULL;
+            CREATE INDEX idx_field_records_campaign_id ON field_records(campaign_id);
+            "#,
+        )
+        .await?;
+
+        // 2. Backfill from the free-text column. Spellings that only differ by case,
+        //    whitespace or punctuation ("Alps 2025", "alps-2025", "Alps2025") share a
+        //    key and become one campaign, named after the most used spelling.
+        //    Dates are seeded from the span of the linked field records.
+        db.execute_unprepared(
+            r#"
+            CREATE TEMPORARY TABLE campaign_keys AS
+            SELECT DISTINCT ON (key) key, name
+            FROM (
+                SELECT LOWER(REGEXP_REPLACE(campaign, '[^[:alnum:]]', '', 'g')) AS key,
+                       BTRIM(campaign) AS name,
+                       COUNT(*) AS uses
+                FROM field_records
+                WHERE campaign IS NOT NULL AND BTRIM(campaign) <> ''
+                GROUP BY 1, 2
+            ) spellings
+            WHERE key <> ''
+            ORDER BY key, uses DESC, name;
+
+            INSERT INTO campaigns (id, name, start_date, end_date)
+            SELECT gen_random_uuid(), k.name, MIN(fr.sampling_date), MAX(fr.sampling_date)
+            FROM campaign_keys k
+            JOIN field_records fr
+              ON LOWER(REGEXP_REPLACE(fr.campaign, '[^[:alnum:]]', '', 'g')) = k.key
+            GROUP BY k.key, k.name;
+
+            UPDATE field_records fr SET campaign_id = c.id
+            FROM campaign_keys k
+            JOIN campaigns c ON c.name = k.name
+            WHERE LOWER(REGEXP_REPLACE(fr.campaign, '[^[:alnum:]]', '', 'g')) = k.key;
+
+            DROP TABLE campaign_keys;
+            "#,
+        )
+        .await?;
+
+        // 3. Drop the free-text column.
+        db.execute_unprepared(r#"ALTER TABLE field_records DROP COLUMN campaign"#)
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        let revert = r#"
+            ALTER TABLE field_records ADD COLUMN campaign TEXT;
+            UPDATE field_records fr SET campaign = c.name
+            FROM campaigns c
+            WHERE fr.campaign_id = c.id;
+            DROP INDEX IF EXISTS idx_field_records_campaign_id;
+            ALTER TABLE field_records DROP CONSTRAINT IF EXISTS fk_field_record_campaign_id;
+            ALTER TABLE field_records DROP COLUMN IF EXISTS campaign_id;
+            DROP TABLE IF EXISTS campaigns;
+        "#;
+
+        db.execute_unprepared(revert).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,69 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // Admin-defined measurement catalogue. A NULL sample_type means the
+        // parameter applies to every habitat.
+        let create_parameters = r#"
+            CREATE TABLE parameters (
+                id UUID NOT NULL,
+                name TEXT NOT NULL,
+                label TEXT NULL,
+                value_type TEXT NOT NULL,
+                unit TEXT NULL,
+                min_value DOUBLE PRECISION NULL,
+                max_value DOUBLE PRECISION NULL,
+                sample_type TEXT NULL,
+                description TEXT NULL,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                PRIMARY KEY (id),
+                UNIQUE (name),
+                CONSTRAINT parameters_value_type_check CHECK (value_type IN ('Number', 'Integer', 'Text')),
+                CONSTRAINT parameters_sample_type_check CHECK (sample_type IS NULL OR sample_type IN ('Snow', 'Soil')),
+                CONSTRAINT parameters_range_check CHECK (min_value IS NULL OR max_value IS NULL OR min_value <= max_value)
+            );
+        "#;
+        db.execute_unprepared(create_parameters).await?;
+
+        // One value per field record and parameter. Numeric types use
+        // value_number so range filters and ordering stay in SQL.
+        let create_values = r#"
+            CREATE TABLE parameter_values (
+                id UUID NOT NULL,
+                field_record_id UUID NOT NULL,
+                parameter_id UUID NOT NULL,
+                value_number DOUBLE PRECISION NULL,
+                value_text TEXT NULL,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                PRIMARY KEY (id),
+                UNIQUE (field_record_id, parameter_id),
+                CONSTRAINT fk_parameter_value_field_record_id
+                    FOREIGN KEY (field_record_id) REFERENCES field_records(id) ON DELETE CASCADE,
+                CONSTRAINT fk_parameter_value_parameter_id
+                    FOREIGN KEY (parameter_id) REFERENCES parameters(id)
+            );
+            CREATE INDEX idx_parameter_values_parameter_id ON parameter_values(parameter_id, value_number);
+        "#;
+        db.execute_unprepared(create_values).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        let drop_tables = r#"
+            DROP TABLE IF EXISTS parameter_values;
+            DROP TABLE IF EXISTS parameters;
+        "#;
+
+        db.execute_unprepared(drop_tables).await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,39 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // Admin overrides for the built-in field record plausibility ranges. A row
+        // replaces both bounds of its column; NULL leaves that side open.
+        let create_thresholds = r#"
+            CREATE TABLE measurement_thresholds (
+                id UUID NOT NULL,
+                column_name TEXT NOT NULL,
+                min_value DOUBLE PRECISION NULL,
+                max_value DOUBLE PRECISION NULL,
+                description TEXT NULL,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                PRIMARY KEY (id),
+                UNIQUE (column_name),
+                CONSTRAINT measurement_thresholds_range_check
+                    CHECK (min_value IS NULL OR max_value IS NULL OR min_value <= max_value)
+            );
+        "#;
+        db.execute_unprepared(create_thresholds).await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute_unprepared("DROP TABLE IF EXISTS measurement_thresholds;")
+            .await?;
+        Ok(())
+    }
+}
@@ -0,0 +1,87 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // 1. Replicate groups: field records taken at one site on one day under one
+        //    treatment. A NULL treatment is the untreated control.
+        db.execute_unprepared(
+            r#"
+            CREATE TABLE replicate_groups (
+                id UUID PRIMARY KEY,
+                name TEXT NOT NULL,
+                site_id UUID NOT NULL,
+                sampling_date DATE NOT NULL,
+                treatment TEXT NULL,
+                expected_replicates INTEGER NOT NULL DEFAULT 3,
+                description TEXT NULL,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                CONSTRAINT fk_replicate_group_site_id
+                    FOREIGN KEY (site_id) REFERENCES sites(id),
+                CONSTRAINT replicate_groups_expected_replicates_check
+                    CHECK (expected_replicates > 0)
+            );
+            CREATE UNIQUE INDEX idx_replicate_groups_design
+                ON replicate_groups(site_id, sampling_date, COALESCE(treatment, ''));
+            "#,
+        )
+        .await?;
+
+        db.execute_unprepared(
+            r#"
+            ALTER TABLE field_records ADD COLUMN replicate_group_id UUID NULL;
+            ALTER TABLE field_records ADD CONSTRAINT fk_field_record_replicate_group_id
+                FOREIGN KEY (replicate_group_id) REFERENCES replicate_groups(id) ON DELETE SET NULL;
+            CREATE INDEX idx_field_records_replicate_group_id ON field_records(replicate_group_id);
+            "#,
+        )
+        .await?;
+
+        // 2. Backfill: every site/date/treatment combination that already holds more
+        //    than one field record becomes a group. Existing triplicates keep the
+        //    default expectation; larger sets expect what they already have.
+        db.execute_unprepared(
+            r#"
+            INSERT INTO replicate_groups (id, name, site_id, sampling_date, treatment, expected_replicates)
+            SELECT gen_random_uuid(),
+                   CONCAT_WS(' ', s.name, fr.sampling_date::TEXT, NULLIF(BTRIM(fr.treatment), '')),
+                   fr.site_id,
+                   fr.sampling_date,
+                   NULLIF(BTRIM(fr.treatment), ''),
+                   GREATEST(COUNT(*), 3)
+            FROM field_records fr
+            JOIN sites s ON s.id = fr.site_id
+            GROUP BY fr.site_id, s.name, fr.sampling_date, NULLIF(BTRIM(fr.treatment), '')
+            HAVING COUNT(*) > 1;
+
+            UPDATE field_records fr SET replicate_group_id = g.id
+            FROM replicate_groups g
+            WHERE g.site_id = fr.site_id
+              AND g.sampling_date = fr.sampling_date
+              AND COALESCE(g.treatment, '') = COALESCE(NULLIF(BTRIM(fr.treatment), ''), '');
+            "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        let revert = r#"
+            DROP INDEX IF EXISTS idx_field_records_replicate_group_id;
+            ALTER TABLE field_records DROP CONSTRAINT IF EXISTS fk_field_record_replicate_group_id;
+            ALTER TABLE field_records DROP COLUMN IF EXISTS replicate_group_id;
+            DROP TABLE IF EXISTS replicate_groups;
+        "#;
+
+        db.execute_unprepared(revert).await?;
+        Ok(())
+    }
+}
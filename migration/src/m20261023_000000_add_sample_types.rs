@@ -0,0 +1,66 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // 1. Habitat catalogue. Records keep referencing the type by name so the
+        //    API payloads stay the same; renames cascade.
+        db.execute_unprepared(
+            r#"
+            CREATE TABLE sample_types (
+                id UUID PRIMARY KEY,
+                name TEXT NOT NULL UNIQUE,
+                description TEXT NULL,
+                envo_id TEXT NULL,
+                envo_label TEXT NULL,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+            );
+
+            INSERT INTO sample_types (id, name, description, envo_id, envo_label) VALUES
+                (gen_random_uuid(), 'Snow', 'Seasonal or perennial snow cover', 'ENVO:01000406', 'snow'),
+                (gen_random_uuid(), 'Soil', 'Soil, including soil under snow cover', 'ENVO:00001998', 'soil');
+            "#,
+        )
+        .await?;
+
+        // 2. Swap the hard-coded CHECK constraints for foreign keys.
+        db.execute_unprepared(
+            r#"
+            ALTER TABLE field_records DROP CONSTRAINT IF EXISTS field_records_sample_type_check;
+            ALTER TABLE field_records ADD CONSTRAINT fk_field_record_sample_type
+                FOREIGN KEY (sample_type) REFERENCES sample_types(name) ON UPDATE CASCADE;
+
+            ALTER TABLE parameters DROP CONSTRAINT IF EXISTS parameters_sample_type_check;
+            ALTER TABLE parameters ADD CONSTRAINT fk_parameter_sample_type
+                FOREIGN KEY (sample_type) REFERENCES sample_types(name) ON UPDATE CASCADE;
+            "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // Restoring the CHECK constraints fails while records use any other type;
+        // those have to be reassigned first.
+        let revert = r#"
+            ALTER TABLE parameters DROP CONSTRAINT IF EXISTS fk_parameter_sample_type;
+            ALTER TABLE parameters ADD CONSTRAINT parameters_sample_type_check
+                CHECK (sample_type IS NULL OR sample_type IN ('Snow', 'Soil'));
+            ALTER TABLE field_records DROP CONSTRAINT IF EXISTS fk_field_record_sample_type;
+            ALTER TABLE field_records ADD CONSTRAINT field_records_sample_type_check
+                CHECK (sample_type IN ('Snow', 'Soil'));
+            DROP TABLE IF EXISTS sample_types;
+        "#;
+
+        db.execute_unprepared(revert).await?;
+        Ok(())
+    }
+}
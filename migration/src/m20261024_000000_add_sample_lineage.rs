@@ -0,0 +1,52 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // Aliquots point at the sample they were split from. A parent cannot be
+        // deleted while aliquots still reference it.
+        db.execute_unprepared(
+            r#"
+            ALTER TABLE samples ADD COLUMN parent_sample_id UUID NULL;
+            ALTER TABLE samples ADD COLUMN quantity DOUBLE PRECISION NULL;
+            ALTER TABLE samples ADD COLUMN quantity_unit TEXT NULL;
+
+            ALTER TABLE samples ADD CONSTRAINT fk_sample_parent_sample_id
+                FOREIGN KEY (parent_sample_id) REFERENCES samples(id) ON DELETE RESTRICT;
+            ALTER TABLE samples ADD CONSTRAINT samples_parent_not_self_check
+                CHECK (parent_sample_id IS NULL OR parent_sample_id <> id);
+            ALTER TABLE samples ADD CONSTRAINT samples_quantity_check
+                CHECK (quantity IS NULL OR quantity >= 0);
+
+            CREATE INDEX idx_samples_parent_sample_id ON samples(parent_sample_id);
+            "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute_unprepared(
+            r#"
+            DROP INDEX IF EXISTS idx_samples_parent_sample_id;
+            ALTER TABLE samples DROP CONSTRAINT IF EXISTS samples_quantity_check;
+            ALTER TABLE samples DROP CONSTRAINT IF EXISTS samples_parent_not_self_check;
+            ALTER TABLE samples DROP CONSTRAINT IF EXISTS fk_sample_parent_sample_id;
+            ALTER TABLE samples DROP COLUMN IF EXISTS quantity_unit;
+            ALTER TABLE samples DROP COLUMN IF EXISTS quantity;
+            ALTER TABLE samples DROP COLUMN IF EXISTS parent_sample_id;
+            "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+}
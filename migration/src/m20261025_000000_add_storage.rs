@@ -0,0 +1,98 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // 1. Freezer -> rack -> box hierarchy. Freezers and racks hold up to
+        //    `capacity` children; boxes are a `rows` x `columns` grid.
+        db.execute_unprepared(
+            r#"
+            CREATE TABLE storage_units (
+                id UUID PRIMARY KEY,
+                parent_id UUID NULL,
+                kind TEXT NOT NULL,
+                name TEXT NOT NULL,
+                capacity INTEGER NULL,
+                rows INTEGER NULL,
+                columns INTEGER NULL,
+                description TEXT NULL,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                CONSTRAINT fk_storage_unit_parent_id
+                    FOREIGN KEY (parent_id) REFERENCES storage_units(id) ON DELETE RESTRICT,
+                CONSTRAINT storage_units_kind_check
+                    CHECK (kind IN ('Freezer', 'Rack', 'Box')),
+                CONSTRAINT storage_units_capacity_check
+                    CHECK (capacity IS NULL OR capacity > 0),
+                CONSTRAINT storage_units_grid_check
+                    CHECK (
+                        (kind = 'Box' AND rows > 0 AND columns > 0)
+                        OR (kind <> 'Box' AND rows IS NULL AND columns IS NULL)
+                    )
+            );
+            CREATE UNIQUE INDEX idx_storage_units_parent_name
+                ON storage_units(COALESCE(parent_id, '00000000-0000-0000-0000-000000000000'), name);
+            CREATE INDEX idx_storage_units_parent_id ON storage_units(parent_id);
+            "#,
+        )
+        .await?;
+
+        // 2. One row per occupied slot, holding exactly one sample, isolate or DNA
+        //    extract. Deleting the item frees the slot.
+        db.execute_unprepared(
+            r#"
+            CREATE TABLE storage_positions (
+                id UUID PRIMARY KEY,
+                storage_unit_id UUID NOT NULL,
+                "row" INTEGER NOT NULL,
+                "column" INTEGER NOT NULL,
+                sample_id UUID NULL,
+                isolate_id UUID NULL,
+                dna_id UUID NULL,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                CONSTRAINT fk_storage_position_storage_unit_id
+                    FOREIGN KEY (storage_unit_id) REFERENCES storage_units(id) ON DELETE RESTRICT,
+                CONSTRAINT fk_storage_position_sample_id
+                    FOREIGN KEY (sample_id) REFERENCES samples(id) ON DELETE CASCADE,
+                CONSTRAINT fk_storage_position_isolate_id
+                    FOREIGN KEY (isolate_id) REFERENCES isolates(id) ON DELETE CASCADE,
+                CONSTRAINT fk_storage_position_dna_id
+                    FOREIGN KEY (dna_id) REFERENCES dna(id) ON DELETE CASCADE,
+                CONSTRAINT storage_positions_slot_check
+                    CHECK ("row" > 0 AND "column" > 0),
+                CONSTRAINT storage_positions_one_item_check
+                    CHECK (num_nonnulls(sample_id, isolate_id, dna_id) = 1),
+                CONSTRAINT storage_positions_slot_unique
+                    UNIQUE (storage_unit_id, "row", "column")
+            );
+            CREATE UNIQUE INDEX idx_storage_positions_sample_id
+                ON storage_positions(sample_id) WHERE sample_id IS NOT NULL;
+            CREATE UNIQUE INDEX idx_storage_positions_isolate_id
+                ON storage_positions(isolate_id) WHERE isolate_id IS NOT NULL;
+            CREATE UNIQUE INDEX idx_storage_positions_dna_id
+                ON storage_positions(dna_id) WHERE dna_id IS NOT NULL;
+            "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute_unprepared(
+            r#"
+            DROP TABLE IF EXISTS storage_positions;
+            DROP TABLE IF EXISTS storage_units;
+            "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,58 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // Requests for material from outside researchers. Each covers one sample or
+        // isolate and moves Pending -> Approved/Rejected -> Shipped -> Returned.
+        db.execute_unprepared(
+            r#"
+            CREATE TABLE material_requests (
+                id UUID PRIMARY KEY,
+                sample_id UUID NULL,
+                isolate_id UUID NULL,
+                requester_name TEXT NOT NULL,
+                requester_email TEXT NOT NULL,
+                institution TEXT NULL,
+                purpose TEXT NOT NULL,
+                shipping_address TEXT NOT NULL,
+                status TEXT NOT NULL DEFAULT 'Pending',
+                admin_note TEXT NULL,
+                tracking_number TEXT NULL,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                decided_at TIMESTAMPTZ NULL,
+                shipped_at TIMESTAMPTZ NULL,
+                returned_at TIMESTAMPTZ NULL,
+                CONSTRAINT fk_material_request_sample_id
+                    FOREIGN KEY (sample_id) REFERENCES samples(id) ON DELETE CASCADE,
+                CONSTRAINT fk_material_request_isolate_id
+                    FOREIGN KEY (isolate_id) REFERENCES isolates(id) ON DELETE CASCADE,
+                CONSTRAINT material_requests_one_item_check
+                    CHECK (num_nonnulls(sample_id, isolate_id) = 1),
+                CONSTRAINT material_requests_status_check
+                    CHECK (status IN ('Pending', 'Approved', 'Rejected', 'Shipped', 'Returned'))
+            );
+            CREATE INDEX idx_material_requests_sample_id ON material_requests(sample_id);
+            CREATE INDEX idx_material_requests_isolate_id ON material_requests(isolate_id);
+            CREATE INDEX idx_material_requests_status ON material_requests(status);
+            "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute_unprepared("DROP TABLE IF EXISTS material_requests;")
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,48 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // Consumption ledger: each row takes `amount` out of one sample (in its
+        // quantity_unit) or one DNA extract (in the unit of its volume).
+        db.execute_unprepared(
+            r#"
+            CREATE TABLE withdrawals (
+                id UUID PRIMARY KEY,
+                sample_id UUID NULL,
+                dna_id UUID NULL,
+                amount DOUBLE PRECISION NOT NULL,
+                withdrawn_by TEXT NOT NULL,
+                purpose TEXT NOT NULL,
+                withdrawn_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                CONSTRAINT fk_withdrawal_sample_id
+                    FOREIGN KEY (sample_id) REFERENCES samples(id) ON DELETE CASCADE,
+                CONSTRAINT fk_withdrawal_dna_id
+                    FOREIGN KEY (dna_id) REFERENCES dna(id) ON DELETE CASCADE,
+                CONSTRAINT withdrawals_one_item_check
+                    CHECK (num_nonnulls(sample_id, dna_id) = 1),
+                CONSTRAINT withdrawals_amount_check CHECK (amount > 0)
+            );
+            CREATE INDEX idx_withdrawals_sample_id ON withdrawals(sample_id);
+            CREATE INDEX idx_withdrawals_dna_id ON withdrawals(dna_id);
+            "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute_unprepared("DROP TABLE IF EXISTS withdrawals;")
+            .await?;
+
+        Ok(())
+    }
+}
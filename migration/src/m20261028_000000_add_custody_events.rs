@@ -0,0 +1,70 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // Chain-of-custody log for one sample, isolate or DNA extract per row. Rows are
+        // never edited or deleted, and items with a history cannot be deleted either:
+        // a tube that is gone gets a 'Destroyed' event instead.
+        db.execute_unprepared(
+            r#"
+            CREATE TABLE custody_events (
+                id UUID PRIMARY KEY,
+                sample_id UUID NULL,
+                isolate_id UUID NULL,
+                dna_id UUID NULL,
+                kind TEXT NOT NULL,
+                actor TEXT NOT NULL,
+                location TEXT NOT NULL,
+                occurred_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                recorded_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                note TEXT NULL,
+                CONSTRAINT fk_custody_event_sample_id
+                    FOREIGN KEY (sample_id) REFERENCES samples(id) ON DELETE RESTRICT,
+                CONSTRAINT fk_custody_event_isolate_id
+                    FOREIGN KEY (isolate_id) REFERENCES isolates(id) ON DELETE RESTRICT,
+                CONSTRAINT fk_custody_event_dna_id
+                    FOREIGN KEY (dna_id) REFERENCES dna(id) ON DELETE RESTRICT,
+                CONSTRAINT custody_events_one_item_check
+                    CHECK (num_nonnulls(sample_id, isolate_id, dna_id) = 1),
+                CONSTRAINT custody_events_kind_check
+                    CHECK (kind IN ('Collected', 'Received', 'Stored', 'Moved', 'Thawed', 'Shipped', 'Destroyed'))
+            );
+            CREATE INDEX idx_custody_events_sample_id ON custody_events(sample_id);
+            CREATE INDEX idx_custody_events_isolate_id ON custody_events(isolate_id);
+            CREATE INDEX idx_custody_events_dna_id ON custody_events(dna_id);
+
+            CREATE FUNCTION custody_events_append_only() RETURNS trigger AS $$
+            BEGIN
+                RAISE EXCEPTION 'custody_events is append-only';
+            END;
+            $$ LANGUAGE plpgsql;
+            CREATE TRIGGER custody_events_append_only
+                BEFORE UPDATE OR DELETE ON custody_events
+                FOR EACH ROW EXECUTE FUNCTION custody_events_append_only();
+            "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute_unprepared(
+            r#"
+            DROP TABLE IF EXISTS custody_events;
+            DROP FUNCTION IF EXISTS custody_events_append_only();
+            "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,59 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // Freeze-thaw cycles are counted from 'Thawed' custody events, including any
+        // already logged.
+        db.execute_unprepared(
+            r#"
+            ALTER TABLE samples
+                ADD COLUMN freeze_thaw_cycles INTEGER NOT NULL DEFAULT 0;
+            ALTER TABLE isolates
+                ADD COLUMN freeze_thaw_cycles INTEGER NOT NULL DEFAULT 0;
+            ALTER TABLE dna
+                ADD COLUMN freeze_thaw_cycles INTEGER NOT NULL DEFAULT 0;
+
+            UPDATE samples SET freeze_thaw_cycles = counts.n
+            FROM (SELECT sample_id, COUNT(*) AS n FROM custody_events
+                  WHERE kind = 'Thawed' AND sample_id IS NOT NULL GROUP BY sample_id) counts
+            WHERE samples.id = counts.sample_id;
+            UPDATE isolates SET freeze_thaw_cycles = counts.n
+            FROM (SELECT isolate_id, COUNT(*) AS n FROM custody_events
+                  WHERE kind = 'Thawed' AND isolate_id IS NOT NULL GROUP BY isolate_id) counts
+            WHERE isolates.id = counts.isolate_id;
+            UPDATE dna SET freeze_thaw_cycles = counts.n
+            FROM (SELECT dna_id, COUNT(*) AS n FROM custody_events
+                  WHERE kind = 'Thawed' AND dna_id IS NOT NULL GROUP BY dna_id) counts
+            WHERE dna.id = counts.dna_id;
+
+            ALTER TABLE material_requests
+                ADD COLUMN freeze_thaw_warning TEXT NULL;
+            "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute_unprepared(
+            r#"
+            ALTER TABLE material_requests DROP COLUMN IF EXISTS freeze_thaw_warning;
+            ALTER TABLE dna DROP COLUMN IF EXISTS freeze_thaw_cycles;
+            ALTER TABLE isolates DROP COLUMN IF EXISTS freeze_thaw_cycles;
+            ALTER TABLE samples DROP COLUMN IF EXISTS freeze_thaw_cycles;
+            "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+}
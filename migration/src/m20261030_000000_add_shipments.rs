@@ -0,0 +1,70 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // Outgoing shipments to partner labs. Items are packed while `shipped_at` is
+        // NULL; shipping freezes the list and records it in the custody log.
+        db.execute_unprepared(
+            r#"
+            CREATE TABLE shipments (
+                id UUID PRIMARY KEY,
+                name TEXT NOT NULL UNIQUE,
+                destination TEXT NOT NULL,
+                address TEXT NULL,
+                carrier TEXT NULL,
+                tracking_number TEXT NULL,
+                note TEXT NULL,
+                shipped_by TEXT NULL,
+                shipped_at TIMESTAMPTZ NULL,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+            );
+
+            CREATE TABLE shipment_items (
+                id UUID PRIMARY KEY,
+                shipment_id UUID NOT NULL,
+                sample_id UUID NULL,
+                isolate_id UUID NULL,
+                dna_id UUID NULL,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                CONSTRAINT fk_shipment_item_shipment_id
+                    FOREIGN KEY (shipment_id) REFERENCES shipments(id) ON DELETE CASCADE,
+                CONSTRAINT fk_shipment_item_sample_id
+                    FOREIGN KEY (sample_id) REFERENCES samples(id) ON DELETE CASCADE,
+                CONSTRAINT fk_shipment_item_isolate_id
+                    FOREIGN KEY (isolate_id) REFERENCES isolates(id) ON DELETE CASCADE,
+                CONSTRAINT fk_shipment_item_dna_id
+                    FOREIGN KEY (dna_id) REFERENCES dna(id) ON DELETE CASCADE,
+                CONSTRAINT shipment_items_one_item_check
+                    CHECK (num_nonnulls(sample_id, isolate_id, dna_id) = 1),
+                CONSTRAINT shipment_items_sample_unique UNIQUE (shipment_id, sample_id),
+                CONSTRAINT shipment_items_isolate_unique UNIQUE (shipment_id, isolate_id),
+                CONSTRAINT shipment_items_dna_unique UNIQUE (shipment_id, dna_id)
+            );
+            CREATE INDEX idx_shipment_items_shipment_id ON shipment_items(shipment_id);
+            "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute_unprepared(
+            r#"
+            DROP TABLE IF EXISTS shipment_items;
+            DROP TABLE IF EXISTS shipments;
+            "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,49 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // Temperature logger readings per storage unit. A reading above the unit's
+        // max_temperature_celsius (or its nearest ancestor's) is an excursion.
+        // Importing the same export twice keeps one reading per timestamp.
+        db.execute_unprepared(
+            r#"
+            ALTER TABLE storage_units
+                ADD COLUMN max_temperature_celsius DOUBLE PRECISION NULL;
+
+            CREATE TABLE temperature_readings (
+                id UUID PRIMARY KEY,
+                storage_unit_id UUID NOT NULL,
+                recorded_at TIMESTAMPTZ NOT NULL,
+                temperature_celsius DOUBLE PRECISION NOT NULL,
+                CONSTRAINT fk_temperature_reading_storage_unit_id
+                    FOREIGN KEY (storage_unit_id) REFERENCES storage_units(id) ON DELETE CASCADE,
+                CONSTRAINT temperature_readings_unit_time_unique
+                    UNIQUE (storage_unit_id, recorded_at)
+            );
+            "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute_unprepared(
+            r#"
+            DROP TABLE IF EXISTS temperature_readings;
+            ALTER TABLE storage_units DROP COLUMN IF EXISTS max_temperature_celsius;
+            "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+}
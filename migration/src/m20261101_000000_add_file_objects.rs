@@ -0,0 +1,110 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // Isolate photos move from base64 data URIs in `isolates.photo` to the
+        // object store, with one file_objects row per stored object. Migrations
+        // cannot reach the store, so decoded photos wait in `pending_data` until
+        // the API moves them across at startup. Values that are not base64 image
+        // data URIs are copied to `isolate_photo_quarantine` with the reason, so
+        // nothing is lost when the column goes.
+        db.execute_unprepared(
+            r#"
+            CREATE TABLE file_objects (
+                id UUID PRIMARY KEY,
+                filename TEXT NULL,
+                content_type TEXT NOT NULL,
+                size_bytes BIGINT NOT NULL,
+                pending_data BYTEA NULL,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+            );
+
+            CREATE TABLE isolate_photo_quarantine (
+                isolate_id UUID PRIMARY KEY,
+                photo TEXT NOT NULL,
+                reason TEXT NOT NULL,
+                quarantined_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                CONSTRAINT fk_isolate_photo_quarantine_isolate_id
+                    FOREIGN KEY (isolate_id) REFERENCES isolates(id) ON DELETE CASCADE
+            );
+
+            ALTER TABLE isolates
+                ADD COLUMN photo_id UUID NULL,
+                ADD CONSTRAINT fk_isolate_photo_id
+                    FOREIGN KEY (photo_id) REFERENCES file_objects(id) ON DELETE SET NULL;
+
+            DO $$
+            DECLARE
+                isolate RECORD;
+                data BYTEA;
+                file_id UUID;
+            BEGIN
+                FOR isolate IN
+                    SELECT id, name, photo FROM isolates WHERE photo IS NOT NULL AND photo <> ''
+                LOOP
+                    BEGIN
+                        IF isolate.photo !~ '^data:image/[A-Za-z0-9.+-]+;base64,' THEN
+                            RAISE EXCEPTION 'not a base64 image data URI';
+                        END IF;
+                        data := decode(
+                            regexp_replace(split_part(isolate.photo, ',', 2), '\s', '', 'g'),
+                            'base64'
+                        );
+                        file_id := gen_random_uuid();
+                        INSERT INTO file_objects (id, content_type, size_bytes, pending_data)
+                        VALUES (
+                            file_id,
+                            substring(isolate.photo FROM '^data:([^;]+);'),
+                            octet_length(data),
+                            data
+                        );
+                        UPDATE isolates SET photo_id = file_id WHERE id = isolate.id;
+                    EXCEPTION WHEN others THEN
+                        INSERT INTO isolate_photo_quarantine (isolate_id, photo, reason)
+                        VALUES (isolate.id, isolate.photo, SQLERRM);
+                        RAISE WARNING 'Photo of isolate % quarantined: %', isolate.name, SQLERRM;
+                    END;
+                END LOOP;
+            END $$;
+
+            ALTER TABLE isolates DROP COLUMN photo;
+            "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // Only photos still waiting in `pending_data` or quarantined can be
+        // restored; those already in the object store stay there.
+        db.execute_unprepared(
+            r#"
+            ALTER TABLE isolates ADD COLUMN photo TEXT NULL;
+            UPDATE isolates i
+                SET photo = 'data:' || f.content_type || ';base64,'
+                    || replace(encode(f.pending_data, 'base64'), E'\n', '')
+                FROM file_objects f
+                WHERE f.id = i.photo_id AND f.pending_data IS NOT NULL;
+            UPDATE isolates i
+                SET photo = q.photo
+                FROM isolate_photo_quarantine q
+                WHERE q.isolate_id = i.id;
+            ALTER TABLE isolates DROP COLUMN IF EXISTS photo_id;
+            DROP TABLE IF EXISTS isolate_photo_quarantine;
+            DROP TABLE IF EXISTS file_objects;
+            "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+}
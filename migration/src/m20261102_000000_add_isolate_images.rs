@@ -0,0 +1,76 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // An isolate now has any number of annotated images instead of one photo.
+        // Existing photos become each isolate's primary colony image. At most one
+        // image per isolate is primary.
+        db.execute_unprepared(
+            r#"
+            CREATE TABLE isolate_images (
+                id UUID PRIMARY KEY,
+                isolate_id UUID NOT NULL,
+                file_id UUID NOT NULL,
+                image_type TEXT NOT NULL DEFAULT 'Colony',
+                caption TEXT NULL,
+                medium TEXT NULL,
+                magnification DOUBLE PRECISION NULL,
+                captured_on DATE NULL,
+                position INTEGER NOT NULL DEFAULT 0,
+                is_primary BOOLEAN NOT NULL DEFAULT false,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+                CONSTRAINT fk_isolate_image_isolate_id
+                    FOREIGN KEY (isolate_id) REFERENCES isolates(id) ON DELETE CASCADE,
+                CONSTRAINT fk_isolate_image_file_id
+                    FOREIGN KEY (file_id) REFERENCES file_objects(id) ON DELETE RESTRICT,
+                CONSTRAINT isolate_images_image_type_check
+                    CHECK (image_type IN ('Colony', 'Microscopy', 'GramStain')),
+                CONSTRAINT isolate_images_magnification_check
+                    CHECK (magnification IS NULL OR magnification > 0)
+            );
+            CREATE INDEX idx_isolate_images_isolate_id ON isolate_images(isolate_id, position);
+            CREATE UNIQUE INDEX idx_isolate_images_one_primary
+                ON isolate_images(isolate_id) WHERE is_primary;
+
+            INSERT INTO isolate_images (id, isolate_id, file_id, image_type, position, is_primary, created_at)
+            SELECT gen_random_uuid(), i.id, i.photo_id, 'Colony', 0, true, f.created_at
+            FROM isolates i
+            JOIN file_objects f ON f.id = i.photo_id;
+
+            ALTER TABLE isolates DROP COLUMN photo_id;
+            "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // The primary image goes back to being the photo. Files of the other images
+        // stay in file_objects and the store, unreferenced.
+        db.execute_unprepared(
+            r#"
+            ALTER TABLE isolates
+                ADD COLUMN photo_id UUID NULL,
+                ADD CONSTRAINT fk_isolate_photo_id
+                    FOREIGN KEY (photo_id) REFERENCES file_objects(id) ON DELETE SET NULL;
+            UPDATE isolates i
+                SET photo_id = m.file_id
+                FROM isolate_images m
+                WHERE m.isolate_id = i.id AND m.is_primary;
+            DROP TABLE IF EXISTS isolate_images;
+            "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,51 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // Ranked lineage next to the free-text taxonomy. The API parses existing
+        // taxonomy strings into the ranks at startup.
+        db.execute_unprepared(
+            r#"
+            ALTER TABLE isolates
+                ADD COLUMN domain TEXT NULL,
+                ADD COLUMN phylum TEXT NULL,
+                ADD COLUMN class TEXT NULL,
+                ADD COLUMN taxonomic_order TEXT NULL,
+                ADD COLUMN family TEXT NULL,
+                ADD COLUMN genus TEXT NULL,
+                ADD COLUMN species TEXT NULL;
+            CREATE INDEX idx_isolates_phylum ON isolates(lower(phylum));
+            CREATE INDEX idx_isolates_genus ON isolates(lower(genus));
+            "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute_unprepared(
+            r#"
+            ALTER TABLE isolates
+                DROP COLUMN IF EXISTS domain,
+                DROP COLUMN IF EXISTS phylum,
+                DROP COLUMN IF EXISTS class,
+                DROP COLUMN IF EXISTS taxonomic_order,
+                DROP COLUMN IF EXISTS family,
+                DROP COLUMN IF EXISTS genus,
+                DROP COLUMN IF EXISTS species;
+            "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,55 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // Reference taxonomies loaded from an NCBI taxdump or a GTDB taxonomy file.
+        // Synonyms are extra rows sharing the taxon id of the accepted name. Isolates
+        // keep the taxon their name resolved to; ids are text as GTDB has no numeric
+        // ones.
+        db.execute_unprepared(
+            r#"
+            CREATE TABLE taxa (
+                id UUID PRIMARY KEY,
+                source TEXT NOT NULL CHECK (source IN ('NCBI', 'GTDB')),
+                taxon_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                rank TEXT NOT NULL,
+                parent_taxon_id TEXT NULL,
+                is_synonym BOOLEAN NOT NULL DEFAULT FALSE
+            );
+            CREATE UNIQUE INDEX taxa_source_taxon_unique
+                ON taxa(source, taxon_id) WHERE NOT is_synonym;
+            CREATE INDEX idx_taxa_name ON taxa(lower(name));
+
+            ALTER TABLE isolates
+                ADD COLUMN taxon_source TEXT NULL CHECK (taxon_source IN ('NCBI', 'GTDB')),
+                ADD COLUMN taxon_id TEXT NULL;
+            "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute_unprepared(
+            r#"
+            ALTER TABLE isolates
+                DROP COLUMN IF EXISTS taxon_source,
+                DROP COLUMN IF EXISTS taxon_id;
+            DROP TABLE IF EXISTS taxa;
+            "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,58 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // Growth of an isolate under one combination of temperature, medium,
+        // salinity and pH, as a yes/no and optionally a specific growth rate.
+        // Conditions left NULL were not varied in the test.
+        db.execute_unprepared(
+            r#"
+            CREATE TABLE growth_tests (
+                id UUID PRIMARY KEY,
+                isolate_id UUID NOT NULL,
+                temperature_celsius DOUBLE PRECISION NULL,
+                medium TEXT NULL,
+                salinity_percent DOUBLE PRECISION NULL
+                    CHECK (salinity_percent >= 0 AND salinity_percent <= 100),
+                ph DOUBLE PRECISION NULL CHECK (ph >= 0 AND ph <= 14),
+                grows BOOLEAN NOT NULL,
+                growth_rate_per_hour DOUBLE PRECISION NULL CHECK (growth_rate_per_hour >= 0),
+                incubation_days INTEGER NULL CHECK (incubation_days > 0),
+                tested_on DATE NULL,
+                notes TEXT NULL,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+                CONSTRAINT fk_growth_test_isolate_id
+                    FOREIGN KEY (isolate_id) REFERENCES isolates(id) ON DELETE CASCADE,
+                CONSTRAINT growth_tests_condition_given CHECK (
+                    temperature_celsius IS NOT NULL OR medium IS NOT NULL
+                    OR salinity_percent IS NOT NULL OR ph IS NOT NULL
+                )
+            );
+            CREATE INDEX idx_growth_tests_isolate_id ON growth_tests(isolate_id);
+            CREATE INDEX idx_growth_tests_temperature ON growth_tests(temperature_celsius);
+            "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute_unprepared(
+            r#"
+            DROP TABLE IF EXISTS growth_tests;
+            "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,50 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // Uploaded FASTA assembly of an isolate, with the statistics the API
+        // computes from it. The bytes live in the file store.
+        db.execute_unprepared(
+            r#"
+            ALTER TABLE isolates
+                ADD COLUMN assembly_file_id UUID NULL
+                    CONSTRAINT fk_isolate_assembly_file_id REFERENCES file_objects(id),
+                ADD COLUMN assembly_length_bp BIGINT NULL,
+                ADD COLUMN assembly_contigs INTEGER NULL,
+                ADD COLUMN assembly_n50_bp BIGINT NULL,
+                ADD COLUMN assembly_gc_percent DOUBLE PRECISION NULL,
+                ADD COLUMN assembly_sha256 TEXT NULL,
+                ADD COLUMN assembly_md5 TEXT NULL;
+            "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute_unprepared(
+            r#"
+            ALTER TABLE isolates
+                DROP COLUMN IF EXISTS assembly_file_id,
+                DROP COLUMN IF EXISTS assembly_length_bp,
+                DROP COLUMN IF EXISTS assembly_contigs,
+                DROP COLUMN IF EXISTS assembly_n50_bp,
+                DROP COLUMN IF EXISTS assembly_gc_percent,
+                DROP COLUMN IF EXISTS assembly_sha256,
+                DROP COLUMN IF EXISTS assembly_md5;
+            "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,50 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // 16S, 18S and ITS barcode sequences of isolates, compared by shared
+        // 8-mers to find isolates already in the collection.
+        db.execute_unprepared(
+            r#"
+            CREATE TABLE marker_sequences (
+                id UUID PRIMARY KEY,
+                isolate_id UUID NOT NULL,
+                marker TEXT NOT NULL,
+                sequence TEXT NOT NULL,
+                length_bp INTEGER NOT NULL CHECK (length_bp > 0),
+                method TEXT NULL,
+                sequenced_on DATE NULL,
+                notes TEXT NULL,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+                CONSTRAINT fk_marker_sequence_isolate_id
+                    FOREIGN KEY (isolate_id) REFERENCES isolates(id) ON DELETE CASCADE,
+                CONSTRAINT marker_sequences_marker_check
+                    CHECK (marker IN ('16S', '18S', 'ITS'))
+            );
+            CREATE INDEX idx_marker_sequences_isolate_id ON marker_sequences(isolate_id);
+            "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute_unprepared(
+            r#"
+            DROP TABLE IF EXISTS marker_sequences;
+            "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,49 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // Deposits of isolates in public culture collections. An accession number
+        // identifies one deposit within its collection.
+        db.execute_unprepared(
+            r#"
+            CREATE TABLE culture_accessions (
+                id UUID PRIMARY KEY,
+                isolate_id UUID NOT NULL,
+                accession TEXT NOT NULL,
+                collection TEXT NOT NULL,
+                deposited_on DATE NULL,
+                url_template TEXT NULL,
+                notes TEXT NULL,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+                CONSTRAINT fk_culture_accession_isolate_id
+                    FOREIGN KEY (isolate_id) REFERENCES isolates(id) ON DELETE CASCADE
+            );
+            CREATE UNIQUE INDEX idx_culture_accessions_collection_accession
+                ON culture_accessions(lower(collection), lower(accession));
+            CREATE INDEX idx_culture_accessions_isolate_id ON culture_accessions(isolate_id);
+            "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute_unprepared(
+            r#"
+            DROP TABLE IF EXISTS culture_accessions;
+            "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+}
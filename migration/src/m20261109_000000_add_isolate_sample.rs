@@ -0,0 +1,42 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // The tube an isolate was cultured from. Isolates outlive a discarded
+        // sample, keeping only their field record.
+        db.execute_unprepared(
+            r#"
+            ALTER TABLE isolates ADD COLUMN sample_id UUID NULL;
+
+            ALTER TABLE isolates ADD CONSTRAINT fk_isolate_sample_id
+                FOREIGN KEY (sample_id) REFERENCES samples(id) ON DELETE SET NULL;
+
+            CREATE INDEX idx_isolates_sample_id ON isolates(sample_id);
+            "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute_unprepared(
+            r#"
+            DROP INDEX IF EXISTS idx_isolates_sample_id;
+            ALTER TABLE isolates DROP CONSTRAINT IF EXISTS fk_isolate_sample_id;
+            ALTER TABLE isolates DROP COLUMN IF EXISTS sample_id;
+            "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+}
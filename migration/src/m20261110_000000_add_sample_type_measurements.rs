@@ -0,0 +1,51 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // Measurement columns reserved for some habitats. A column listed for any
+        // sample type may only be filled on field records of the types listing it;
+        // the snow columns start out reserved for Snow.
+        db.execute_unprepared(
+            r#"
+            CREATE TABLE sample_type_measurements (
+                id UUID PRIMARY KEY,
+                sample_type_id UUID NOT NULL,
+                column_name TEXT NOT NULL,
+                created_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+                CONSTRAINT fk_sample_type_measurement_sample_type_id
+                    FOREIGN KEY (sample_type_id) REFERENCES sample_types(id) ON DELETE CASCADE,
+                UNIQUE (sample_type_id, column_name)
+            );
+            CREATE INDEX idx_sample_type_measurements_column_name
+                ON sample_type_measurements(column_name);
+
+            INSERT INTO sample_type_measurements (id, sample_type_id, column_name)
+            SELECT gen_random_uuid(), id, column_name
+            FROM sample_types, (VALUES ('snow_depth_cm'), ('snow_temperature_celsius')) AS c(column_name)
+            WHERE name = 'Snow';
+            "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute_unprepared(
+            r#"
+            DROP TABLE IF EXISTS sample_type_measurements;
+            "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,67 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // Every slot an item has occupied, so temperature excursions can list what
+        // was in a unit at the time. `storage_positions` only holds the current
+        // slot; moves update it in place. Entries of a position outlive it, since
+        // removing an item from storage is part of its history.
+        db.execute_unprepared(
+            r#"
+            CREATE TABLE storage_position_history (
+                id UUID PRIMARY KEY,
+                storage_position_id UUID NOT NULL,
+                storage_unit_id UUID NOT NULL,
+                "row" INTEGER NOT NULL,
+                "column" INTEGER NOT NULL,
+                sample_id UUID NULL,
+                isolate_id UUID NULL,
+                dna_id UUID NULL,
+                stored_at TIMESTAMPTZ NOT NULL,
+                removed_at TIMESTAMPTZ NULL,
+                CONSTRAINT fk_storage_position_history_storage_unit_id
+                    FOREIGN KEY (storage_unit_id) REFERENCES storage_units(id) ON DELETE CASCADE,
+                CONSTRAINT fk_storage_position_history_sample_id
+                    FOREIGN KEY (sample_id) REFERENCES samples(id) ON DELETE CASCADE,
+                CONSTRAINT fk_storage_position_history_isolate_id
+                    FOREIGN KEY (isolate_id) REFERENCES isolates(id) ON DELETE CASCADE,
+                CONSTRAINT fk_storage_position_history_dna_id
+                    FOREIGN KEY (dna_id) REFERENCES dna(id) ON DELETE CASCADE
+            );
+            CREATE INDEX idx_storage_position_history_storage_unit_id
+                ON storage_position_history(storage_unit_id);
+            CREATE INDEX idx_storage_position_history_open
+                ON storage_position_history(storage_position_id) WHERE removed_at IS NULL;
+
+            INSERT INTO storage_position_history
+                (id, storage_position_id, storage_unit_id, "row", "column",
+                 sample_id, isolate_id, dna_id, stored_at)
+            SELECT gen_random_uuid(), id, storage_unit_id, "row", "column",
+                   sample_id, isolate_id, dna_id, created_at
+            FROM storage_positions;
+            "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute_unprepared(
+            r#"
+            DROP TABLE IF EXISTS storage_position_history;
+            "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,45 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // Taxa of an import still streaming in. They are committed in batches and
+        // moved to `taxa` in one statement once the upload has been read, so a
+        // taxdump is never held in memory or in one long transaction.
+        db.execute_unprepared(
+            r#"
+            CREATE TABLE taxa_staging (
+                id UUID PRIMARY KEY,
+                import_id UUID NOT NULL,
+                taxon_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                rank TEXT NOT NULL,
+                parent_taxon_id TEXT NULL,
+                is_synonym BOOLEAN NOT NULL
+            );
+            CREATE INDEX idx_taxa_staging_import_taxon ON taxa_staging(import_id, taxon_id);
+            "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute_unprepared(
+            r#"
+            DROP TABLE IF EXISTS taxa_staging;
+            "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,47 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        // `field_record_id` is NOT NULL, so SET NULL only ever failed the delete with
+        // a not-null violation. Refusing it outright reports the conflict, and keeps
+        // isolates, with their images and assemblies, from disappearing along with a
+        // field record without their files being removed.
+        db.execute_unprepared(
+            r#"
+            ALTER TABLE isolates
+                DROP CONSTRAINT fk_isolate_field_record_id,
+                ADD CONSTRAINT fk_isolate_field_record_id
+                    FOREIGN KEY (field_record_id) REFERENCES field_records(id)
+                    ON DELETE RESTRICT
+                    ON UPDATE CASCADE;
+            "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        let db = manager.get_connection();
+
+        db.execute_unprepared(
+            r#"
+            ALTER TABLE isolates
+                DROP CONSTRAINT fk_isolate_field_record_id,
+                ADD CONSTRAINT fk_isolate_field_record_id
+                    FOREIGN KEY (field_record_id) REFERENCES field_records(id)
+                    ON DELETE SET NULL
+                    ON UPDATE CASCADE;
+            "#,
+        )
+        .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,235 @@
+//! Statistics of FASTA genome assemblies, plain or gzip-compressed, read chunk by
+//! chunk as the upload arrives.
+
+use flate2::write::MultiGzDecoder;
+use md5::Md5;
+use sha2::{Digest, Sha256};
+use std::io::{self, Write};
+
+use super::models::AssemblyStats;
+
+const GZIP_MAGIC: [u8; 2] = [0x1F, 0x8B];
+
+/// IUPAC nucleotide codes.
+const BASES: &[u8] = b"ACGTURYSWKMBDHVN";
+
+/// Length of the contig at which half of the assembly is in contigs at least as long.
+fn n50(mut lengths: Vec<u64>, total: u64) -> u64 {
+    lengths.sort_unstable_by(|a, b| b.cmp(a));
+    let mut covered = 0;
+    for length in lengths {
+        covered += length;
+        if covered * 2 >= total {
+            return length;
+        }
+    }
+    0
+}
+
+/// The assembly's contigs, counted from its decompressed text.
+#[derive(Default)]
+struct Contigs {
+    lengths: Vec<u64>,
+    gc: u64,
+    acgt: u64,
+    /// The current line, up to the chunk read last.
+    line: Vec<u8>,
+    number: usize,
+    /// Why the text was refused; the gzip decoder only passes on an `io::Error`.
+    error: Option<String>,
+}
+
+impl Contigs {
+    fn end_line(&mut self) -> Result<(), String> {
+        self.number += 1;
+        let mut line = std::mem::take(&mut self.line);
+        let result = self.count(line.trim_ascii());
+        line.clear();
+        self.line = line;
+        result
+    }
+
+    fn count(&mut self, text: &[u8]) -> Result<(), String> {
+        let number = self.number;
+        if text.is_empty() {
+            return Ok(());
+        }
+        if text.starts_with(b">") {
+            if self.lengths.last() == Some(&0) {
+                return Err(format!(
+                    "Line {number}: the previous record has no sequence"
+                ));
+            }
+            self.lengths.push(0);
+            return Ok(());
+        }
+        let Some(length) = self.lengths.last_mut() else {
+            return Err(format!(
+                "Line {number}: expected a '>' header before the sequence"
+            ));
+        };
+        *length += text.len() as u64;
+        for &base in text {
+            let base = base.to_ascii_uppercase();
+            if !BASES.contains(&base) {
+                return Err(format!(
+                    "Line {number}: '{}' is not a nucleotide code",
+                    base as char
+                ));
+            }
+            match base {
+                b'G' | b'C' => {
+                    self.gc += 1;
+                    self.acgt += 1;
+                }
+                b'A' | b'T' | b'U' => self.acgt += 1,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    fn read(&mut self, mut text: &[u8]) -> Result<(), String> {
+        while let Some(end) = text.iter().position(|&byte| byte == b'\n') {
+            self.line.extend_from_slice(&text[..end]);
+            self.end_line()?;
+            text = &text[end + 1..];
+        }
+        self.line.extend_from_slice(text);
+        Ok(())
+    }
+
+    fn finish(mut self) -> Result<AssemblyStats, String> {
+        if !self.line.is_empty() {
+            self.end_line()?;
+        }
+        if self.lengths.is_empty() {
+            return Err("The file holds no FASTA records".to_string());
+        }
+        if self.lengths.last() == Some(&0) {
+            return Err("The last record has no sequence".to_string());
+        }
+
+        let total: u64 = self.lengths.iter().sum();
+        Ok(AssemblyStats {
+            length_bp: total as i64,
+            contigs: self.lengths.len() as i32,
+            n50_bp: n50(self.lengths, total) as i64,
+            gc_percent: (self.acgt > 0).then(|| 100.0 * self.gc as f64 / self.acgt as f64),
+        })
+    }
+}
+
+impl Write for Contigs {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.read(buf).map_err(|message| {
+            self.error = Some(message.clone());
+            io::Error::new(io::ErrorKind::InvalidData, message)
+        })?;
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+enum Decoder {
+    Plain(Contigs),
+    Gzip(MultiGzDecoder<Contigs>),
+}
+
+/// The refusal of the text if there was one, otherwise the decompression error.
+fn gzip_error(contigs: &mut Contigs, err: io::Error) -> String {
+    contigs
+        .error
+        .take()
+        .unwrap_or_else(|| format!("The file cannot be decompressed: {err}"))
+}
+
+impl Decoder {
+    fn new(head: &[u8]) -> Self {
+        if head.starts_with(&GZIP_MAGIC) {
+            Self::Gzip(MultiGzDecoder::new(Contigs::default()))
+        } else {
+            Self::Plain(Contigs::default())
+        }
+    }
+
+    fn write(&mut self, chunk: &[u8]) -> Result<(), String> {
+        match self {
+            Self::Plain(contigs) => contigs.read(chunk),
+            Self::Gzip(decoder) => decoder
+                .write_all(chunk)
+                .map_err(|err| gzip_error(decoder.get_mut(), err)),
+        }
+    }
+
+    fn finish(self) -> Result<AssemblyStats, String> {
+        match self {
+            Self::Plain(contigs) => contigs.finish(),
+            Self::Gzip(mut decoder) => {
+                decoder
+                    .try_finish()
+                    .map_err(|err| gzip_error(decoder.get_mut(), err))?;
+                decoder
+                    .finish()
+                    .map_err(|err| format!("The file cannot be decompressed: {err}"))?
+                    .finish()
+            }
+        }
+    }
+}
+
+/// What an upload turned out to hold.
+pub struct Summary {
+    pub stats: AssemblyStats,
+    pub gzip: bool,
+    /// Checksums of the file as stored, so they match a download.
+    pub sha256: String,
+    pub md5: String,
+}
+
+/// Reads an assembly as it is uploaded. The message of an error names the line.
+#[derive(Default)]
+pub struct Assembly {
+    sha256: Sha256,
+    md5: Md5,
+    /// Leading bytes, kept until it is known whether the file is compressed.
+    head: Vec<u8>,
+    decoder: Option<Decoder>,
+}
+
+impl Assembly {
+    pub fn update(&mut self, chunk: &[u8]) -> Result<(), String> {
+        self.sha256.update(chunk);
+        self.md5.update(chunk);
+        if let Some(decoder) = &mut self.decoder {
+            return decoder.write(chunk);
+        }
+        self.head.extend_from_slice(chunk);
+        if self.head.len() < GZIP_MAGIC.len() {
+            return Ok(());
+        }
+        let head = std::mem::take(&mut self.head);
+        self.decoder.insert(Decoder::new(&head)).write(&head)
+    }
+
+    pub fn finish(self) -> Result<Summary, String> {
+        let decoder = match self.decoder {
+            Some(decoder) => decoder,
+            None => {
+                let mut decoder = Decoder::new(&self.head);
+                decoder.write(&self.head)?;
+                decoder
+            }
+        };
+        let gzip = matches!(decoder, Decoder::Gzip(_));
+        Ok(Summary {
+            stats: decoder.finish()?,
+            gzip,
+            sha256: format!("{:x}", self.sha256.finalize()),
+            md5: format!("{:x}", self.md5.finalize()),
+        })
+    }
+}
@@ -0,0 +1,5 @@
+pub mod fasta;
+pub mod models;
+pub mod services;
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,9 @@
+/// Computed from an uploaded assembly and stored on its isolate.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AssemblyStats {
+    pub length_bp: i64,
+    pub contigs: i32,
+    pub n50_bp: i64,
+    /// Over unambiguous bases; none when the assembly has only ambiguity codes.
+    pub gc_percent: Option<f64>,
+}
@@ -0,0 +1,129 @@
+use axum::body::Bytes;
+use crudcrate::validation::ValidationError;
+use crudcrate::{ApiError, CRUDResource};
+use futures::Stream;
+use object_store::ObjectStore;
+use sea_orm::{
+    ActiveModelTrait, DatabaseConnection, EntityTrait, IntoActiveModel, QueryFilter, Set,
+    TransactionTrait,
+};
+use uuid::Uuid;
+
+use super::fasta;
+use crate::isolates::db::{Entity, Isolate, Model};
+use crate::{files, middleware};
+
+/// Largest accepted assembly, enough for most fungal genomes uncompressed.
+pub const MAX_UPLOAD_BYTES: usize = 200 * 1024 * 1024;
+
+pub fn url(isolate_id: Uuid) -> String {
+    format!("/api/isolates/{isolate_id}/assembly")
+}
+
+/// The isolate, if the caller may see it.
+async fn find_isolate(db: &DatabaseConnection, id: Uuid, scoped: bool) -> Result<Model, ApiError> {
+    let mut isolate = Entity::find_by_id(id);
+    if scoped {
+        isolate = isolate.filter(middleware::isolates_scope());
+    }
+    isolate
+        .one(db)
+        .await?
+        .ok_or_else(|| ApiError::not_found("isolate", Some(id.to_string())))
+}
+
+/// Stores an uploaded FASTA assembly in place of the isolate's previous one and
+/// records its statistics on the isolate. The file is checked and stored as it
+/// streams in rather than held in memory.
+pub async fn upload(
+    db: &DatabaseConnection,
+    store: &dyn ObjectStore,
+    isolate_id: Uuid,
+    filename: Option<String>,
+    chunks: impl Stream<Item = Result<Bytes, ApiError>>,
+) -> Result<Isolate, ApiError> {
+    let isolate = find_isolate(db, isolate_id, false).await?;
+    let invalid = |message| ApiError::from(ValidationError::new("assembly", message));
+    let mut assembly = fasta::Assembly::default();
+    let (file_id, size_bytes) = files::services::stream_file(store, chunks, |chunk| {
+        assembly.update(chunk).map_err(invalid)
+    })
+    .await?;
+    let summary = match assembly.finish() {
+        Ok(summary) => summary,
+        Err(message) => {
+            files::services::discard(store, file_id).await;
+            return Err(invalid(message));
+        }
+    };
+    let content_type = if summary.gzip {
+        "application/gzip"
+    } else {
+        "text/x-fasta"
+    };
+
+    let previous = isolate.assembly_file_id;
+    let recorded: Result<(), ApiError> = async {
+        let txn = db.begin().await?;
+        files::services::insert(&txn, store, file_id, filename, content_type, size_bytes).await?;
+        let stats = summary.stats;
+        let mut model = isolate.into_active_model();
+        model.assembly_file_id = Set(Some(file_id));
+        model.assembly_length_bp = Set(Some(stats.length_bp));
+        model.assembly_contigs = Set(Some(stats.contigs));
+        model.assembly_n50_bp = Set(Some(stats.n50_bp));
+        model.assembly_gc_percent = Set(stats.gc_percent);
+        model.assembly_sha256 = Set(Some(summary.sha256));
+        model.assembly_md5 = Set(Some(summary.md5));
+        model.update(&txn).await?;
+        txn.commit().await?;
+        Ok(())
+    }
+    .await;
+    if let Err(err) = recorded {
+        files::services::discard(store, file_id).await;
+        return Err(err);
+    }
+
+    files::services::remove_unreferenced(db, store, previous.as_slice()).await?;
+    Isolate::get_one(db, isolate_id).await
+}
+
+/// The isolate and its assembly's file, if the caller may see the isolate.
+pub async fn find(
+    db: &DatabaseConnection,
+    isolate_id: Uuid,
+    scoped: bool,
+) -> Result<(Model, files::db::Model), ApiError> {
+    let isolate = find_isolate(db, isolate_id, scoped).await?;
+    let file_id = isolate
+        .assembly_file_id
+        .ok_or_else(|| ApiError::not_found("assembly", Some(isolate_id.to_string())))?;
+    let file = files::services::find(db, file_id).await?;
+    Ok((isolate, file))
+}
+
+/// Clears the isolate's assembly and its statistics, and deletes the file.
+pub async fn remove(
+    db: &DatabaseConnection,
+    store: &dyn ObjectStore,
+    isolate_id: Uuid,
+) -> Result<(), ApiError> {
+    let isolate = find_isolate(db, isolate_id, false).await?;
+    let Some(file_id) = isolate.assembly_file_id else {
+        return Err(ApiError::not_found(
+            "assembly",
+            Some(isolate_id.to_string()),
+        ));
+    };
+    let mut model = isolate.into_active_model();
+    model.assembly_file_id = Set(None);
+    model.assembly_length_bp = Set(None);
+    model.assembly_contigs = Set(None);
+    model.assembly_n50_bp = Set(None);
+    model.assembly_gc_percent = Set(None);
+    model.assembly_sha256 = Set(None);
+    model.assembly_md5 = Set(None);
+    model.update(db).await?;
+    files::services::remove_unreferenced(db, store, &[file_id]).await
+}
@@ -0,0 +1,231 @@
+use axum::{body::to_bytes, http::StatusCode};
+use flate2::{write::GzEncoder, Compression};
+use md5::Md5;
+use sea_orm::{EntityTrait, PaginatorTrait};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::io::Write;
+
+use super::fasta;
+use super::models::AssemblyStats;
+use crate::test_utils::{
+    build_app_with_db, build_scoped_app_with_db, create, create_field_record, create_site, get,
+    json_body, send, send_raw, setup_sqlite_db,
+};
+
+/// Three contigs of 60, 30 and 10 bp; GC is 40 of the 100 unambiguous bases.
+const ASSEMBLY: &[u8] = b">contig_1 len=60\n\
+    GGGGGGGGGGCCCCCCCCCCAAAAAAAAAA\nTTTTTTTTTTAAAAAAAAAATTTTTTTTTT\n\
+    >contig_2\r\nggggggggggccccccccccaaaaaaaaaa\r\n\
+    \n>contig_3\nTTTTTTTTTT\n";
+
+async fn upload(app: &axum::Router, uri: &str, bytes: &[u8]) -> (StatusCode, Value) {
+    let boundary = "assembly";
+    let mut body = format!(
+        "--{boundary}\r\nContent-Disposition: form-data; name=\"assembly\"; filename=\"iso.fasta\"\r\n\r\n"
+    )
+    .into_bytes();
+    body.extend_from_slice(bytes);
+    body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+    let resp = send_raw(
+        app,
+        "PUT",
+        uri,
+        &[(
+            "Content-Type",
+            &format!("multipart/form-data; boundary={boundary}"),
+        )],
+        body,
+    )
+    .await;
+    (resp.status(), json_body(resp).await)
+}
+
+async fn file_count(db: &sea_orm::DatabaseConnection) -> u64 {
+    crate::files::db::Entity::find().count(db).await.unwrap()
+}
+
+/// The public ISO-GENOME and the private ISO-SECRET, from field record FR-7, and
+/// the assembly URI of each.
+async fn isolates(app: &axum::Router) -> ((String, String), (String, String)) {
+    let site_id = create_site(app, "Glacier G").await;
+    let fr = create_field_record(app, &site_id, "FR-7").await;
+    let mut isolates = Vec::new();
+    for (name, is_private) in [("ISO-GENOME", false), ("ISO-SECRET", true)] {
+        let id = create(
+            app,
+            "/api/isolates",
+            json!({ "name": name, "field_record_id": fr, "is_private": is_private }),
+        )
+        .await;
+        let assembly = format!("/api/isolates/{id}/assembly");
+        isolates.push((id, assembly));
+    }
+    let private = isolates.pop().unwrap();
+    (isolates.pop().unwrap(), private)
+}
+
+/// The assembly's statistics, read in chunks of `size` bytes.
+fn read(bytes: &[u8], size: usize) -> Result<AssemblyStats, String> {
+    let mut assembly = fasta::Assembly::default();
+    for chunk in bytes.chunks(size) {
+        assembly.update(chunk)?;
+    }
+    assembly.finish().map(|summary| summary.stats)
+}
+
+#[test]
+fn stats_of_plain_and_gzipped_fasta_agree() {
+    let stats = read(ASSEMBLY, ASSEMBLY.len()).unwrap();
+    assert_eq!(stats.length_bp, 100);
+    assert_eq!(stats.contigs, 3);
+    assert_eq!(stats.n50_bp, 60);
+    assert_eq!(stats.gc_percent, Some(40.0));
+
+    let mut gzip = GzEncoder::new(Vec::new(), Compression::default());
+    gzip.write_all(ASSEMBLY).unwrap();
+    let gzip = gzip.finish().unwrap();
+    for size in [1, 7, gzip.len()] {
+        assert_eq!(read(ASSEMBLY, size).unwrap(), stats, "plain, {size}");
+        assert_eq!(read(&gzip, size).unwrap(), stats, "gzip, {size}");
+    }
+
+    for (bad, line) in [
+        (&b"ACGT\n"[..], "Line 1"),
+        (b">a\n>b\nACGT\n", "Line 2"),
+        (b">a\nACGTXQ\n", "Line 2"),
+        (b">a\n", "last record"),
+        (b"", "no FASTA records"),
+    ] {
+        let message = read(bad, 3).unwrap_err();
+        assert!(message.contains(line), "{message}");
+    }
+}
+
+/// Scenario: files that are not nucleotide FASTA and a public upload are sent.
+/// Expected behaviour: each is refused and no file is left behind.
+#[tokio::test]
+async fn refused_assemblies_leave_no_file() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db.clone());
+    let scoped = build_scoped_app_with_db(db.clone());
+    let ((_, assembly), _) = isolates(&app).await;
+
+    let (status, _) = upload(&app, &assembly, b"MKVLAAGIVG\n").await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "not FASTA");
+    let (status, _) = upload(&app, &assembly, b">p\nMKVLEEFQ\n").await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "protein");
+    let (status, _) = upload(&scoped, &assembly, ASSEMBLY).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    assert_eq!(file_count(&db).await, 0);
+}
+
+/// Scenario: an assembly is uploaded and then replaced by another.
+/// Expected behaviour: the statistics and checksums of the new one appear on the
+/// isolate and can be filtered on; the replaced file is deleted.
+#[tokio::test]
+async fn assemblies_are_summarised_on_the_isolate() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db.clone());
+    let ((_, assembly), _) = isolates(&app).await;
+
+    let (status, isolate) = upload(&app, &assembly, b">only\nACGT\n").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(isolate["assembly_contigs"], 1);
+    let (status, isolate) = upload(&app, &assembly, ASSEMBLY).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(isolate["assembly_length_bp"], 100);
+    assert_eq!(isolate["assembly_contigs"], 3);
+    assert_eq!(isolate["assembly_n50_bp"], 60);
+    assert_eq!(isolate["assembly_gc_percent"], 40.0);
+    assert_eq!(
+        isolate["assembly_sha256"],
+        format!("{:x}", Sha256::digest(ASSEMBLY))
+    );
+    assert_eq!(
+        isolate["assembly_md5"],
+        format!("{:x}", Md5::digest(ASSEMBLY))
+    );
+    assert_eq!(isolate["assembly_url"], assembly);
+    assert_eq!(file_count(&db).await, 1, "the replaced assembly is deleted");
+
+    let (_, listed) = get(
+        &app,
+        "/api/isolates?filter=%7B%22assembly_n50_bp_gte%22%3A50%7D",
+    )
+    .await;
+    assert_eq!(listed.as_array().unwrap().len(), 1);
+    let (_, listed) = get(
+        &app,
+        "/api/isolates?filter=%7B%22assembly_n50_bp_gte%22%3A61%7D",
+    )
+    .await;
+    assert_eq!(listed, json!([]));
+}
+
+/// Scenario: assemblies of a public and a private isolate are downloaded.
+/// Expected behaviour: the file downloads as uploaded, but not for public callers
+/// when the isolate is private, and public lists only link the public one.
+#[tokio::test]
+async fn assemblies_download_within_scope() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db.clone());
+    let scoped = build_scoped_app_with_db(db);
+    let ((_, public), (_, private)) = isolates(&app).await;
+    upload(&app, &public, ASSEMBLY).await;
+    upload(&app, &private, ASSEMBLY).await;
+
+    let resp = send_raw(&scoped, "GET", &public, &[], vec![]).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()["content-type"], "text/x-fasta");
+    assert_eq!(
+        resp.headers()["content-disposition"],
+        "attachment; filename=\"iso.fasta\""
+    );
+    let body = to_bytes(resp.into_body(), 1024 * 1024).await.unwrap();
+    assert_eq!(body, ASSEMBLY);
+    let (status, _) = get(&scoped, &private).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let resp = send_raw(&app, "GET", &private, &[], vec![]).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()["cache-control"], "private, no-cache");
+
+    let (_, listed) = get(&scoped, "/api/isolates").await;
+    assert_eq!(listed.as_array().unwrap().len(), 1);
+    assert_eq!(listed[0]["assembly_url"], public);
+}
+
+/// Scenario: one assembly is removed, and the isolate of the other is deleted.
+/// Expected behaviour: only admins can remove an assembly; removing it clears the
+/// isolate's statistics, and no file outlives its assembly or isolate.
+#[tokio::test]
+async fn removed_assemblies_leave_no_file() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db.clone());
+    let scoped = build_scoped_app_with_db(db.clone());
+    let ((public_id, public), (private_id, private)) = isolates(&app).await;
+    upload(&app, &public, ASSEMBLY).await;
+    upload(&app, &private, ASSEMBLY).await;
+    assert_eq!(file_count(&db).await, 2);
+
+    let (status, _) = send(&scoped, "DELETE", &public, Value::Null).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = send(&app, "DELETE", &public, Value::Null).await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (_, isolate) = get(&app, &format!("/api/isolates/{public_id}")).await;
+    assert_eq!(isolate["assembly_url"], Value::Null);
+    assert_eq!(isolate["assembly_n50_bp"], Value::Null);
+    let (status, _) = get(&app, &public).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(file_count(&db).await, 1);
+
+    let (status, _) = send(
+        &app,
+        "DELETE",
+        &format!("/api/isolates/{private_id}"),
+        Value::Null,
+    )
+    .await;
+    assert!(status.is_success());
+    assert_eq!(file_count(&db).await, 0, "no assembly outlives its isolate");
+}
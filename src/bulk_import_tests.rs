@@ -380,6 +380,9 @@ async fn field_record_accepts_new_fields() {
     let db = setup_sqlite_db().await;
     let app = build_app_with_db(db);
 
+    let campaigns = batch_create(&app, "campaigns", json!([{ "name": "Summer 2026" }])).await;
+    let campaign_id = campaigns[0]["id"].as_str().unwrap();
+
     let fr_id = seed_site_field_record(
         &app,
         json!({
@@ -387,7 +390,7 @@ async fn field_record_accepts_new_fields() {
             "sample_type": "Soil",
             "sampling_date": "2026-06-01",
             "treatment": "control",
-            "campaign": "Summer 2026",
+            "campaign_id": campaign_id,
             "water_content": 34.2,
             "total_carbon": 5.1,
             "total_organic_carbon": 4.7,
@@ -399,7 +402,7 @@ async fn field_record_accepts_new_fields() {
     let (status, fr) = get_one(&app, &format!("/api/field_records/{fr_id}")).await;
     assert_eq!(status, StatusCode::OK);
     assert_eq!(fr["treatment"], json!("control"));
-    assert_eq!(fr["campaign"], json!("Summer 2026"));
+    assert_eq!(fr["campaign_id"], json!(campaign_id));
     assert_eq!(fr["water_content"], json!(34.2));
     assert_eq!(fr["total_carbon"], json!(5.1));
     assert_eq!(fr["total_organic_carbon"], json!(4.7));
@@ -449,6 +452,8 @@ async fn text_fields_preserve_diacritics() {
     let app = build_app_with_db(db);
 
     let campaign = "Forêt d'Aletsch, évapotranspiration à Genève (çàéèêëîïôûù)";
+    let campaigns = batch_create(&app, "campaigns", json!([{ "name": campaign }])).await;
+    let campaign_id = campaigns[0]["id"].as_str().unwrap();
 
     let fr_id = seed_site_field_record(
         &app,
@@ -457,7 +462,7 @@ async fn text_fields_preserve_diacritics() {
             "sample_type": "Soil",
             "sampling_date": "2026-06-01",
             "treatment": "témoin (contrôle)",
-            "campaign": campaign
+            "campaign_id": campaign_id
         }),
     )
     .await;
@@ -466,5 +471,9 @@ async fn text_fields_preserve_diacritics() {
     assert_eq!(status, StatusCode::OK);
     assert_eq!(fr["name"], json!("Relevé Zürich-Genève"));
     assert_eq!(fr["treatment"], json!("témoin (contrôle)"));
-    assert_eq!(fr["campaign"], json!(campaign));
+    assert_eq!(fr["campaign_id"], json!(campaign_id));
+
+    let (status, c) = get_one(&app, &format!("/api/campaigns/{campaign_id}")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(c["name"], json!(campaign));
 }
@@ -0,0 +1,82 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use crudcrate::validation::{Validatable, ValidationError};
+use crudcrate::{CRUDResource, EntityToModels};
+use sea_orm::entity::prelude::*;
+use uuid::Uuid;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, EntityToModels)]
+#[sea_orm(table_name = "campaigns")]
+#[crudcrate(
+    generate_router,
+    api_struct = "Campaign",
+    name_singular = "campaign",
+    name_plural = "campaigns",
+    description = "Field campaigns grouping field records collected during one expedition",
+    derive_partial_eq,
+    update::one::pre = crate::campaigns::services::check_update,
+    update::many::pre = crate::campaigns::services::check_update_many
+)]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    #[crudcrate(primary_key, filterable, exclude(update, create), on_create = Uuid::new_v4())]
+    pub id: Uuid,
+    #[sea_orm(unique)]
+    #[crudcrate(sortable, filterable, fulltext)]
+    pub name: String,
+    #[crudcrate(sortable, filterable)]
+    pub start_date: Option<NaiveDate>,
+    #[crudcrate(sortable, filterable)]
+    pub end_date: Option<NaiveDate>,
+    #[crudcrate(sortable, filterable, fulltext)]
+    pub principal_investigator: Option<String>,
+    #[crudcrate(sortable, filterable, fulltext)]
+    pub description: Option<String>,
+    #[crudcrate(filterable, exclude(scoped), on_create = false)]
+    pub is_private: bool,
+    #[crudcrate(sortable, filterable, exclude(create, update), on_create = chrono::Utc::now())]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "crate::field_records::db::Entity")]
+    FieldRecords,
+}
+
+impl Related<crate::field_records::db::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::FieldRecords.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+pub(super) fn validate_date_order(
+    start_date: Option<NaiveDate>,
+    end_date: Option<NaiveDate>,
+) -> Result<(), ValidationError> {
+    match (start_date, end_date) {
+        (Some(start), Some(end)) if start > end => Err(ValidationError::new(
+            "end_date",
+            "Must not be before start_date",
+        )),
+        _ => Ok(()),
+    }
+}
+
+impl Validatable for CampaignCreate {
+    fn validate(&self) -> Result<(), ValidationError> {
+        validate_date_order(self.start_date, self.end_date)
+    }
+}
+
+impl Validatable for CampaignUpdate {
+    fn validate(&self) -> Result<(), ValidationError> {
+        // A single date is checked against the stored one in
+        // `services::check_update`.
+        if let (Some(start_date), Some(end_date)) = (self.start_date, self.end_date) {
+            validate_date_order(start_date, end_date)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+#[derive(ToSchema, Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct CampaignSite {
+    pub id: Uuid,
+    pub name: String,
+    pub latitude_4326: f64,
+    pub longitude_4326: f64,
+    pub elevation_metres: f64,
+    pub field_record_count: u64,
+}
+
+/// What a campaign page shows besides the campaign itself: the sites visited and
+/// how much material came back. Counts respect the caller's privacy scope.
+#[derive(ToSchema, Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct CampaignSummary {
+    pub campaign_id: Uuid,
+    pub sites: Vec<CampaignSite>,
+    pub field_record_count: u64,
+    pub sample_count: u64,
+    pub isolate_count: u64,
+    pub dna_count: u64,
+}
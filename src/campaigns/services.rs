@@ -0,0 +1,32 @@
+use crudcrate::ApiError;
+use sea_orm::{DatabaseConnection, EntityTrait};
+use uuid::Uuid;
+
+use super::db::{validate_date_order, CampaignUpdate, Entity};
+
+/// A request changing one date is checked against the other, stored one.
+pub async fn check_update(
+    db: &DatabaseConnection,
+    id: Uuid,
+    data: &CampaignUpdate,
+) -> Result<(), ApiError> {
+    let existing = Entity::find_by_id(id)
+        .one(db)
+        .await?
+        .ok_or_else(|| ApiError::not_found("campaign", Some(id.to_string())))?;
+    validate_date_order(
+        data.start_date.unwrap_or(existing.start_date),
+        data.end_date.unwrap_or(existing.end_date),
+    )?;
+    Ok(())
+}
+
+pub async fn check_update_many(
+    db: &DatabaseConnection,
+    updates: &[(Uuid, CampaignUpdate)],
+) -> Result<(), ApiError> {
+    for (id, data) in updates {
+        check_update(db, *id, data).await?;
+    }
+    Ok(())
+}
@@ -0,0 +1,203 @@
+use axum::http::StatusCode;
+use serde_json::json;
+
+use crate::test_utils::{
+    build_app_with_db, build_scoped_app_with_db, create, create_site, get, send, setup_sqlite_db,
+};
+
+#[tokio::test]
+async fn campaigns_reject_end_before_start() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db);
+
+    let (status, _) = send(
+        &app,
+        "POST",
+        "/api/campaigns",
+        json!({ "name": "Alps 2025", "start_date": "2025-08-01", "end_date": "2025-07-01" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+    let (status, campaign) = send(
+        &app,
+        "POST",
+        "/api/campaigns",
+        json!({
+            "name": "Alps 2025", "start_date": "2025-07-01", "end_date": "2025-08-01",
+            "principal_investigator": "A. Researcher"
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(campaign["principal_investigator"], "A. Researcher");
+
+    let id = campaign["id"].as_str().unwrap();
+    for (method, path, payload) in [
+        (
+            "PUT",
+            format!("/api/campaigns/{id}"),
+            json!({ "end_date": "2025-06-30" }),
+        ),
+        (
+            "PATCH",
+            "/api/campaigns/batch".to_string(),
+            json!([{ "id": id, "start_date": "2025-08-02" }]),
+        ),
+    ] {
+        let (status, body) = send(&app, method, &path, payload).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{method}: {body}");
+    }
+    let (status, _) = send(
+        &app,
+        "PUT",
+        &format!("/api/campaigns/{id}"),
+        json!({ "end_date": "2025-07-01" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+/// Campaign Alps 2025 visits Glacier A and Glacier B; Glacier B is only reached by
+/// a private field record, and one sample is private. Another campaign visits
+/// Glacier B too. Returns the id of Alps 2025.
+async fn visited_campaign(admin: &axum::Router) -> String {
+    let campaign_id = create(admin, "/api/campaigns", json!({ "name": "Alps 2025" })).await;
+    let other_campaign_id = create(admin, "/api/campaigns", json!({ "name": "Arctic 2025" })).await;
+    let site_ids = [
+        create_site(admin, "Glacier A").await,
+        create_site(admin, "Glacier B").await,
+    ];
+
+    let public_fr = create(
+        admin,
+        "/api/field_records",
+        json!({
+            "name": "FR-A-1", "site_id": site_ids[0], "campaign_id": campaign_id,
+            "sample_type": "Snow", "sampling_date": "2025-07-10"
+        }),
+    )
+    .await;
+    create(
+        admin,
+        "/api/field_records",
+        json!({
+            "name": "FR-A-2", "site_id": site_ids[0], "campaign_id": campaign_id,
+            "sample_type": "Soil", "sampling_date": "2025-07-11"
+        }),
+    )
+    .await;
+    let private_fr = create(
+        admin,
+        "/api/field_records",
+        json!({
+            "name": "FR-B-1", "site_id": site_ids[1], "campaign_id": campaign_id,
+            "sample_type": "Snow", "sampling_date": "2025-07-12", "is_private": true
+        }),
+    )
+    .await;
+    create(
+        admin,
+        "/api/field_records",
+        json!({
+            "name": "FR-OTHER", "site_id": site_ids[1], "campaign_id": other_campaign_id,
+            "sample_type": "Snow", "sampling_date": "2025-03-01"
+        }),
+    )
+    .await;
+
+    for (name, fr, is_private) in [
+        ("S-1", &public_fr, false),
+        ("S-2", &public_fr, true),
+        ("S-3", &private_fr, false),
+    ] {
+        create(
+            admin,
+            "/api/samples",
+            json!({ "name": name, "field_record_id": fr, "is_private": is_private }),
+        )
+        .await;
+    }
+    create(
+        admin,
+        "/api/isolates",
+        json!({ "name": "I-1", "field_record_id": public_fr }),
+    )
+    .await;
+    create(
+        admin,
+        "/api/dna",
+        json!({ "name": "D-1", "field_record_id": private_fr }),
+    )
+    .await;
+    campaign_id
+}
+
+/// Expected behaviour: admins see every site, field record, sample, isolate and DNA
+/// extract of the campaign, and nothing of the other campaign.
+#[tokio::test]
+async fn campaign_summary_lists_sites_and_counts() {
+    let db = setup_sqlite_db().await;
+    let admin = build_app_with_db(db);
+    let campaign_id = visited_campaign(&admin).await;
+
+    let (status, summary) = get(&admin, &format!("/api/campaigns/{campaign_id}/summary")).await;
+    assert_eq!(status, StatusCode::OK, "{summary}");
+    assert_eq!(summary["field_record_count"], 3);
+    assert_eq!(summary["sample_count"], 3);
+    assert_eq!(summary["isolate_count"], 1);
+    assert_eq!(summary["dna_count"], 1);
+    let sites = summary["sites"].as_array().unwrap();
+    assert_eq!(sites.len(), 2);
+    assert_eq!(sites[0]["name"], "Glacier A");
+    assert_eq!(sites[0]["field_record_count"], 2);
+    assert_eq!(sites[1]["field_record_count"], 1);
+}
+
+/// Expected behaviour: the public summary leaves out the private rows and the site
+/// only they reached.
+#[tokio::test]
+async fn public_campaign_summary_leaves_out_private_rows() {
+    let db = setup_sqlite_db().await;
+    let admin = build_app_with_db(db.clone());
+    let scoped = build_scoped_app_with_db(db);
+    let campaign_id = visited_campaign(&admin).await;
+
+    let (status, summary) = get(&scoped, &format!("/api/campaigns/{campaign_id}/summary")).await;
+    assert_eq!(status, StatusCode::OK, "{summary}");
+    assert_eq!(summary["field_record_count"], 2);
+    assert_eq!(
+        summary["sample_count"], 1,
+        "private sample and private parent hidden"
+    );
+    assert_eq!(summary["isolate_count"], 1);
+    assert_eq!(summary["dna_count"], 0);
+    let sites = summary["sites"].as_array().unwrap();
+    assert_eq!(
+        sites.len(),
+        1,
+        "site only reached by a private record: {sites:?}"
+    );
+    assert_eq!(sites[0]["name"], "Glacier A");
+}
+
+#[tokio::test]
+async fn private_campaign_hidden_from_public() {
+    let db = setup_sqlite_db().await;
+    let admin = build_app_with_db(db.clone());
+    let scoped = build_scoped_app_with_db(db);
+
+    let id = create(
+        &admin,
+        "/api/campaigns",
+        json!({ "name": "Embargoed", "is_private": true }),
+    )
+    .await;
+
+    let (status, _) = get(&scoped, &format!("/api/campaigns/{id}")).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = get(&scoped, &format!("/api/campaigns/{id}/summary")).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = get(&admin, &format!("/api/campaigns/{id}/summary")).await;
+    assert_eq!(status, StatusCode::OK);
+}
@@ -0,0 +1,108 @@
+use axum::extract::{Path, Request, State};
+use axum::{routing::get, Json, Router};
+use crudcrate::ApiError;
+use sea_orm::{
+    ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder,
+    QuerySelect,
+};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use super::models::{CampaignSite, CampaignSummary};
+use crate::{dna, field_records, isolates, middleware, samples, sites};
+
+/// Routes mounted next to the generated CRUD router under `/api/campaigns`.
+pub fn router(db: &DatabaseConnection) -> Router {
+    Router::new()
+        .route("/{id}/summary", get(get_campaign_summary))
+        .with_state(db.clone())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/campaigns/{id}/summary",
+    params(("id" = Uuid, Path, description = "Campaign id")),
+    responses(
+        (status = OK, description = "Sites visited and material counts", body = CampaignSummary),
+        (status = NOT_FOUND, description = "Campaign not found")
+    )
+)]
+pub async fn get_campaign_summary(
+    State(db): State<DatabaseConnection>,
+    Path(id): Path<Uuid>,
+    req: Request,
+) -> Result<Json<CampaignSummary>, ApiError> {
+    let scope_public = middleware::is_scoped(&req);
+
+    let mut campaign = super::db::Entity::find_by_id(id);
+    if scope_public {
+        campaign = campaign.filter(middleware::campaigns_scope());
+    }
+    if campaign.one(&db).await?.is_none() {
+        return Err(ApiError::not_found("campaign", Some(id.to_string())));
+    }
+
+    // The field record scope already requires a public site and area, so the sites
+    // and children below only need their own privacy checks.
+    let mut field_record_query = field_records::db::Entity::find()
+        .select_only()
+        .column(field_records::db::Column::Id)
+        .column(field_records::db::Column::SiteId)
+        .filter(field_records::db::Column::CampaignId.eq(id));
+    if scope_public {
+        field_record_query = field_record_query.filter(middleware::field_records_scope());
+    }
+    let field_records: Vec<(Uuid, Uuid)> = field_record_query.into_tuple().all(&db).await?;
+
+    let mut per_site: HashMap<Uuid, u64> = HashMap::new();
+    for (_, site_id) in &field_records {
+        *per_site.entry(*site_id).or_default() += 1;
+    }
+    let field_record_ids: Vec<Uuid> = field_records.iter().map(|(id, _)| *id).collect();
+
+    let sites = sites::db::Entity::find()
+        .filter(sites::db::Column::Id.is_in(per_site.keys().copied()))
+        .order_by_asc(sites::db::Column::Name)
+        .all(&db)
+        .await?
+        .into_iter()
+        .map(|site| CampaignSite {
+            field_record_count: per_site.get(&site.id).copied().unwrap_or_default(),
+            id: site.id,
+            name: site.name,
+            latitude_4326: site.latitude_4326,
+            longitude_4326: site.longitude_4326,
+            elevation_metres: site.elevation_metres,
+        })
+        .collect();
+
+    let (sample_count, isolate_count, dna_count) = if field_record_ids.is_empty() {
+        (0, 0, 0)
+    } else {
+        let mut samples = samples::db::Entity::find()
+            .filter(samples::db::Column::FieldRecordId.is_in(field_record_ids.clone()));
+        let mut isolates = isolates::db::Entity::find()
+            .filter(isolates::db::Column::FieldRecordId.is_in(field_record_ids.clone()));
+        let mut dna = dna::db::Entity::find()
+            .filter(dna::db::Column::FieldRecordId.is_in(field_record_ids.clone()));
+        if scope_public {
+            samples = samples.filter(middleware::samples_scope());
+            isolates = isolates.filter(middleware::isolates_scope());
+            dna = dna.filter(middleware::dna_scope());
+        }
+        (
+            samples.count(&db).await?,
+            isolates.count(&db).await?,
+            dna.count(&db).await?,
+        )
+    };
+
+    Ok(Json(CampaignSummary {
+        campaign_id: id,
+        sites,
+        field_record_count: field_record_ids.len() as u64,
+        sample_count,
+        isolate_count,
+        dna_count,
+    }))
+}
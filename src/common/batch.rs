@@ -0,0 +1,15 @@
+//! Checks shared by the custom batch hooks.
+
+use crudcrate::{ApiError, CRUDResource};
+
+/// Refuses a batch `operation` (`create`, `update` or `delete`) of more items than
+/// `R::batch_limit()`, as the generated batch handlers do.
+pub fn check_limit<R: CRUDResource>(operation: &str, items: usize) -> Result<(), ApiError> {
+    if items > R::batch_limit() {
+        return Err(ApiError::bad_request(format!(
+            "Batch {operation} limited to {} items. Received {items} items.",
+            R::batch_limit()
+        )));
+    }
+    Ok(())
+}
@@ -0,0 +1,15 @@
+//! Helpers for the CSV downloads.
+
+/// One RFC 4180 record with its CRLF terminator; fields holding a separator, quote
+/// or line break are quoted.
+pub fn record<S: AsRef<str>>(fields: &[S]) -> String {
+    let mut writer = csv::WriterBuilder::new()
+        .terminator(csv::Terminator::CRLF)
+        .from_writer(vec![]);
+    writer
+        .write_record(fields.iter().map(|field| field.as_ref()))
+        .and_then(|()| writer.flush().map_err(csv::Error::from))
+        .expect("writing to memory cannot fail");
+    let bytes = writer.into_inner().expect("writing to memory cannot fail");
+    String::from_utf8(bytes).expect("fields are UTF-8")
+}
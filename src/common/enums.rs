@@ -1,27 +1,192 @@
 use sea_orm::entity::prelude::*;
 use serde::{Deserialize, Serialize};
-use std::fmt;
 use utoipa::ToSchema;
 
+/// Storage type of an admin-defined measurement parameter. `Integer` values are
+/// stored alongside `Number` ones and only differ in validation.
 #[derive(
     Clone, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
 )]
 #[sea_orm(rs_type = "String", db_type = "Text")]
 #[derive(Default)]
-pub enum SampleType {
-    #[sea_orm(string_value = "Snow")]
+pub enum ParameterValueType {
+    #[sea_orm(string_value = "Number")]
     #[default]
-    Snow,
-    #[sea_orm(string_value = "Soil")]
-    Soil,
+    Number,
+    #[sea_orm(string_value = "Integer")]
+    Integer,
+    #[sea_orm(string_value = "Text")]
+    Text,
 }
 
+impl ParameterValueType {
+    pub fn is_numeric(&self) -> bool {
+        matches!(self, ParameterValueType::Number | ParameterValueType::Integer)
+    }
+}
+
+/// Level in the freezer storage hierarchy. Racks sit in freezers and boxes in racks.
+#[derive(
+    Clone, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "Text")]
+#[derive(Default)]
+pub enum StorageUnitKind {
+    #[sea_orm(string_value = "Freezer")]
+    #[default]
+    Freezer,
+    #[sea_orm(string_value = "Rack")]
+    Rack,
+    #[sea_orm(string_value = "Box")]
+    Box,
+}
+
+impl StorageUnitKind {
+    /// The kind a unit of this kind has to sit in.
+    pub fn parent_kind(&self) -> Option<StorageUnitKind> {
+        match self {
+            StorageUnitKind::Freezer => None,
+            StorageUnitKind::Rack => Some(StorageUnitKind::Freezer),
+            StorageUnitKind::Box => Some(StorageUnitKind::Rack),
+        }
+    }
+}
 
-impl fmt::Display for SampleType {
-    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+impl std::fmt::Display for StorageUnitKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            SampleType::Snow => write!(f, "Snow"),
-            SampleType::Soil => write!(f, "Soil"),
+            StorageUnitKind::Freezer => write!(f, "Freezer"),
+            StorageUnitKind::Rack => write!(f, "Rack"),
+            StorageUnitKind::Box => write!(f, "Box"),
         }
     }
 }
+
+/// Where a material request is in its lifecycle. Only the transition endpoints move
+/// a request between states.
+#[derive(
+    Clone, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "Text")]
+#[derive(Default)]
+pub enum MaterialRequestStatus {
+    #[sea_orm(string_value = "Pending")]
+    #[default]
+    Pending,
+    #[sea_orm(string_value = "Approved")]
+    Approved,
+    #[sea_orm(string_value = "Rejected")]
+    Rejected,
+    #[sea_orm(string_value = "Shipped")]
+    Shipped,
+    #[sea_orm(string_value = "Returned")]
+    Returned,
+}
+
+impl std::fmt::Display for MaterialRequestStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MaterialRequestStatus::Pending => write!(f, "Pending"),
+            MaterialRequestStatus::Approved => write!(f, "Approved"),
+            MaterialRequestStatus::Rejected => write!(f, "Rejected"),
+            MaterialRequestStatus::Shipped => write!(f, "Shipped"),
+            MaterialRequestStatus::Returned => write!(f, "Returned"),
+        }
+    }
+}
+
+/// What happened to a physical sample, isolate or DNA extract in a custody event.
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "Text")]
+#[derive(Default)]
+pub enum CustodyEventKind {
+    #[sea_orm(string_value = "Collected")]
+    Collected,
+    #[sea_orm(string_value = "Received")]
+    #[default]
+    Received,
+    #[sea_orm(string_value = "Stored")]
+    Stored,
+    #[sea_orm(string_value = "Moved")]
+    Moved,
+    #[sea_orm(string_value = "Thawed")]
+    Thawed,
+    #[sea_orm(string_value = "Shipped")]
+    Shipped,
+    #[sea_orm(string_value = "Destroyed")]
+    Destroyed,
+}
+
+impl std::fmt::Display for CustodyEventKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            CustodyEventKind::Collected => write!(f, "Collected"),
+            CustodyEventKind::Received => write!(f, "Received"),
+            CustodyEventKind::Stored => write!(f, "Stored"),
+            CustodyEventKind::Moved => write!(f, "Moved"),
+            CustodyEventKind::Thawed => write!(f, "Thawed"),
+            CustodyEventKind::Shipped => write!(f, "Shipped"),
+            CustodyEventKind::Destroyed => write!(f, "Destroyed"),
+        }
+    }
+}
+
+/// What an isolate image shows.
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "Text")]
+#[derive(Default)]
+pub enum IsolateImageType {
+    /// Colonies on a plate.
+    #[sea_orm(string_value = "Colony")]
+    #[default]
+    Colony,
+    #[sea_orm(string_value = "Microscopy")]
+    Microscopy,
+    #[sea_orm(string_value = "GramStain")]
+    GramStain,
+}
+
+/// Reference taxonomy an isolate name was resolved against.
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "Text")]
+#[derive(Default)]
+pub enum TaxonomySource {
+    /// NCBI Taxonomy; taxon ids are NCBI tax ids.
+    #[sea_orm(string_value = "NCBI")]
+    #[serde(rename = "NCBI")]
+    #[default]
+    Ncbi,
+    /// Genome Taxonomy Database; taxon ids are the rank-prefixed names, e.g.
+    /// `g__Pseudomonas`.
+    #[sea_orm(string_value = "GTDB")]
+    #[serde(rename = "GTDB")]
+    Gtdb,
+}
+
+/// Marker gene of an isolate's barcode sequence.
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, EnumIter, DeriveActiveEnum, Serialize, Deserialize, ToSchema,
+)]
+#[sea_orm(rs_type = "String", db_type = "Text")]
+#[derive(Default)]
+pub enum MarkerGene {
+    /// Bacterial and archaeal small-subunit rRNA.
+    #[sea_orm(string_value = "16S")]
+    #[serde(rename = "16S")]
+    #[default]
+    Rrna16S,
+    /// Eukaryotic small-subunit rRNA.
+    #[sea_orm(string_value = "18S")]
+    #[serde(rename = "18S")]
+    Rrna18S,
+    /// Fungal internal transcribed spacer.
+    #[sea_orm(string_value = "ITS")]
+    #[serde(rename = "ITS")]
+    Its,
+}
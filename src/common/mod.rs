@@ -1,4 +1,7 @@
 pub mod auth;
+pub mod batch;
+pub mod csv;
 pub mod enums;
 pub mod models;
+pub mod pdf;
 pub mod views;
@@ -1,6 +1,4 @@
-use crate::common::enums::SampleType;
 use crate::config::Config;
-use sea_orm::Iterable;
 use serde::{Deserialize, Serialize};
 use utoipa::ToSchema;
 
@@ -19,7 +17,8 @@ pub struct UIConfiguration {
 }
 
 impl UIConfiguration {
-    pub fn new() -> Self {
+    /// `sample_types` comes from the habitat catalogue, so the caller loads it.
+    pub fn new(sample_types: Vec<String>) -> Self {
         let config: Config = Config::from_env();
         Self {
             keycloak: Keycloak {
@@ -28,7 +27,7 @@ impl UIConfiguration {
                 url: config.keycloak_url,
             },
             deployment: config.deployment,
-            sample_types: SampleType::iter().map(|st| st.to_string()).collect(),
+            sample_types,
         }
     }
 }
@@ -0,0 +1,93 @@
+//! PDF output shared by label sheets and packing lists: A4 pages drawn with
+//! rectangles and the two standard Helvetica fonts, which every viewer has built in.
+
+use pdf_writer::{Content, Name, Pdf, Rect, Ref, Str};
+
+pub const POINTS_PER_MM: f64 = 72.0 / 25.4;
+/// A4 page in millimetres.
+pub const A4_MM: (f64, f64) = (210.0, 297.0);
+/// Average Helvetica glyph width as a fraction of the font size, used to truncate.
+pub const AVERAGE_GLYPH_WIDTH: f64 = 0.55;
+
+/// Helvetica and Helvetica-Bold, as named in page content.
+pub const REGULAR: Name<'static> = Name(b"F1");
+pub const BOLD: Name<'static> = Name(b"F2");
+
+pub fn truncate(text: &str, max_chars: usize) -> String {
+    if text.chars().count() <= max_chars {
+        return text.to_string();
+    }
+    let kept: String = text.chars().take(max_chars.saturating_sub(2)).collect();
+    format!("{kept}..")
+}
+
+/// PDF coordinates of a point given in millimetres from the top-left corner of the
+/// page; PDF y runs upwards from the bottom.
+pub fn position(left_mm: f64, top_mm: f64) -> (f32, f32) {
+    (
+        (left_mm * POINTS_PER_MM) as f32,
+        ((A4_MM.1 - top_mm) * POINTS_PER_MM) as f32,
+    )
+}
+
+/// Writes `value` in WinAnsiEncoding with its baseline starting at the given point,
+/// in millimetres from the top-left corner. Characters outside Latin-1 become `?`.
+pub fn text(
+    content: &mut Content,
+    font: Name,
+    size_pt: f64,
+    left_mm: f64,
+    top_mm: f64,
+    value: &str,
+) {
+    let bytes: Vec<u8> = value
+        .chars()
+        .map(|c| u8::try_from(u32::from(c)).unwrap_or(b'?'))
+        .collect();
+    let (x, y) = position(left_mm, top_mm);
+    content
+        .begin_text()
+        .set_font(font, size_pt as f32)
+        .next_line(x, y)
+        .show(Str(&bytes))
+        .end_text();
+}
+
+/// A4 document with one page per content stream, drawn with `REGULAR` and `BOLD`.
+pub fn document(pages: Vec<Content>) -> Vec<u8> {
+    let catalog_id = Ref::new(1);
+    let tree_id = Ref::new(2);
+    let regular_id = Ref::new(3);
+    let bold_id = Ref::new(4);
+    // Each page then takes a page and a content object.
+    let page_ids: Vec<Ref> = (0..pages.len())
+        .map(|i| Ref::new(5 + 2 * i as i32))
+        .collect();
+
+    let mut pdf = Pdf::new();
+    pdf.catalog(catalog_id).pages(tree_id);
+    pdf.pages(tree_id)
+        .kids(page_ids.iter().copied())
+        .count(pages.len() as i32);
+    for (id, base_font) in [(regular_id, "Helvetica"), (bold_id, "Helvetica-Bold")] {
+        pdf.type1_font(id)
+            .base_font(Name(base_font.as_bytes()))
+            .encoding_predefined(Name(b"WinAnsiEncoding"));
+    }
+    let (width, height) = position(A4_MM.0, 0.0);
+    for (content, id) in pages.into_iter().zip(page_ids) {
+        let content_id = Ref::new(id.get() + 1);
+        {
+            let mut page = pdf.page(id);
+            page.parent(tree_id)
+                .media_box(Rect::new(0.0, 0.0, width, height))
+                .contents(content_id);
+            page.resources()
+                .fonts()
+                .pair(REGULAR, regular_id)
+                .pair(BOLD, bold_id);
+        }
+        pdf.stream(content_id, &content.finish());
+    }
+    pdf.finish()
+}
@@ -1,7 +1,9 @@
 use super::models::HealthCheck;
 use crate::common::models::UIConfiguration;
+use crate::sample_types;
 
 use axum::{extract::State, http::StatusCode, Json};
+use crudcrate::ApiError;
 use sea_orm::DatabaseConnection;
 
 #[utoipa::path(
@@ -17,7 +19,7 @@ use sea_orm::DatabaseConnection;
     )
 )]
 pub async fn healthz(State(db): State<DatabaseConnection>) -> (StatusCode, Json<HealthCheck>) {
-    if let Err(_) = db.ping().await {
+    if db.ping().await.is_err() {
         return (
             StatusCode::INTERNAL_SERVER_ERROR,
             Json(HealthCheck {
@@ -46,6 +48,9 @@ pub async fn healthz(State(db): State<DatabaseConnection>) -> (StatusCode, Json<
         )
     )
 )]
-pub async fn get_ui_config() -> Json<UIConfiguration> {
-    Json(UIConfiguration::new())
+pub async fn get_ui_config(
+    State(db): State<DatabaseConnection>,
+) -> Result<Json<UIConfiguration>, ApiError> {
+    let sample_types = sample_types::services::names(&db).await?;
+    Ok(Json(UIConfiguration::new(sample_types)))
 }
@@ -10,6 +10,7 @@ pub struct Config {
     pub keycloak_realm: String,
     pub deployment: String,
     pub area_buffer_metres: f64,
+    pub freeze_thaw: FreezeThawLimit,
 }
 
 impl Config {
@@ -27,8 +28,6 @@ impl Config {
             ))
         });
 
-        
-
         Config {
             app_name: env::var("APP_NAME").expect("APP_NAME must be set"),
             keycloak_ui_id: env::var("KEYCLOAK_UI_ID").expect("KEYCLOAK_UI_ID must be set"),
@@ -40,7 +39,98 @@ impl Config {
                 .unwrap_or_else(|_| "100".to_string())
                 .parse()
                 .expect("AREA_BUFFER_METRES must be a number"),
+            freeze_thaw: FreezeThawLimit::from_env(),
             db_url,
         }
     }
 }
+
+/// What happens to material requests for items thawed as often as the maximum allows.
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FreezeThawPolicy {
+    /// The request goes through with a warning for the admin deciding it.
+    Warn,
+    /// The request is refused.
+    Block,
+}
+
+#[derive(Deserialize, Debug, Clone, Copy)]
+pub struct FreezeThawLimit {
+    pub max_cycles: i32,
+    pub policy: FreezeThawPolicy,
+}
+
+/// What `FREEZE_THAW_MAX_CYCLES` and `FREEZE_THAW_POLICY` default to.
+impl Default for FreezeThawLimit {
+    fn default() -> Self {
+        FreezeThawLimit {
+            max_cycles: 5,
+            policy: FreezeThawPolicy::Warn,
+        }
+    }
+}
+
+impl FreezeThawLimit {
+    /// Read once at startup as part of `Config`, so a malformed setting stops the
+    /// server before it takes requests.
+    fn from_env() -> Self {
+        FreezeThawLimit {
+            max_cycles: env::var("FREEZE_THAW_MAX_CYCLES")
+                .unwrap_or_else(|_| "5".to_string())
+                .parse()
+                .expect("FREEZE_THAW_MAX_CYCLES must be an integer"),
+            policy: match env::var("FREEZE_THAW_POLICY")
+                .unwrap_or_else(|_| "warn".to_string())
+                .to_lowercase()
+                .as_str()
+            {
+                "warn" => FreezeThawPolicy::Warn,
+                "block" => FreezeThawPolicy::Block,
+                _ => panic!("FREEZE_THAW_POLICY must be warn or block"),
+            },
+        }
+    }
+
+    /// Why an item thawed `cycles` times should not be thawed again, if it should not.
+    pub fn exceeded(&self, cycles: i32) -> Option<String> {
+        (cycles >= self.max_cycles).then(|| {
+            format!(
+                "Thawed {cycles} times; the maximum is {} freeze-thaw cycles",
+                self.max_cycles
+            )
+        })
+    }
+}
+
+/// Where uploaded files such as isolate images are kept.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FileStoreConfig {
+    /// A directory on local disk.
+    Filesystem { root: String },
+    /// An S3 bucket, or any S3-compatible service (e.g. MinIO) set with `AWS_ENDPOINT`.
+    /// Credentials and region come from the usual `AWS_*` variables.
+    S3 { bucket: String },
+    /// Lost on restart; for tests and throwaway instances.
+    Memory,
+}
+
+impl FileStoreConfig {
+    pub fn from_env() -> Self {
+        dotenv().ok();
+        match env::var("FILE_STORE")
+            .unwrap_or_else(|_| "filesystem".to_string())
+            .to_lowercase()
+            .as_str()
+        {
+            "filesystem" => FileStoreConfig::Filesystem {
+                root: env::var("FILE_STORE_PATH").unwrap_or_else(|_| "data/files".to_string()),
+            },
+            "s3" => FileStoreConfig::S3 {
+                bucket: env::var("FILE_STORE_BUCKET")
+                    .expect("FILE_STORE_BUCKET must be set when FILE_STORE is s3"),
+            },
+            "memory" => FileStoreConfig::Memory,
+            _ => panic!("FILE_STORE must be filesystem, s3 or memory"),
+        }
+    }
+}
@@ -0,0 +1,108 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use crudcrate::validation::{Validatable, ValidationError};
+use crudcrate::{CRUDResource, EntityToModels};
+use sea_orm::entity::prelude::*;
+use uuid::Uuid;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+#[sea_orm(table_name = "culture_accessions")]
+#[crudcrate(
+    generate_router,
+    api_struct = "CultureAccession",
+    name_singular = "culture_accession",
+    name_plural = "culture_accessions",
+    description = "Deposits of isolates in public culture collections such as DSMZ and NCCB",
+    no_eq,
+    derive_partial_eq,
+    read::one::transform = crate::culture_accessions::services::with_url,
+    read::many::transform = crate::culture_accessions::services::with_list_urls,
+    create::one::transform = crate::culture_accessions::services::with_url,
+    update::one::transform = crate::culture_accessions::services::with_url
+)]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    #[crudcrate(primary_key, filterable, exclude(update, create), on_create = Uuid::new_v4())]
+    pub id: Uuid,
+    #[crudcrate(sortable, filterable, exclude(update))]
+    pub isolate_id: Uuid,
+    /// Accession number as the collection writes it, e.g. `DSM 12345`. Unique
+    /// within its collection.
+    #[crudcrate(sortable, filterable, fulltext)]
+    pub accession: String,
+    /// Collection acronym, e.g. `DSMZ` or `NCCB`.
+    #[crudcrate(sortable, filterable, fulltext)]
+    pub collection: String,
+    #[crudcrate(sortable, filterable)]
+    pub deposited_on: Option<NaiveDate>,
+    /// Catalogue page with `{accession}` in place of the accession number, e.g.
+    /// `https://www.dsmz.de/collection/catalogue/details/culture/{accession}`.
+    pub url_template: Option<String>,
+    pub notes: Option<String>,
+    #[crudcrate(sortable, filterable, exclude(create, update), on_create = chrono::Utc::now())]
+    pub created_at: DateTime<Utc>,
+    /// Catalogue page of this accession, from `url_template`.
+    #[sea_orm(ignore)]
+    #[crudcrate(non_db_attr, exclude(create, update))]
+    pub url: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "crate::isolates::db::Entity",
+        from = "Column::IsolateId",
+        to = "crate::isolates::db::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Isolate,
+}
+
+impl Related<crate::isolates::db::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Isolate.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+fn check_text(field: &str, value: &str) -> Result<(), ValidationError> {
+    if value.trim().is_empty() {
+        return Err(ValidationError::new(field, "Must not be blank"));
+    }
+    Ok(())
+}
+
+fn check_url_template(template: Option<&str>) -> Result<(), ValidationError> {
+    match template {
+        Some(template)
+            if !(template.starts_with("https://") || template.starts_with("http://"))
+                || !template.contains("{accession}") =>
+        {
+            Err(ValidationError::new(
+                "url_template",
+                "Give an http(s) URL with {accession} in place of the accession number",
+            ))
+        }
+        _ => Ok(()),
+    }
+}
+
+impl Validatable for CultureAccessionCreate {
+    fn validate(&self) -> Result<(), ValidationError> {
+        check_text("accession", &self.accession)?;
+        check_text("collection", &self.collection)?;
+        check_url_template(self.url_template.as_deref())
+    }
+}
+
+impl Validatable for CultureAccessionUpdate {
+    fn validate(&self) -> Result<(), ValidationError> {
+        if let Some(Some(accession)) = &self.accession {
+            check_text("accession", accession)?;
+        }
+        if let Some(Some(collection)) = &self.collection {
+            check_text("collection", collection)?;
+        }
+        check_url_template(self.url_template.clone().flatten().as_deref())
+    }
+}
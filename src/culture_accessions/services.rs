@@ -0,0 +1,66 @@
+use crudcrate::ApiError;
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use sea_orm::sea_query::{Expr, Func, Query};
+use sea_orm::{ColumnTrait, Condition, DatabaseConnection};
+use std::collections::HashMap;
+
+use super::db::{Column, CultureAccession, CultureAccessionList, Entity};
+use crate::isolates;
+
+/// Isolate query parameters that filter on accessions, case-insensitively.
+const FILTERS: [(&str, Column); 2] = [
+    ("accession", Column::Accession),
+    ("collection", Column::Collection),
+];
+
+/// Everything but unreserved URL characters is percent-encoded, so `DSM 12345`
+/// becomes `DSM%2012345`.
+const ENCODED: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+pub fn url(template: Option<&str>, accession: &str) -> Option<String> {
+    template.map(|template| {
+        template.replace(
+            "{accession}",
+            &utf8_percent_encode(accession.trim(), ENCODED).to_string(),
+        )
+    })
+}
+
+pub async fn with_url(
+    _db: &DatabaseConnection,
+    mut accession: CultureAccession,
+) -> Result<CultureAccession, ApiError> {
+    accession.url = url(accession.url_template.as_deref(), &accession.accession);
+    Ok(accession)
+}
+
+pub async fn with_list_urls(
+    _db: &DatabaseConnection,
+    mut accessions: Vec<CultureAccessionList>,
+) -> Result<Vec<CultureAccessionList>, ApiError> {
+    for accession in &mut accessions {
+        accession.url = url(accession.url_template.as_deref(), &accession.accession);
+    }
+    Ok(accessions)
+}
+
+/// Conditions on isolates for `?accession=DSM%2012345` and `?collection=DSMZ`:
+/// the isolate has a matching deposit.
+pub fn isolate_filters(params: &HashMap<String, String>) -> Vec<Condition> {
+    FILTERS
+        .into_iter()
+        .filter_map(|(param, column)| {
+            let value = params.get(param)?.trim().to_lowercase();
+            let deposits = Query::select()
+                .column(Column::IsolateId)
+                .from(Entity)
+                .and_where(Expr::expr(Func::lower(Expr::col(column))).eq(value))
+                .to_owned();
+            Some(Condition::all().add(isolates::db::Column::Id.in_subquery(deposits)))
+        })
+        .collect()
+}
@@ -0,0 +1,234 @@
+use axum::{body::to_bytes, http::StatusCode, Router};
+use serde_json::{json, Value};
+
+use crate::test_utils::{
+    build_app_with_db, build_scoped_app_with_db, create, create_field_record, create_json,
+    create_site, get, names, send, send_raw, setup_sqlite_db,
+};
+
+const DSMZ_TEMPLATE: &str = "https://www.dsmz.de/collection/catalogue/details/culture/{accession}";
+
+/// A public isolate deposited at DSMZ and NCCB and a private one deposited at DSMZ,
+/// returned as (public, private) ids.
+async fn deposited_isolates(app: &Router) -> (String, String) {
+    let site = create_site(app, "Glacier J").await;
+    let fr = create_field_record(app, &site, "FR-9").await;
+    let public = create(
+        app,
+        "/api/isolates",
+        json!({ "name": "ISO-DEPOSITED", "field_record_id": fr, "genus": "Bacillus" }),
+    )
+    .await;
+    let private = create(
+        app,
+        "/api/isolates",
+        json!({ "name": "ISO-EMBARGOED", "field_record_id": fr, "is_private": true }),
+    )
+    .await;
+    for (isolate, accession, collection) in [
+        (&public, "DSM 12345", "DSMZ"),
+        (&public, "NCCB 100200", "NCCB"),
+        (&private, "DSM 99999", "DSMZ"),
+    ] {
+        create(
+            app,
+            "/api/culture_accessions",
+            json!({
+                "isolate_id": isolate,
+                "accession": accession,
+                "collection": collection,
+                "url_template": (collection == "DSMZ").then_some(DSMZ_TEMPLATE)
+            }),
+        )
+        .await;
+    }
+    (public, private)
+}
+
+async fn export(app: &Router, standard: &str) -> String {
+    let resp = send_raw(
+        app,
+        "GET",
+        &format!("/api/isolates/export?standard={standard}"),
+        &[],
+        vec![],
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()["content-type"], "text/csv; charset=utf-8");
+    let body = to_bytes(resp.into_body(), 1024 * 1024).await.unwrap();
+    String::from_utf8(body.to_vec()).unwrap()
+}
+
+/// Scenario: accessions that are blank, or URL templates without a placeholder.
+/// Expected behaviour: both are refused, and public callers cannot deposit.
+#[tokio::test]
+async fn invalid_accessions_are_refused() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db.clone());
+    let scoped = build_scoped_app_with_db(db);
+    let (public, _) = deposited_isolates(&app).await;
+
+    for (payload, problem) in [
+        (
+            json!({ "isolate_id": public, "accession": " ", "collection": "DSMZ" }),
+            "blank accession",
+        ),
+        (
+            json!({ "isolate_id": public, "accession": "DSM 1", "collection": "DSMZ", "url_template": "https://www.dsmz.de/" }),
+            "no placeholder",
+        ),
+    ] {
+        let (status, _) = send(&app, "POST", "/api/culture_accessions", payload).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{problem}");
+    }
+
+    let (status, _) = send(
+        &scoped,
+        "POST",
+        "/api/culture_accessions",
+        json!({ "isolate_id": public, "accession": "DSM 2", "collection": "DSMZ" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+/// Scenario: accessions with and without a URL template, listed by a public caller.
+/// Expected behaviour: the accession is percent-encoded into the catalogue link, and
+/// only the public isolate's deposits are listed.
+#[tokio::test]
+async fn accessions_link_to_the_catalogue_within_scope() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db.clone());
+    let scoped = build_scoped_app_with_db(db);
+    let (public, _) = deposited_isolates(&app).await;
+
+    let deposit = create_json(
+        &app,
+        "/api/culture_accessions",
+        json!({
+            "isolate_id": public,
+            "accession": " DSM 2/b ",
+            "collection": "DSMZ",
+            "deposited_on": "2025-09-01",
+            "url_template": DSMZ_TEMPLATE
+        }),
+    )
+    .await;
+    assert_eq!(
+        deposit["url"],
+        "https://www.dsmz.de/collection/catalogue/details/culture/DSM%202%2Fb"
+    );
+
+    let (_, listed) = get(&scoped, "/api/culture_accessions").await;
+    let mut urls: Vec<&Value> = listed
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|accession| &accession["url"])
+        .collect();
+    urls.sort_by_key(|url| url.to_string());
+    assert_eq!(
+        urls,
+        [
+            &json!("https://www.dsmz.de/collection/catalogue/details/culture/DSM%2012345"),
+            &json!("https://www.dsmz.de/collection/catalogue/details/culture/DSM%202%2Fb"),
+            &Value::Null,
+        ]
+    );
+}
+
+/// Scenario: isolates looked up by accession or collection, and accessions through
+/// `/api/search`, by a public caller.
+/// Expected behaviour: matches regardless of case, without the private isolate's
+/// deposit.
+#[tokio::test]
+async fn isolates_are_found_by_accession_within_scope() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db.clone());
+    let scoped = build_scoped_app_with_db(db);
+    deposited_isolates(&app).await;
+
+    let (_, found) = get(&scoped, "/api/isolates?accession=dsm%2012345").await;
+    assert_eq!(names(&found), ["ISO-DEPOSITED"]);
+    let (_, found) = get(&scoped, "/api/isolates?collection=nccb").await;
+    assert_eq!(names(&found), ["ISO-DEPOSITED"]);
+    let (_, found) = get(&scoped, "/api/isolates?accession=DSM%2099999").await;
+    assert_eq!(found, json!([]));
+
+    let (status, results) = get(&scoped, "/api/search?q=DSM").await;
+    assert_eq!(status, StatusCode::OK);
+    let accessions: Vec<&str> = results["results"]["culture_accessions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|accession| accession["accession"].as_str().unwrap())
+        .collect();
+    assert_eq!(accessions, ["DSM 12345"]);
+}
+
+/// Scenario: an isolate with two deposits is deleted.
+/// Expected behaviour: its deposits go with it and the other isolate's remain.
+#[tokio::test]
+async fn deleting_an_isolate_deletes_its_accessions() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db);
+    let (public, _) = deposited_isolates(&app).await;
+
+    let (status, _) = send(
+        &app,
+        "DELETE",
+        &format!("/api/isolates/{public}"),
+        Value::Null,
+    )
+    .await;
+    assert!(status.is_success());
+    let (_, remaining) = get(&app, "/api/culture_accessions").await;
+    assert_eq!(remaining.as_array().unwrap().len(), 1);
+}
+
+/// Scenario: a public caller exports the isolates in Darwin Core and MIxS terms.
+/// Expected behaviour: one row per public isolate, with its deposits as other catalogue
+/// numbers and source material ids.
+#[tokio::test]
+async fn accessions_are_exported_in_darwin_core_and_mixs() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db.clone());
+    let scoped = build_scoped_app_with_db(db);
+    let (public, _) = deposited_isolates(&app).await;
+
+    let dwc = export(&scoped, "darwin_core").await;
+    let rows: Vec<&str> = dwc.lines().collect();
+    assert_eq!(rows.len(), 2, "{dwc}");
+    assert!(rows[0].starts_with("occurrenceID,catalogNumber,basisOfRecord,scientificName,"));
+    assert!(rows[0].ends_with(",locality,otherCatalogNumbers"));
+    assert_eq!(
+        rows[1],
+        format!(
+            "{public},ISO-DEPOSITED,LivingSpecimen,Bacillus,,,,,,Bacillus,2025-07-10,Snow,\
+             46.1,7,EPSG:4326,2500,Glacier J,DSMZ:DSM 12345 | NCCB:NCCB 100200"
+        )
+    );
+
+    let mixs = export(&scoped, "mixs").await;
+    assert_eq!(
+        mixs,
+        "samp_name,source_mat_id,collection_date,lat_lon,elev,env_medium\r\n\
+         ISO-DEPOSITED,DSMZ:DSM 12345; NCCB:NCCB 100200,2025-07-10,46.1 7,2500,Snow\r\n"
+    );
+
+    let admin = export(&app, "mixs").await;
+    assert!(admin.contains("ISO-EMBARGOED,DSMZ:DSM 99999,"), "{admin}");
+    let filtered = export(&scoped, "mixs&accession=NCCB%20999").await;
+    assert_eq!(filtered.lines().count(), 1, "{filtered}");
+
+    let resp = send_raw(
+        &app,
+        "GET",
+        "/api/isolates/export?standard=abcd",
+        &[],
+        vec![],
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::BAD_REQUEST);
+}
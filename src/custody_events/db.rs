@@ -0,0 +1,119 @@
+use crate::common::enums::CustodyEventKind;
+use chrono::{DateTime, Utc};
+use crudcrate::validation::{Validatable, ValidationError};
+use crudcrate::{CRUDResource, EntityToModels};
+use sea_orm::entity::prelude::*;
+use uuid::Uuid;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+#[sea_orm(table_name = "custody_events")]
+#[crudcrate(
+    generate_router,
+    api_struct = "CustodyEvent",
+    name_singular = "custody_event",
+    name_plural = "custody_events",
+    description = "Append-only chain-of-custody log of who handled a sample, isolate or DNA extract, when and where",
+    no_eq,
+    derive_partial_eq,
+    create::one::pre = crate::custody_events::services::check_create,
+    create::many::pre = crate::custody_events::services::check_create_many,
+    create::one::post = crate::custody_events::services::count_thaw,
+    create::many::post = crate::custody_events::services::count_thaws,
+    update::one::pre = crate::custody_events::services::reject_update,
+    update::many::pre = crate::custody_events::services::reject_update_many,
+    delete::one::pre = crate::custody_events::services::reject_delete,
+    delete::many::pre = crate::custody_events::services::reject_delete_many
+)]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    #[crudcrate(primary_key, filterable, exclude(update, create), on_create = Uuid::new_v4())]
+    pub id: Uuid,
+    /// Exactly one of `sample_id`, `isolate_id` and `dna_id` is set.
+    #[crudcrate(sortable, filterable, exclude(update))]
+    pub sample_id: Option<Uuid>,
+    #[crudcrate(sortable, filterable, exclude(update))]
+    pub isolate_id: Option<Uuid>,
+    #[crudcrate(sortable, filterable, exclude(update))]
+    pub dna_id: Option<Uuid>,
+    #[crudcrate(sortable, filterable, exclude(update))]
+    pub kind: CustodyEventKind,
+    /// Person or courier who handled the item.
+    #[crudcrate(sortable, filterable, fulltext, exclude(update))]
+    pub actor: String,
+    /// Where the item was after the event, e.g. a freezer path or a lab.
+    #[crudcrate(sortable, filterable, fulltext, exclude(update))]
+    pub location: String,
+    /// When it happened; defaults to now, and may be earlier for events logged late.
+    #[crudcrate(sortable, filterable, exclude(update), on_create = chrono::Utc::now())]
+    pub occurred_at: DateTime<Utc>,
+    #[crudcrate(sortable, filterable, exclude(create, update), on_create = chrono::Utc::now())]
+    pub recorded_at: DateTime<Utc>,
+    #[crudcrate(fulltext, exclude(update))]
+    pub note: Option<String>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "crate::samples::db::Entity",
+        from = "Column::SampleId",
+        to = "crate::samples::db::Column::Id"
+    )]
+    Sample,
+    #[sea_orm(
+        belongs_to = "crate::isolates::db::Entity",
+        from = "Column::IsolateId",
+        to = "crate::isolates::db::Column::Id"
+    )]
+    Isolate,
+    #[sea_orm(
+        belongs_to = "crate::dna::db::Entity",
+        from = "Column::DnaId",
+        to = "crate::dna::db::Column::Id"
+    )]
+    Dna,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// Tolerance for devices whose clocks run ahead of the server's.
+const CLOCK_SKEW_MINUTES: i64 = 5;
+
+fn validate_required(field: &str, value: &str) -> Result<(), ValidationError> {
+    if value.trim().is_empty() {
+        return Err(ValidationError::new(field, "Must not be blank"));
+    }
+    Ok(())
+}
+
+impl Validatable for CustodyEventCreate {
+    fn validate(&self) -> Result<(), ValidationError> {
+        let items = [self.sample_id, self.isolate_id, self.dna_id]
+            .iter()
+            .filter(|id| id.is_some())
+            .count();
+        if items != 1 {
+            return Err(ValidationError::new(
+                "sample_id",
+                "Record the event for exactly one sample, isolate or DNA extract",
+            ));
+        }
+        if self
+            .occurred_at
+            .is_some_and(|at| at > Utc::now() + chrono::Duration::minutes(CLOCK_SKEW_MINUTES))
+        {
+            return Err(ValidationError::new(
+                "occurred_at",
+                "Must not be in the future",
+            ));
+        }
+        validate_required("actor", &self.actor)?;
+        validate_required("location", &self.location)
+    }
+}
+
+impl Validatable for CustodyEventUpdate {
+    fn validate(&self) -> Result<(), ValidationError> {
+        Ok(())
+    }
+}
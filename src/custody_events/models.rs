@@ -0,0 +1,42 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use super::db::CustodyEvent;
+use crate::common::enums::CustodyEventKind;
+
+#[derive(Deserialize, IntoParams, Debug)]
+pub struct TimelineParams {
+    pub sample_id: Option<Uuid>,
+    pub isolate_id: Option<Uuid>,
+    pub dna_id: Option<Uuid>,
+}
+
+/// An event that cannot follow the ones before it, such as a thaw of a tube that was
+/// never stored or anything after its destruction.
+#[derive(ToSchema, Serialize, Debug, Clone)]
+pub struct CustodyIssue {
+    pub event_id: Uuid,
+    pub kind: CustodyEventKind,
+    pub occurred_at: DateTime<Utc>,
+    pub message: String,
+}
+
+/// Custody history of one item, oldest first.
+#[derive(ToSchema, Serialize, Debug, Clone)]
+pub struct Timeline {
+    pub events: Vec<CustodyEvent>,
+    /// Location recorded by the latest event.
+    pub location: Option<String>,
+    pub issues: Vec<CustodyIssue>,
+}
+
+/// An item whose timeline has at least one impossible event.
+#[derive(ToSchema, Serialize, Debug, Clone)]
+pub struct ItemIssues {
+    pub sample_id: Option<Uuid>,
+    pub isolate_id: Option<Uuid>,
+    pub dna_id: Option<Uuid>,
+    pub issues: Vec<CustodyIssue>,
+}
@@ -0,0 +1,198 @@
+use axum::http::StatusCode;
+use crudcrate::validation::ValidationError;
+use crudcrate::ApiError;
+use sea_orm::sea_query::Expr;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use uuid::Uuid;
+
+use super::db::{CustodyEvent, CustodyEventCreate, CustodyEventUpdate, Model};
+use super::models::CustodyIssue;
+use crate::common::enums::CustodyEventKind;
+use crate::{dna, isolates, samples};
+
+/// Where an item is between events, as far as the log can tell.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Custody {
+    /// No event recorded yet.
+    Unrecorded,
+    InHand,
+    /// In a freezer, between `Stored` and `Thawed`.
+    Frozen,
+    InTransit,
+    Destroyed,
+}
+
+/// State after `kind`, or why `kind` cannot follow `state`. The state after an
+/// impossible event is still the one the event implies, so a single bad entry is
+/// flagged once rather than throwing off the rest of the timeline.
+fn step(state: Custody, kind: CustodyEventKind) -> (Custody, Option<&'static str>) {
+    use Custody::*;
+    use CustodyEventKind as Kind;
+
+    if state == Destroyed {
+        return (Destroyed, Some("after the item was destroyed"));
+    }
+    match kind {
+        Kind::Collected => (
+            InHand,
+            (state != Unrecorded).then_some("after the item was already in custody"),
+        ),
+        Kind::Received => (
+            InHand,
+            (state == Frozen).then_some("while the item was stored in a freezer"),
+        ),
+        Kind::Stored => (
+            Frozen,
+            match state {
+                Frozen => Some("while the item was already stored; record a move instead"),
+                InTransit => Some("while the item was in transit; record its receipt first"),
+                _ => None,
+            },
+        ),
+        Kind::Moved => match state {
+            Unrecorded => (InHand, Some("before the item was collected or received")),
+            InTransit => (InTransit, Some("while the item was in transit")),
+            _ => (state, None),
+        },
+        Kind::Thawed => (
+            InHand,
+            (state != Frozen).then_some("while the item was not stored frozen"),
+        ),
+        Kind::Shipped => (
+            InTransit,
+            match state {
+                Unrecorded => Some("before the item was collected or received"),
+                InTransit => Some("while the item was already in transit"),
+                _ => None,
+            },
+        ),
+        Kind::Destroyed => (Destroyed, None),
+    }
+}
+
+/// Events of one item that cannot follow the events before them. `events` must be
+/// in the order they happened.
+pub fn check_sequence(events: &[Model]) -> Vec<CustodyIssue> {
+    let mut state = Custody::Unrecorded;
+    let mut issues = vec![];
+    for event in events {
+        let (next, problem) = step(state, event.kind);
+        if let Some(problem) = problem {
+            issues.push(CustodyIssue {
+                event_id: event.id,
+                kind: event.kind,
+                occurred_at: event.occurred_at,
+                message: format!("{} {problem}", event.kind),
+            });
+        }
+        state = next;
+    }
+    issues
+}
+
+/// The item must exist. Impossible sequences are accepted and flagged by the check
+/// endpoints instead: the log records what people report, and events logged late
+/// can fill gaps afterwards.
+pub async fn check_create(
+    db: &DatabaseConnection,
+    data: &CustodyEventCreate,
+) -> Result<(), ApiError> {
+    let (field, exists) = match (data.sample_id, data.isolate_id, data.dna_id) {
+        (Some(id), _, _) => (
+            "sample_id",
+            samples::db::Entity::find_by_id(id).one(db).await?.is_some(),
+        ),
+        (None, Some(id), _) => (
+            "isolate_id",
+            isolates::db::Entity::find_by_id(id)
+                .one(db)
+                .await?
+                .is_some(),
+        ),
+        (None, None, Some(id)) => (
+            "dna_id",
+            dna::db::Entity::find_by_id(id).one(db).await?.is_some(),
+        ),
+        (None, None, None) => return Ok(()),
+    };
+    if !exists {
+        return Err(ValidationError::new(field, "Unknown item").into());
+    }
+    Ok(())
+}
+
+pub async fn check_create_many(
+    db: &DatabaseConnection,
+    data: &[CustodyEventCreate],
+) -> Result<(), ApiError> {
+    for item in data {
+        check_create(db, item).await?;
+    }
+    Ok(())
+}
+
+/// Each `Thawed` event adds a freeze-thaw cycle to its item, including events
+/// logged late for thaws before the log existed.
+pub async fn count_thaw(db: &DatabaseConnection, event: &CustodyEvent) -> Result<(), ApiError> {
+    if event.kind != CustodyEventKind::Thawed {
+        return Ok(());
+    }
+    macro_rules! increment {
+        ($module:ident, $id:expr) => {{
+            use $module::db::{Column, Entity};
+            Entity::update_many()
+                .col_expr(
+                    Column::FreezeThawCycles,
+                    Expr::col(Column::FreezeThawCycles).add(1),
+                )
+                .filter(Column::Id.eq($id))
+                .exec(db)
+                .await?;
+        }};
+    }
+    match (event.sample_id, event.isolate_id, event.dna_id) {
+        (Some(id), _, _) => increment!(samples, id),
+        (None, Some(id), _) => increment!(isolates, id),
+        (None, None, Some(id)) => increment!(dna, id),
+        (None, None, None) => {}
+    }
+    Ok(())
+}
+
+pub async fn count_thaws(db: &DatabaseConnection, events: &[CustodyEvent]) -> Result<(), ApiError> {
+    for event in events {
+        count_thaw(db, event).await?;
+    }
+    Ok(())
+}
+
+fn append_only() -> ApiError {
+    ApiError::custom(
+        StatusCode::METHOD_NOT_ALLOWED,
+        "The custody log is append-only; record a new event instead",
+        None,
+    )
+}
+
+pub async fn reject_update(
+    _db: &DatabaseConnection,
+    _id: Uuid,
+    _data: &CustodyEventUpdate,
+) -> Result<(), ApiError> {
+    Err(append_only())
+}
+
+pub async fn reject_update_many(
+    _db: &DatabaseConnection,
+    _updates: &[(Uuid, CustodyEventUpdate)],
+) -> Result<(), ApiError> {
+    Err(append_only())
+}
+
+pub async fn reject_delete(_db: &DatabaseConnection, _id: Uuid) -> Result<(), ApiError> {
+    Err(append_only())
+}
+
+pub async fn reject_delete_many(_db: &DatabaseConnection, _ids: &[Uuid]) -> Result<(), ApiError> {
+    Err(append_only())
+}
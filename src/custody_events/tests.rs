@@ -0,0 +1,202 @@
+use axum::http::StatusCode;
+use serde_json::{json, Value};
+
+use crate::test_utils::{
+    build_app_with_db, build_scoped_app_with_db, create, create_field_record, create_site, get,
+    send, setup_sqlite_db,
+};
+
+fn event(item: (&str, &str), kind: &str, location: &str, occurred_at: &str) -> Value {
+    json!({
+        item.0: item.1, "kind": kind, "actor": "A. Keller",
+        "location": location, "occurred_at": occurred_at
+    })
+}
+
+/// A sample and a DNA extract on one field record.
+async fn sample_and_extract(app: &axum::Router) -> (String, String) {
+    let site_id = create_site(app, "Glacier A").await;
+    let fr = create_field_record(app, &site_id, "FR-1").await;
+    let sample = create(
+        app,
+        "/api/samples",
+        json!({ "name": "S-1", "field_record_id": fr }),
+    )
+    .await;
+    let extract = create(
+        app,
+        "/api/dna",
+        json!({ "name": "DNA-1", "field_record_id": fr }),
+    )
+    .await;
+    (sample, extract)
+}
+
+/// Logs a sample's life from collection to destruction, out of order on purpose.
+async fn log_lifecycle(app: &axum::Router, sample: &str) {
+    let item = ("sample_id", sample);
+    for payload in [
+        event(item, "Stored", "Freezer B / Rack 2", "2025-07-11T12:00:00Z"),
+        event(item, "Collected", "Glacier A", "2025-07-10T08:00:00Z"),
+        event(item, "Received", "Lab 3", "2025-07-11T09:00:00Z"),
+        event(item, "Thawed", "Lab 3", "2025-07-20T10:00:00Z"),
+        event(item, "Destroyed", "Lab 3", "2025-07-21T10:00:00Z"),
+    ] {
+        create(app, "/api/custody_events", payload).await;
+    }
+}
+
+async fn timeline(app: &axum::Router, sample: &str) -> Value {
+    let (status, timeline) = get(
+        app,
+        &format!("/api/custody_events/timeline?sample_id={sample}"),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{timeline}");
+    timeline
+}
+
+/// Scenario: a sample is collected, received, stored, thawed and destroyed, with
+/// the events logged out of order.
+/// Expected behaviour: the timeline comes back in order with the latest location
+/// and no issues.
+#[tokio::test]
+async fn timeline_is_ordered_by_occurrence() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db);
+    let (sample, _) = sample_and_extract(&app).await;
+    log_lifecycle(&app, &sample).await;
+
+    let timeline = timeline(&app, &sample).await;
+    let kinds: Vec<&str> = timeline["events"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| e["kind"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        kinds,
+        ["Collected", "Received", "Stored", "Thawed", "Destroyed"]
+    );
+    assert_eq!(timeline["location"], "Lab 3");
+    assert_eq!(timeline["issues"].as_array().unwrap().len(), 0);
+}
+
+/// Scenario: someone logs a move of the destroyed tube; a DNA extract is thawed
+/// without ever having been stored.
+/// Expected behaviour: exactly those two events are flagged, on the timeline and
+/// by the check across all items.
+#[tokio::test]
+async fn impossible_events_are_flagged() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db);
+    let (sample, extract) = sample_and_extract(&app).await;
+    log_lifecycle(&app, &sample).await;
+
+    let moved = create(
+        &app,
+        "/api/custody_events",
+        event(
+            ("sample_id", &sample),
+            "Moved",
+            "Freezer C",
+            "2025-07-22T10:00:00Z",
+        ),
+    )
+    .await;
+    create(
+        &app,
+        "/api/custody_events",
+        event(
+            ("dna_id", &extract),
+            "Thawed",
+            "Lab 3",
+            "2025-07-15T10:00:00Z",
+        ),
+    )
+    .await;
+
+    let timeline = timeline(&app, &sample).await;
+    assert_eq!(timeline["location"], "Freezer C");
+    assert_eq!(timeline["issues"][0]["event_id"], moved);
+    assert_eq!(
+        timeline["issues"][0]["message"],
+        "Moved after the item was destroyed"
+    );
+    let (status, flagged) = get(&app, "/api/custody_events/check").await;
+    assert_eq!(status, StatusCode::OK, "{flagged}");
+    let flagged = flagged.as_array().unwrap();
+    assert_eq!(flagged.len(), 2);
+    assert!(flagged.iter().any(|item| item["dna_id"] == extract));
+}
+
+#[tokio::test]
+async fn logged_events_cannot_be_edited_or_deleted() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db);
+    let (sample, _) = sample_and_extract(&app).await;
+    let logged = create(
+        &app,
+        "/api/custody_events",
+        event(
+            ("sample_id", &sample),
+            "Collected",
+            "Glacier A",
+            "2025-07-10T08:00:00Z",
+        ),
+    )
+    .await;
+
+    let (status, _) = send(
+        &app,
+        "PUT",
+        &format!("/api/custody_events/{logged}"),
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::METHOD_NOT_ALLOWED);
+    let (status, _) = send(
+        &app,
+        "DELETE",
+        &format!("/api/custody_events/{logged}"),
+        Value::Null,
+    )
+    .await;
+    assert_eq!(status, StatusCode::METHOD_NOT_ALLOWED);
+}
+
+/// Expected behaviour: an event names exactly one item, a location and a time that
+/// is not in the future; a timeline needs an item; the log is not public.
+#[tokio::test]
+async fn malformed_events_and_public_callers_are_refused() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db.clone());
+    let scoped = build_scoped_app_with_db(db);
+    let (sample, extract) = sample_and_extract(&app).await;
+
+    for payload in [
+        json!({
+            "sample_id": sample, "dna_id": extract, "kind": "Moved",
+            "actor": "A. Keller", "location": "Lab 3"
+        }),
+        json!({ "sample_id": sample, "kind": "Moved", "actor": "A. Keller", "location": " " }),
+        event(
+            ("sample_id", &sample),
+            "Moved",
+            "Lab 3",
+            "2999-01-01T00:00:00Z",
+        ),
+    ] {
+        let (status, body) = send(&app, "POST", "/api/custody_events", payload).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{body}");
+    }
+
+    let (status, _) = get(&app, "/api/custody_events/timeline").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = get(
+        &scoped,
+        &format!("/api/custody_events/timeline?sample_id={sample}"),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
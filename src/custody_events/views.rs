@@ -0,0 +1,114 @@
+use axum::extract::{Query, State};
+use axum::{routing::get, Json, Router};
+use crudcrate::ApiError;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+use std::collections::BTreeMap;
+
+use super::db::{Column, CustodyEvent, Entity, Model};
+use super::models::{ItemIssues, Timeline, TimelineParams};
+use super::services::check_sequence;
+use crate::{dna, isolates, samples};
+
+/// Routes mounted next to the generated CRUD router under `/api/custody_events`.
+pub fn router(db: &DatabaseConnection) -> Router {
+    Router::new()
+        .route("/timeline", get(get_timeline))
+        .route("/check", get(get_check))
+        .with_state(db.clone())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/custody_events/timeline",
+    params(TimelineParams),
+    responses(
+        (status = OK, description = "Custody events of the item, oldest first, with impossible ones flagged", body = Timeline),
+        (status = BAD_REQUEST, description = "Not exactly one of sample_id, isolate_id and dna_id given"),
+        (status = NOT_FOUND, description = "Item not found")
+    )
+)]
+pub async fn get_timeline(
+    State(db): State<DatabaseConnection>,
+    Query(params): Query<TimelineParams>,
+) -> Result<Json<Timeline>, ApiError> {
+    let condition = match (params.sample_id, params.isolate_id, params.dna_id) {
+        (Some(id), None, None) => {
+            samples::db::Entity::find_by_id(id)
+                .one(&db)
+                .await?
+                .ok_or_else(|| ApiError::not_found("sample", Some(id.to_string())))?;
+            Column::SampleId.eq(id)
+        }
+        (None, Some(id), None) => {
+            isolates::db::Entity::find_by_id(id)
+                .one(&db)
+                .await?
+                .ok_or_else(|| ApiError::not_found("isolate", Some(id.to_string())))?;
+            Column::IsolateId.eq(id)
+        }
+        (None, None, Some(id)) => {
+            dna::db::Entity::find_by_id(id)
+                .one(&db)
+                .await?
+                .ok_or_else(|| ApiError::not_found("dna", Some(id.to_string())))?;
+            Column::DnaId.eq(id)
+        }
+        _ => {
+            return Err(ApiError::bad_request(
+                "Pass exactly one of sample_id, isolate_id and dna_id",
+            ))
+        }
+    };
+
+    let events = Entity::find()
+        .filter(condition)
+        .order_by_asc(Column::OccurredAt)
+        .order_by_asc(Column::RecordedAt)
+        .all(&db)
+        .await?;
+    let issues = check_sequence(&events);
+    Ok(Json(Timeline {
+        location: events.last().map(|event| event.location.clone()),
+        events: events.into_iter().map(CustodyEvent::from).collect(),
+        issues,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/custody_events/check",
+    responses(
+        (status = OK, description = "Items whose custody timeline contains impossible events", body = Vec<ItemIssues>)
+    )
+)]
+pub async fn get_check(
+    State(db): State<DatabaseConnection>,
+) -> Result<Json<Vec<ItemIssues>>, ApiError> {
+    let mut timelines: BTreeMap<_, Vec<Model>> = BTreeMap::new();
+    for event in Entity::find()
+        .order_by_asc(Column::OccurredAt)
+        .order_by_asc(Column::RecordedAt)
+        .all(&db)
+        .await?
+    {
+        timelines
+            .entry((event.sample_id, event.isolate_id, event.dna_id))
+            .or_default()
+            .push(event);
+    }
+
+    Ok(Json(
+        timelines
+            .into_iter()
+            .filter_map(|((sample_id, isolate_id, dna_id), events)| {
+                let issues = check_sequence(&events);
+                (!issues.is_empty()).then_some(ItemIssues {
+                    sample_id,
+                    isolate_id,
+                    dna_id,
+                    issues,
+                })
+            })
+            .collect(),
+    ))
+}
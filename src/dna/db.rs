@@ -12,7 +12,9 @@ use uuid::Uuid;
     name_plural = "dna",
     description = "DNA extraction and analysis records for biological samples",
     no_eq,
-    derive_partial_eq
+    derive_partial_eq,
+    update::one::pre = crate::dna::services::check_update,
+    update::many::pre = crate::dna::services::check_update_many
 )]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
@@ -33,6 +35,9 @@ pub struct Model {
     pub volume: Option<f64>,
     #[crudcrate(sortable, filterable)]
     pub concentration: Option<f64>,
+    /// Times thawed, counted from `Thawed` custody events.
+    #[crudcrate(sortable, filterable, exclude(create, update), on_create = 0)]
+    pub freeze_thaw_cycles: i32,
     #[crudcrate(sortable, filterable, exclude(create, update), on_create = chrono::Utc::now())]
     pub created_at: DateTime<Utc>,
 }
@@ -1,3 +1,4 @@
 pub mod db;
+pub mod services;
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,44 @@
+use crudcrate::validation::ValidationError;
+use crudcrate::ApiError;
+use sea_orm::{DatabaseConnection, EntityTrait};
+use uuid::Uuid;
+
+use super::db::{DNAUpdate, Entity};
+use crate::withdrawals;
+
+/// The recorded volume must keep covering what has been withdrawn from the extract.
+pub async fn check_update(
+    db: &DatabaseConnection,
+    id: Uuid,
+    data: &DNAUpdate,
+) -> Result<(), ApiError> {
+    let Some(volume) = &data.volume else {
+        return Ok(());
+    };
+    let existing = Entity::find_by_id(id)
+        .one(db)
+        .await?
+        .ok_or_else(|| ApiError::not_found("dna", Some(id.to_string())))?;
+    if *volume == existing.volume {
+        return Ok(());
+    }
+    let withdrawn = withdrawals::services::withdrawn_from_dna(db, id).await?;
+    if withdrawn > 0.0 && volume.is_none_or(|volume| volume < withdrawn) {
+        return Err(ValidationError::new(
+            "volume",
+            format!("Must cover the {withdrawn} already withdrawn"),
+        )
+        .into());
+    }
+    Ok(())
+}
+
+pub async fn check_update_many(
+    db: &DatabaseConnection,
+    updates: &[(Uuid, DNAUpdate)],
+) -> Result<(), ApiError> {
+    for (id, data) in updates {
+        check_update(db, *id, data).await?;
+    }
+    Ok(())
+}
@@ -5,7 +5,7 @@ use axum::{
 use serde_json::json;
 use tower::ServiceExt;
 
-use crate::test_utils::{build_app_with_db, setup_clean_db, setup_sqlite_db};
+use crate::test_utils::{build_app_with_db, image_upload, setup_clean_db, setup_sqlite_db, test_png};
 
 async fn post_json(
     app: &axum::Router,
@@ -130,7 +130,7 @@ async fn create_and_get_sample() {
 }
 
 #[tokio::test]
-async fn create_isolate_without_photo() {
+async fn create_isolate_without_images() {
     let db = setup_sqlite_db().await;
     let app = build_app_with_db(db);
 
@@ -156,11 +156,12 @@ async fn create_isolate_without_photo() {
     assert_eq!(status, StatusCode::OK);
     assert_eq!(isolate["name"].as_str().unwrap(), "Isolate-001");
     assert_eq!(isolate["taxonomy"].as_str().unwrap(), "Pseudomonas");
-    assert!(isolate["photo"].is_null() || isolate["photo"].as_str() == Some(""));
+    assert_eq!(isolate["image_count"], 0);
+    assert!(isolate["thumbnails"].is_null());
 }
 
 #[tokio::test]
-async fn create_isolate_with_photo() {
+async fn create_isolate_with_image() {
     let db = setup_sqlite_db().await;
     let app = build_app_with_db(db);
 
@@ -172,21 +173,43 @@ async fn create_isolate_with_photo() {
         "/api/isolates",
         json!({
             "field_record_id": fr_id,
-            "name": "Isolate-WithPhoto",
-            "taxonomy": "Bacillus",
-            "photo": "data:image/png;base64,iVBOR..."
+            "name": "Isolate-WithImage",
+            "taxonomy": "Bacillus"
         }),
     )
     .await;
     assert_eq!(status, StatusCode::CREATED);
 
     let isolate_id = isolate["id"].as_str().unwrap();
+    let png = test_png();
+    let (content_type, body) = image_upload(&png, &[("caption", "Colonies after 5 days")]);
+    let req = Request::builder()
+        .method("POST")
+        .uri(format!("/api/isolates/{isolate_id}/images"))
+        .header("Content-Type", content_type)
+        .body(Body::from(body))
+        .unwrap();
+    let resp = app.clone().oneshot(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    let image: serde_json::Value =
+        serde_json::from_slice(&to_bytes(resp.into_body(), 1024 * 1024).await.unwrap()).unwrap();
+    assert_eq!(image["caption"], "Colonies after 5 days");
+    assert_eq!(image["is_primary"], true);
+
     let (status, isolate) = get_one(&app, &format!("/api/isolates/{isolate_id}")).await;
     assert_eq!(status, StatusCode::OK);
-    assert_eq!(
-        isolate["photo"].as_str().unwrap(),
-        "data:image/png;base64,iVBOR..."
-    );
+    assert_eq!(isolate["image_count"], 1);
+    assert!(isolate.get("photo").is_none(), "no data URI in the row");
+
+    let req = Request::builder()
+        .uri(image["file_url"].as_str().unwrap())
+        .body(Body::empty())
+        .unwrap();
+    let resp = app.clone().oneshot(req).await.unwrap();
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()["content-type"], "image/png");
+    let bytes = to_bytes(resp.into_body(), 1024 * 1024).await.unwrap();
+    assert_eq!(bytes, png);
 }
 
 #[tokio::test]
@@ -1,4 +1,3 @@
-use crate::common::enums::SampleType;
 use chrono::{DateTime, NaiveDate, Utc};
 use crudcrate::{CRUDResource, EntityToModels};
 use sea_orm::entity::prelude::*;
@@ -13,7 +12,11 @@ use uuid::Uuid;
     name_plural = "field_records",
     description = "Field record sampling points with detailed environmental and chemical data",
     no_eq,
-    derive_partial_eq
+    derive_partial_eq,
+    create::one::pre = crate::field_records::services::check_create,
+    create::many::pre = crate::field_records::services::check_create_many,
+    update::one::pre = crate::field_records::services::check_update,
+    update::many::pre = crate::field_records::services::check_update_many
 )]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
@@ -26,16 +29,19 @@ pub struct Model {
     #[crudcrate(sortable, filterable, fulltext)]
     pub name: String,
 
+    /// Name of an entry in the `sample_types` catalogue.
     #[sea_orm(column_name = "sample_type")]
     #[crudcrate(sortable, filterable)]
-    pub sample_type: SampleType,
+    pub sample_type: String,
 
     #[crudcrate(sortable, filterable)]
     pub sampling_date: NaiveDate,
     #[crudcrate(sortable, filterable, fulltext)]
     pub treatment: Option<String>,
-    #[crudcrate(sortable, filterable, fulltext)]
-    pub campaign: Option<String>,
+    #[crudcrate(sortable, filterable)]
+    pub campaign_id: Option<Uuid>,
+    #[crudcrate(sortable, filterable)]
+    pub replicate_group_id: Option<Uuid>,
     #[crudcrate(sortable, filterable)]
     pub sample_depth_cm: Option<f64>,
     #[crudcrate(sortable, filterable)]
@@ -119,6 +125,9 @@ pub struct Model {
     #[sea_orm(ignore)]
     #[crudcrate(non_db_attr, join(one, all, depth = 1))]
     pub dna: Vec<crate::dna::db::DNA>,
+    #[sea_orm(ignore)]
+    #[crudcrate(non_db_attr, join(one, all, depth = 1))]
+    pub parameter_values: Vec<crate::parameter_values::db::ParameterValue>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -129,12 +138,26 @@ pub enum Relation {
         to = "crate::sites::db::Column::Id"
     )]
     Site,
+    #[sea_orm(
+        belongs_to = "crate::campaigns::db::Entity",
+        from = "Column::CampaignId",
+        to = "crate::campaigns::db::Column::Id"
+    )]
+    Campaign,
+    #[sea_orm(
+        belongs_to = "crate::replicate_groups::db::Entity",
+        from = "Column::ReplicateGroupId",
+        to = "crate::replicate_groups::db::Column::Id"
+    )]
+    ReplicateGroup,
     #[sea_orm(has_many = "crate::dna::db::Entity")]
-    DNA,
+    Dna,
     #[sea_orm(has_many = "crate::samples::db::Entity")]
     Samples,
     #[sea_orm(has_many = "crate::isolates::db::Entity")]
     Isolates,
+    #[sea_orm(has_many = "crate::parameter_values::db::Entity")]
+    ParameterValues,
 }
 
 impl Related<crate::sites::db::Entity> for Entity {
@@ -143,9 +166,21 @@ impl Related<crate::sites::db::Entity> for Entity {
     }
 }
 
+impl Related<crate::campaigns::db::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Campaign.def()
+    }
+}
+
+impl Related<crate::replicate_groups::db::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ReplicateGroup.def()
+    }
+}
+
 impl Related<crate::dna::db::Entity> for Entity {
     fn to() -> RelationDef {
-        Relation::DNA.def()
+        Relation::Dna.def()
     }
 }
 
@@ -161,4 +196,10 @@ impl Related<crate::isolates::db::Entity> for Entity {
     }
 }
 
+impl Related<crate::parameter_values::db::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ParameterValues.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
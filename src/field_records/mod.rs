@@ -1,3 +1,5 @@
 pub mod db;
+pub mod services;
 #[cfg(test)]
 mod tests;
+pub mod views;
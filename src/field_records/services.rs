@@ -0,0 +1,291 @@
+use crudcrate::traits::MergeIntoActiveModel;
+use crudcrate::validation::{validators::validate_range, ValidationError};
+use crudcrate::ApiError;
+use sea_orm::entity::prelude::*;
+use sea_orm::sea_query::{NullOrdering, Query, SimpleExpr};
+use sea_orm::{
+    Condition, DatabaseConnection, IntoActiveModel, Iterable, Order, QueryOrder, QuerySelect,
+    TryIntoModel,
+};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use super::db::{
+    ActiveModel, Column, Entity, FieldRecordCreate, FieldRecordList, FieldRecordUpdate, Model,
+};
+use crate::{
+    measurement_thresholds, parameter_values, parameters, replicate_groups,
+    sample_type_measurements, sample_types,
+};
+
+/// The record's value of parameter `name` in `value_column`, as a correlated
+/// subquery on `parameter_values`.
+fn parameter_value(name: &str, value_column: parameter_values::db::Column) -> SimpleExpr {
+    use parameter_values::db::{Column as ValueColumn, Entity as ValueEntity};
+    use parameters::db::{Column as ParameterColumn, Entity as ParameterEntity};
+
+    SimpleExpr::SubQuery(
+        None,
+        Box::new(
+            Query::select()
+                .column((ValueEntity, value_column))
+                .from(ValueEntity)
+                .inner_join(
+                    ParameterEntity,
+                    Expr::col((ParameterEntity, ParameterColumn::Id))
+                        .equals((ValueEntity, ValueColumn::ParameterId)),
+                )
+                .and_where(
+                    Expr::col((ValueEntity, ValueColumn::FieldRecordId))
+                        .equals((Entity, Column::Id)),
+                )
+                .and_where(Expr::col((ParameterEntity, ParameterColumn::Name)).eq(name))
+                .to_owned()
+                .into_sub_query_statement(),
+        ),
+    )
+}
+
+/// Field records matching `condition`, ordered by their value of custom parameter
+/// `name`, numeric or text, with the records that have none last.
+pub async fn sorted_by_parameter(
+    db: &DatabaseConnection,
+    condition: Condition,
+    name: &str,
+    order: Order,
+    offset: u64,
+    limit: u64,
+) -> Result<Vec<FieldRecordList>, ApiError> {
+    let models = Entity::find()
+        .filter(condition)
+        .order_by_with_nulls(
+            parameter_value(name, parameter_values::db::Column::ValueNumber),
+            order.clone(),
+            NullOrdering::Last,
+        )
+        .order_by_with_nulls(
+            parameter_value(name, parameter_values::db::Column::ValueText),
+            order,
+            NullOrdering::Last,
+        )
+        .order_by_asc(Column::Name)
+        .offset(offset)
+        .limit(limit)
+        .all(db)
+        .await?;
+    Ok(models.into_iter().map(Into::into).collect())
+}
+
+/// Built-in plausibility rule for one measurement column. The bounds are defaults
+/// that a `measurement_thresholds` row for the same column replaces; the habitats a
+/// measurement applies to are kept in `sample_type_measurements`.
+pub struct MeasurementRule {
+    pub column: Column,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+}
+
+const fn rule(column: Column, min: Option<f64>, max: Option<f64>) -> MeasurementRule {
+    MeasurementRule { column, min, max }
+}
+
+const fn non_negative(column: Column) -> MeasurementRule {
+    rule(column, Some(0.0), None)
+}
+
+pub const MEASUREMENT_RULES: &[MeasurementRule] = &[
+    non_negative(Column::SampleDepthCm),
+    non_negative(Column::SnowDepthCm),
+    rule(Column::AirTemperatureCelsius, Some(-90.0), Some(60.0)),
+    rule(Column::SnowTemperatureCelsius, Some(-90.0), Some(0.0)),
+    rule(Column::SoilTemperatureCelsius, Some(-60.0), Some(70.0)),
+    rule(
+        Column::PhotosyntheticActiveRadiation,
+        Some(0.0),
+        Some(3000.0),
+    ),
+    non_negative(Column::FlowCytometryCellNumber),
+    non_negative(Column::CfuCountR2a),
+    non_negative(Column::CfuCountAnother),
+    non_negative(Column::WaterContent),
+    rule(Column::Ph, Some(0.0), Some(14.0)),
+    non_negative(Column::TotalCarbon),
+    non_negative(Column::TotalOrganicCarbon),
+    non_negative(Column::TotalNitrogen),
+    non_negative(Column::IonsFluoride),
+    non_negative(Column::IonsChloride),
+    non_negative(Column::IonsNitrite),
+    non_negative(Column::IonsNitrate),
+    non_negative(Column::IonsBromide),
+    non_negative(Column::IonsSulfate),
+    non_negative(Column::IonsPhosphate),
+    non_negative(Column::IonsSodium),
+    non_negative(Column::IonsAmmonium),
+    non_negative(Column::IonsPotassium),
+    non_negative(Column::IonsMagnesium),
+    non_negative(Column::IonsCalcium),
+    non_negative(Column::OrganicAcidsFormate),
+    non_negative(Column::OrganicAcidsMalate),
+    non_negative(Column::OrganicAcidsPropionate),
+    non_negative(Column::OrganicAcidsCitrate),
+    non_negative(Column::OrganicAcidsLactate),
+    non_negative(Column::OrganicAcidsButyrate),
+    non_negative(Column::OrganicAcidsOxalate),
+    non_negative(Column::OrganicAcidsAcetate),
+];
+
+pub fn measurement_rule(column_name: &str) -> Option<&'static MeasurementRule> {
+    MEASUREMENT_RULES
+        .iter()
+        .find(|rule| rule.column.as_str() == column_name)
+}
+
+pub(crate) fn as_f64(value: Value) -> Option<f64> {
+    match value {
+        Value::Double(v) => v,
+        Value::Float(v) => v.map(f64::from),
+        Value::Int(v) => v.map(f64::from),
+        Value::BigInt(v) => v.map(|v| v as f64),
+        _ => None,
+    }
+}
+
+/// Admin-managed parts of the measurement checks, loaded once per request.
+struct MeasurementChecks {
+    /// Overrides keyed by column name, as `(min, max)`.
+    thresholds: HashMap<String, (Option<f64>, Option<f64>)>,
+    /// Sample types each reserved column is limited to.
+    habitats: HashMap<String, Vec<String>>,
+}
+
+async fn load_checks(db: &DatabaseConnection) -> Result<MeasurementChecks, ApiError> {
+    let thresholds = measurement_thresholds::db::Entity::find()
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|t| (t.column_name, (t.min_value, t.max_value)))
+        .collect();
+    let mut habitats: HashMap<String, Vec<String>> = HashMap::new();
+    for (reserved, sample_type) in sample_type_measurements::db::Entity::find()
+        .find_also_related(sample_types::db::Entity)
+        .all(db)
+        .await?
+    {
+        if let Some(sample_type) = sample_type {
+            habitats
+                .entry(reserved.column_name)
+                .or_default()
+                .push(sample_type.name);
+        }
+    }
+    for names in habitats.values_mut() {
+        names.sort_unstable();
+    }
+    Ok(MeasurementChecks {
+        thresholds,
+        habitats,
+    })
+}
+
+fn check_measurements(record: &Model, checks: &MeasurementChecks) -> Result<(), ValidationError> {
+    for rule in MEASUREMENT_RULES {
+        let name = rule.column.as_str();
+        let Some(value) = as_f64(record.get(rule.column)) else {
+            continue;
+        };
+        if let Some(habitats) = checks.habitats.get(name) {
+            if !habitats.contains(&record.sample_type) {
+                return Err(ValidationError::new(
+                    name,
+                    format!("Only applies to {} field records", habitats.join(", ")),
+                ));
+            }
+        }
+        // An override may set only one bound; the other keeps its default.
+        let (min, max) = match checks.thresholds.get(name) {
+            Some(&(min, max)) => (min.or(rule.min), max.or(rule.max)),
+            None => (rule.min, rule.max),
+        };
+        validate_range(name, value, min, max)?;
+    }
+    Ok(())
+}
+
+/// The record as it would be stored, so create and update share one check.
+fn created_model(data: &FieldRecordCreate) -> Result<Model, ApiError> {
+    Ok(ActiveModel::from(data.clone()).try_into_model()?)
+}
+
+pub async fn check_create(
+    db: &DatabaseConnection,
+    data: &FieldRecordCreate,
+) -> Result<(), ApiError> {
+    let checks = load_checks(db).await?;
+    let record = created_model(data)?;
+    sample_types::services::check_known(db, &record.sample_type).await?;
+    check_measurements(&record, &checks)?;
+    replicate_groups::services::check_membership(db, &record).await
+}
+
+pub async fn check_create_many(
+    db: &DatabaseConnection,
+    data: &[FieldRecordCreate],
+) -> Result<(), ApiError> {
+    let checks = load_checks(db).await?;
+    for item in data {
+        let record = created_model(item)?;
+        sample_types::services::check_known(db, &record.sample_type).await?;
+        check_measurements(&record, &checks)?;
+        replicate_groups::services::check_membership(db, &record).await?;
+    }
+    Ok(())
+}
+
+/// Updates are checked against the merged record, so changing `sample_type` alone
+/// is caught when the stored measurements no longer fit it.
+async fn updated_model(
+    db: &DatabaseConnection,
+    id: Uuid,
+    data: &FieldRecordUpdate,
+) -> Result<Model, ApiError> {
+    let existing = Entity::find_by_id(id)
+        .one(db)
+        .await?
+        .ok_or_else(|| ApiError::not_found("field_record", Some(id.to_string())))?;
+    // Fields absent from the request come back `NotSet`; fill them from the stored row.
+    let mut merged = data
+        .clone()
+        .merge_into_activemodel(existing.clone().into_active_model())?;
+    for column in Column::iter() {
+        if merged.get(column).is_not_set() {
+            merged.set(column, existing.get(column));
+        }
+    }
+    Ok(merged.try_into_model()?)
+}
+
+pub async fn check_update(
+    db: &DatabaseConnection,
+    id: Uuid,
+    data: &FieldRecordUpdate,
+) -> Result<(), ApiError> {
+    let checks = load_checks(db).await?;
+    let record = updated_model(db, id, data).await?;
+    sample_types::services::check_known(db, &record.sample_type).await?;
+    check_measurements(&record, &checks)?;
+    replicate_groups::services::check_membership(db, &record).await
+}
+
+pub async fn check_update_many(
+    db: &DatabaseConnection,
+    updates: &[(Uuid, FieldRecordUpdate)],
+) -> Result<(), ApiError> {
+    let checks = load_checks(db).await?;
+    for (id, data) in updates {
+        let record = updated_model(db, *id, data).await?;
+        sample_types::services::check_known(db, &record.sample_type).await?;
+        check_measurements(&record, &checks)?;
+        replicate_groups::services::check_membership(db, &record).await?;
+    }
+    Ok(())
+}
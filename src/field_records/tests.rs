@@ -5,7 +5,7 @@ use axum::{
 use serde_json::json;
 use tower::ServiceExt;
 
-use crate::test_utils::{build_app_with_db, setup_clean_db};
+use crate::test_utils::{build_app_with_db, create_site, send, setup_clean_db, setup_sqlite_db};
 
 #[tokio::test]
 #[ignore]
@@ -143,3 +143,155 @@ async fn test_field_records_invalid_data() {
     let response = app.oneshot(request).await.unwrap();
     assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
 }
+
+#[tokio::test]
+async fn implausible_measurements_are_rejected() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db);
+    let site_id = create_site(&app, "Prabe_S1").await;
+
+    for (extra, reason) in [
+        (json!({ "sample_type": "Soil", "ph": 42.0 }), "pH range"),
+        (
+            json!({ "sample_type": "Soil", "ions_nitrate": -0.1 }),
+            "negative concentration",
+        ),
+        (
+            json!({ "sample_type": "Soil", "cfu_count_r2a": -3 }),
+            "negative count",
+        ),
+        (
+            json!({ "sample_type": "Soil", "snow_depth_cm": 30.0 }),
+            "snow depth on Soil",
+        ),
+        (
+            json!({ "sample_type": "Soil", "snow_temperature_celsius": -2.0 }),
+            "snow temperature on Soil",
+        ),
+        (
+            json!({ "sample_type": "Snow", "snow_temperature_celsius": 4.0 }),
+            "melted snow",
+        ),
+    ] {
+        let mut payload =
+            json!({ "name": "FR-BAD", "site_id": site_id, "sampling_date": "2025-07-10" });
+        payload
+            .as_object_mut()
+            .unwrap()
+            .extend(extra.as_object().unwrap().clone());
+        let (status, body) = send(&app, "POST", "/api/field_records", payload).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{reason}: {body}");
+    }
+
+    let (status, body) = send(
+        &app,
+        "POST",
+        "/api/field_records",
+        json!({
+            "name": "FR-OK", "site_id": site_id, "sample_type": "Snow", "sampling_date": "2025-07-10",
+            "snow_depth_cm": 120.5, "snow_temperature_celsius": -3.0,
+            "soil_temperature_celsius": -1.0, "ph": 6.2
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "{body}");
+    let id = body["id"].as_str().unwrap();
+
+    // The stored snow measurements no longer fit once the habitat changes.
+    let (status, _) = send(
+        &app,
+        "PUT",
+        &format!("/api/field_records/{id}"),
+        json!({ "sample_type": "Soil" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    let (status, _) = send(
+        &app,
+        "PUT",
+        &format!("/api/field_records/{id}"),
+        json!({ "ph": 14.5 }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+#[tokio::test]
+async fn admin_thresholds_override_defaults() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db);
+    let site_id = create_site(&app, "Prabe_S1").await;
+
+    let (status, _) = send(
+        &app,
+        "POST",
+        "/api/measurement_thresholds",
+        json!({ "column_name": "colour", "min_value": 0.0 }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "unknown column");
+
+    let (status, body) = send(
+        &app,
+        "POST",
+        "/api/measurement_thresholds",
+        json!({ "column_name": "air_temperature_celsius", "min_value": -40.0, "max_value": 30.0 }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "{body}");
+    let (status, _) = send(
+        &app,
+        "POST",
+        "/api/measurement_thresholds",
+        json!({ "column_name": "ph", "min_value": 3.0, "max_value": 10.0 }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let (status, _) = send(
+        &app,
+        "POST",
+        "/api/measurement_thresholds",
+        json!({ "column_name": "snow_temperature_celsius", "min_value": -30.0 }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    for (extra, expected) in [
+        (
+            json!({ "air_temperature_celsius": -50.0 }),
+            StatusCode::UNPROCESSABLE_ENTITY,
+        ),
+        (json!({ "ph": 2.0 }), StatusCode::UNPROCESSABLE_ENTITY),
+        (
+            json!({ "air_temperature_celsius": 25.0, "ph": 9.5 }),
+            StatusCode::CREATED,
+        ),
+        (
+            json!({ "ions_calcium": -1.0 }),
+            StatusCode::UNPROCESSABLE_ENTITY,
+        ),
+        (
+            json!({ "sample_type": "Snow", "snow_temperature_celsius": -35.0 }),
+            StatusCode::UNPROCESSABLE_ENTITY,
+        ),
+        (
+            json!({ "sample_type": "Snow", "snow_temperature_celsius": 2.0 }),
+            StatusCode::UNPROCESSABLE_ENTITY,
+        ),
+        (
+            json!({ "sample_type": "Snow", "snow_temperature_celsius": -20.0 }),
+            StatusCode::CREATED,
+        ),
+    ] {
+        let mut payload = json!({
+            "name": format!("FR-{extra}"), "site_id": site_id,
+            "sample_type": "Soil", "sampling_date": "2025-07-10"
+        });
+        payload
+            .as_object_mut()
+            .unwrap()
+            .extend(extra.as_object().unwrap().clone());
+        let (status, body) = send(&app, "POST", "/api/field_records", payload).await;
+        assert_eq!(status, expected, "{extra}: {body}");
+    }
+}
@@ -0,0 +1,66 @@
+use axum::extract::{Query, Request, State};
+use axum::response::{IntoResponse, Response};
+use axum::{routing::get, Json, Router};
+use crudcrate::filter::parse_pagination;
+use crudcrate::models::FilterOptions;
+use crudcrate::{ApiError, CRUDResource};
+use sea_orm::{DatabaseConnection, Order};
+use serde::Deserialize;
+use utoipa::IntoParams;
+
+use super::db::{FieldRecord, FieldRecordList, FieldRecordScopedList};
+use crate::middleware;
+
+/// Routes mounted next to the generated CRUD router under `/api/field_records`.
+pub fn router(db: &DatabaseConnection) -> Router {
+    Router::new()
+        .route("/sorted", get(get_sorted))
+        .with_state(db.clone())
+}
+
+#[derive(Deserialize, IntoParams, Debug)]
+pub struct ParameterSortParams {
+    /// Name of the custom parameter to sort by.
+    pub parameter: String,
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/field_records/sorted",
+    params(
+        ParameterSortParams,
+        ("order" = Option<String>, Query, description = "`ASC` (default) or `DESC`"),
+        ("page" = Option<u64>, Query, description = "1-based page of `per_page` records, or `range=[start,end]` as on the list")
+    ),
+    responses(
+        (status = OK, description = "Field records by their value of the parameter, numeric or text, those without one last", body = [FieldRecordList])
+    )
+)]
+pub async fn get_sorted(
+    State(db): State<DatabaseConnection>,
+    Query(params): Query<ParameterSortParams>,
+    Query(options): Query<FilterOptions>,
+    req: Request,
+) -> Result<Response, ApiError> {
+    let order = match options.order.as_deref() {
+        Some(order) if order.eq_ignore_ascii_case("desc") => Order::Desc,
+        _ => Order::Asc,
+    };
+    let (offset, limit) = parse_pagination(&options);
+    let limit = limit.min(FieldRecord::max_page_size());
+    let records = super::services::sorted_by_parameter(
+        &db,
+        middleware::scope_condition(&req),
+        &params.parameter,
+        order,
+        offset,
+        limit,
+    )
+    .await?;
+    Ok(if middleware::is_scoped(&req) {
+        let records: Vec<FieldRecordScopedList> = records.into_iter().map(Into::into).collect();
+        Json(records).into_response()
+    } else {
+        Json(records).into_response()
+    })
+}
@@ -0,0 +1,24 @@
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use uuid::Uuid;
+
+/// An uploaded file. The bytes live in the object store under the row's id.
+#[derive(Clone, Debug, PartialEq, Eq, DeriveEntityModel)]
+#[sea_orm(table_name = "file_objects")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    /// Name of the uploaded file, if the client sent one.
+    pub filename: Option<String>,
+    pub content_type: String,
+    pub size_bytes: i64,
+    /// Bytes not yet moved to the object store, from photos migrated out of the
+    /// database.
+    pub pending_data: Option<Vec<u8>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+/// Fixed thumbnail sizes, by their longest edge.
+#[derive(ToSchema, Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ThumbnailSize {
+    /// 160 px, for list cards.
+    Small,
+    /// 480 px, for detail panels.
+    Medium,
+    /// 1024 px, for viewing without loading the original.
+    Large,
+}
+
+impl ThumbnailSize {
+    pub const ALL: [ThumbnailSize; 3] = [
+        ThumbnailSize::Small,
+        ThumbnailSize::Medium,
+        ThumbnailSize::Large,
+    ];
+
+    pub fn max_edge(self) -> u32 {
+        match self {
+            ThumbnailSize::Small => 160,
+            ThumbnailSize::Medium => 480,
+            ThumbnailSize::Large => 1024,
+        }
+    }
+}
+
+impl std::fmt::Display for ThumbnailSize {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ThumbnailSize::Small => write!(f, "small"),
+            ThumbnailSize::Medium => write!(f, "medium"),
+            ThumbnailSize::Large => write!(f, "large"),
+        }
+    }
+}
+
+/// Where each thumbnail of an image is served.
+#[derive(ToSchema, Deserialize, Serialize, Debug, Clone, PartialEq, Eq, Default)]
+pub struct ThumbnailUrls {
+    pub small: String,
+    pub medium: String,
+    pub large: String,
+}
+
+impl ThumbnailUrls {
+    /// Thumbnails served under `base`, e.g. `/api/isolate_images/{id}/thumbnails`.
+    pub fn under(base: &str) -> Self {
+        ThumbnailUrls {
+            small: format!("{base}/{}", ThumbnailSize::Small),
+            medium: format!("{base}/{}", ThumbnailSize::Medium),
+            large: format!("{base}/{}", ThumbnailSize::Large),
+        }
+    }
+}
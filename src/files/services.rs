@@ -0,0 +1,300 @@
+use axum::body::{Body, Bytes};
+use chrono::Utc;
+use crudcrate::validation::ValidationError;
+use crudcrate::ApiError;
+use futures::{Stream, TryStreamExt};
+use object_store::{ObjectStore, WriteMultipart};
+use sea_orm::sea_query::Query;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter,
+    QuerySelect, Set,
+};
+use std::collections::HashSet;
+use std::pin::pin;
+use uuid::Uuid;
+
+use super::db::{ActiveModel, Column, Entity, Model};
+use super::store::path;
+use super::thumbnails;
+use crate::{isolate_images, isolates};
+
+/// Largest accepted upload.
+pub const MAX_UPLOAD_BYTES: usize = 20 * 1024 * 1024;
+
+/// Parts of a streamed upload sent to the store at once.
+const MAX_CONCURRENT_PARTS: usize = 4;
+
+/// Pending files moved to the store per query at startup.
+const PENDING_BATCH: u64 = 50;
+
+fn store_error(err: object_store::Error) -> ApiError {
+    ApiError::internal("The file store is unavailable", Some(err.to_string()))
+}
+
+/// Image type from the file's leading bytes; the type the client sends is not trusted.
+pub fn image_type(bytes: &[u8]) -> Option<&'static str> {
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("image/png")
+    } else if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("image/jpeg")
+    } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        Some("image/gif")
+    } else if bytes.len() >= 12 && bytes.starts_with(b"RIFF") && &bytes[8..12] == b"WEBP" {
+        Some("image/webp")
+    } else if bytes.starts_with(b"II*\0") || bytes.starts_with(b"MM\0*") {
+        Some("image/tiff")
+    } else {
+        None
+    }
+}
+
+pub async fn find(db: &DatabaseConnection, id: Uuid) -> Result<Model, ApiError> {
+    Entity::find_by_id(id)
+        .one(db)
+        .await?
+        .ok_or_else(|| ApiError::not_found("file", Some(id.to_string())))
+}
+
+/// Stores an uploaded image and records it. Pass a transaction to tie the record to
+/// whatever ends up referencing it, and `discard` the file if that rolls back.
+pub async fn put_image(
+    db: &impl ConnectionTrait,
+    store: &dyn ObjectStore,
+    field: &str,
+    filename: Option<String>,
+    bytes: Bytes,
+) -> Result<Model, ApiError> {
+    let content_type = image_type(&bytes).ok_or_else(|| {
+        ValidationError::new(field, "Upload a PNG, JPEG, GIF, WebP or TIFF image")
+    })?;
+    let rendered = thumbnails::render_upload(field, bytes.clone()).await?;
+    let id = Uuid::new_v4();
+    let size_bytes = bytes.len() as i64;
+    store
+        .put(&path(id), bytes.into())
+        .await
+        .map_err(store_error)?;
+    thumbnails::put(store, id, rendered).await?;
+
+    let record = ActiveModel {
+        id: Set(id),
+        filename: Set(filename),
+        content_type: Set(content_type.to_string()),
+        size_bytes: Set(size_bytes),
+        pending_data: Set(None),
+        created_at: Set(Utc::now()),
+    };
+    match record.insert(db).await {
+        Ok(model) => Ok(model),
+        Err(err) => {
+            discard(store, id).await;
+            Err(err.into())
+        }
+    }
+}
+
+/// Streams a file into the store, handing each chunk to `inspect` before it is
+/// written. Returns the new file's id and size; nothing is left in the store when
+/// reading, inspecting or storing fails.
+pub async fn stream_file<S, F>(
+    store: &dyn ObjectStore,
+    chunks: S,
+    mut inspect: F,
+) -> Result<(Uuid, i64), ApiError>
+where
+    S: Stream<Item = Result<Bytes, ApiError>>,
+    F: FnMut(&[u8]) -> Result<(), ApiError>,
+{
+    let id = Uuid::new_v4();
+    let upload = store.put_multipart(&path(id)).await.map_err(store_error)?;
+    let mut writer = WriteMultipart::new(upload);
+    let mut chunks = pin!(chunks);
+    let mut size_bytes = 0;
+    let written: Result<(), ApiError> = async {
+        while let Some(chunk) = chunks.try_next().await? {
+            inspect(&chunk)?;
+            writer
+                .wait_for_capacity(MAX_CONCURRENT_PARTS)
+                .await
+                .map_err(store_error)?;
+            size_bytes += chunk.len() as i64;
+            writer.put(chunk);
+        }
+        Ok(())
+    }
+    .await;
+    match written {
+        Ok(()) => {
+            writer.finish().await.map_err(store_error)?;
+            Ok((id, size_bytes))
+        }
+        Err(err) => {
+            let _ = writer.abort().await;
+            Err(err)
+        }
+    }
+}
+
+/// Records a file already written to the store under `id`, discarding its objects
+/// if the record cannot be inserted. As with `put_image`, `discard` it if the
+/// caller's transaction rolls back.
+pub async fn insert(
+    db: &impl ConnectionTrait,
+    store: &dyn ObjectStore,
+    id: Uuid,
+    filename: Option<String>,
+    content_type: &str,
+    size_bytes: i64,
+) -> Result<Model, ApiError> {
+    let record = ActiveModel {
+        id: Set(id),
+        filename: Set(filename),
+        content_type: Set(content_type.to_string()),
+        size_bytes: Set(size_bytes),
+        pending_data: Set(None),
+        created_at: Set(Utc::now()),
+    };
+    match record.insert(db).await {
+        Ok(model) => Ok(model),
+        Err(err) => {
+            discard(store, id).await;
+            Err(err.into())
+        }
+    }
+}
+
+/// Deletes the objects of a file whose record was never committed. Failures are
+/// ignored; the objects are unreachable either way.
+pub async fn discard(store: &dyn ObjectStore, id: Uuid) {
+    let _ = store.delete(&path(id)).await;
+    let _ = thumbnails::remove(store, id).await;
+}
+
+/// The file's bytes, streamed from the store.
+pub async fn open(store: &dyn ObjectStore, file: &Model) -> Result<Body, ApiError> {
+    if let Some(data) = &file.pending_data {
+        return Ok(Body::from(data.clone()));
+    }
+    let object = store.get(&path(file.id)).await.map_err(store_error)?;
+    Ok(Body::from_stream(object.into_stream()))
+}
+
+/// Deletes the record, its object and its thumbnails. An object already gone is
+/// not an error.
+pub async fn remove(
+    db: &DatabaseConnection,
+    store: &dyn ObjectStore,
+    id: Uuid,
+) -> Result<(), ApiError> {
+    Entity::delete_by_id(id).exec(db).await?;
+    match store.delete(&path(id)).await {
+        Ok(()) | Err(object_store::Error::NotFound { .. }) => {}
+        Err(err) => return Err(store_error(err)),
+    }
+    thumbnails::remove(store, id).await
+}
+
+/// Removes those of `ids` that no image or assembly refers to any more, such as the
+/// files of a deleted isolate or a replaced assembly.
+pub async fn remove_unreferenced(
+    db: &DatabaseConnection,
+    store: &dyn ObjectStore,
+    ids: &[Uuid],
+) -> Result<(), ApiError> {
+    if ids.is_empty() {
+        return Ok(());
+    }
+    let mut referenced: HashSet<Uuid> = isolate_images::db::Entity::find()
+        .select_only()
+        .column(isolate_images::db::Column::FileId)
+        .filter(isolate_images::db::Column::FileId.is_in(ids.iter().copied()))
+        .into_tuple()
+        .all(db)
+        .await?
+        .into_iter()
+        .collect();
+    referenced.extend(
+        isolates::db::Entity::find()
+            .select_only()
+            .column(isolates::db::Column::AssemblyFileId)
+            .filter(isolates::db::Column::AssemblyFileId.is_in(ids.iter().copied()))
+            .into_tuple::<Uuid>()
+            .all(db)
+            .await?,
+    );
+    for id in ids.iter().filter(|id| !referenced.contains(id)) {
+        remove(db, store, *id).await?;
+    }
+    Ok(())
+}
+
+/// Removes every file that no image or assembly refers to. The generated delete
+/// handlers only reach the database, so files left behind by a deleted isolate or
+/// image are swept up here afterwards. Returns how many were removed.
+pub async fn remove_orphans(
+    db: &DatabaseConnection,
+    store: &dyn ObjectStore,
+) -> Result<u64, ApiError> {
+    let orphans: Vec<Uuid> = Entity::find()
+        .select_only()
+        .column(Column::Id)
+        .filter(
+            Column::Id.not_in_subquery(
+                Query::select()
+                    .column(isolate_images::db::Column::FileId)
+                    .from(isolate_images::db::Entity)
+                    .to_owned(),
+            ),
+        )
+        .filter(
+            Column::Id.not_in_subquery(
+                Query::select()
+                    .column(isolates::db::Column::AssemblyFileId)
+                    .from(isolates::db::Entity)
+                    .and_where(isolates::db::Column::AssemblyFileId.is_not_null())
+                    .to_owned(),
+            ),
+        )
+        .into_tuple()
+        .all(db)
+        .await?;
+    for id in &orphans {
+        remove(db, store, *id).await?;
+    }
+    Ok(orphans.len() as u64)
+}
+
+/// Moves photos migrated out of the database into the store. Returns how many
+/// were moved.
+pub async fn store_pending(
+    db: &DatabaseConnection,
+    store: &dyn ObjectStore,
+) -> Result<u64, ApiError> {
+    let mut moved = 0;
+    loop {
+        let pending = Entity::find()
+            .filter(Column::PendingData.is_not_null())
+            .limit(PENDING_BATCH)
+            .all(db)
+            .await?;
+        if pending.is_empty() {
+            return Ok(moved);
+        }
+        for file in pending {
+            let data = Bytes::from(file.pending_data.clone().unwrap_or_default());
+            // Thumbnails of photos that cannot be decoded are left out; requesting
+            // one reports the problem.
+            if let Ok(rendered) = thumbnails::render_upload("image", data.clone()).await {
+                thumbnails::put(store, file.id, rendered).await?;
+            }
+            store
+                .put(&path(file.id), data.into())
+                .await
+                .map_err(store_error)?;
+            let mut record: ActiveModel = file.into();
+            record.pending_data = Set(None);
+            record.update(db).await?;
+            moved += 1;
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use object_store::aws::AmazonS3Builder;
+use object_store::local::LocalFileSystem;
+use object_store::memory::InMemory;
+use object_store::path::Path;
+use object_store::ObjectStore;
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::config::FileStoreConfig;
+
+pub fn build(config: &FileStoreConfig) -> object_store::Result<Arc<dyn ObjectStore>> {
+    Ok(match config {
+        FileStoreConfig::Filesystem { root } => {
+            std::fs::create_dir_all(root).map_err(|source| object_store::Error::Generic {
+                store: "LocalFileSystem",
+                source: Box::new(source),
+            })?;
+            Arc::new(LocalFileSystem::new_with_prefix(root)?)
+        }
+        FileStoreConfig::S3 { bucket } => Arc::new(
+            AmazonS3Builder::from_env()
+                .with_bucket_name(bucket)
+                .build()?,
+        ),
+        FileStoreConfig::Memory => Arc::new(InMemory::new()),
+    })
+}
+
+/// Objects are keyed by their `file_objects` id.
+pub fn path(id: Uuid) -> Path {
+    Path::from(id.to_string())
+}
@@ -0,0 +1,130 @@
+//! JPEG thumbnails of uploaded images, cached in the object store next to the
+//! originals. They are rendered upright from the EXIF orientation and, being
+//! re-encoded, carry none of the original's metadata (camera, GPS position).
+
+use axum::body::{Body, Bytes};
+use crudcrate::validation::ValidationError;
+use crudcrate::ApiError;
+use image::codecs::jpeg::JpegEncoder;
+use image::imageops::FilterType;
+use image::{DynamicImage, ImageDecoder, ImageReader};
+use object_store::path::Path;
+use object_store::ObjectStore;
+use std::io::Cursor;
+use uuid::Uuid;
+
+use super::db::Model;
+use super::models::ThumbnailSize;
+
+const JPEG_QUALITY: u8 = 85;
+
+fn path(id: Uuid, size: ThumbnailSize) -> Path {
+    Path::from(format!("thumbnails/{id}/{size}.jpg"))
+}
+
+fn store_error(err: object_store::Error) -> ApiError {
+    ApiError::internal("The file store is unavailable", Some(err.to_string()))
+}
+
+fn decode(bytes: &[u8]) -> image::ImageResult<DynamicImage> {
+    let mut decoder = ImageReader::new(Cursor::new(bytes))
+        .with_guessed_format()?
+        .into_decoder()?;
+    let orientation = decoder.orientation()?;
+    let mut image = DynamicImage::from_decoder(decoder)?;
+    image.apply_orientation(orientation);
+    Ok(image)
+}
+
+fn encode(image: &DynamicImage) -> image::ImageResult<Vec<u8>> {
+    let mut jpeg = Vec::new();
+    JpegEncoder::new_with_quality(&mut jpeg, JPEG_QUALITY).encode_image(&image.to_rgb8())?;
+    Ok(jpeg)
+}
+
+/// Every thumbnail size of an image. Images smaller than a size are not enlarged.
+pub fn render(bytes: &[u8]) -> image::ImageResult<Vec<(ThumbnailSize, Vec<u8>)>> {
+    let image = decode(bytes)?;
+    ThumbnailSize::ALL
+        .into_iter()
+        .map(|size| {
+            let edge = size.max_edge();
+            let scaled = if image.width() > edge || image.height() > edge {
+                image.resize(edge, edge, FilterType::Triangle)
+            } else {
+                image.clone()
+            };
+            Ok((size, encode(&scaled)?))
+        })
+        .collect()
+}
+
+/// Renders thumbnails off the async runtime. An image that cannot be decoded is
+/// rejected as a validation error on `field`.
+pub async fn render_upload(
+    field: &str,
+    bytes: Bytes,
+) -> Result<Vec<(ThumbnailSize, Vec<u8>)>, ApiError> {
+    tokio::task::spawn_blocking(move || render(&bytes))
+        .await
+        .map_err(|err| ApiError::internal("Thumbnail rendering failed", Some(err.to_string())))?
+        .map_err(|err| {
+            ValidationError::new(field, format!("The image cannot be read: {err}")).into()
+        })
+}
+
+pub async fn put(
+    store: &dyn ObjectStore,
+    id: Uuid,
+    thumbnails: Vec<(ThumbnailSize, Vec<u8>)>,
+) -> Result<(), ApiError> {
+    for (size, jpeg) in thumbnails {
+        store
+            .put(&path(id, size), jpeg.into())
+            .await
+            .map_err(store_error)?;
+    }
+    Ok(())
+}
+
+/// A thumbnail of `file`, rendering and caching all sizes if it is missing.
+pub async fn open(
+    store: &dyn ObjectStore,
+    file: &Model,
+    size: ThumbnailSize,
+) -> Result<Body, ApiError> {
+    match store.get(&path(file.id, size)).await {
+        Ok(object) => return Ok(Body::from_stream(object.into_stream())),
+        Err(object_store::Error::NotFound { .. }) => {}
+        Err(err) => return Err(store_error(err)),
+    }
+    let original = match &file.pending_data {
+        Some(data) => Bytes::from(data.clone()),
+        None => store
+            .get(&super::store::path(file.id))
+            .await
+            .map_err(store_error)?
+            .bytes()
+            .await
+            .map_err(store_error)?,
+    };
+    let thumbnails = render_upload("file", original).await?;
+    let body = thumbnails
+        .iter()
+        .find(|(rendered, _)| *rendered == size)
+        .map(|(_, jpeg)| Body::from(jpeg.clone()))
+        .unwrap_or_default();
+    put(store, file.id, thumbnails).await?;
+    Ok(body)
+}
+
+/// Removes every thumbnail of a file. Ones already gone are not an error.
+pub async fn remove(store: &dyn ObjectStore, id: Uuid) -> Result<(), ApiError> {
+    for size in ThumbnailSize::ALL {
+        match store.delete(&path(id, size)).await {
+            Ok(()) | Err(object_store::Error::NotFound { .. }) => {}
+            Err(err) => return Err(store_error(err)),
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,152 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use crudcrate::validation::{Validatable, ValidationError};
+use crudcrate::{CRUDResource, EntityToModels};
+use sea_orm::entity::prelude::*;
+use uuid::Uuid;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+#[sea_orm(table_name = "growth_tests")]
+#[crudcrate(
+    generate_router,
+    api_struct = "GrowthTest",
+    name_singular = "growth_test",
+    name_plural = "growth_tests",
+    description = "Growth of isolates across temperatures, media, salinity and pH",
+    no_eq,
+    derive_partial_eq
+)]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    #[crudcrate(primary_key, filterable, exclude(update, create), on_create = Uuid::new_v4())]
+    pub id: Uuid,
+    #[crudcrate(sortable, filterable, exclude(update))]
+    pub isolate_id: Uuid,
+    /// Conditions left empty were not varied in the test; at least one is set.
+    #[crudcrate(sortable, filterable)]
+    pub temperature_celsius: Option<f64>,
+    #[crudcrate(sortable, filterable, fulltext)]
+    pub medium: Option<String>,
+    /// NaCl, in % w/v.
+    #[crudcrate(sortable, filterable)]
+    pub salinity_percent: Option<f64>,
+    #[crudcrate(sortable, filterable)]
+    pub ph: Option<f64>,
+    #[crudcrate(sortable, filterable)]
+    pub grows: bool,
+    /// Specific growth rate µ; zero when the isolate did not grow.
+    #[crudcrate(sortable, filterable)]
+    pub growth_rate_per_hour: Option<f64>,
+    #[crudcrate(sortable, filterable)]
+    pub incubation_days: Option<i32>,
+    #[crudcrate(sortable, filterable)]
+    pub tested_on: Option<NaiveDate>,
+    #[crudcrate(fulltext)]
+    pub notes: Option<String>,
+    #[crudcrate(sortable, filterable, exclude(create, update), on_create = chrono::Utc::now())]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "crate::isolates::db::Entity",
+        from = "Column::IsolateId",
+        to = "crate::isolates::db::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Isolate,
+}
+
+impl Related<crate::isolates::db::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Isolate.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+fn check_range(field: &str, value: Option<f64>, min: f64, max: f64) -> Result<(), ValidationError> {
+    match value {
+        Some(value) if !(value.is_finite() && (min..=max).contains(&value)) => Err(
+            ValidationError::new(field, format!("Must be between {min} and {max}")),
+        ),
+        _ => Ok(()),
+    }
+}
+
+fn check_values(
+    temperature_celsius: Option<f64>,
+    salinity_percent: Option<f64>,
+    ph: Option<f64>,
+    growth_rate_per_hour: Option<f64>,
+    incubation_days: Option<i32>,
+) -> Result<(), ValidationError> {
+    check_range("temperature_celsius", temperature_celsius, -40.0, 130.0)?;
+    check_range("salinity_percent", salinity_percent, 0.0, 100.0)?;
+    check_range("ph", ph, 0.0, 14.0)?;
+    check_range("growth_rate_per_hour", growth_rate_per_hour, 0.0, f64::MAX)?;
+    if incubation_days.is_some_and(|days| days <= 0) {
+        return Err(ValidationError::new(
+            "incubation_days",
+            "Must be a positive number of days",
+        ));
+    }
+    Ok(())
+}
+
+/// A positive rate means growth and a zero rate none.
+fn check_rate(grows: bool, growth_rate_per_hour: Option<f64>) -> Result<(), ValidationError> {
+    match growth_rate_per_hour {
+        Some(rate) if (rate > 0.0) != grows => Err(ValidationError::new(
+            "growth_rate_per_hour",
+            if grows {
+                "An isolate that grows has a positive rate"
+            } else {
+                "An isolate that does not grow has a rate of zero"
+            },
+        )),
+        _ => Ok(()),
+    }
+}
+
+impl Validatable for GrowthTestCreate {
+    fn validate(&self) -> Result<(), ValidationError> {
+        if self.temperature_celsius.is_none()
+            && self
+                .medium
+                .as_deref()
+                .is_none_or(|medium| medium.trim().is_empty())
+            && self.salinity_percent.is_none()
+            && self.ph.is_none()
+        {
+            return Err(ValidationError::new(
+                "temperature_celsius",
+                "Give at least one of temperature_celsius, medium, salinity_percent and ph",
+            ));
+        }
+        check_values(
+            self.temperature_celsius,
+            self.salinity_percent,
+            self.ph,
+            self.growth_rate_per_hour,
+            self.incubation_days,
+        )?;
+        check_rate(self.grows, self.growth_rate_per_hour)
+    }
+}
+
+impl Validatable for GrowthTestUpdate {
+    fn validate(&self) -> Result<(), ValidationError> {
+        check_values(
+            self.temperature_celsius.flatten(),
+            self.salinity_percent.flatten(),
+            self.ph.flatten(),
+            self.growth_rate_per_hour.flatten(),
+            self.incubation_days.flatten(),
+        )?;
+        if let Some(grows) = self.grows.flatten() {
+            check_rate(grows, self.growth_rate_per_hour.flatten())?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+#[derive(ToSchema, Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MatrixFormat {
+    #[default]
+    Json,
+    /// One row per isolate and one column per condition.
+    Csv,
+}
+
+#[derive(Deserialize, IntoParams, Debug)]
+pub struct MatrixParams {
+    /// `json` (default) or `csv`.
+    pub format: Option<MatrixFormat>,
+}
+
+/// A combination of test conditions; conditions not varied are null.
+#[derive(ToSchema, Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct GrowthCondition {
+    pub temperature_celsius: Option<f64>,
+    pub medium: Option<String>,
+    pub salinity_percent: Option<f64>,
+    pub ph: Option<f64>,
+}
+
+/// The tests of one isolate under one condition.
+#[derive(ToSchema, Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct GrowthResult {
+    /// True when any replicate grew.
+    pub grows: bool,
+    /// Mean of the recorded rates.
+    pub growth_rate_per_hour: Option<f64>,
+    pub tests: u64,
+}
+
+#[derive(ToSchema, Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct GrowthMatrixRow {
+    pub isolate_id: Uuid,
+    pub isolate_name: String,
+    /// One entry per condition, null where the isolate was not tested.
+    pub results: Vec<Option<GrowthResult>>,
+}
+
+#[derive(ToSchema, Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct GrowthMatrix {
+    pub conditions: Vec<GrowthCondition>,
+    /// Tested isolates by name.
+    pub rows: Vec<GrowthMatrixRow>,
+}
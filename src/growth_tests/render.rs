@@ -0,0 +1,47 @@
+//! CSV download of the growth matrix.
+
+use super::models::{GrowthCondition, GrowthMatrix, GrowthResult};
+use crate::common::csv;
+
+/// Column heading such as `4 °C / R2A / 3 % NaCl / pH 7`.
+fn label(condition: &GrowthCondition) -> String {
+    let mut parts = Vec::new();
+    if let Some(temperature) = condition.temperature_celsius {
+        parts.push(format!("{temperature} °C"));
+    }
+    if let Some(medium) = &condition.medium {
+        parts.push(medium.clone());
+    }
+    if let Some(salinity) = condition.salinity_percent {
+        parts.push(format!("{salinity} % NaCl"));
+    }
+    if let Some(ph) = condition.ph {
+        parts.push(format!("pH {ph}"));
+    }
+    parts.join(" / ")
+}
+
+/// The growth rate when one was measured, otherwise `+` or `-`.
+fn cell(result: &Option<GrowthResult>) -> String {
+    match result {
+        Some(GrowthResult {
+            growth_rate_per_hour: Some(rate),
+            ..
+        }) => rate.to_string(),
+        Some(GrowthResult { grows: true, .. }) => "+".to_string(),
+        Some(GrowthResult { grows: false, .. }) => "-".to_string(),
+        None => String::new(),
+    }
+}
+
+pub fn csv(matrix: &GrowthMatrix) -> String {
+    let mut header = vec!["isolate_id".to_string(), "isolate".to_string()];
+    header.extend(matrix.conditions.iter().map(label));
+    let mut out = csv::record(&header);
+    for row in &matrix.rows {
+        let mut fields = vec![row.isolate_id.to_string(), row.isolate_name.clone()];
+        fields.extend(row.results.iter().map(cell));
+        out.push_str(&csv::record(&fields));
+    }
+    out
+}
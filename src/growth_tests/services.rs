@@ -0,0 +1,172 @@
+use crudcrate::ApiError;
+use sea_orm::sea_query::{Expr, Func, Query};
+use sea_orm::{
+    ColumnTrait, Condition, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, QuerySelect,
+    QueryTrait,
+};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use super::db::{Column, Entity, Model};
+use super::models::{GrowthCondition, GrowthMatrix, GrowthMatrixRow, GrowthResult};
+use crate::isolates;
+
+/// Isolate query parameters that filter on growth tests, with the condition each
+/// tests and whether the isolate must have grown.
+const FILTERS: [(&str, Column, bool); 8] = [
+    ("grows_at", Column::TemperatureCelsius, true),
+    ("no_growth_at", Column::TemperatureCelsius, false),
+    ("grows_on", Column::Medium, true),
+    ("no_growth_on", Column::Medium, false),
+    ("grows_at_salinity", Column::SalinityPercent, true),
+    ("no_growth_at_salinity", Column::SalinityPercent, false),
+    ("grows_at_ph", Column::Ph, true),
+    ("no_growth_at_ph", Column::Ph, false),
+];
+
+/// Numbers in filters match tests within this distance, so `4` finds 4.0 °C.
+const TOLERANCE: f64 = 0.05;
+
+/// Conditions on isolates for `?grows_at=4&no_growth_at=25` and the like: each
+/// value needs a test under that condition with that outcome, so a psychrophile
+/// is one shown to grow at 4 °C and shown not to at 25 °C. Values may be
+/// comma-separated to require several.
+pub fn isolate_filters(params: &HashMap<String, String>) -> Result<Vec<Condition>, String> {
+    let mut conditions = Vec::new();
+    for (param, column, grows) in FILTERS {
+        let Some(values) = params.get(param) else {
+            continue;
+        };
+        for value in values.split(',').map(str::trim).filter(|v| !v.is_empty()) {
+            let mut tests = Query::select();
+            tests
+                .column(Column::IsolateId)
+                .from(Entity)
+                .and_where(Column::Grows.eq(grows));
+            if matches!(column, Column::Medium) {
+                tests.and_where(
+                    Expr::expr(Func::lower(Expr::col(Column::Medium))).eq(value.to_lowercase()),
+                );
+            } else {
+                let number: f64 = value
+                    .parse()
+                    .map_err(|_| format!("{param} expects numbers, got '{value}'"))?;
+                tests.and_where(column.between(number - TOLERANCE, number + TOLERANCE));
+            }
+            conditions
+                .push(Condition::all().add(isolates::db::Column::Id.in_subquery(tests.to_owned())));
+        }
+    }
+    Ok(conditions)
+}
+
+fn cmp_number(a: Option<f64>, b: Option<f64>) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) => a.total_cmp(&b),
+        // Untested conditions last.
+        (a, b) => a.is_none().cmp(&b.is_none()),
+    }
+}
+
+fn cmp_condition(a: &GrowthCondition, b: &GrowthCondition) -> Ordering {
+    cmp_number(a.temperature_celsius, b.temperature_celsius)
+        .then_with(|| a.medium.is_none().cmp(&b.medium.is_none()))
+        .then_with(|| a.medium.cmp(&b.medium))
+        .then_with(|| cmp_number(a.salinity_percent, b.salinity_percent))
+        .then_with(|| cmp_number(a.ph, b.ph))
+}
+
+/// -0.0 as 0.0, which `total_cmp` would otherwise order apart.
+fn normalised(value: Option<f64>) -> Option<f64> {
+    value.map(|v| if v == 0.0 { 0.0 } else { v })
+}
+
+fn condition_of(test: &Model) -> GrowthCondition {
+    GrowthCondition {
+        temperature_celsius: normalised(test.temperature_celsius),
+        medium: test.medium.clone(),
+        salinity_percent: normalised(test.salinity_percent),
+        ph: normalised(test.ph),
+    }
+}
+
+/// The distinct conditions of `tests`, in the order of the matrix columns.
+pub(super) fn conditions_of(tests: &[Model]) -> Vec<GrowthCondition> {
+    let mut conditions: Vec<GrowthCondition> = tests.iter().map(condition_of).collect();
+    conditions.sort_by(cmp_condition);
+    conditions.dedup_by(|a, b| cmp_condition(a, b) == Ordering::Equal);
+    conditions
+}
+
+fn result_of(tests: &[&Model]) -> GrowthResult {
+    let rates: Vec<f64> = tests
+        .iter()
+        .filter_map(|t| t.growth_rate_per_hour)
+        .collect();
+    GrowthResult {
+        grows: tests.iter().any(|t| t.grows),
+        growth_rate_per_hour: (!rates.is_empty())
+            .then(|| rates.iter().sum::<f64>() / rates.len() as f64),
+        tests: tests.len() as u64,
+    }
+}
+
+/// Growth of the isolates matching `condition` under every tested condition, with
+/// replicates combined. Isolates without tests are left out.
+pub async fn matrix(
+    db: &DatabaseConnection,
+    condition: Condition,
+) -> Result<GrowthMatrix, ApiError> {
+    let isolates: Vec<(Uuid, String)> = isolates::db::Entity::find()
+        .select_only()
+        .columns([isolates::db::Column::Id, isolates::db::Column::Name])
+        .filter(condition.clone())
+        .order_by_asc(isolates::db::Column::Name)
+        .into_tuple()
+        .all(db)
+        .await?;
+    let ids = isolates::db::Entity::find()
+        .select_only()
+        .column(isolates::db::Column::Id)
+        .filter(condition)
+        .into_query();
+    let tests = Entity::find()
+        .filter(Column::IsolateId.in_subquery(ids))
+        .all(db)
+        .await?;
+
+    let conditions = conditions_of(&tests);
+
+    let mut by_isolate: HashMap<Uuid, Vec<Vec<&Model>>> = HashMap::new();
+    for test in &tests {
+        let column = conditions
+            .binary_search_by(|c| cmp_condition(c, &condition_of(test)))
+            .map_err(|_| {
+                ApiError::internal(
+                    format!("Growth test {} has no column in the matrix", test.id),
+                    None,
+                )
+            })?;
+        by_isolate
+            .entry(test.isolate_id)
+            .or_insert_with(|| vec![Vec::new(); conditions.len()])[column]
+            .push(test);
+    }
+
+    let rows = isolates
+        .into_iter()
+        .filter_map(|(isolate_id, isolate_name)| {
+            let cells = by_isolate.remove(&isolate_id)?;
+            Some(GrowthMatrixRow {
+                isolate_id,
+                isolate_name,
+                results: cells
+                    .iter()
+                    .map(|tests| (!tests.is_empty()).then(|| result_of(tests)))
+                    .collect(),
+            })
+        })
+        .collect();
+    Ok(GrowthMatrix { conditions, rows })
+}
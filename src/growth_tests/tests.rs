@@ -0,0 +1,197 @@
+use axum::{body::to_bytes, http::StatusCode};
+use serde_json::{json, Value};
+
+use super::services::conditions_of;
+use crate::test_utils::{
+    build_app_with_db, build_scoped_app_with_db, create, create_field_record, create_site, get,
+    names, send, send_raw, setup_sqlite_db,
+};
+
+/// Isolates ISO-COLD, ISO-WARM and the private ISO-HIDDEN with their growth tests:
+/// the cold one grows at 4 °C in two replicates, not at 25 °C and on R2A; the warm
+/// one the other way round and at 37 °C; the hidden one like the cold one.
+async fn tested_isolates(app: &axum::Router) -> (String, String, String) {
+    let site_id = create_site(app, "Glacier E").await;
+    let fr = create_field_record(app, &site_id, "FR-5").await;
+    let isolate = |name: &'static str, is_private: bool| {
+        create(
+            app,
+            "/api/isolates",
+            json!({ "name": name, "field_record_id": fr, "is_private": is_private }),
+        )
+    };
+    let cold = isolate("ISO-COLD", false).await;
+    let warm = isolate("ISO-WARM", false).await;
+    let hidden = isolate("ISO-HIDDEN", true).await;
+    for payload in [
+        json!({ "isolate_id": cold, "temperature_celsius": 4.0, "grows": true, "growth_rate_per_hour": 0.25, "incubation_days": 14 }),
+        json!({ "isolate_id": cold, "temperature_celsius": 4.0, "grows": true, "growth_rate_per_hour": 0.75 }),
+        json!({ "isolate_id": cold, "temperature_celsius": 25.0, "grows": false }),
+        json!({ "isolate_id": cold, "medium": "R2A", "grows": true }),
+        json!({ "isolate_id": warm, "temperature_celsius": 4.0, "grows": false, "growth_rate_per_hour": 0.0 }),
+        json!({ "isolate_id": warm, "temperature_celsius": 25.0, "grows": true }),
+        json!({ "isolate_id": warm, "temperature_celsius": 37.0, "grows": true }),
+        json!({ "isolate_id": hidden, "temperature_celsius": 4.0, "grows": true }),
+        json!({ "isolate_id": hidden, "temperature_celsius": 25.0, "grows": false }),
+    ] {
+        create(app, "/api/growth_tests", payload).await;
+    }
+    (cold, warm, hidden)
+}
+
+/// Scenario: tests without a condition, with a pH above 14 and with a rate but no
+/// growth are recorded.
+/// Expected behaviour: each is refused.
+#[tokio::test]
+async fn implausible_growth_tests_are_refused() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db);
+    let site_id = create_site(&app, "Glacier E").await;
+    let fr = create_field_record(&app, &site_id, "FR-5").await;
+    let isolate = create(
+        &app,
+        "/api/isolates",
+        json!({ "name": "ISO-COLD", "field_record_id": fr }),
+    )
+    .await;
+
+    for (payload, problem) in [
+        (
+            json!({ "isolate_id": isolate, "grows": true }),
+            "no condition",
+        ),
+        (
+            json!({ "isolate_id": isolate, "ph": 15.0, "grows": true }),
+            "pH out of range",
+        ),
+        (
+            json!({ "isolate_id": isolate, "temperature_celsius": 4.0, "grows": false, "growth_rate_per_hour": 0.1 }),
+            "rate without growth",
+        ),
+    ] {
+        let (status, body) = send(&app, "POST", "/api/growth_tests", payload).await;
+        assert_eq!(
+            status,
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "{problem}: {body}"
+        );
+    }
+}
+
+/// Scenario: public callers filter isolates by growth, then an isolate is deleted.
+/// Expected behaviour: a psychrophile is one shown to grow at 4 °C and shown not to
+/// at 25 °C; the private isolate and its tests stay hidden; deleting an isolate
+/// deletes its tests.
+#[tokio::test]
+async fn growth_filters_find_public_isolates() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db.clone());
+    let scoped = build_scoped_app_with_db(db);
+    let (_, warm, _) = tested_isolates(&app).await;
+
+    let (_, psychrophiles) = get(&scoped, "/api/isolates?grows_at=4&no_growth_at=25").await;
+    assert_eq!(names(&psychrophiles), ["ISO-COLD"]);
+    let (_, both) = get(&scoped, "/api/isolates?grows_at=25,37").await;
+    assert_eq!(names(&both), ["ISO-WARM"]);
+    let (_, on_r2a) = get(&scoped, "/api/isolates?grows_on=r2a").await;
+    assert_eq!(names(&on_r2a), ["ISO-COLD"]);
+    let (status, _) = get(&scoped, "/api/isolates?grows_at=cold").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (_, public_tests) = get(&scoped, "/api/growth_tests").await;
+    assert_eq!(public_tests.as_array().unwrap().len(), 7);
+
+    let (status, _) = send(
+        &app,
+        "DELETE",
+        &format!("/api/isolates/{warm}"),
+        Value::Null,
+    )
+    .await;
+    assert!(status.is_success());
+    let (_, remaining) = get(&app, "/api/growth_tests").await;
+    assert_eq!(remaining.as_array().unwrap().len(), 6);
+}
+
+/// Scenario: public callers fetch the growth matrix as JSON and CSV, and filtered.
+/// Expected behaviour: conditions run by temperature with untested ones last,
+/// replicates are combined, and the private isolate is left out.
+#[tokio::test]
+async fn growth_matrix_combines_replicates() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db.clone());
+    let scoped = build_scoped_app_with_db(db);
+    let (cold, warm, _) = tested_isolates(&app).await;
+
+    let (status, matrix) = get(&scoped, "/api/isolates/growth_matrix").await;
+    assert_eq!(status, StatusCode::OK);
+    let temperatures: Vec<&Value> = matrix["conditions"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| &c["temperature_celsius"])
+        .collect();
+    assert_eq!(
+        temperatures,
+        [&json!(4.0), &json!(25.0), &json!(37.0), &Value::Null]
+    );
+    assert_eq!(matrix["conditions"][3]["medium"], "R2A");
+    assert_eq!(matrix["rows"].as_array().unwrap().len(), 2);
+    assert_eq!(matrix["rows"][0]["isolate_name"], "ISO-COLD");
+    assert_eq!(
+        matrix["rows"][0]["results"],
+        json!([
+            { "grows": true, "growth_rate_per_hour": 0.5, "tests": 2 },
+            { "grows": false, "growth_rate_per_hour": null, "tests": 1 },
+            null,
+            { "grows": true, "growth_rate_per_hour": null, "tests": 1 }
+        ])
+    );
+
+    let resp = send_raw(
+        &scoped,
+        "GET",
+        "/api/isolates/growth_matrix?format=csv",
+        &[],
+        vec![],
+    )
+    .await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    let csv = to_bytes(resp.into_body(), 1024 * 1024).await.unwrap();
+    assert_eq!(
+        String::from_utf8(csv.to_vec()).unwrap(),
+        format!(
+            "isolate_id,isolate,4 °C,25 °C,37 °C,R2A\r\n{cold},ISO-COLD,0.5,-,,+\r\n{warm},ISO-WARM,0,+,+,\r\n"
+        )
+    );
+    let (_, filtered) = get(&scoped, "/api/isolates/growth_matrix?no_growth_at=4").await;
+    assert_eq!(filtered["rows"].as_array().unwrap().len(), 1);
+    assert_eq!(filtered["rows"][0]["isolate_name"], "ISO-WARM");
+}
+
+/// Scenario: tests were recorded at 0 °C and -0 °C, which SQLite does not keep
+/// apart but PostgreSQL does.
+/// Expected behaviour: the matrix has one 0 °C column for both.
+#[test]
+fn negative_zero_is_the_same_condition_as_zero() {
+    let test = |temperature_celsius: f64| crate::growth_tests::db::Model {
+        id: uuid::Uuid::new_v4(),
+        isolate_id: uuid::Uuid::new_v4(),
+        temperature_celsius: Some(temperature_celsius),
+        medium: None,
+        salinity_percent: None,
+        ph: None,
+        grows: true,
+        growth_rate_per_hour: None,
+        incubation_days: None,
+        tested_on: None,
+        notes: None,
+        created_at: chrono::Utc::now(),
+    };
+    let conditions = conditions_of(&[test(0.0), test(-0.0), test(4.0), test(-0.0)]);
+    let temperatures: Vec<Option<f64>> = conditions
+        .iter()
+        .map(|condition| condition.temperature_celsius)
+        .collect();
+    assert_eq!(temperatures, [Some(0.0), Some(4.0)]);
+    assert!(temperatures[0].unwrap().is_sign_positive());
+}
@@ -0,0 +1,103 @@
+use crate::common::enums::IsolateImageType;
+use crate::files::models::ThumbnailUrls;
+use chrono::{DateTime, NaiveDate, Utc};
+use crudcrate::validation::{Validatable, ValidationError};
+use crudcrate::{CRUDResource, EntityToModels};
+use sea_orm::entity::prelude::*;
+use uuid::Uuid;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+#[sea_orm(table_name = "isolate_images")]
+#[crudcrate(
+    generate_router,
+    api_struct = "IsolateImage",
+    name_singular = "isolate_image",
+    name_plural = "isolate_images",
+    description = "Annotated colony, microscopy and Gram stain images of isolates",
+    no_eq,
+    derive_partial_eq,
+    create::one::pre = crate::isolate_images::services::reject_create,
+    create::many::pre = crate::isolate_images::services::reject_create_many,
+    read::one::transform = crate::isolate_images::services::with_urls,
+    read::many::transform = crate::isolate_images::services::with_list_urls,
+    update::one::pre = crate::isolate_images::services::check_update,
+    update::many::pre = crate::isolate_images::services::check_update_many,
+    update::one::body = crate::isolate_images::services::update_image,
+    update::many::body = crate::isolate_images::services::update_images,
+    update::one::transform = crate::isolate_images::services::with_urls,
+    delete::one::body = crate::isolate_images::services::delete_image,
+    delete::many::body = crate::isolate_images::services::delete_images
+)]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    #[crudcrate(primary_key, filterable, exclude(update, create), on_create = Uuid::new_v4())]
+    pub id: Uuid,
+    /// Images are added by uploading to `/api/isolates/{id}/images`.
+    #[crudcrate(sortable, filterable, exclude(update))]
+    pub isolate_id: Uuid,
+    #[crudcrate(filterable, exclude(create, update))]
+    pub file_id: Uuid,
+    #[crudcrate(sortable, filterable)]
+    pub image_type: IsolateImageType,
+    #[crudcrate(fulltext)]
+    pub caption: Option<String>,
+    /// Growth medium of a colony image, e.g. `R2A`.
+    #[crudcrate(sortable, filterable, fulltext)]
+    pub medium: Option<String>,
+    /// Total magnification of a microscopy image, e.g. `1000`.
+    #[crudcrate(sortable, filterable)]
+    pub magnification: Option<f64>,
+    #[crudcrate(sortable, filterable)]
+    pub captured_on: Option<NaiveDate>,
+    /// Sort key among the isolate's images; `/api/isolates/{id}/images/order`
+    /// renumbers them.
+    #[crudcrate(sortable, filterable, exclude(create), on_create = 0)]
+    pub position: i32,
+    /// The image shown for the isolate in lists. Setting it clears the flag on the
+    /// isolate's other images.
+    #[crudcrate(sortable, filterable, exclude(create), on_create = false)]
+    pub is_primary: bool,
+    #[crudcrate(sortable, filterable, exclude(create, update), on_create = chrono::Utc::now())]
+    pub created_at: DateTime<Utc>,
+    #[sea_orm(ignore)]
+    #[crudcrate(non_db_attr, exclude(create, update))]
+    pub file_url: String,
+    #[sea_orm(ignore)]
+    #[crudcrate(non_db_attr, exclude(create, update))]
+    pub thumbnails: ThumbnailUrls,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "crate::isolates::db::Entity",
+        from = "Column::IsolateId",
+        to = "crate::isolates::db::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Isolate,
+}
+
+impl Related<crate::isolates::db::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Isolate.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+pub(crate) fn validate_magnification(magnification: Option<f64>) -> Result<(), ValidationError> {
+    match magnification {
+        Some(value) if !(value.is_finite() && value > 0.0) => Err(ValidationError::new(
+            "magnification",
+            "Must be a positive number",
+        )),
+        _ => Ok(()),
+    }
+}
+
+impl Validatable for IsolateImageUpdate {
+    fn validate(&self) -> Result<(), ValidationError> {
+        validate_magnification(self.magnification.flatten())
+    }
+}
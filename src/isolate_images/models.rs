@@ -0,0 +1,26 @@
+use axum::body::Bytes;
+use chrono::NaiveDate;
+use serde::Deserialize;
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+use crate::common::enums::IsolateImageType;
+
+/// An uploaded image with the annotations sent alongside it.
+#[derive(Debug, Clone, Default)]
+pub struct ImageUpload {
+    pub filename: Option<String>,
+    pub bytes: Bytes,
+    pub image_type: IsolateImageType,
+    pub caption: Option<String>,
+    pub medium: Option<String>,
+    pub magnification: Option<f64>,
+    pub captured_on: Option<NaiveDate>,
+    pub is_primary: bool,
+}
+
+/// Every image of an isolate, in the order to show them.
+#[derive(ToSchema, Deserialize, Debug, Clone)]
+pub struct ImageOrder {
+    pub image_ids: Vec<Uuid>,
+}
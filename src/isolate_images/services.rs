@@ -0,0 +1,366 @@
+use axum::http::StatusCode;
+use crudcrate::validation::ValidationError;
+use crudcrate::{ApiError, MergeIntoActiveModel};
+use object_store::ObjectStore;
+use sea_orm::entity::prelude::*;
+use sea_orm::sea_query::{Expr, Query};
+use sea_orm::{
+    DatabaseConnection, IntoActiveModel, QueryOrder, QuerySelect, Set, TransactionTrait,
+};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+use super::db::{
+    validate_magnification, ActiveModel, Column, Entity, IsolateImage, IsolateImageCreate,
+    IsolateImageList, IsolateImageUpdate, Model,
+};
+use super::models::{ImageOrder, ImageUpload};
+use crate::common::batch;
+use crate::files::models::ThumbnailUrls;
+use crate::{files, isolates};
+
+pub fn file_url(id: Uuid) -> String {
+    format!("/api/isolate_images/{id}/file")
+}
+
+pub fn thumbnail_urls(id: Uuid) -> ThumbnailUrls {
+    ThumbnailUrls::under(&format!("/api/isolate_images/{id}/thumbnails"))
+}
+
+pub async fn with_urls(
+    _db: &DatabaseConnection,
+    mut image: IsolateImage,
+) -> Result<IsolateImage, ApiError> {
+    image.file_url = file_url(image.id);
+    image.thumbnails = thumbnail_urls(image.id);
+    Ok(image)
+}
+
+pub async fn with_list_urls(
+    _db: &DatabaseConnection,
+    mut images: Vec<IsolateImageList>,
+) -> Result<Vec<IsolateImageList>, ApiError> {
+    for image in &mut images {
+        image.file_url = file_url(image.id);
+        image.thumbnails = thumbnail_urls(image.id);
+    }
+    Ok(images)
+}
+
+fn upload_only() -> ApiError {
+    ApiError::custom(
+        StatusCode::METHOD_NOT_ALLOWED,
+        "Upload images as multipart/form-data to /api/isolates/{id}/images",
+        None,
+    )
+}
+
+pub async fn reject_create(
+    _db: &DatabaseConnection,
+    _data: &IsolateImageCreate,
+) -> Result<(), ApiError> {
+    Err(upload_only())
+}
+
+pub async fn reject_create_many(
+    _db: &DatabaseConnection,
+    _data: &[IsolateImageCreate],
+) -> Result<(), ApiError> {
+    Err(upload_only())
+}
+
+async fn find(db: &DatabaseConnection, id: Uuid) -> Result<Model, ApiError> {
+    Entity::find_by_id(id)
+        .one(db)
+        .await?
+        .ok_or_else(|| ApiError::not_found("isolate_image", Some(id.to_string())))
+}
+
+async fn find_isolate(db: &DatabaseConnection, id: Uuid) -> Result<isolates::db::Model, ApiError> {
+    isolates::db::Entity::find_by_id(id)
+        .one(db)
+        .await?
+        .ok_or_else(|| ApiError::not_found("isolate", Some(id.to_string())))
+}
+
+/// Clears the primary flag on the isolate's images, ahead of setting it on another.
+async fn clear_primary(db: &impl ConnectionTrait, isolate_id: Uuid) -> Result<(), ApiError> {
+    Entity::update_many()
+        .col_expr(Column::IsPrimary, Expr::value(false))
+        .filter(Column::IsolateId.eq(isolate_id))
+        .filter(Column::IsPrimary.eq(true))
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+/// An isolate with images always has a primary one: it can be moved to another
+/// image but not unset.
+pub async fn check_update(
+    db: &DatabaseConnection,
+    id: Uuid,
+    data: &IsolateImageUpdate,
+) -> Result<(), ApiError> {
+    let image = find(db, id).await?;
+    if data.is_primary == Some(Some(false)) && image.is_primary {
+        return Err(
+            ValidationError::new("is_primary", "Make another image primary instead").into(),
+        );
+    }
+    Ok(())
+}
+
+/// Applies an update, first taking the primary flag off the isolate's other images
+/// if the image becomes primary. Run it in a transaction, so a failed update leaves
+/// the previous primary in place.
+async fn update_one(
+    db: &impl ConnectionTrait,
+    id: Uuid,
+    data: IsolateImageUpdate,
+) -> Result<Model, ApiError> {
+    let image = Entity::find_by_id(id)
+        .one(db)
+        .await?
+        .ok_or_else(|| ApiError::not_found("isolate_image", Some(id.to_string())))?;
+    if data.is_primary == Some(Some(true)) && !image.is_primary {
+        clear_primary(db, image.isolate_id).await?;
+    }
+    let model = data.merge_into_activemodel(image.into_active_model())?;
+    Ok(model.update(db).await?)
+}
+
+pub async fn update_image(
+    db: &DatabaseConnection,
+    id: Uuid,
+    data: IsolateImageUpdate,
+) -> Result<IsolateImage, ApiError> {
+    let txn = db.begin().await?;
+    let image = update_one(&txn, id, data).await?;
+    txn.commit().await?;
+    Ok(image.into())
+}
+
+pub async fn update_images(
+    db: &DatabaseConnection,
+    updates: Vec<(Uuid, IsolateImageUpdate)>,
+) -> Result<Vec<IsolateImage>, ApiError> {
+    batch::check_limit::<IsolateImage>("update", updates.len())?;
+    let txn = db.begin().await?;
+    let mut result = Vec::with_capacity(updates.len());
+    for (id, data) in updates {
+        result.push(IsolateImage::from(update_one(&txn, id, data).await?));
+    }
+    txn.commit().await?;
+    Ok(result)
+}
+
+pub async fn check_update_many(
+    db: &DatabaseConnection,
+    updates: &[(Uuid, IsolateImageUpdate)],
+) -> Result<(), ApiError> {
+    let mut primaries = HashSet::new();
+    for (id, data) in updates {
+        if data.is_primary == Some(Some(true)) && !primaries.insert(find(db, *id).await?.isolate_id)
+        {
+            return Err(ValidationError::new(
+                "is_primary",
+                "Only one image per isolate can be primary",
+            )
+            .into());
+        }
+    }
+    for (id, data) in updates {
+        check_update(db, *id, data).await?;
+    }
+    Ok(())
+}
+
+/// Makes the first remaining image primary on those of the isolates whose primary
+/// was deleted.
+async fn promote_primaries(db: &DatabaseConnection, isolate_ids: &[Uuid]) -> Result<(), ApiError> {
+    let with_primary = Query::select()
+        .column(Column::IsolateId)
+        .from(Entity)
+        .and_where(Column::IsPrimary.eq(true))
+        .to_owned();
+    let candidates = Entity::find()
+        .filter(Column::IsolateId.is_in(isolate_ids.iter().copied()))
+        .filter(Column::IsolateId.not_in_subquery(with_primary))
+        .order_by_asc(Column::Position)
+        .order_by_asc(Column::CreatedAt)
+        .all(db)
+        .await?;
+    let mut promoted = HashSet::new();
+    for image in candidates {
+        if promoted.insert(image.isolate_id) {
+            Entity::update_many()
+                .col_expr(Column::IsPrimary, Expr::value(true))
+                .filter(Column::Id.eq(image.id))
+                .exec(db)
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+pub async fn delete_image(db: &DatabaseConnection, id: Uuid) -> Result<Uuid, ApiError> {
+    delete_images(db, vec![id])
+        .await?
+        .pop()
+        .ok_or_else(|| ApiError::not_found("isolate_image", Some(id.to_string())))
+}
+
+/// Deletes the images and promotes another image of each isolate that lost its
+/// primary. Their files are left to `files::services::remove_orphans`.
+pub async fn delete_images(db: &DatabaseConnection, ids: Vec<Uuid>) -> Result<Vec<Uuid>, ApiError> {
+    batch::check_limit::<IsolateImage>("delete", ids.len())?;
+    let images = Entity::find().filter(Column::Id.is_in(ids)).all(db).await?;
+    if images.is_empty() {
+        return Ok(vec![]);
+    }
+    let deleted: Vec<Uuid> = images.iter().map(|image| image.id).collect();
+    Entity::delete_many()
+        .filter(Column::Id.is_in(deleted.clone()))
+        .exec(db)
+        .await?;
+    let isolate_ids: Vec<Uuid> = images.iter().map(|image| image.isolate_id).collect();
+    promote_primaries(db, &isolate_ids).await?;
+    Ok(deleted)
+}
+
+/// The isolate's images in display order.
+pub async fn list(
+    db: &DatabaseConnection,
+    isolate_id: Uuid,
+) -> Result<Vec<IsolateImage>, ApiError> {
+    let images = Entity::find()
+        .filter(Column::IsolateId.eq(isolate_id))
+        .order_by_asc(Column::Position)
+        .order_by_asc(Column::CreatedAt)
+        .all(db)
+        .await?;
+    let mut listed = Vec::with_capacity(images.len());
+    for image in images {
+        listed.push(with_urls(db, image.into()).await?);
+    }
+    Ok(listed)
+}
+
+/// Number of images and the primary image of each isolate that has any.
+pub async fn summaries(
+    db: &DatabaseConnection,
+    isolate_ids: &[Uuid],
+) -> Result<HashMap<Uuid, (i64, Option<Uuid>)>, ApiError> {
+    let images: Vec<(Uuid, Uuid, bool)> = Entity::find()
+        .select_only()
+        .column(Column::IsolateId)
+        .column(Column::Id)
+        .column(Column::IsPrimary)
+        .filter(Column::IsolateId.is_in(isolate_ids.iter().copied()))
+        .into_tuple()
+        .all(db)
+        .await?;
+    let mut summaries: HashMap<Uuid, (i64, Option<Uuid>)> = HashMap::new();
+    for (isolate_id, id, is_primary) in images {
+        let summary = summaries.entry(isolate_id).or_default();
+        summary.0 += 1;
+        if is_primary {
+            summary.1 = Some(id);
+        }
+    }
+    Ok(summaries)
+}
+
+/// Stores an uploaded image after the isolate's others. The first image of an
+/// isolate is always primary.
+pub async fn upload(
+    db: &DatabaseConnection,
+    store: &dyn ObjectStore,
+    isolate_id: Uuid,
+    upload: ImageUpload,
+) -> Result<IsolateImage, ApiError> {
+    validate_magnification(upload.magnification)?;
+    find_isolate(db, isolate_id).await?;
+    let existing = Entity::find()
+        .filter(Column::IsolateId.eq(isolate_id))
+        .all(db)
+        .await?;
+    let position = existing
+        .iter()
+        .map(|image| image.position + 1)
+        .max()
+        .unwrap_or(0);
+    let is_primary = upload.is_primary || existing.is_empty();
+
+    let txn = db.begin().await?;
+    let file =
+        files::services::put_image(&txn, store, "image", upload.filename, upload.bytes).await?;
+    let inserted = async {
+        if is_primary {
+            clear_primary(&txn, isolate_id).await?;
+        }
+        let image = ActiveModel {
+            id: Set(Uuid::new_v4()),
+            isolate_id: Set(isolate_id),
+            file_id: Set(file.id),
+            image_type: Set(upload.image_type),
+            caption: Set(upload.caption),
+            medium: Set(upload.medium),
+            magnification: Set(upload.magnification),
+            captured_on: Set(upload.captured_on),
+            position: Set(position),
+            is_primary: Set(is_primary),
+            created_at: Set(chrono::Utc::now()),
+        }
+        .insert(&txn)
+        .await?;
+        txn.commit().await?;
+        Ok::<_, ApiError>(image)
+    }
+    .await;
+    let image = match inserted {
+        Ok(image) => image,
+        Err(err) => {
+            files::services::discard(store, file.id).await;
+            return Err(err);
+        }
+    };
+
+    with_urls(db, image.into()).await
+}
+
+/// Renumbers the isolate's images in the given order.
+pub async fn reorder(
+    db: &DatabaseConnection,
+    isolate_id: Uuid,
+    order: &ImageOrder,
+) -> Result<Vec<IsolateImage>, ApiError> {
+    find_isolate(db, isolate_id).await?;
+    let current: HashSet<Uuid> = Entity::find()
+        .filter(Column::IsolateId.eq(isolate_id))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|image| image.id)
+        .collect();
+    let requested: HashSet<Uuid> = order.image_ids.iter().copied().collect();
+    if requested.len() != order.image_ids.len() || requested != current {
+        return Err(ValidationError::new(
+            "image_ids",
+            "List each of the isolate's images exactly once",
+        )
+        .into());
+    }
+
+    let txn = db.begin().await?;
+    for (position, id) in order.image_ids.iter().enumerate() {
+        Entity::update_many()
+            .col_expr(Column::Position, Expr::value(position as i32))
+            .filter(Column::Id.eq(*id))
+            .exec(&txn)
+            .await?;
+    }
+    txn.commit().await?;
+
+    list(db, isolate_id).await
+}
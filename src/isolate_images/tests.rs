@@ -0,0 +1,411 @@
+use axum::{body::to_bytes, http::StatusCode};
+use sea_orm::{EntityTrait, PaginatorTrait};
+use serde_json::{json, Value};
+
+use crate::test_utils::{
+    build_app_with_db, build_scoped_app_with_db, create, create_field_record, create_site, get,
+    image_upload, json_body, send, send_raw, setup_sqlite_db, test_png,
+};
+
+/// A 300×200 JPEG whose EXIF orientation says to rotate it a quarter turn, as
+/// phones store portrait shots.
+fn rotated_jpeg() -> Vec<u8> {
+    let mut jpeg = Vec::new();
+    image::RgbImage::from_pixel(300, 200, image::Rgb([30, 90, 160]))
+        .write_to(
+            &mut std::io::Cursor::new(&mut jpeg),
+            image::ImageFormat::Jpeg,
+        )
+        .unwrap();
+    // APP1 segment holding one IFD entry: Orientation (0x0112) = 6.
+    let exif: &[u8] = b"\xFF\xE1\x00\x22Exif\x00\x00MM\x00\x2A\x00\x00\x00\x08\x00\x01\x01\x12\x00\x03\x00\x00\x00\x01\x00\x06\x00\x00\x00\x00\x00\x00";
+    jpeg.splice(2..2, exif.iter().copied());
+    jpeg
+}
+
+async fn upload(
+    app: &axum::Router,
+    uri: &str,
+    bytes: &[u8],
+    fields: &[(&str, &str)],
+) -> axum::response::Response {
+    let (content_type, body) = image_upload(bytes, fields);
+    send_raw(app, "POST", uri, &[("Content-Type", &content_type)], body).await
+}
+
+/// Uploads an image, asserting it was created, and returns the image.
+async fn uploaded(app: &axum::Router, uri: &str, bytes: &[u8], fields: &[(&str, &str)]) -> Value {
+    let resp = upload(app, uri, bytes, fields).await;
+    assert_eq!(resp.status(), StatusCode::CREATED);
+    json_body(resp).await
+}
+
+async fn file_count(db: &sea_orm::DatabaseConnection) -> u64 {
+    crate::files::db::Entity::find().count(db).await.unwrap()
+}
+
+/// Isolate `name` from snow field record FR-1 at Glacier A, and the URI of its
+/// image list.
+async fn isolate(app: &axum::Router, name: &str, is_private: bool) -> (String, String) {
+    let site_id = create_site(app, "Glacier A").await;
+    let fr = create_field_record(app, &site_id, "FR-1").await;
+    let isolate = create(
+        app,
+        "/api/isolates",
+        json!({ "name": name, "field_record_id": fr, "is_private": is_private }),
+    )
+    .await;
+    let images = format!("/api/isolates/{isolate}/images");
+    (isolate, images)
+}
+
+/// Scenario: files that are not images, images that do not decode, bad annotations,
+/// a public upload and a JSON create are sent for an isolate.
+/// Expected behaviour: each is refused and no file is left behind.
+#[tokio::test]
+async fn refused_uploads_leave_no_file() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db.clone());
+    let scoped = build_scoped_app_with_db(db.clone());
+    let (isolate, images) = isolate(&app, "ISO-1", false).await;
+
+    let (_, list) = get(&app, &images).await;
+    assert_eq!(list, json!([]));
+    let resp = upload(&app, &images, b"%PDF-1.4", &[]).await;
+    assert_eq!(
+        resp.status(),
+        StatusCode::UNPROCESSABLE_ENTITY,
+        "not an image"
+    );
+    let resp = upload(&app, &images, b"\x89PNG\r\n\x1a\nnot really pixels", &[]).await;
+    assert_eq!(
+        resp.status(),
+        StatusCode::UNPROCESSABLE_ENTITY,
+        "cannot be decoded"
+    );
+    let png = test_png();
+    for fields in [
+        [("image_type", "Photo")],
+        [("magnification", "-40")],
+        [("captured_on", "10/07/2025")],
+    ] {
+        let resp = upload(&app, &images, &png, &fields).await;
+        assert_eq!(
+            resp.status(),
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "{fields:?}"
+        );
+    }
+    let resp = upload(&scoped, &images, &png, &[]).await;
+    assert_eq!(resp.status(), StatusCode::FORBIDDEN);
+    let (status, _) = send(
+        &app,
+        "POST",
+        "/api/isolate_images",
+        json!({ "isolate_id": isolate, "image_type": "Colony" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::METHOD_NOT_ALLOWED);
+    assert_eq!(file_count(&db).await, 0);
+}
+
+/// Scenario: an annotated plate photo and then a microscopy image are uploaded.
+/// Expected behaviour: the first image is primary and later ones queue after it;
+/// the file streams publicly with its sniffed type and an ETag answering 304.
+#[tokio::test]
+async fn first_image_is_primary_and_files_stream_with_etags() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db.clone());
+    let scoped = build_scoped_app_with_db(db);
+    let (_, images) = isolate(&app, "ISO-1", false).await;
+    let png = test_png();
+
+    let colony = uploaded(
+        &app,
+        &images,
+        &png,
+        &[
+            ("caption", "Orange colonies"),
+            ("medium", "R2A"),
+            ("captured_on", "2025-07-14"),
+        ],
+    )
+    .await;
+    assert_eq!(colony["image_type"], "Colony");
+    assert_eq!(colony["medium"], "R2A");
+    assert_eq!(colony["captured_on"], "2025-07-14");
+    assert_eq!(colony["position"], 0);
+    assert_eq!(colony["is_primary"], true);
+
+    let file = colony["file_url"].as_str().unwrap();
+    let resp = send_raw(&scoped, "GET", file, &[], vec![]).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()["content-type"], "image/png");
+    assert_eq!(resp.headers()["cache-control"], "public, no-cache");
+    let etag = resp.headers()["etag"].to_str().unwrap().to_string();
+    let bytes = to_bytes(resp.into_body(), 1024 * 1024).await.unwrap();
+    assert_eq!(bytes, png);
+    let resp = send_raw(&app, "GET", file, &[("If-None-Match", &etag)], vec![]).await;
+    assert_eq!(resp.status(), StatusCode::NOT_MODIFIED);
+
+    let microscopy = uploaded(
+        &app,
+        &images,
+        &png,
+        &[("image_type", "Microscopy"), ("magnification", "1000")],
+    )
+    .await;
+    assert_eq!(microscopy["magnification"], 1000.0);
+    assert_eq!(microscopy["position"], 1);
+    assert_eq!(microscopy["is_primary"], false);
+}
+
+/// Scenario: a portrait phone shot is uploaded and its thumbnails fetched.
+/// Expected behaviour: thumbnails are upright JPEGs without metadata, scaled to the
+/// requested size and cached by ETag; unknown sizes are a 400.
+#[tokio::test]
+async fn thumbnails_are_upright_jpegs_without_metadata() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db.clone());
+    let scoped = build_scoped_app_with_db(db);
+    let (_, images) = isolate(&app, "ISO-1", false).await;
+    let image = uploaded(&app, &images, &rotated_jpeg(), &[]).await;
+
+    let small = image["thumbnails"]["small"].as_str().unwrap();
+    let resp = send_raw(&scoped, "GET", small, &[], vec![]).await;
+    assert_eq!(resp.status(), StatusCode::OK);
+    assert_eq!(resp.headers()["content-type"], "image/jpeg");
+    let etag = resp.headers()["etag"].to_str().unwrap().to_string();
+    let bytes = to_bytes(resp.into_body(), 1024 * 1024).await.unwrap();
+    assert!(
+        !bytes.windows(4).any(|window| window == b"Exif"),
+        "metadata is stripped"
+    );
+    let thumbnail = image::load_from_memory(&bytes).unwrap();
+    assert_eq!(
+        (thumbnail.width(), thumbnail.height()),
+        (107, 160),
+        "rotated upright and scaled to fit 160 px"
+    );
+    let resp = send_raw(&app, "GET", small, &[("If-None-Match", &etag)], vec![]).await;
+    assert_eq!(resp.status(), StatusCode::NOT_MODIFIED);
+    let (status, _) = get(
+        &app,
+        &format!(
+            "/api/isolate_images/{}/thumbnails/huge",
+            image["id"].as_str().unwrap()
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+/// Scenario: the primary flag moves to the second image, which is then put first.
+/// Expected behaviour: the flag can only be moved, not cleared, and moving it clears
+/// it on the other image; reordering needs every image; the isolate list counts the
+/// images and shows the primary's thumbnails.
+#[tokio::test]
+async fn primary_flag_moves_and_images_reorder() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db.clone());
+    let scoped = build_scoped_app_with_db(db);
+    let (isolate, images) = isolate(&app, "ISO-1", false).await;
+    let colony = uploaded(&app, &images, &test_png(), &[]).await;
+    let microscopy = uploaded(&app, &images, &rotated_jpeg(), &[]).await;
+    let colony_id = colony["id"].as_str().unwrap();
+    let microscopy_id = microscopy["id"].as_str().unwrap();
+
+    let (status, _) = send(
+        &app,
+        "PUT",
+        &format!("/api/isolate_images/{colony_id}"),
+        json!({ "is_primary": false }),
+    )
+    .await;
+    assert_eq!(
+        status,
+        StatusCode::UNPROCESSABLE_ENTITY,
+        "the primary image can only be replaced"
+    );
+    let (status, body) = send(
+        &app,
+        "PUT",
+        &format!("/api/isolate_images/{microscopy_id}"),
+        json!({ "is_primary": true, "caption": "Rods, 1000x oil" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["is_primary"], true);
+
+    let order = format!("{images}/order");
+    let (status, _) = send(&app, "PUT", &order, json!({ "image_ids": [microscopy_id] })).await;
+    assert_eq!(
+        status,
+        StatusCode::UNPROCESSABLE_ENTITY,
+        "every image listed"
+    );
+    let (status, reordered) = send(
+        &app,
+        "PUT",
+        &order,
+        json!({ "image_ids": [microscopy_id, colony_id] }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(reordered[0]["id"], microscopy_id);
+    assert_eq!(reordered[0]["is_primary"], true);
+    assert_eq!(reordered[0]["caption"], "Rods, 1000x oil");
+    assert_eq!(reordered[1]["id"], colony_id);
+    assert_eq!(
+        reordered[1]["is_primary"], false,
+        "moving the flag clears it elsewhere"
+    );
+    assert_eq!(reordered[1]["position"], 1);
+
+    let (_, list) = get(&scoped, "/api/isolates").await;
+    assert_eq!(list[0]["image_count"], 2);
+    assert_eq!(
+        list[0]["thumbnails"]["small"], microscopy["thumbnails"]["small"],
+        "thumbnails of the primary image"
+    );
+    let (status, filtered) = get(
+        &scoped,
+        &format!("/api/isolate_images?filter=%7B%22isolate_id%22%3A%22{isolate}%22%7D"),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(filtered.as_array().unwrap().len(), 2);
+}
+
+/// Scenario: a batch update makes the second image primary but also names an image
+/// that does not exist.
+/// Expected behaviour: the batch is refused as a whole and the first image is still
+/// the primary one.
+#[tokio::test]
+async fn refused_primary_updates_keep_the_primary() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db);
+    let (_, images) = isolate(&app, "ISO-1", false).await;
+    let colony = uploaded(&app, &images, &test_png(), &[]).await;
+    let microscopy = uploaded(&app, &images, &test_png(), &[]).await;
+
+    let (status, _) = send(
+        &app,
+        "PATCH",
+        "/api/isolate_images/batch",
+        json!([
+            { "id": microscopy["id"], "is_primary": true },
+            { "id": uuid::Uuid::new_v4(), "caption": "Lost" }
+        ]),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (_, listed) = get(&app, &images).await;
+    assert_eq!(listed[0]["id"], colony["id"]);
+    assert_eq!(listed[0]["is_primary"], true);
+    assert_eq!(listed[1]["is_primary"], false);
+}
+
+/// Scenario: the field record an isolate with an image was cultured from is deleted.
+/// Expected behaviour: the delete is refused while the isolate exists, so neither
+/// the isolate nor its image's file is lost.
+#[tokio::test]
+async fn field_records_with_isolates_cannot_be_deleted() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db.clone());
+    let (isolate, images) = isolate(&app, "ISO-1", false).await;
+    uploaded(&app, &images, &test_png(), &[]).await;
+    let (_, record) = get(&app, &format!("/api/isolates/{isolate}")).await;
+
+    let (status, _) = send(
+        &app,
+        "DELETE",
+        &format!(
+            "/api/field_records/{}",
+            record["field_record_id"].as_str().unwrap()
+        ),
+        Value::Null,
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    let (status, _) = get(&app, &format!("/api/isolates/{isolate}")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(file_count(&db).await, 1);
+}
+
+/// Scenario: the primary image is deleted, then a batch of images, then the isolate.
+/// Expected behaviour: the next image is promoted to primary and no file outlives
+/// its image.
+#[tokio::test]
+async fn deleting_images_promotes_the_next_and_removes_files() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db.clone());
+    let (isolate, images) = isolate(&app, "ISO-1", false).await;
+    let mut ids = Vec::new();
+    for _ in 0..3 {
+        ids.push(uploaded(&app, &images, &test_png(), &[]).await["id"].clone());
+    }
+    assert_eq!(file_count(&db).await, 3);
+
+    let (status, _) = send(
+        &app,
+        "DELETE",
+        &format!("/api/isolate_images/{}", ids[0].as_str().unwrap()),
+        Value::Null,
+    )
+    .await;
+    assert!(status.is_success());
+    let (_, remaining) = get(&app, &images).await;
+    assert_eq!(remaining.as_array().unwrap().len(), 2);
+    assert_eq!(remaining[0]["id"], ids[1]);
+    assert_eq!(
+        remaining[0]["is_primary"], true,
+        "the next image is promoted"
+    );
+    assert_eq!(file_count(&db).await, 2);
+
+    let (status, _) = send(&app, "DELETE", "/api/isolate_images/batch", json!([ids[1]])).await;
+    assert!(status.is_success());
+    let (_, remaining) = get(&app, &images).await;
+    assert_eq!(remaining[0]["id"], ids[2]);
+    assert_eq!(remaining[0]["is_primary"], true);
+    assert_eq!(file_count(&db).await, 1);
+
+    let (status, _) = send(
+        &app,
+        "DELETE",
+        &format!("/api/isolates/{isolate}"),
+        Value::Null,
+    )
+    .await;
+    assert!(status.is_success());
+    assert_eq!(
+        crate::isolate_images::db::Entity::find()
+            .count(&db)
+            .await
+            .unwrap(),
+        0
+    );
+    assert_eq!(file_count(&db).await, 0);
+}
+
+/// Scenario: a public caller asks for the images of a private isolate.
+/// Expected behaviour: the isolate, its image list and the files are not found.
+#[tokio::test]
+async fn private_isolate_images_are_hidden() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db.clone());
+    let scoped = build_scoped_app_with_db(db);
+    let (_, images) = isolate(&app, "ISO-2", true).await;
+    let image = uploaded(&app, &images, &test_png(), &[]).await;
+    let file = image["file_url"].as_str().unwrap();
+
+    let (status, _) = get(&scoped, &images).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let resp = send_raw(&scoped, "GET", file, &[], vec![]).await;
+    assert_eq!(resp.status(), StatusCode::NOT_FOUND);
+    let (_, list) = get(&scoped, "/api/isolate_images").await;
+    assert_eq!(list, json!([]));
+    let resp = send_raw(&app, "GET", file, &[], vec![]).await;
+    assert_eq!(resp.headers()["cache-control"], "private, no-cache");
+}
@@ -0,0 +1,144 @@
+use axum::body::Body;
+use axum::extract::{Path, Request, State};
+use axum::http::{header, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::{routing::get, Extension, Router};
+use crudcrate::ApiError;
+use object_store::ObjectStore;
+use sea_orm::{DatabaseConnection, EntityTrait, QueryFilter};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::files::{self, models::ThumbnailSize};
+use crate::{isolates, middleware};
+
+/// Routes mounted next to the generated CRUD router under `/api/isolate_images`.
+pub fn router(db: &DatabaseConnection, store: &Arc<dyn ObjectStore>) -> Router {
+    Router::new()
+        .route("/{id}/file", get(get_file))
+        .route("/{id}/thumbnails/{size}", get(get_thumbnail))
+        .layer(Extension(store.clone()))
+        .with_state(db.clone())
+}
+
+/// The image's isolate, if the caller may see it, and its file.
+async fn visible_file(
+    db: &DatabaseConnection,
+    id: Uuid,
+    scoped: bool,
+) -> Result<(isolates::db::Model, files::db::Model), ApiError> {
+    let not_found = || ApiError::not_found("isolate_image", Some(id.to_string()));
+    let image = super::db::Entity::find_by_id(id)
+        .one(db)
+        .await?
+        .ok_or_else(not_found)?;
+    let mut isolate = isolates::db::Entity::find_by_id(image.isolate_id);
+    if scoped {
+        isolate = isolate.filter(middleware::isolates_scope());
+    }
+    let isolate = isolate.one(db).await?.ok_or_else(not_found)?;
+    let file = files::services::find(db, image.file_id).await?;
+    Ok((isolate, file))
+}
+
+/// Streams an image, or answers 304 when the client's copy is current. Files are
+/// never rewritten, so `etag` derived from the file id is a strong validator.
+async fn image_response(
+    if_none_match: Option<HeaderValue>,
+    isolate: &isolates::db::Model,
+    etag: String,
+    content_type: &str,
+    body: impl std::future::Future<Output = Result<Body, ApiError>>,
+) -> Result<Response, ApiError> {
+    let cache_control = if isolate.is_private {
+        "private, no-cache"
+    } else {
+        "public, no-cache"
+    };
+    let unchanged = if_none_match.is_some_and(|value| value.as_bytes() == etag.as_bytes());
+    if unchanged {
+        return Ok((
+            StatusCode::NOT_MODIFIED,
+            [
+                (header::ETAG, etag),
+                (header::CACHE_CONTROL, cache_control.to_string()),
+            ],
+        )
+            .into_response());
+    }
+    Ok((
+        [
+            (header::CONTENT_TYPE, content_type.to_string()),
+            (header::ETAG, etag),
+            (header::CACHE_CONTROL, cache_control.to_string()),
+        ],
+        body.await?,
+    )
+        .into_response())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/isolate_images/{id}/file",
+    params(("id" = Uuid, Path, description = "Isolate image id")),
+    responses(
+        (status = OK, description = "The image as uploaded, streamed with its image content type"),
+        (status = NOT_MODIFIED, description = "The image matches `If-None-Match`"),
+        (status = NOT_FOUND, description = "Image not found")
+    )
+)]
+pub async fn get_file(
+    State(db): State<DatabaseConnection>,
+    Extension(store): Extension<Arc<dyn ObjectStore>>,
+    Path(id): Path<Uuid>,
+    req: Request,
+) -> Result<Response, ApiError> {
+    let if_none_match = req.headers().get(header::IF_NONE_MATCH).cloned();
+    let (isolate, file) = visible_file(&db, id, middleware::is_scoped(&req)).await?;
+    let mut response = image_response(
+        if_none_match,
+        &isolate,
+        format!("\"{}\"", file.id),
+        &file.content_type,
+        files::services::open(&*store, &file),
+    )
+    .await?;
+    if response.status() == StatusCode::OK {
+        response
+            .headers_mut()
+            .insert(header::CONTENT_LENGTH, file.size_bytes.into());
+    }
+    Ok(response)
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/isolate_images/{id}/thumbnails/{size}",
+    params(
+        ("id" = Uuid, Path, description = "Isolate image id"),
+        ("size" = ThumbnailSize, Path, description = "`small` (160 px), `medium` (480 px) or `large` (1024 px)")
+    ),
+    responses(
+        (status = OK, description = "Upright JPEG thumbnail without the image's metadata", content_type = "image/jpeg"),
+        (status = NOT_MODIFIED, description = "The thumbnail matches `If-None-Match`"),
+        (status = NOT_FOUND, description = "Image not found"),
+        (status = UNPROCESSABLE_ENTITY, description = "The stored image cannot be decoded")
+    )
+)]
+pub async fn get_thumbnail(
+    State(db): State<DatabaseConnection>,
+    Extension(store): Extension<Arc<dyn ObjectStore>>,
+    Path((id, size)): Path<(Uuid, ThumbnailSize)>,
+    req: Request,
+) -> Result<Response, ApiError> {
+    let if_none_match = req.headers().get(header::IF_NONE_MATCH).cloned();
+    let (isolate, file) = visible_file(&db, id, middleware::is_scoped(&req)).await?;
+    image_response(
+        if_none_match,
+        &isolate,
+        format!("\"{}-{size}\"", file.id),
+        "image/jpeg",
+        files::thumbnails::open(&*store, &file, size),
+    )
+    .await
+}
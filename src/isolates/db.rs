@@ -1,3 +1,5 @@
+use crate::common::enums::TaxonomySource;
+use crate::files::models::ThumbnailUrls;
 use chrono::{DateTime, Utc};
 use crudcrate::{CRUDResource, EntityToModels};
 use sea_orm::entity::prelude::*;
@@ -10,10 +12,17 @@ use uuid::Uuid;
     api_struct = "Isolate",
     name_singular = "isolate",
     name_plural = "isolates",
-    description = "Biological isolates with photos and metadata from sample collection sites",
+    description = "Biological isolates with images and metadata from sample collection sites",
     no_eq,
     derive_partial_eq,
-    read::many::body = crate::isolates::services::get_all_isolates_with_photo_flag
+    create::one::body = crate::isolates::services::create_isolate,
+    create::many::body = crate::isolates::services::create_isolates,
+    update::one::body = crate::isolates::services::update_isolate,
+    update::many::body = crate::isolates::services::update_isolates,
+    read::one::transform = crate::isolates::services::with_images,
+    read::many::body = crate::isolates::services::get_all_isolates_with_images,
+    delete::one::body = crate::isolates::services::delete_isolate,
+    delete::many::body = crate::isolates::services::delete_isolates
 )]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
@@ -21,6 +30,10 @@ pub struct Model {
     pub id: Uuid,
     #[crudcrate(sortable, filterable)]
     pub field_record_id: Uuid,
+    /// Sample the isolate was cultured from, taken under the same field record.
+    /// Hidden from public callers, as the sample may be private.
+    #[crudcrate(sortable, filterable, exclude(scoped))]
+    pub sample_id: Option<Uuid>,
 
     #[sea_orm(unique)]
     #[crudcrate(sortable, filterable, fulltext)]
@@ -28,12 +41,36 @@ pub struct Model {
 
     #[crudcrate(sortable, filterable, fulltext)]
     pub taxonomy: Option<String>,
-    #[crudcrate(filterable, exclude(list))]
-    pub photo: Option<String>,
+    /// Ranked lineage, parsed from `taxonomy` when it is saved without any rank;
+    /// set the ranks directly for lineages the parser cannot read.
+    #[crudcrate(sortable, filterable)]
+    pub domain: Option<String>,
+    #[crudcrate(sortable, filterable)]
+    pub phylum: Option<String>,
+    #[crudcrate(sortable, filterable)]
+    pub class: Option<String>,
+    /// Named apart from the `order` sort parameter.
+    #[crudcrate(sortable, filterable)]
+    pub taxonomic_order: Option<String>,
+    #[crudcrate(sortable, filterable)]
+    pub family: Option<String>,
+    #[crudcrate(sortable, filterable)]
+    pub genus: Option<String>,
+    /// Binomial, e.g. `Pseudomonas fluorescens`.
+    #[crudcrate(sortable, filterable, fulltext)]
+    pub species: Option<String>,
+    /// Reference taxonomy the lowest rank resolved against, once one is loaded.
+    #[crudcrate(sortable, filterable, exclude(create, update))]
+    pub taxon_source: Option<TaxonomySource>,
+    /// NCBI tax id or GTDB taxon of the lowest rank.
+    #[crudcrate(sortable, filterable, exclude(create, update))]
+    pub taxon_id: Option<String>,
     #[crudcrate(sortable, filterable)]
     pub temperature_of_isolation: Option<f64>,
     #[crudcrate(sortable, filterable, fulltext)]
     pub media_used_for_isolation: Option<String>,
+    /// Free-text location from before `storage_positions`; kept for items that have
+    /// not been placed in a box yet.
     #[crudcrate(sortable, filterable, fulltext, exclude(scoped))]
     pub storage_location: Option<String>,
     #[crudcrate(sortable, filterable, fulltext, exclude(scoped))]
@@ -41,13 +78,41 @@ pub struct Model {
 
     #[crudcrate(sortable, filterable, fulltext)]
     pub genome_url: Option<String>,
+    /// Uploaded FASTA assembly, downloaded from `assembly_url`.
+    #[crudcrate(filterable, exclude(create, update))]
+    pub assembly_file_id: Option<Uuid>,
+    /// Assembly statistics, computed on upload.
+    #[crudcrate(sortable, filterable, exclude(create, update))]
+    pub assembly_length_bp: Option<i64>,
+    #[crudcrate(sortable, filterable, exclude(create, update))]
+    pub assembly_contigs: Option<i32>,
+    #[crudcrate(sortable, filterable, exclude(create, update))]
+    pub assembly_n50_bp: Option<i64>,
+    #[crudcrate(sortable, filterable, exclude(create, update))]
+    pub assembly_gc_percent: Option<f64>,
+    /// Checksums of the file as uploaded, compressed or not.
+    #[crudcrate(filterable, exclude(create, update))]
+    pub assembly_sha256: Option<String>,
+    #[crudcrate(filterable, exclude(create, update))]
+    pub assembly_md5: Option<String>,
+    /// Times thawed, counted from `Thawed` custody events.
+    #[crudcrate(sortable, filterable, exclude(create, update), on_create = 0)]
+    pub freeze_thaw_cycles: i32,
     #[crudcrate(filterable, exclude(scoped), on_create = false)]
     pub is_private: bool,
     #[crudcrate(sortable, filterable, exclude(create, update), on_create = chrono::Utc::now())]
     pub created_at: DateTime<Utc>,
     #[sea_orm(ignore)]
     #[crudcrate(non_db_attr, exclude(create, update))]
-    pub has_photo: bool,
+    pub image_count: i64,
+    /// Thumbnails of the primary image, set when the isolate has images.
+    #[sea_orm(ignore)]
+    #[crudcrate(non_db_attr, exclude(create, update))]
+    pub thumbnails: Option<ThumbnailUrls>,
+    /// Set when the isolate has an assembly.
+    #[sea_orm(ignore)]
+    #[crudcrate(non_db_attr, exclude(create, update))]
+    pub assembly_url: Option<String>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -58,6 +123,13 @@ pub enum Relation {
         to = "crate::field_records::db::Column::Id"
     )]
     FieldRecord,
+    #[sea_orm(
+        belongs_to = "crate::samples::db::Entity",
+        from = "Column::SampleId",
+        to = "crate::samples::db::Column::Id",
+        on_delete = "SetNull"
+    )]
+    Sample,
 }
 
 impl Related<crate::field_records::db::Entity> for Entity {
@@ -65,4 +137,19 @@ impl Related<crate::field_records::db::Entity> for Entity {
         Relation::FieldRecord.def()
     }
 }
-impl ActiveModelBehavior for ActiveModel {}
+
+impl Related<crate::samples::db::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Sample.def()
+    }
+}
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        super::lineage::fill(&mut self, insert);
+        Ok(self)
+    }
+}
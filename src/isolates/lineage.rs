@@ -0,0 +1,176 @@
+//! Ranked lineage read from the free-text `taxonomy` of an isolate.
+//!
+//! Accepted forms are rank-prefixed lineages as written by GTDB, QIIME and
+//! SILVA (`d__Bacteria;p__Pseudomonadota;...;g__Pseudomonas`), lineages separated
+//! by `;`, `|` or `>` from the domain down or up to the genus, and plain names
+//! (`Pseudomonas`, `Pseudomonas fluorescens Pf-5`).
+
+use sea_orm::ActiveValue::{self, Set};
+
+use super::db::ActiveModel;
+
+/// Rank names, from the top down, as used in the tree and the filters.
+pub const RANKS: [&str; 7] = [
+    "domain",
+    "phylum",
+    "class",
+    "taxonomic_order",
+    "family",
+    "genus",
+    "species",
+];
+
+const GENUS: usize = 5;
+const SPECIES: usize = 6;
+
+const DOMAINS: [&str; 5] = ["Bacteria", "Archaea", "Eukaryota", "Eukarya", "Viruses"];
+
+/// Placeholders that mean the rank is not known.
+const UNKNOWN: [&str; 6] = ["", "na", "n/a", "unclassified", "unknown", "uncultured"];
+
+/// One name per rank in `RANKS` order.
+pub type Lineage = [Option<String>; 7];
+
+fn rank_of_prefix(prefix: &str) -> Option<usize> {
+    match prefix.to_ascii_lowercase().as_str() {
+        "d" | "k" => Some(0),
+        "p" => Some(1),
+        "c" => Some(2),
+        "o" => Some(3),
+        "f" => Some(4),
+        "g" => Some(5),
+        "s" => Some(6),
+        _ => None,
+    }
+}
+
+fn name(value: &str) -> Option<String> {
+    let value = value.trim().trim_matches('"').trim();
+    let known = !UNKNOWN.contains(&value.to_ascii_lowercase().as_str())
+        && !value.to_ascii_lowercase().starts_with("unclassified ");
+    known.then(|| value.to_string())
+}
+
+/// Genus and, unless the epithet is `sp.`, species of a binomial. Words after the
+/// epithet, such as a strain, are dropped.
+fn binomial(text: &str) -> (Option<String>, Option<String>) {
+    let mut words = text.split_whitespace();
+    let Some(mut genus) = words.next().map(str::to_string) else {
+        return (None, None);
+    };
+    if genus == "Candidatus" {
+        match words.next() {
+            Some(word) => genus = format!("Candidatus {word}"),
+            None => return (None, None),
+        }
+    }
+    if !genus.starts_with(|c: char| c.is_uppercase()) {
+        return (None, None);
+    }
+    let species = words
+        .next()
+        .filter(|epithet| {
+            epithet.starts_with(|c: char| c.is_lowercase())
+                && !matches!(*epithet, "sp" | "sp." | "spp" | "spp.")
+        })
+        .map(|epithet| format!("{genus} {epithet}"));
+    (Some(genus), species)
+}
+
+fn is_binomial(text: &str) -> bool {
+    binomial(text).1.is_some()
+}
+
+pub fn parse(taxonomy: &str) -> Lineage {
+    let mut lineage = Lineage::default();
+    let parts: Vec<&str> = taxonomy
+        .split([';', '|', '>'])
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .collect();
+
+    let prefixed: Vec<(usize, &str)> = parts
+        .iter()
+        .filter_map(|part| {
+            let (prefix, value) = part.split_once("__")?;
+            Some((rank_of_prefix(prefix)?, value))
+        })
+        .collect();
+    if !prefixed.is_empty() {
+        for (rank, value) in prefixed {
+            lineage[rank] = name(value);
+        }
+        // Greengenes writes only the epithet at the species rank.
+        if let (Some(genus), Some(species)) = (&lineage[GENUS], &lineage[SPECIES]) {
+            if species.starts_with(|c: char| c.is_lowercase()) {
+                lineage[SPECIES] = Some(format!("{genus} {species}"));
+            }
+        }
+    } else if let [single] = parts.as_slice() {
+        (lineage[GENUS], lineage[SPECIES]) = binomial(single);
+    } else if !parts.is_empty() {
+        let (ranked, species) = match parts.split_last() {
+            Some((last, rest)) if is_binomial(last) => (rest, Some(*last)),
+            _ => (parts.as_slice(), None),
+        };
+        let start = if DOMAINS.contains(&ranked[0]) {
+            0
+        } else {
+            (GENUS + 1).saturating_sub(ranked.len())
+        };
+        for (rank, part) in (start..SPECIES).zip(ranked) {
+            lineage[rank] = name(part);
+        }
+        if let Some(species) = species {
+            let (genus, species) = binomial(species);
+            lineage[SPECIES] = species;
+            if lineage[GENUS].is_none() {
+                lineage[GENUS] = genus;
+            }
+        }
+    }
+    if lineage[GENUS].is_none() {
+        lineage[GENUS] = lineage[SPECIES]
+            .as_deref()
+            .and_then(|species| binomial(species).0);
+    }
+    lineage
+}
+
+/// The rank fields of an isolate in `RANKS` order.
+pub fn ranks(model: &mut ActiveModel) -> [&mut ActiveValue<Option<String>>; 7] {
+    [
+        &mut model.domain,
+        &mut model.phylum,
+        &mut model.class,
+        &mut model.taxonomic_order,
+        &mut model.family,
+        &mut model.genus,
+        &mut model.species,
+    ]
+}
+
+/// Fills the ranks from `taxonomy` when it is saved without any of them: on insert
+/// when all are empty, on update when the taxonomy is changed and no rank is.
+pub fn fill(model: &mut ActiveModel, insert: bool) {
+    let taxonomy = match &model.taxonomy {
+        ActiveValue::Set(taxonomy) => taxonomy.clone(),
+        _ => return,
+    };
+    let derive = if insert {
+        ranks(model)
+            .iter()
+            .all(|rank| matches!(rank, ActiveValue::NotSet | ActiveValue::Set(None)))
+    } else {
+        ranks(model)
+            .iter()
+            .all(|rank| !matches!(rank, ActiveValue::Set(_)))
+    };
+    if !derive {
+        return;
+    }
+    let lineage = taxonomy.as_deref().map(parse).unwrap_or_default();
+    for (rank, value) in ranks(model).into_iter().zip(lineage) {
+        *rank = Set(value);
+    }
+}
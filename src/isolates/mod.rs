@@ -1,4 +1,8 @@
 pub mod db;
+pub mod lineage;
+pub mod models;
+pub mod render;
 pub mod services;
 #[cfg(test)]
 mod tests;
+pub mod views;
@@ -0,0 +1,46 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use super::db::Model;
+use crate::{field_records, sites};
+
+/// A taxon and the isolates classified under it. `name` is null for isolates
+/// whose lineage skips this rank but continues below it.
+#[derive(ToSchema, Serialize, Debug, Clone, PartialEq)]
+pub struct TaxonNode {
+    /// One of `domain`, `phylum`, `class`, `taxonomic_order`, `family`, `genus`
+    /// and `species`.
+    pub rank: String,
+    pub name: Option<String>,
+    /// Isolates at or below this taxon.
+    pub count: u64,
+    #[schema(no_recursion)]
+    pub children: Vec<TaxonNode>,
+}
+
+#[derive(ToSchema, Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportStandard {
+    /// Darwin Core occurrence terms, as taken by GBIF.
+    DarwinCore,
+    /// MIxS sample terms, as taken by the INSDC archives.
+    Mixs,
+}
+
+#[derive(Deserialize, IntoParams, Debug)]
+pub struct ExportParams {
+    /// `darwin_core` or `mixs`.
+    pub standard: ExportStandard,
+}
+
+/// An isolate with where it was sampled and where it is deposited, for the exports.
+#[derive(Debug, Clone)]
+pub struct ExportRecord {
+    pub isolate: Model,
+    pub field_record: field_records::db::Model,
+    pub site: sites::db::Model,
+    /// Left out for private areas when exporting to the public.
+    pub area: Option<String>,
+    /// `collection:accession` for each deposit, such as `DSMZ:DSM 12345`.
+    pub accessions: Vec<String>,
+}
@@ -0,0 +1,106 @@
+//! Darwin Core and MIxS CSV downloads of isolates.
+
+use super::models::ExportRecord;
+use crate::common::csv;
+
+const DARWIN_CORE: [&str; 18] = [
+    "occurrenceID",
+    "catalogNumber",
+    "basisOfRecord",
+    "scientificName",
+    "kingdom",
+    "phylum",
+    "class",
+    "order",
+    "family",
+    "genus",
+    "eventDate",
+    "habitat",
+    "decimalLatitude",
+    "decimalLongitude",
+    "geodeticDatum",
+    "minimumElevationInMeters",
+    "locality",
+    "otherCatalogNumbers",
+];
+
+const MIXS: [&str; 6] = [
+    "samp_name",
+    "source_mat_id",
+    "collection_date",
+    "lat_lon",
+    "elev",
+    "env_medium",
+];
+
+/// The lowest rank the isolate was classified to.
+fn scientific_name(record: &ExportRecord) -> String {
+    let isolate = &record.isolate;
+    [
+        &isolate.species,
+        &isolate.genus,
+        &isolate.family,
+        &isolate.taxonomic_order,
+        &isolate.class,
+        &isolate.phylum,
+        &isolate.domain,
+    ]
+    .into_iter()
+    .find_map(Clone::clone)
+    .unwrap_or_default()
+}
+
+/// `Area, Site`, or the site alone when the area is unknown or private.
+fn locality(record: &ExportRecord) -> String {
+    match &record.area {
+        Some(area) => format!("{area}, {}", record.site.name),
+        None => record.site.name.clone(),
+    }
+}
+
+pub fn darwin_core(records: &[ExportRecord]) -> String {
+    let mut out = csv::record(&DARWIN_CORE);
+    for record in records {
+        let isolate = &record.isolate;
+        let rank = |rank: &Option<String>| rank.clone().unwrap_or_default();
+        out.push_str(&csv::record(&[
+            isolate.id.to_string(),
+            isolate.name.clone(),
+            "LivingSpecimen".to_string(),
+            scientific_name(record),
+            rank(&isolate.domain),
+            rank(&isolate.phylum),
+            rank(&isolate.class),
+            rank(&isolate.taxonomic_order),
+            rank(&isolate.family),
+            rank(&isolate.genus),
+            record.field_record.sampling_date.to_string(),
+            record.field_record.sample_type.clone(),
+            record.site.latitude_4326.to_string(),
+            record.site.longitude_4326.to_string(),
+            "EPSG:4326".to_string(),
+            record.site.elevation_metres.to_string(),
+            locality(record),
+            record.accessions.join(" | "),
+        ]));
+    }
+    out
+}
+
+pub fn mixs(records: &[ExportRecord]) -> String {
+    let mut out = csv::record(&MIXS);
+    for record in records {
+        out.push_str(&csv::record(&[
+            record.isolate.name.clone(),
+            record.accessions.join("; "),
+            record.field_record.sampling_date.to_string(),
+            format!(
+                "{} {}",
+                record.site.latitude_4326, record.site.longitude_4326
+            ),
+            record.site.elevation_metres.to_string(),
+            record.field_record.sample_type.clone(),
+        ]));
+    }
+    out
+}
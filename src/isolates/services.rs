@@ -1,18 +1,28 @@
-use crudcrate::ApiError;
+use crudcrate::validation::ValidationError;
+use crudcrate::{ApiError, CRUDResource, MergeIntoActiveModel};
 use sea_orm::entity::prelude::*;
+use sea_orm::sea_query::Query;
 use sea_orm::{
-    Condition, DatabaseConnection, DbBackend, Order, QueryOrder, QuerySelect, Statement,
+    Condition, DatabaseConnection, IntoActiveModel, Order, QueryOrder, QuerySelect,
+    TransactionTrait,
 };
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashMap};
 use uuid::Uuid;
 
-use super::db::{Column, Entity, IsolateList};
+use super::db::{
+    ActiveModel, Column, Entity, Isolate, IsolateCreate, IsolateList, IsolateUpdate, Model,
+};
+use super::lineage::{self, Lineage};
+use super::models::{ExportRecord, TaxonNode};
+use crate::common::batch;
+use crate::{
+    areas, assemblies, culture_accessions, field_records, isolate_images, samples, sites, taxa,
+};
 
-/// Custom list hook — runs the default scoped query, then batches a second
-/// lookup to flag which isolates have a non-empty photo. Keeps `photo` out of
-/// the list payload (still `exclude(list)`) while letting the UI show a
-/// "photo available" cue without per-card detail fetches.
-pub(super) async fn get_all_isolates_with_photo_flag(
+/// Custom list hook — runs the default scoped query and adds each isolate's image
+/// count, the thumbnails of its primary image and its assembly link, so the UI can
+/// show previews without per-card fetches.
+pub(super) async fn get_all_isolates_with_images(
     db: &DatabaseConnection,
     condition: &Condition,
     order_column: Column,
@@ -27,39 +37,378 @@ pub(super) async fn get_all_isolates_with_photo_flag(
         .limit(limit)
         .all(db)
         .await?;
-
     let ids: Vec<Uuid> = models.iter().map(|m| m.id).collect();
-    let with_photo = photo_id_set(db, &ids).await;
+    let summaries = isolate_images::services::summaries(db, &ids).await?;
 
     Ok(models
         .into_iter()
         .map(|m| {
-            let id = m.id;
+            let (image_count, primary) = summaries.get(&m.id).copied().unwrap_or_default();
             let mut list: IsolateList = m.into();
-            list.has_photo = with_photo.contains(&id);
+            list.image_count = image_count;
+            list.thumbnails = primary.map(isolate_images::services::thumbnail_urls);
+            list.assembly_url = list
+                .assembly_file_id
+                .map(|_| assemblies::services::url(list.id));
             list
         })
         .collect())
 }
 
-async fn photo_id_set(db: &DatabaseConnection, ids: &[Uuid]) -> HashSet<Uuid> {
-    if ids.is_empty() {
-        return HashSet::new();
+/// Fills the lineage and resolves it against the loaded taxonomies before a save.
+/// `before_save` fills the lineage too, but cannot report an unknown name as a
+/// validation error.
+async fn prepare<C: ConnectionTrait>(
+    db: &C,
+    model: &mut ActiveModel,
+    insert: bool,
+) -> Result<(), ApiError> {
+    lineage::fill(model, insert);
+    taxa::services::resolve_isolate(db, model).await
+}
+
+/// The source sample must exist and have been taken under the isolate's field
+/// record. On update, links absent from the request are read from `existing`.
+async fn check_sample<C: ConnectionTrait>(
+    db: &C,
+    model: &ActiveModel,
+    existing: Option<&Model>,
+) -> Result<(), ApiError> {
+    if !model.sample_id.is_set() && !model.field_record_id.is_set() {
+        return Ok(());
+    }
+    let sample_id = model
+        .sample_id
+        .try_as_ref()
+        .copied()
+        .or(existing.map(|isolate| isolate.sample_id))
+        .flatten();
+    let field_record_id = model
+        .field_record_id
+        .try_as_ref()
+        .copied()
+        .or(existing.map(|isolate| isolate.field_record_id));
+    let (Some(sample_id), Some(field_record_id)) = (sample_id, field_record_id) else {
+        return Ok(());
+    };
+    let Some(sample) = samples::db::Entity::find_by_id(sample_id).one(db).await? else {
+        return Err(ValidationError::new("sample_id", "Unknown sample").into());
+    };
+    if sample.field_record_id != field_record_id {
+        return Err(ValidationError::new(
+            "sample_id",
+            format!(
+                "Sample '{}' was taken under another field record",
+                sample.name
+            ),
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// Inserts through the active model, unlike the generated create, so that the
+/// lineage is filled and resolved.
+pub(super) async fn create_isolate(
+    db: &DatabaseConnection,
+    data: IsolateCreate,
+) -> Result<Isolate, ApiError> {
+    let mut model = ActiveModel::from(data);
+    check_sample(db, &model, None).await?;
+    prepare(db, &mut model, true).await?;
+    let model = model.insert(db).await?;
+    Isolate::get_one(db, model.id).await
+}
+
+pub(super) async fn create_isolates(
+    db: &DatabaseConnection,
+    data: Vec<IsolateCreate>,
+) -> Result<Vec<Isolate>, ApiError> {
+    batch::check_limit::<Isolate>("create", data.len())?;
+    let txn = db.begin().await?;
+    let mut result = Vec::with_capacity(data.len());
+    for item in data {
+        let mut model = ActiveModel::from(item);
+        check_sample(&txn, &model, None).await?;
+        prepare(&txn, &mut model, true).await?;
+        result.push(Isolate::from(model.insert(&txn).await?));
+    }
+    txn.commit().await?;
+    Ok(result)
+}
+
+async fn update_one<C: ConnectionTrait>(
+    db: &C,
+    id: Uuid,
+    data: IsolateUpdate,
+) -> Result<Model, ApiError> {
+    let existing = Entity::find_by_id(id)
+        .one(db)
+        .await?
+        .ok_or_else(|| ApiError::not_found("isolate", Some(id.to_string())))?;
+    let mut model = data.merge_into_activemodel(existing.clone().into_active_model())?;
+    check_sample(db, &model, Some(&existing)).await?;
+    prepare(db, &mut model, false).await?;
+    Ok(model.update(db).await?)
+}
+
+pub(super) async fn update_isolate(
+    db: &DatabaseConnection,
+    id: Uuid,
+    data: IsolateUpdate,
+) -> Result<Isolate, ApiError> {
+    update_one(db, id, data).await?;
+    Isolate::get_one(db, id).await
+}
+
+pub(super) async fn update_isolates(
+    db: &DatabaseConnection,
+    updates: Vec<(Uuid, IsolateUpdate)>,
+) -> Result<Vec<Isolate>, ApiError> {
+    batch::check_limit::<Isolate>("update", updates.len())?;
+    let txn = db.begin().await?;
+    let mut result = Vec::with_capacity(updates.len());
+    for (id, data) in updates {
+        result.push(Isolate::from(update_one(&txn, id, data).await?));
+    }
+    txn.commit().await?;
+    Ok(result)
+}
+
+/// Parses the lineage of isolates that have a taxonomy but no ranks, such as those
+/// recorded before ranks existed. Returns how many were filled.
+pub async fn fill_lineages(db: &DatabaseConnection) -> Result<u64, ApiError> {
+    let unranked = Entity::find()
+        .filter(Column::Taxonomy.is_not_null())
+        .filter(Column::Domain.is_null())
+        .filter(Column::Phylum.is_null())
+        .filter(Column::Class.is_null())
+        .filter(Column::TaxonomicOrder.is_null())
+        .filter(Column::Family.is_null())
+        .filter(Column::Genus.is_null())
+        .filter(Column::Species.is_null())
+        .all(db)
+        .await?;
+    let mut filled = 0;
+    for isolate in unranked {
+        let lineage = lineage::parse(isolate.taxonomy.as_deref().unwrap_or_default());
+        if lineage.iter().all(Option::is_none) {
+            continue;
+        }
+        let mut model = isolate.into_active_model();
+        model.reset(Column::Taxonomy);
+        model.update(db).await?;
+        filled += 1;
+    }
+    Ok(filled)
+}
+
+#[derive(Default)]
+struct Taxon {
+    count: u64,
+    children: BTreeMap<Option<String>, Taxon>,
+}
+
+impl Taxon {
+    /// Counts an isolate down to its lowest known rank.
+    fn add(&mut self, lineage: &Lineage) {
+        self.count += 1;
+        let Some(lowest) = lineage.iter().rposition(Option::is_some) else {
+            return;
+        };
+        let mut node = self;
+        for name in &lineage[..=lowest] {
+            node = node.children.entry(name.clone()).or_default();
+            node.count += 1;
+        }
+    }
+
+    /// Children largest first, with the unnamed group last.
+    fn nodes(self, depth: usize) -> Vec<TaxonNode> {
+        let mut nodes: Vec<TaxonNode> = self
+            .children
+            .into_iter()
+            .map(|(name, taxon)| TaxonNode {
+                rank: lineage::RANKS[depth].to_string(),
+                name,
+                count: taxon.count,
+                children: taxon.nodes(depth + 1),
+            })
+            .collect();
+        nodes.sort_by(|a, b| {
+            (a.name.is_none(), std::cmp::Reverse(a.count), &a.name).cmp(&(
+                b.name.is_none(),
+                std::cmp::Reverse(b.count),
+                &b.name,
+            ))
+        });
+        nodes
+    }
+}
+
+/// Isolate counts per taxon, from the domains down, over the isolates matching
+/// `condition`. Isolates without any rank are left out.
+pub async fn taxonomy_tree(
+    db: &DatabaseConnection,
+    condition: Condition,
+) -> Result<Vec<TaxonNode>, ApiError> {
+    type Ranks = (
+        Option<String>,
+        Option<String>,
+        Option<String>,
+        Option<String>,
+        Option<String>,
+        Option<String>,
+        Option<String>,
+    );
+    let rows: Vec<Ranks> = Entity::find()
+        .select_only()
+        .columns([
+            Column::Domain,
+            Column::Phylum,
+            Column::Class,
+            Column::TaxonomicOrder,
+            Column::Family,
+            Column::Genus,
+            Column::Species,
+        ])
+        .filter(condition)
+        .into_tuple()
+        .all(db)
+        .await?;
+    let mut root = Taxon::default();
+    for (domain, phylum, class, order, family, genus, species) in rows {
+        root.add(&[domain, phylum, class, order, family, genus, species]);
     }
+    Ok(root.nodes(0))
+}
+
+/// The isolates matching `condition`, by name, with their field record, site, area
+/// and deposits. `public` leaves out the names of private areas.
+pub async fn export_records(
+    db: &DatabaseConnection,
+    condition: Condition,
+    public: bool,
+) -> Result<Vec<ExportRecord>, ApiError> {
+    let isolate_ids = Query::select()
+        .column(Column::Id)
+        .from(Entity)
+        .cond_where(condition.clone())
+        .to_owned();
+    let field_record_ids = Query::select()
+        .column(Column::FieldRecordId)
+        .from(Entity)
+        .cond_where(condition.clone())
+        .to_owned();
+    let site_ids = Query::select()
+        .column(field_records::db::Column::SiteId)
+        .from(field_records::db::Entity)
+        .and_where(field_records::db::Column::Id.in_subquery(field_record_ids.clone()))
+        .to_owned();
 
-    let rows = match db
-        .query_all(Statement::from_sql_and_values(
-            DbBackend::Postgres,
-            "SELECT id FROM isolates WHERE id = ANY($1) AND photo IS NOT NULL AND photo <> ''",
-            vec![ids.to_vec().into()],
-        ))
-        .await
+    let isolates = Entity::find()
+        .filter(condition)
+        .order_by_asc(Column::Name)
+        .all(db)
+        .await?;
+    let field_records: HashMap<Uuid, field_records::db::Model> = field_records::db::Entity::find()
+        .filter(field_records::db::Column::Id.in_subquery(field_record_ids))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|record| (record.id, record))
+        .collect();
+    let sites: HashMap<Uuid, sites::db::Model> = sites::db::Entity::find()
+        .filter(sites::db::Column::Id.in_subquery(site_ids))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|site| (site.id, site))
+        .collect();
+    let areas: HashMap<Uuid, String> = areas::db::Entity::find()
+        .all(db)
+        .await?
+        .into_iter()
+        .filter(|area| !(public && area.is_private))
+        .map(|area| (area.id, area.name))
+        .collect();
+    let mut accessions: HashMap<Uuid, Vec<String>> = HashMap::new();
+    for deposit in culture_accessions::db::Entity::find()
+        .filter(culture_accessions::db::Column::IsolateId.in_subquery(isolate_ids))
+        .order_by_asc(culture_accessions::db::Column::Collection)
+        .order_by_asc(culture_accessions::db::Column::Accession)
+        .all(db)
+        .await?
     {
-        Ok(rows) => rows,
-        Err(_) => return HashSet::new(),
-    };
+        accessions
+            .entry(deposit.isolate_id)
+            .or_default()
+            .push(format!("{}:{}", deposit.collection, deposit.accession));
+    }
 
-    rows.into_iter()
-        .filter_map(|r| r.try_get::<Uuid>("", "id").ok())
+    isolates
+        .into_iter()
+        .map(|isolate| {
+            let field_record = field_records
+                .get(&isolate.field_record_id)
+                .cloned()
+                .ok_or_else(|| ApiError::internal("Isolate without its field record", None))?;
+            let site = sites
+                .get(&field_record.site_id)
+                .cloned()
+                .ok_or_else(|| ApiError::internal("Field record without its site", None))?;
+            Ok(ExportRecord {
+                area: site.area_id.and_then(|id| areas.get(&id).cloned()),
+                accessions: accessions.remove(&isolate.id).unwrap_or_default(),
+                isolate,
+                field_record,
+                site,
+            })
+        })
         .collect()
 }
+
+pub async fn with_images(
+    db: &DatabaseConnection,
+    mut isolate: Isolate,
+) -> Result<Isolate, ApiError> {
+    let summaries = isolate_images::services::summaries(db, &[isolate.id]).await?;
+    let (image_count, primary) = summaries.get(&isolate.id).copied().unwrap_or_default();
+    isolate.image_count = image_count;
+    isolate.thumbnails = primary.map(isolate_images::services::thumbnail_urls);
+    isolate.assembly_url = isolate
+        .assembly_file_id
+        .map(|_| assemblies::services::url(isolate.id));
+    Ok(isolate)
+}
+
+/// Deletes the isolate. Its images go with it; their files and its assembly's are
+/// left to `files::services::remove_orphans`.
+pub async fn delete_isolate(db: &DatabaseConnection, id: Uuid) -> Result<Uuid, ApiError> {
+    delete_isolates(db, vec![id])
+        .await?
+        .pop()
+        .ok_or_else(|| ApiError::not_found("isolate", Some(id.to_string())))
+}
+
+pub async fn delete_isolates(
+    db: &DatabaseConnection,
+    ids: Vec<Uuid>,
+) -> Result<Vec<Uuid>, ApiError> {
+    batch::check_limit::<Isolate>("delete", ids.len())?;
+    let deleted: Vec<Uuid> = Entity::find()
+        .select_only()
+        .column(Column::Id)
+        .filter(Column::Id.is_in(ids))
+        .into_tuple()
+        .all(db)
+        .await?;
+    if deleted.is_empty() {
+        return Ok(vec![]);
+    }
+    Entity::delete_many()
+        .filter(Column::Id.is_in(deleted.clone()))
+        .exec(db)
+        .await?;
+    Ok(deleted)
+}
@@ -2,10 +2,13 @@ use axum::{
     body::{to_bytes, Body},
     http::{Request, StatusCode},
 };
-use serde_json::json;
+use serde_json::{json, Value};
 use tower::ServiceExt;
 
-use crate::test_utils::{build_app_with_db, setup_clean_db};
+use crate::test_utils::{
+    build_app_with_db, build_scoped_app_with_db, create, create_field_record, create_json,
+    create_site, get, image_upload, names, send, setup_clean_db, setup_sqlite_db, test_png,
+};
 
 #[tokio::test]
 #[ignore]
@@ -57,7 +60,6 @@ async fn create_isolate_valid() {
         "name": "Isolate A",
         "field_record_id": field_record_id,
         "taxonomy": "Pseudomonas",
-        "photo": "",
         "temperature_of_isolation": 20.5,
         "media_used_for_isolation": "M9",
         "storage_location": "Isolates: A1",
@@ -117,7 +119,6 @@ async fn test_isolates_invalid_data() {
         "name": "Isolate_Invalid_Temp",
         "field_record_id": field_record_id,
         "taxonomy": "Pseudomonas",
-        "photo": "",
         "temperature_of_isolation": "hot",
         "media_used_for_isolation": "M9",
         "storage_location": "Isolates: Test",
@@ -136,7 +137,6 @@ async fn test_isolates_invalid_data() {
         "name": "Isolate_Invalid_UUID",
         "field_record_id": "not-a-uuid",
         "taxonomy": "Pseudomonas",
-        "photo": "",
         "temperature_of_isolation": 20.5,
         "media_used_for_isolation": "M9",
         "storage_location": "Isolates: Test",
@@ -202,7 +202,6 @@ async fn test_isolate_images() {
         "name": "Isolate B",
         "field_record_id": field_record_id,
         "taxonomy": "Pseudomonas",
-        "photo": "",
         "temperature_of_isolation": 20.5,
         "media_used_for_isolation": "M9",
         "storage_location": "Isolates: A1",
@@ -220,17 +219,15 @@ async fn test_isolate_images() {
     let isolate: serde_json::Value = serde_json::from_slice(&isolate_body).unwrap();
     let isolate_id = isolate.get("id").unwrap().as_str().unwrap();
 
-    let image_payload = json!({
-        "photo": "data:image/png;base64,abc123"
-    });
+    let (content_type, body) = image_upload(&test_png(), &[]);
     let request = Request::builder()
-        .method("PUT")
-        .uri(format!("/api/isolates/{isolate_id}"))
-        .header("Content-Type", "application/json")
-        .body(Body::from(image_payload.to_string()))
+        .method("POST")
+        .uri(format!("/api/isolates/{isolate_id}/images"))
+        .header("Content-Type", content_type)
+        .body(Body::from(body))
         .unwrap();
     let response = app.clone().oneshot(request).await.unwrap();
-    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.status(), StatusCode::CREATED);
 
     let request = Request::builder()
         .method("GET")
@@ -245,19 +242,13 @@ async fn test_isolate_images() {
     );
     let isolate_body = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
     let isolate: serde_json::Value = serde_json::from_slice(&isolate_body).unwrap();
-    let photo = isolate.get("photo").unwrap();
-    assert_eq!(
-        photo.as_str().unwrap(),
-        "data:image/png;base64,abc123",
-        "Photo should be returned in get one"
-    );
     assert!(
         isolate.get("created_at").and_then(|v| v.as_str()).is_some(),
         "created_at should be serialized in get one response"
     );
 
     let create_second_payload = json!({
-        "name": "Isolate NoPhoto",
+        "name": "Isolate NoImages",
         "field_record_id": field_record_id,
         "taxonomy": "Bacillus",
         "temperature_of_isolation": 4.0,
@@ -294,17 +285,14 @@ async fn test_isolate_images() {
         .iter()
         .find(|i| i.get("id").unwrap().as_str().unwrap() == isolate_id)
         .unwrap();
-    // The list response omits `photo` entirely (exclude(list) + has_photo flag),
-    // so the key may be absent — treat absent and null the same.
     assert_eq!(
-        isolate.get("photo").and_then(|v| v.as_str()),
-        None,
-        "Photo should not be returned in get all"
+        isolate.get("image_count").and_then(|v| v.as_i64()),
+        Some(1),
+        "image_count should count the isolate's images"
     );
-    assert_eq!(
-        isolate.get("has_photo").and_then(|v| v.as_bool()),
-        Some(true),
-        "has_photo should be true for an isolate with a non-empty photo"
+    assert!(
+        isolate["thumbnails"]["small"].is_string(),
+        "thumbnails of the primary image should be linked"
     );
     assert!(
         isolate.get("created_at").and_then(|v| v.as_str()).is_some(),
@@ -316,8 +304,165 @@ async fn test_isolate_images() {
         .find(|i| i.get("id").unwrap().as_str().unwrap() == second_id)
         .unwrap();
     assert_eq!(
-        second_in_list.get("has_photo").and_then(|v| v.as_bool()),
-        Some(false),
-        "has_photo should be false for an isolate without a photo"
+        second_in_list.get("image_count").and_then(|v| v.as_i64()),
+        Some(0),
+        "image_count should be 0 for an isolate without images"
     );
+    assert!(second_in_list["thumbnails"].is_null());
+}
+
+/// Isolates named in `list`, sorted.
+fn sorted_names(list: &Value) -> Vec<&str> {
+    let mut names = names(list);
+    names.sort_unstable();
+    names
+}
+
+/// A GTDB lineage, a lineage with `>` separators, ranks given without a lineage and
+/// a private lineage, all on one field record.
+async fn ranked_isolates(app: &axum::Router) {
+    let site = create_site(app, "Glacier C").await;
+    let fr = create_field_record(app, &site, "FR-3").await;
+    for (name, taxonomy, ranks) in [
+        (
+            "ISO-GTDB",
+            "d__Bacteria;p__Pseudomonadota;c__Gammaproteobacteria;o__Pseudomonadales;f__Pseudomonadaceae;g__Pseudomonas;s__Pseudomonas fluorescens",
+            json!({}),
+        ),
+        (
+            "ISO-PLAIN",
+            "Bacteria > Pseudomonadota > Gammaproteobacteria > Pseudomonadales > Pseudomonadaceae > Pseudomonas > Pseudomonas syringae",
+            json!({}),
+        ),
+        (
+            "ISO-EXPLICIT",
+            "Bacillus sp.",
+            json!({ "phylum": "Bacillota", "genus": "Bacillus" }),
+        ),
+        (
+            "ISO-PRIVATE",
+            "Bacteria;Bacillota;Bacilli;Bacillales;Bacillaceae;Bacillus",
+            json!({ "is_private": true }),
+        ),
+    ] {
+        let mut payload = json!({ "name": name, "field_record_id": fr, "taxonomy": taxonomy });
+        payload
+            .as_object_mut()
+            .unwrap()
+            .extend(ranks.as_object().unwrap().clone());
+        create(app, "/api/isolates", payload).await;
+    }
+}
+
+/// Scenario: isolates are recorded with a GTDB lineage, a plain binomial and an
+/// explicit genus, and the binomial is later corrected to a full lineage.
+/// Expected behaviour: ranks are read from the taxonomy unless given, and a
+/// corrected taxonomy is read again.
+#[tokio::test]
+async fn taxonomy_is_read_into_ranks_unless_given() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db);
+    let site = create_site(&app, "Glacier C").await;
+    let fr = create_field_record(&app, &site, "FR-3").await;
+
+    let gtdb = create_json(
+        &app,
+        "/api/isolates",
+        json!({
+            "name": "ISO-GTDB",
+            "field_record_id": fr,
+            "taxonomy": "d__Bacteria;p__Pseudomonadota;c__Gammaproteobacteria;o__Pseudomonadales;f__Pseudomonadaceae;g__Pseudomonas;s__Pseudomonas fluorescens"
+        }),
+    )
+    .await;
+    assert_eq!(gtdb["domain"], "Bacteria");
+    assert_eq!(gtdb["phylum"], "Pseudomonadota");
+    assert_eq!(gtdb["taxonomic_order"], "Pseudomonadales");
+    assert_eq!(gtdb["species"], "Pseudomonas fluorescens");
+
+    let plain = create_json(
+        &app,
+        "/api/isolates",
+        json!({ "name": "ISO-PLAIN", "field_record_id": fr, "taxonomy": "Pseudomonas syringae pv. tomato" }),
+    )
+    .await;
+    assert_eq!(plain["domain"], Value::Null);
+    assert_eq!(plain["genus"], "Pseudomonas");
+    assert_eq!(plain["species"], "Pseudomonas syringae");
+
+    let explicit = create_json(
+        &app,
+        "/api/isolates",
+        json!({
+            "name": "ISO-EXPLICIT",
+            "field_record_id": fr,
+            "taxonomy": "Bacillus sp.",
+            "phylum": "Bacillota",
+            "genus": "Bacillus"
+        }),
+    )
+    .await;
+    assert_eq!(explicit["phylum"], "Bacillota");
+    assert_eq!(explicit["species"], Value::Null);
+
+    let (status, updated) = send(
+        &app,
+        "PUT",
+        &format!("/api/isolates/{}", plain["id"].as_str().unwrap()),
+        json!({ "taxonomy": "Bacteria > Pseudomonadota > Gammaproteobacteria > Pseudomonadales > Pseudomonadaceae > Pseudomonas > Pseudomonas syringae" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{updated}");
+    assert_eq!(updated["domain"], "Bacteria");
+    assert_eq!(updated["family"], "Pseudomonadaceae");
+    assert_eq!(updated["species"], "Pseudomonas syringae");
+}
+
+/// Expected behaviour: public callers filter by rank regardless of case, without the
+/// private isolate.
+#[tokio::test]
+async fn isolates_filter_by_rank_within_scope() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db.clone());
+    let scoped = build_scoped_app_with_db(db);
+    ranked_isolates(&app).await;
+
+    let (_, all) = get(&app, "/api/isolates").await;
+    assert_eq!(all.as_array().unwrap().len(), 4);
+    let (_, pseudomonas) = get(&scoped, "/api/isolates?genus=pseudomonas").await;
+    assert_eq!(sorted_names(&pseudomonas), ["ISO-GTDB", "ISO-PLAIN"]);
+    let (_, bacillota) = get(&scoped, "/api/isolates?phylum=Bacillota").await;
+    assert_eq!(sorted_names(&bacillota), ["ISO-EXPLICIT"]);
+}
+
+/// Expected behaviour: public callers get a tree of counts from the domain down,
+/// without the private isolate, narrowed by the rank filters.
+#[tokio::test]
+async fn taxonomy_tree_counts_public_isolates() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db.clone());
+    let scoped = build_scoped_app_with_db(db);
+    ranked_isolates(&app).await;
+
+    let (status, tree) = get(&scoped, "/api/isolates/taxonomy_tree").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(tree[0]["rank"], "domain");
+    assert_eq!(tree[0]["name"], "Bacteria");
+    assert_eq!(tree[0]["count"], 2);
+    let species = &tree[0]["children"][0]["children"][0]["children"][0]["children"][0]["children"]
+        [0]["children"];
+    assert_eq!(species[0]["name"], "Pseudomonas fluorescens");
+    assert_eq!(species[1]["name"], "Pseudomonas syringae");
+    assert_eq!(
+        tree[1]["name"],
+        Value::Null,
+        "ranks above the phylum unknown"
+    );
+    assert_eq!(tree[1]["children"][0]["name"], "Bacillota");
+    assert_eq!(tree[1]["children"][0]["count"], 1);
+    assert_eq!(tree.as_array().unwrap().len(), 2);
+
+    let (_, tree) = get(&scoped, "/api/isolates/taxonomy_tree?phylum=bacillota").await;
+    assert_eq!(tree.as_array().unwrap().len(), 1);
+    assert_eq!(tree[0]["count"], 1);
 }
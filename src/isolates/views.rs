@@ -0,0 +1,359 @@
+use axum::extract::multipart::{Field, MultipartError};
+use axum::extract::{DefaultBodyLimit, Multipart, Path, Query, Request, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::{
+    routing::{get, put},
+    Extension, Json, Router,
+};
+use crudcrate::validation::ValidationError;
+use crudcrate::ApiError;
+use futures::TryStreamExt;
+use object_store::ObjectStore;
+use sea_orm::{DatabaseConnection, EntityTrait, QueryFilter};
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::assemblies;
+use crate::files;
+use crate::growth_tests::{
+    self,
+    models::{GrowthMatrix, MatrixFormat, MatrixParams},
+};
+use crate::isolate_images::{
+    self,
+    db::IsolateImage,
+    models::{ImageOrder, ImageUpload},
+};
+use crate::middleware;
+
+use super::db::Isolate;
+use super::models::{ExportParams, ExportStandard, TaxonNode};
+
+/// Room for the multipart boundaries, headers and annotations around the image.
+const MULTIPART_OVERHEAD: usize = 64 * 1024;
+
+/// Routes mounted next to the generated CRUD router under `/api/isolates`.
+pub fn router(db: &DatabaseConnection, store: &Arc<dyn ObjectStore>) -> Router {
+    Router::new()
+        .route("/taxonomy_tree", get(get_taxonomy_tree))
+        .route("/growth_matrix", get(get_growth_matrix))
+        .route("/export", get(get_export))
+        .route("/{id}/images", get(get_images).post(post_image))
+        .route("/{id}/images/order", put(put_image_order))
+        .route(
+            "/{id}/assembly",
+            get(get_assembly)
+                .put(put_assembly)
+                .delete(delete_assembly)
+                .layer(DefaultBodyLimit::max(
+                    assemblies::services::MAX_UPLOAD_BYTES + MULTIPART_OVERHEAD,
+                )),
+        )
+        .layer(DefaultBodyLimit::max(
+            files::services::MAX_UPLOAD_BYTES + MULTIPART_OVERHEAD,
+        ))
+        .layer(Extension(store.clone()))
+        .with_state(db.clone())
+}
+
+fn multipart_error(err: MultipartError) -> ApiError {
+    ApiError::custom(err.status(), err.body_text(), None)
+}
+
+/// A text field of the upload form, with blank values read as absent.
+async fn text(field: Field<'_>) -> Result<Option<String>, ApiError> {
+    let value = field.text().await.map_err(multipart_error)?;
+    let value = value.trim();
+    Ok((!value.is_empty()).then(|| value.to_string()))
+}
+
+fn parse<T: std::str::FromStr>(name: &str, value: Option<String>) -> Result<Option<T>, ApiError> {
+    value
+        .map(|value| {
+            value
+                .parse()
+                .map_err(|_| ValidationError::new(name, format!("Cannot read '{value}'")).into())
+        })
+        .transpose()
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/isolates/taxonomy_tree",
+    params(
+        ("sample_type" = Option<String>, Query, description = "Only isolates from field records of this habitat"),
+        ("phylum" = Option<String>, Query, description = "Only isolates of this phylum, and likewise for the other ranks from `domain` to `species`")
+    ),
+    responses(
+        (status = OK, description = "Isolate counts nested from domain down to species", body = [TaxonNode]),
+        (status = UNPROCESSABLE_ENTITY, description = "Unknown sample type")
+    )
+)]
+pub async fn get_taxonomy_tree(
+    State(db): State<DatabaseConnection>,
+    req: Request,
+) -> Result<Json<Vec<TaxonNode>>, ApiError> {
+    let condition = middleware::scope_condition(&req);
+    Ok(Json(super::services::taxonomy_tree(&db, condition).await?))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/isolates/growth_matrix",
+    params(
+        MatrixParams,
+        ("grows_at" = Option<String>, Query, description = "Only isolates shown to grow at these temperatures (°C, comma-separated); likewise `grows_on` for media and `grows_at_salinity` and `grows_at_ph`"),
+        ("no_growth_at" = Option<String>, Query, description = "Only isolates shown not to grow at these temperatures; likewise `no_growth_on`, `no_growth_at_salinity` and `no_growth_at_ph`")
+    ),
+    responses(
+        (status = OK, description = "Growth of the tested isolates per condition as JSON, or as CSV with the rate, `+` or `-` in each cell", body = GrowthMatrix),
+        (status = BAD_REQUEST, description = "A growth filter is not a number")
+    )
+)]
+pub async fn get_growth_matrix(
+    State(db): State<DatabaseConnection>,
+    Query(params): Query<MatrixParams>,
+    req: Request,
+) -> Result<Response, ApiError> {
+    let condition = middleware::scope_condition(&req);
+    let matrix = growth_tests::services::matrix(&db, condition).await?;
+    Ok(match params.format.unwrap_or_default() {
+        MatrixFormat::Json => Json(matrix).into_response(),
+        MatrixFormat::Csv => (
+            [(header::CONTENT_TYPE, "text/csv; charset=utf-8")],
+            growth_tests::render::csv(&matrix),
+        )
+            .into_response(),
+    })
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/isolates/export",
+    params(
+        ExportParams,
+        ("accession" = Option<String>, Query, description = "Only isolates deposited under this accession; the other isolate filters apply likewise")
+    ),
+    responses(
+        (status = OK, description = "CSV with one row per isolate in Darwin Core or MIxS terms, its culture collection accessions included"),
+        (status = BAD_REQUEST, description = "Missing or unknown standard")
+    )
+)]
+pub async fn get_export(
+    State(db): State<DatabaseConnection>,
+    Query(params): Query<ExportParams>,
+    req: Request,
+) -> Result<Response, ApiError> {
+    let condition = middleware::scope_condition(&req);
+    let records =
+        super::services::export_records(&db, condition, !middleware::is_admin(&req)).await?;
+    let csv = match params.standard {
+        ExportStandard::DarwinCore => super::render::darwin_core(&records),
+        ExportStandard::Mixs => super::render::mixs(&records),
+    };
+    Ok(([(header::CONTENT_TYPE, "text/csv; charset=utf-8")], csv).into_response())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/isolates/{id}/images",
+    params(("id" = Uuid, Path, description = "Isolate id")),
+    responses(
+        (status = OK, description = "The isolate's images in display order", body = [IsolateImage]),
+        (status = NOT_FOUND, description = "Isolate not found")
+    )
+)]
+pub async fn get_images(
+    State(db): State<DatabaseConnection>,
+    Path(id): Path<Uuid>,
+    req: Request,
+) -> Result<Json<Vec<IsolateImage>>, ApiError> {
+    let mut isolate = super::db::Entity::find_by_id(id);
+    if middleware::is_scoped(&req) {
+        isolate = isolate.filter(middleware::isolates_scope());
+    }
+    if isolate.one(&db).await?.is_none() {
+        return Err(ApiError::not_found("isolate", Some(id.to_string())));
+    }
+    Ok(Json(isolate_images::services::list(&db, id).await?))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/isolates/{id}/images",
+    params(("id" = Uuid, Path, description = "Isolate id")),
+    request_body(
+        content_type = "multipart/form-data",
+        description = "The image in a field named `image`, with optional `image_type` (`Colony`, `Microscopy` or `GramStain`), `caption`, `medium`, `magnification`, `captured_on` (YYYY-MM-DD) and `is_primary` fields"
+    ),
+    responses(
+        (status = CREATED, description = "Image stored after the isolate's others", body = IsolateImage),
+        (status = NOT_FOUND, description = "Isolate not found"),
+        (status = PAYLOAD_TOO_LARGE, description = "The image exceeds 20 MiB"),
+        (status = UNPROCESSABLE_ENTITY, description = "No `image` field, an unreadable annotation, or not a PNG, JPEG, GIF, WebP or TIFF image")
+    )
+)]
+pub async fn post_image(
+    State(db): State<DatabaseConnection>,
+    Extension(store): Extension<Arc<dyn ObjectStore>>,
+    Path(id): Path<Uuid>,
+    mut multipart: Multipart,
+) -> Result<(StatusCode, Json<IsolateImage>), ApiError> {
+    let mut upload = ImageUpload::default();
+    let mut has_image = false;
+    while let Some(field) = multipart.next_field().await.map_err(multipart_error)? {
+        match field.name().unwrap_or_default() {
+            "image" => {
+                upload.filename = field.file_name().map(str::to_string);
+                upload.bytes = field.bytes().await.map_err(multipart_error)?;
+                has_image = true;
+            }
+            "image_type" => {
+                if let Some(value) = text(field).await? {
+                    upload.image_type =
+                        serde_json::from_value(value.clone().into()).map_err(|_| {
+                            ValidationError::new(
+                                "image_type",
+                                format!("Expected Colony, Microscopy or GramStain, got '{value}'"),
+                            )
+                        })?;
+                }
+            }
+            "caption" => upload.caption = text(field).await?,
+            "medium" => upload.medium = text(field).await?,
+            "magnification" => upload.magnification = parse("magnification", text(field).await?)?,
+            "captured_on" => upload.captured_on = parse("captured_on", text(field).await?)?,
+            "is_primary" => {
+                upload.is_primary = parse("is_primary", text(field).await?)?.unwrap_or(false)
+            }
+            _ => {}
+        }
+    }
+    if !has_image {
+        return Err(ValidationError::new(
+            "image",
+            "Attach the image as the multipart field 'image'",
+        )
+        .into());
+    }
+    let image = isolate_images::services::upload(&db, &*store, id, upload).await?;
+    Ok((StatusCode::CREATED, Json(image)))
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/isolates/{id}/images/order",
+    params(("id" = Uuid, Path, description = "Isolate id")),
+    request_body = ImageOrder,
+    responses(
+        (status = OK, description = "The isolate's images in their new order", body = [IsolateImage]),
+        (status = NOT_FOUND, description = "Isolate not found"),
+        (status = UNPROCESSABLE_ENTITY, description = "The ids are not exactly the isolate's images")
+    )
+)]
+pub async fn put_image_order(
+    State(db): State<DatabaseConnection>,
+    Path(id): Path<Uuid>,
+    Json(order): Json<ImageOrder>,
+) -> Result<Json<Vec<IsolateImage>>, ApiError> {
+    Ok(Json(
+        isolate_images::services::reorder(&db, id, &order).await?,
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/isolates/{id}/assembly",
+    params(("id" = Uuid, Path, description = "Isolate id")),
+    responses(
+        (status = OK, description = "The FASTA assembly as uploaded, plain or gzip-compressed"),
+        (status = NOT_FOUND, description = "Isolate not found or without an assembly")
+    )
+)]
+pub async fn get_assembly(
+    State(db): State<DatabaseConnection>,
+    Extension(store): Extension<Arc<dyn ObjectStore>>,
+    Path(id): Path<Uuid>,
+    req: Request,
+) -> Result<Response, ApiError> {
+    let (isolate, file) = assemblies::services::find(&db, id, middleware::is_scoped(&req)).await?;
+    let filename = file
+        .filename
+        .clone()
+        .unwrap_or_else(|| format!("{}.fasta", isolate.name))
+        .replace(['"', '\\', '\r', '\n'], "_");
+    let cache_control = if isolate.is_private {
+        "private, no-cache"
+    } else {
+        "public, no-cache"
+    };
+    Ok((
+        [
+            (header::CONTENT_TYPE, file.content_type.clone()),
+            (header::CONTENT_LENGTH, file.size_bytes.to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{filename}\""),
+            ),
+            (header::CACHE_CONTROL, cache_control.to_string()),
+        ],
+        files::services::open(&*store, &file).await?,
+    )
+        .into_response())
+}
+
+#[utoipa::path(
+    put,
+    path = "/api/isolates/{id}/assembly",
+    params(("id" = Uuid, Path, description = "Isolate id")),
+    request_body(
+        content_type = "multipart/form-data",
+        description = "A nucleotide FASTA file, optionally gzip-compressed, in a field named `assembly`"
+    ),
+    responses(
+        (status = OK, description = "The isolate with the assembly's statistics, replacing any previous assembly", body = Isolate),
+        (status = NOT_FOUND, description = "Isolate not found"),
+        (status = PAYLOAD_TOO_LARGE, description = "The file exceeds 200 MiB"),
+        (status = UNPROCESSABLE_ENTITY, description = "No `assembly` field, or the file is not a nucleotide FASTA; the message names the line")
+    )
+)]
+pub async fn put_assembly(
+    State(db): State<DatabaseConnection>,
+    Extension(store): Extension<Arc<dyn ObjectStore>>,
+    Path(id): Path<Uuid>,
+    mut multipart: Multipart,
+) -> Result<Json<Isolate>, ApiError> {
+    while let Some(field) = multipart.next_field().await.map_err(multipart_error)? {
+        if field.name() == Some("assembly") {
+            let filename = field.file_name().map(str::to_string);
+            let chunks = field.map_err(multipart_error);
+            return Ok(Json(
+                assemblies::services::upload(&db, &*store, id, filename, chunks).await?,
+            ));
+        }
+    }
+    Err(ValidationError::new(
+        "assembly",
+        "Attach the FASTA file as the multipart field 'assembly'",
+    )
+    .into())
+}
+
+#[utoipa::path(
+    delete,
+    path = "/api/isolates/{id}/assembly",
+    params(("id" = Uuid, Path, description = "Isolate id")),
+    responses(
+        (status = NO_CONTENT, description = "Assembly and its statistics removed"),
+        (status = NOT_FOUND, description = "Isolate not found or without an assembly")
+    )
+)]
+pub async fn delete_assembly(
+    State(db): State<DatabaseConnection>,
+    Extension(store): Extension<Arc<dyn ObjectStore>>,
+    Path(id): Path<Uuid>,
+) -> Result<StatusCode, ApiError> {
+    assemblies::services::remove(&db, &*store, id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
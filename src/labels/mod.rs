@@ -0,0 +1,6 @@
+pub mod models;
+pub mod render;
+pub mod services;
+#[cfg(test)]
+mod tests;
+pub mod views;
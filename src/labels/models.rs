@@ -0,0 +1,107 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+/// Upper bound on labels in one PDF sheet request, copies included.
+pub const MAX_SHEET_LABELS: usize = 2000;
+
+#[derive(ToSchema, Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LabelKind {
+    Sample,
+    Isolate,
+    Dna,
+    #[serde(rename = "box")]
+    StorageBox,
+}
+
+impl LabelKind {
+    pub fn default_size(self) -> LabelSize {
+        match self {
+            LabelKind::StorageBox => LabelSize::Box,
+            _ => LabelSize::CryoTube,
+        }
+    }
+}
+
+/// Standard cryo-label stock.
+#[derive(ToSchema, Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum LabelSize {
+    /// 1.28 × 0.5 in, the side of a 1.5–2 mL cryo tube.
+    CryoTube,
+    /// 0.94 × 0.5 in, for 0.5 mL tubes and cryo vials.
+    MicroTube,
+    /// 2 × 1 in, the end of a freezer box.
+    Box,
+}
+
+impl LabelSize {
+    /// Width and height in millimetres.
+    pub fn dimensions_mm(self) -> (f64, f64) {
+        match self {
+            LabelSize::CryoTube => (32.5, 12.7),
+            LabelSize::MicroTube => (23.9, 12.7),
+            LabelSize::Box => (50.8, 25.4),
+        }
+    }
+}
+
+#[derive(ToSchema, Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum LabelFormat {
+    #[default]
+    Svg,
+    /// The code alone, for printers that lay out the text themselves.
+    Png,
+}
+
+#[derive(Deserialize, IntoParams, Debug)]
+pub struct LabelParams {
+    /// `svg` (default) or `png`.
+    pub format: Option<LabelFormat>,
+    /// Defaults to `box` for storage boxes and `cryo_tube` otherwise.
+    pub size: Option<LabelSize>,
+}
+
+#[derive(ToSchema, Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct LabelItem {
+    pub kind: LabelKind,
+    pub id: Uuid,
+}
+
+#[derive(ToSchema, Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct LabelSheetRequest {
+    pub items: Vec<LabelItem>,
+    /// Defaults to `cryo_tube`.
+    pub size: Option<LabelSize>,
+    /// Labels printed per item, e.g. 2 for a tube side and a box map. Defaults to 1.
+    pub copies: Option<u32>,
+}
+
+/// What is printed on one label. The code carries the id; the text is for people.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Label {
+    pub id: Uuid,
+    pub name: String,
+    /// Field record name, or the storage path for a box.
+    pub context: Option<String>,
+    /// Sampling date of the field record, or the creation date for a box.
+    pub date: Option<NaiveDate>,
+}
+
+impl Label {
+    /// The encoded identifier: the UUID in upper case, which fits QR alphanumeric mode
+    /// and keeps the code at 25×25 modules.
+    pub fn payload(&self) -> String {
+        self.id.hyphenated().to_string().to_uppercase()
+    }
+
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines = vec![self.name.clone()];
+        lines.extend(self.context.clone());
+        lines.extend(self.date.map(|date| date.to_string()));
+        lines
+    }
+}
@@ -0,0 +1,192 @@
+//! QR codes and the SVG, PNG and PDF output for labels.
+
+use std::fmt::Write;
+use std::io::Cursor;
+
+use image::{ImageFormat, Luma};
+use pdf_writer::Content;
+use qrcode::types::QrError;
+use qrcode::{Color, EcLevel, QrCode, Version};
+
+use super::models::{Label, LabelSize};
+use crate::common::pdf::{self, truncate, AVERAGE_GLYPH_WIDTH, BOLD, POINTS_PER_MM, REGULAR};
+
+const PADDING_MM: f64 = 1.0;
+const MAX_FONT_MM: f64 = 3.0;
+
+/// A4 sheet in millimetres, with the margin and gutter between labels.
+const SHEET_MM: (f64, f64) = pdf::A4_MM;
+const SHEET_MARGIN_MM: f64 = 10.0;
+const SHEET_GUTTER_MM: f64 = 2.0;
+
+/// Largest QR version we generate; 57×57 modules is already too dense for a cryo
+/// label.
+const MAX_QR_VERSION: i16 = 10;
+/// PNG modules are this many pixels wide, surrounded by the 4-module quiet zone.
+const PNG_SCALE: u32 = 8;
+
+/// Encodes `payload` at error correction level M in the smallest version that holds
+/// it, up to `MAX_QR_VERSION`.
+pub fn qr_code(payload: &str) -> Result<QrCode, QrError> {
+    let code = QrCode::with_error_correction_level(payload, EcLevel::M)?;
+    match code.version() {
+        Version::Normal(version) if version <= MAX_QR_VERSION => Ok(code),
+        _ => Err(QrError::DataTooLong),
+    }
+}
+
+fn is_dark(code: &QrCode, x: usize, y: usize) -> bool {
+    code[(x, y)] == Color::Dark
+}
+
+/// Positions within one label, in millimetres from its top-left corner. The code
+/// fills the label height with a one-module quiet zone inside it.
+struct Layout {
+    code_x: f64,
+    code_y: f64,
+    module: f64,
+    text_x: f64,
+    font: f64,
+    /// Baselines of the text lines.
+    baselines: [f64; 3],
+    max_chars: usize,
+}
+
+impl Layout {
+    fn new(size: LabelSize, code: &QrCode) -> Self {
+        let (width, height) = size.dimensions_mm();
+        let side = height - 2.0 * PADDING_MM;
+        let module = side / (code.width() + 2) as f64;
+        let text_x = PADDING_MM + side + PADDING_MM;
+        let line_height = side / 3.0;
+        let font = (line_height * 0.75).min(MAX_FONT_MM);
+        let baselines = [1.0, 2.0, 3.0].map(|i| PADDING_MM + line_height * i - line_height * 0.25);
+        let max_chars = ((width - text_x - PADDING_MM) / (font * AVERAGE_GLYPH_WIDTH)) as usize;
+        Self {
+            code_x: PADDING_MM + module,
+            code_y: PADDING_MM + module,
+            module,
+            text_x,
+            font,
+            baselines,
+            max_chars,
+        }
+    }
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+pub fn svg(label: &Label, code: &QrCode, size: LabelSize) -> String {
+    let (width, height) = size.dimensions_mm();
+    let layout = Layout::new(size, code);
+
+    let mut modules = String::new();
+    for y in 0..code.width() {
+        for x in 0..code.width() {
+            if is_dark(code, x, y) {
+                let _ = write!(modules, "M{x},{y}h1v1h-1z");
+            }
+        }
+    }
+
+    let mut svg = format!(
+        "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{width}mm\" height=\"{height}mm\" \
+         viewBox=\"0 0 {width} {height}\">\
+         <rect width=\"{width}\" height=\"{height}\" fill=\"#fff\"/>\
+         <path transform=\"translate({:.3} {:.3}) scale({:.4})\" d=\"{modules}\" \
+         fill=\"#000\" shape-rendering=\"crispEdges\"/>",
+        layout.code_x, layout.code_y, layout.module,
+    );
+    for (i, (line, baseline)) in label.lines().iter().zip(layout.baselines).enumerate() {
+        let weight = if i == 0 { " font-weight=\"bold\"" } else { "" };
+        let _ = write!(
+            svg,
+            "<text x=\"{:.3}\" y=\"{baseline:.3}\" font-family=\"Helvetica, Arial, sans-serif\" \
+             font-size=\"{:.3}\"{weight}>{}</text>",
+            layout.text_x,
+            layout.font,
+            xml_escape(&truncate(line, layout.max_chars)),
+        );
+    }
+    svg.push_str("</svg>");
+    svg
+}
+
+/// 8-bit greyscale PNG of the code alone, with the quiet zone.
+pub fn png(code: &QrCode) -> Result<Vec<u8>, image::ImageError> {
+    let image = code
+        .render::<Luma<u8>>()
+        .module_dimensions(PNG_SCALE, PNG_SCALE)
+        .build();
+    let mut out = Cursor::new(Vec::new());
+    image.write_to(&mut out, ImageFormat::Png)?;
+    Ok(out.into_inner())
+}
+
+/// Labels per row and per column on an A4 sheet.
+pub fn sheet_grid(size: LabelSize) -> (usize, usize) {
+    let (width, height) = size.dimensions_mm();
+    let fit = |available: f64, item: f64| {
+        ((available - 2.0 * SHEET_MARGIN_MM + SHEET_GUTTER_MM) / (item + SHEET_GUTTER_MM)) as usize
+    };
+    (fit(SHEET_MM.0, width), fit(SHEET_MM.1, height))
+}
+
+/// Page content drawing `labels` top to bottom, left to right.
+fn page_content(labels: &[(Label, QrCode)], size: LabelSize) -> Content {
+    let (width, height) = size.dimensions_mm();
+    let (columns, _) = sheet_grid(size);
+    let mut content = Content::new();
+
+    for (i, (label, code)) in labels.iter().enumerate() {
+        let left = SHEET_MARGIN_MM + (i % columns) as f64 * (width + SHEET_GUTTER_MM);
+        let top = SHEET_MARGIN_MM + (i / columns) as f64 * (height + SHEET_GUTTER_MM);
+        let layout = Layout::new(size, code);
+
+        // One unit per module, with y running down the code.
+        let (x, y) = pdf::position(left + layout.code_x, top + layout.code_y);
+        let module = (layout.module * POINTS_PER_MM) as f32;
+        content
+            .save_state()
+            .transform([module, 0.0, 0.0, -module, x, y]);
+        for row in 0..code.width() {
+            for column in 0..code.width() {
+                if is_dark(code, column, row) {
+                    content.rect(column as f32, row as f32, 1.0, 1.0);
+                }
+            }
+        }
+        content.fill_nonzero().restore_state();
+
+        let font = layout.font * POINTS_PER_MM;
+        for (j, (line, baseline)) in label.lines().iter().zip(layout.baselines).enumerate() {
+            pdf::text(
+                &mut content,
+                if j == 0 { BOLD } else { REGULAR },
+                font,
+                left + layout.text_x,
+                top + baseline,
+                &truncate(line, layout.max_chars),
+            );
+        }
+    }
+    content
+}
+
+/// Multi-page A4 PDF with the labels laid out in the grid for `size`.
+pub fn pdf_sheet(labels: &[(Label, QrCode)], size: LabelSize) -> Vec<u8> {
+    let (columns, rows) = sheet_grid(size);
+    let per_page = (columns * rows).max(1);
+    let pages: Vec<&[(Label, QrCode)]> = if labels.is_empty() {
+        vec![&[]]
+    } else {
+        labels.chunks(per_page).collect()
+    };
+
+    pdf::document(pages.iter().map(|page| page_content(page, size)).collect())
+}
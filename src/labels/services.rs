@@ -0,0 +1,70 @@
+use crudcrate::ApiError;
+use sea_orm::{DatabaseConnection, EntityTrait};
+use uuid::Uuid;
+
+use super::models::{Label, LabelKind};
+use crate::common::enums::StorageUnitKind;
+use crate::{dna, field_records, isolates, samples, storage_units};
+
+async fn field_record_label(
+    db: &DatabaseConnection,
+    id: Uuid,
+    name: String,
+    field_record_id: Uuid,
+) -> Result<Label, ApiError> {
+    let record = field_records::db::Entity::find_by_id(field_record_id)
+        .one(db)
+        .await?;
+    Ok(Label {
+        id,
+        name,
+        context: record.as_ref().map(|record| record.name.clone()),
+        date: record.map(|record| record.sampling_date),
+    })
+}
+
+/// Name, context line and date for one item.
+pub async fn load(db: &DatabaseConnection, kind: LabelKind, id: Uuid) -> Result<Label, ApiError> {
+    match kind {
+        LabelKind::Sample => {
+            let sample = samples::db::Entity::find_by_id(id)
+                .one(db)
+                .await?
+                .ok_or_else(|| ApiError::not_found("sample", Some(id.to_string())))?;
+            field_record_label(db, id, sample.name, sample.field_record_id).await
+        }
+        LabelKind::Isolate => {
+            let isolate = isolates::db::Entity::find_by_id(id)
+                .one(db)
+                .await?
+                .ok_or_else(|| ApiError::not_found("isolate", Some(id.to_string())))?;
+            field_record_label(db, id, isolate.name, isolate.field_record_id).await
+        }
+        LabelKind::Dna => {
+            let extract = dna::db::Entity::find_by_id(id)
+                .one(db)
+                .await?
+                .ok_or_else(|| ApiError::not_found("dna", Some(id.to_string())))?;
+            field_record_label(db, id, extract.name, extract.field_record_id).await
+        }
+        LabelKind::StorageBox => {
+            let units = storage_units::services::all_units(db).await?;
+            let Some(unit) = units
+                .get(&id)
+                .filter(|unit| unit.kind == StorageUnitKind::Box)
+            else {
+                return Err(ApiError::not_found("box", Some(id.to_string())));
+            };
+            // The box's own name is the first line, so the path stops at its rack.
+            let rack_path = unit
+                .parent_id
+                .map(|parent_id| storage_units::services::path(&units, parent_id));
+            Ok(Label {
+                id,
+                name: unit.name.clone(),
+                context: rack_path,
+                date: Some(unit.created_at.date_naive()),
+            })
+        }
+    }
+}
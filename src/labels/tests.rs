@@ -0,0 +1,209 @@
+use axum::{
+    body::{to_bytes, Body},
+    http::{header, Request, StatusCode},
+};
+use image::GenericImageView;
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+use super::render;
+use crate::test_utils::{
+    build_app_with_db, build_scoped_app_with_db, create, create_field_record, create_site,
+    setup_sqlite_db,
+};
+
+/// Status, content type and raw body.
+async fn fetch(
+    app: &axum::Router,
+    method: &str,
+    uri: &str,
+    payload: Option<Value>,
+) -> (StatusCode, String, Vec<u8>) {
+    let req = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("Content-Type", "application/json")
+        .body(payload.map_or(Body::empty(), |p| Body::from(p.to_string())))
+        .unwrap();
+    let resp = app.clone().oneshot(req).await.unwrap();
+    let status = resp.status();
+    let content_type = resp
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .map(|v| v.to_str().unwrap().to_string())
+        .unwrap_or_default();
+    let body = to_bytes(resp.into_body(), 4 * 1024 * 1024).await.unwrap();
+    (status, content_type, body.to_vec())
+}
+
+/// A sample named `S-1 <A&B>` from field record FR-1 (sampled 2025-07-10), and
+/// box `Box 7` in `Freezer B / Rack 2`.
+async fn sample_and_box(app: &axum::Router) -> (String, String) {
+    let site_id = create_site(app, "Glacier A").await;
+    let fr = create_field_record(app, &site_id, "FR-1").await;
+    let sample = create(
+        app,
+        "/api/samples",
+        json!({ "name": "S-1 <A&B>", "field_record_id": fr }),
+    )
+    .await;
+    let freezer = create(
+        app,
+        "/api/storage_units",
+        json!({ "name": "Freezer B", "kind": "Freezer" }),
+    )
+    .await;
+    let rack = create(
+        app,
+        "/api/storage_units",
+        json!({ "name": "Rack 2", "kind": "Rack", "parent_id": freezer }),
+    )
+    .await;
+    let storage_box = create(
+        app,
+        "/api/storage_units",
+        json!({ "name": "Box 7", "kind": "Box", "parent_id": rack, "rows": 9, "columns": 9 }),
+    )
+    .await;
+    (sample, storage_box)
+}
+
+#[test]
+fn uuid_payload_fits_a_version_2_code() {
+    let code = render::qr_code("0F8FAD5B-D9CB-469F-A165-70867728950E").unwrap();
+    assert_eq!(code.width(), 25);
+    assert_eq!(code.error_correction_level(), qrcode::EcLevel::M);
+
+    let long = "x".repeat(400);
+    assert!(render::qr_code(&long).is_err(), "larger than version 10");
+}
+
+#[test]
+fn png_draws_the_code_modules_with_a_quiet_zone() {
+    let code = render::qr_code("0F8FAD5B-D9CB-469F-A165-70867728950E").unwrap();
+    let png = render::png(&code).unwrap();
+    let image = image::load_from_memory(&png).unwrap();
+    // (25 modules + 2 × 4 quiet zone) × 8 px.
+    assert_eq!(image.dimensions(), (264, 264));
+
+    for y in 0..code.width() {
+        for x in 0..code.width() {
+            let pixel = image.get_pixel((x as u32 + 4) * 8 + 4, (y as u32 + 4) * 8 + 4);
+            let dark = code[(x, y)] == qrcode::Color::Dark;
+            assert_eq!(pixel[0] == 0, dark, "module ({x}, {y})");
+        }
+    }
+    assert_eq!(image.get_pixel(0, 0)[0], 255, "quiet zone");
+}
+
+/// Expected behaviour: the SVG carries the escaped name, field record, date and
+/// the label size; a box label shows its location.
+#[tokio::test]
+async fn svg_labels_carry_the_item_details() {
+    let db = setup_sqlite_db().await;
+    let admin = build_app_with_db(db);
+    let (sample, storage_box) = sample_and_box(&admin).await;
+
+    let (status, content_type, body) =
+        fetch(&admin, "GET", &format!("/api/labels/sample/{sample}"), None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(content_type, "image/svg+xml");
+    let svg = String::from_utf8(body).unwrap();
+    for text in [
+        "S-1 &lt;A&amp;B&gt;",
+        "FR-1",
+        "2025-07-10",
+        "width=\"32.5mm\"",
+    ] {
+        assert!(svg.contains(text), "{text} missing from {svg}");
+    }
+
+    let (status, _, body) = fetch(
+        &admin,
+        "GET",
+        &format!("/api/labels/box/{storage_box}"),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let svg = String::from_utf8(body).unwrap();
+    assert!(
+        svg.contains("Box 7") && svg.contains("Freezer B /") && svg.contains("width=\"50.8mm\"")
+    );
+
+    let (status, _, _) = fetch(&admin, "GET", &format!("/api/labels/box/{sample}"), None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND, "a sample is not a box");
+}
+
+#[tokio::test]
+async fn png_labels_are_the_bare_code() {
+    let db = setup_sqlite_db().await;
+    let admin = build_app_with_db(db);
+    let (sample, _) = sample_and_box(&admin).await;
+
+    let (status, content_type, body) = fetch(
+        &admin,
+        "GET",
+        &format!("/api/labels/sample/{sample}?format=png"),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(content_type, "image/png");
+    assert_eq!(
+        image::load_from_memory(&body).unwrap().dimensions(),
+        (264, 264)
+    );
+}
+
+/// Scenario: two copies each of a sample and a box label on cryo-tube labels.
+/// Expected behaviour: all four fit one A4 page; an empty request is 400.
+#[tokio::test]
+async fn sheets_lay_out_copies_on_a4_pages() {
+    let db = setup_sqlite_db().await;
+    let admin = build_app_with_db(db);
+    let (sample, storage_box) = sample_and_box(&admin).await;
+
+    let (status, content_type, body) = fetch(
+        &admin,
+        "POST",
+        "/api/labels/sheet",
+        Some(json!({
+            "items": [{ "kind": "sample", "id": sample }, { "kind": "box", "id": storage_box }],
+            "copies": 2
+        })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(content_type, "application/pdf");
+    assert!(body.starts_with(b"%PDF-") && body.ends_with(b"%%EOF"));
+    let pdf = String::from_utf8_lossy(&body);
+    assert_eq!(pdf.matches("(FR-1) Tj").count(), 2);
+    assert!(pdf.contains("/Count 1"), "one page");
+
+    let (status, _, _) = fetch(
+        &admin,
+        "POST",
+        "/api/labels/sheet",
+        Some(json!({ "items": [] })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn labels_are_admin_only() {
+    let db = setup_sqlite_db().await;
+    let admin = build_app_with_db(db.clone());
+    let scoped = build_scoped_app_with_db(db);
+    let (sample, _) = sample_and_box(&admin).await;
+
+    let (status, _, _) = fetch(
+        &scoped,
+        "GET",
+        &format!("/api/labels/sample/{sample}"),
+        None,
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
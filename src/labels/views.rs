@@ -0,0 +1,106 @@
+use axum::extract::{Path, Query, State};
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use axum::{
+    routing::{get, post},
+    Json, Router,
+};
+use crudcrate::ApiError;
+use qrcode::QrCode;
+use sea_orm::DatabaseConnection;
+use uuid::Uuid;
+
+use super::models::{LabelFormat, LabelKind, LabelParams, LabelSheetRequest, MAX_SHEET_LABELS};
+use super::{render, services};
+
+/// Label routes mounted under `/api/labels`.
+pub fn router(db: &DatabaseConnection) -> Router {
+    Router::new()
+        .route("/sheet", post(post_label_sheet))
+        .route("/{kind}/{id}", get(get_label))
+        .with_state(db.clone())
+}
+
+fn encode(payload: &str) -> Result<QrCode, ApiError> {
+    render::qr_code(payload)
+        .map_err(|err| ApiError::internal("Could not encode label", Some(err.to_string())))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/labels/{kind}/{id}",
+    params(
+        ("kind" = LabelKind, Path, description = "sample, isolate, dna or box"),
+        ("id" = Uuid, Path, description = "Item id"),
+        LabelParams
+    ),
+    responses(
+        (status = OK, description = "Label as SVG, or the bare code as PNG", content_type = "image/svg+xml"),
+        (status = NOT_FOUND, description = "Item not found")
+    )
+)]
+pub async fn get_label(
+    State(db): State<DatabaseConnection>,
+    Path((kind, id)): Path<(LabelKind, Uuid)>,
+    Query(params): Query<LabelParams>,
+) -> Result<Response, ApiError> {
+    let label = services::load(&db, kind, id).await?;
+    let code = encode(&label.payload())?;
+    let size = params.size.unwrap_or(kind.default_size());
+
+    Ok(match params.format.unwrap_or_default() {
+        LabelFormat::Svg => (
+            [(header::CONTENT_TYPE, "image/svg+xml")],
+            render::svg(&label, &code, size),
+        )
+            .into_response(),
+        LabelFormat::Png => {
+            let png = render::png(&code).map_err(|err| {
+                ApiError::internal("Could not render label", Some(err.to_string()))
+            })?;
+            ([(header::CONTENT_TYPE, "image/png")], png).into_response()
+        }
+    })
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/labels/sheet",
+    request_body = LabelSheetRequest,
+    responses(
+        (status = OK, description = "A4 PDF with the labels laid out for the label size", content_type = "application/pdf"),
+        (status = BAD_REQUEST, description = "No items, or more labels than one request may print"),
+        (status = NOT_FOUND, description = "An item was not found")
+    )
+)]
+pub async fn post_label_sheet(
+    State(db): State<DatabaseConnection>,
+    Json(request): Json<LabelSheetRequest>,
+) -> Result<Response, ApiError> {
+    let copies = request.copies.unwrap_or(1) as usize;
+    let total = request.items.len().saturating_mul(copies);
+    if total == 0 {
+        return Err(ApiError::bad_request("Nothing to print"));
+    }
+    if total > MAX_SHEET_LABELS {
+        return Err(ApiError::bad_request(format!(
+            "At most {MAX_SHEET_LABELS} labels per sheet request"
+        )));
+    }
+    let size = request.size.unwrap_or(super::models::LabelSize::CryoTube);
+
+    let mut labels = Vec::with_capacity(total);
+    for item in &request.items {
+        let label = services::load(&db, item.kind, item.id).await?;
+        let code = encode(&label.payload())?;
+        for _ in 0..copies {
+            labels.push((label.clone(), code.clone()));
+        }
+    }
+
+    Ok((
+        [(header::CONTENT_TYPE, "application/pdf")],
+        render::pdf_sheet(&labels, size),
+    )
+        .into_response())
+}
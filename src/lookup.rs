@@ -0,0 +1,369 @@
+use axum::extract::{Query, Request, State};
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::Json;
+use sea_orm::{
+    ColumnTrait, Condition, DatabaseConnection, DbErr, EntityTrait, QueryFilter, QuerySelect,
+};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::middleware;
+use crate::{areas, dna, field_records, isolates, samples, sites, storage_units};
+
+/// Length of a short ID: the first group of hex digits of the UUID.
+const SHORT_ID_LEN: usize = 8;
+
+#[derive(Debug, Deserialize)]
+pub struct LookupParams {
+    pub code: String,
+    /// Answer a unique match with `303 See Other` to the entity instead of JSON.
+    #[serde(default)]
+    pub redirect: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Resource {
+    Area,
+    Site,
+    FieldRecord,
+    Sample,
+    Isolate,
+    Dna,
+    StorageUnit,
+}
+
+impl Resource {
+    const ALL: [Resource; 7] = [
+        Resource::Area,
+        Resource::Site,
+        Resource::FieldRecord,
+        Resource::Sample,
+        Resource::Isolate,
+        Resource::Dna,
+        Resource::StorageUnit,
+    ];
+
+    fn path_segment(self) -> &'static str {
+        match self {
+            Resource::Area => "areas",
+            Resource::Site => "sites",
+            Resource::FieldRecord => "field_records",
+            Resource::Sample => "samples",
+            Resource::Isolate => "isolates",
+            Resource::Dna => "dna",
+            Resource::StorageUnit => "storage_units",
+        }
+    }
+}
+
+/// Which part of the code resolved the entity.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchedOn {
+    Id,
+    Name,
+    ShortId,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LookupEntity {
+    pub resource: Resource,
+    pub id: Uuid,
+    pub name: String,
+    /// API path of the entity, e.g. `/api/samples/<id>`.
+    pub path: String,
+}
+
+impl LookupEntity {
+    fn new(resource: Resource, id: Uuid, name: String) -> Self {
+        Self {
+            path: format!("/api/{}/{id}", resource.path_segment()),
+            resource,
+            id,
+            name,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct LookupMatch {
+    #[serde(flatten)]
+    pub entity: LookupEntity,
+    pub matched_on: MatchedOn,
+    /// Parents from the area down, ending with the entity's direct parent. Aliquots
+    /// list the samples they were split from after their field record, and isolates
+    /// the sample they were cultured from; storage units list the units they sit in,
+    /// freezer first.
+    pub ancestry: Vec<LookupEntity>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LookupResponse {
+    pub code: String,
+    pub matches: Vec<LookupMatch>,
+}
+
+/// `(id, name)` pairs of one resource matching `condition`.
+async fn find<E: EntityTrait>(
+    db: &DatabaseConnection,
+    id: E::Column,
+    name: E::Column,
+    condition: Condition,
+    scope: Option<Condition>,
+) -> Result<Vec<(Uuid, String)>, DbErr> {
+    let mut select = E::find()
+        .select_only()
+        .column(id)
+        .column(name)
+        .filter(condition);
+    if let Some(scope) = scope {
+        select = select.filter(scope);
+    }
+    select.into_tuple().all(db).await
+}
+
+enum Criterion {
+    Id(Uuid),
+    Name(String),
+    /// Inclusive UUID range sharing the short ID's leading bits.
+    ShortId(Uuid, Uuid),
+}
+
+async fn find_in(
+    db: &DatabaseConnection,
+    resource: Resource,
+    criterion: &Criterion,
+    scope_public: bool,
+) -> Result<Vec<(Uuid, String)>, DbErr> {
+    macro_rules! find_entity {
+        ($module:ident, $scope:expr) => {{
+            use $module::db::{Column, Entity};
+            let condition = match criterion {
+                Criterion::Id(id) => Condition::all().add(Column::Id.eq(*id)),
+                Criterion::Name(name) => Condition::all().add(Column::Name.eq(name.as_str())),
+                Criterion::ShortId(low, high) => {
+                    Condition::all().add(Column::Id.between(*low, *high))
+                }
+            };
+            find::<Entity>(
+                db,
+                Column::Id,
+                Column::Name,
+                condition,
+                scope_public.then($scope),
+            )
+            .await
+        }};
+    }
+
+    match resource {
+        Resource::Area => find_entity!(areas, middleware::areas_scope),
+        Resource::Site => find_entity!(sites, middleware::sites_scope),
+        Resource::FieldRecord => find_entity!(field_records, middleware::field_records_scope),
+        Resource::Sample => find_entity!(samples, middleware::samples_scope),
+        Resource::Isolate => find_entity!(isolates, middleware::isolates_scope),
+        Resource::Dna => find_entity!(dna, middleware::dna_scope),
+        // Storage is admin-only, so box labels resolve for admins alone.
+        Resource::StorageUnit if scope_public => Ok(vec![]),
+        Resource::StorageUnit => find_entity!(storage_units, Condition::all),
+    }
+}
+
+/// The samples `sample` was split from, nearest first.
+async fn parent_samples(
+    db: &DatabaseConnection,
+    mut current: Option<samples::db::Model>,
+) -> Result<Vec<LookupEntity>, DbErr> {
+    let mut parents = vec![];
+    while let Some(parent_id) = current.and_then(|sample| sample.parent_sample_id) {
+        current = samples::db::Entity::find_by_id(parent_id).one(db).await?;
+        if let Some(parent) = &current {
+            parents.push(LookupEntity::new(
+                Resource::Sample,
+                parent.id,
+                parent.name.clone(),
+            ));
+        }
+    }
+    Ok(parents)
+}
+
+/// Ancestors root first. A public entity's parents are public by construction of the
+/// scopes, so they are not filtered again; only the sample a public isolate was
+/// cultured from may be private, and is then left out for public callers.
+async fn ancestry(
+    db: &DatabaseConnection,
+    resource: Resource,
+    id: Uuid,
+    scope_public: bool,
+) -> Result<Vec<LookupEntity>, DbErr> {
+    let mut chain = vec![];
+    let mut field_record_id = None;
+    match resource {
+        Resource::Area => return Ok(chain),
+        Resource::Site => {}
+        Resource::FieldRecord => field_record_id = Some(id),
+        Resource::Sample => {
+            let sample = samples::db::Entity::find_by_id(id).one(db).await?;
+            field_record_id = sample.as_ref().map(|sample| sample.field_record_id);
+            chain.extend(parent_samples(db, sample).await?);
+        }
+        Resource::Isolate => {
+            let isolate = isolates::db::Entity::find_by_id(id).one(db).await?;
+            field_record_id = isolate.as_ref().map(|isolate| isolate.field_record_id);
+            if let Some(sample_id) = isolate.and_then(|isolate| isolate.sample_id) {
+                let mut sample = samples::db::Entity::find_by_id(sample_id);
+                if scope_public {
+                    sample = sample.filter(middleware::samples_scope());
+                }
+                if let Some(sample) = sample.one(db).await? {
+                    chain.push(LookupEntity::new(
+                        Resource::Sample,
+                        sample.id,
+                        sample.name.clone(),
+                    ));
+                    chain.extend(parent_samples(db, Some(sample)).await?);
+                }
+            }
+        }
+        Resource::Dna => {
+            field_record_id = dna::db::Entity::find_by_id(id)
+                .one(db)
+                .await?
+                .map(|extract| extract.field_record_id);
+        }
+        Resource::StorageUnit => {
+            let mut current = storage_units::db::Entity::find_by_id(id).one(db).await?;
+            while let Some(parent_id) = current.and_then(|unit| unit.parent_id) {
+                current = storage_units::db::Entity::find_by_id(parent_id)
+                    .one(db)
+                    .await?;
+                if let Some(parent) = &current {
+                    chain.push(LookupEntity::new(
+                        Resource::StorageUnit,
+                        parent.id,
+                        parent.name.clone(),
+                    ));
+                }
+            }
+        }
+    }
+
+    let mut site_id = (resource == Resource::Site).then_some(id);
+    if let Some(field_record_id) = field_record_id {
+        if let Some(record) = field_records::db::Entity::find_by_id(field_record_id)
+            .one(db)
+            .await?
+        {
+            site_id = Some(record.site_id);
+            if resource != Resource::FieldRecord {
+                chain.push(LookupEntity::new(
+                    Resource::FieldRecord,
+                    record.id,
+                    record.name,
+                ));
+            }
+        }
+    }
+    if let Some(site_id) = site_id {
+        if let Some(site) = sites::db::Entity::find_by_id(site_id).one(db).await? {
+            if resource != Resource::Site {
+                chain.push(LookupEntity::new(Resource::Site, site.id, site.name));
+            }
+            if let Some(area_id) = site.area_id {
+                if let Some(area) = areas::db::Entity::find_by_id(area_id).one(db).await? {
+                    chain.push(LookupEntity::new(Resource::Area, area.id, area.name));
+                }
+            }
+        }
+    }
+
+    chain.reverse();
+    Ok(chain)
+}
+
+/// Criteria to try in order; the first that matches anything wins, so a name made of
+/// eight hex digits is taken as a name before a short ID.
+fn criteria(code: &str) -> Vec<(MatchedOn, Criterion)> {
+    if let Ok(id) = Uuid::parse_str(code) {
+        return vec![(MatchedOn::Id, Criterion::Id(id))];
+    }
+    let mut criteria = vec![(MatchedOn::Name, Criterion::Name(code.to_string()))];
+    if code.len() == SHORT_ID_LEN {
+        if let Ok(prefix) = u32::from_str_radix(code, 16) {
+            let low = u128::from(prefix) << 96;
+            let high = low | (u128::MAX >> 32);
+            criteria.push((
+                MatchedOn::ShortId,
+                Criterion::ShortId(Uuid::from_u128(low), Uuid::from_u128(high)),
+            ));
+        }
+    }
+    criteria
+}
+
+/// Exact counterpart of `/api/search`: resolves a scanned UUID, name or short ID to
+/// the areas, sites, field records, samples, isolates, DNA and storage units it
+/// identifies.
+pub async fn lookup(
+    State(db): State<DatabaseConnection>,
+    Query(params): Query<LookupParams>,
+    req: Request,
+) -> Result<Response, (StatusCode, String)> {
+    let code = params.code.trim().to_string();
+    if code.is_empty() || code.len() > 200 {
+        return Err((
+            StatusCode::BAD_REQUEST,
+            "Code must be between 1 and 200 characters".to_string(),
+        ));
+    }
+    let scope_public = req.extensions().get::<middleware::PublicLookup>().is_some();
+    let internal_error = |_: DbErr| {
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "Lookup failed".to_string(),
+        )
+    };
+
+    let mut matches = vec![];
+    for (matched_on, criterion) in criteria(&code) {
+        for resource in Resource::ALL {
+            for (id, name) in find_in(&db, resource, &criterion, scope_public)
+                .await
+                .map_err(internal_error)?
+            {
+                matches.push(LookupMatch {
+                    entity: LookupEntity::new(resource, id, name),
+                    matched_on,
+                    ancestry: ancestry(&db, resource, id, scope_public)
+                        .await
+                        .map_err(internal_error)?,
+                });
+            }
+        }
+        if !matches.is_empty() {
+            break;
+        }
+    }
+
+    if matches.is_empty() {
+        return Err((StatusCode::NOT_FOUND, format!("Nothing matches '{code}'")));
+    }
+    if params.redirect {
+        if let [only] = matches.as_slice() {
+            return Ok((
+                StatusCode::SEE_OTHER,
+                [(header::LOCATION, only.entity.path.clone())],
+            )
+                .into_response());
+        }
+        return Ok((
+            StatusCode::MULTIPLE_CHOICES,
+            Json(LookupResponse { code, matches }),
+        )
+            .into_response());
+    }
+    Ok(Json(LookupResponse { code, matches }).into_response())
+}
@@ -0,0 +1,230 @@
+use axum::{
+    body::{to_bytes, Body},
+    http::{header, Request, StatusCode},
+};
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+use crate::test_utils::{
+    build_app_with_db, build_scoped_app_with_db, create, create_field_record, create_site,
+    setup_sqlite_db,
+};
+
+/// Status, `Location` header and JSON body.
+async fn lookup(app: &axum::Router, query: &str) -> (StatusCode, Option<String>, Value) {
+    let req = Request::builder()
+        .method("GET")
+        .uri(format!("/api/lookup?{query}"))
+        .body(Body::empty())
+        .unwrap();
+    let resp = app.clone().oneshot(req).await.unwrap();
+    let status = resp.status();
+    let location = resp
+        .headers()
+        .get(header::LOCATION)
+        .map(|v| v.to_str().unwrap().to_string());
+    let body = to_bytes(resp.into_body(), 1024 * 1024).await.unwrap();
+    (
+        status,
+        location,
+        serde_json::from_slice(&body).unwrap_or(Value::Null),
+    )
+}
+
+fn ancestry_names(found: &Value) -> Vec<&str> {
+    found["ancestry"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|a| a["name"].as_str().unwrap())
+        .collect()
+}
+
+/// Site Glacier A → field record FR-1 → sample S-1 → aliquot S-1a. Returns the
+/// field record, sample and aliquot ids.
+async fn sample_lineage(app: &axum::Router) -> (String, String, String) {
+    let site_id = create_site(app, "Glacier A").await;
+    let fr = create_field_record(app, &site_id, "FR-1").await;
+    let sample = create(
+        app,
+        "/api/samples",
+        json!({ "name": "S-1", "field_record_id": fr }),
+    )
+    .await;
+    let aliquot = create(
+        app,
+        "/api/samples",
+        json!({ "name": "S-1a", "field_record_id": fr, "parent_sample_id": sample }),
+    )
+    .await;
+    (fr, sample, aliquot)
+}
+
+/// Expected behaviour: the upper-case UUID printed on a label resolves to the sample
+/// with its site and field record.
+#[tokio::test]
+async fn label_uuids_resolve_with_ancestry() {
+    let db = setup_sqlite_db().await;
+    let admin = build_app_with_db(db.clone());
+    let scoped = build_scoped_app_with_db(db);
+    let (_, sample, _) = sample_lineage(&admin).await;
+
+    let (status, _, body) = lookup(&scoped, &format!("code={}", sample.to_uppercase())).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let found = &body["matches"][0];
+    assert_eq!(found["resource"], "sample");
+    assert_eq!(found["matched_on"], "id");
+    assert_eq!(found["path"], format!("/api/samples/{sample}"));
+    assert_eq!(ancestry_names(found), ["Glacier A", "FR-1"]);
+}
+
+/// Expected behaviour: a padded name resolves the aliquot alone, listing the sample
+/// it was split from after its field record.
+#[tokio::test]
+async fn names_resolve_aliquots_below_their_parent_sample() {
+    let db = setup_sqlite_db().await;
+    let admin = build_app_with_db(db.clone());
+    let scoped = build_scoped_app_with_db(db);
+    let (_, _, aliquot) = sample_lineage(&admin).await;
+
+    let (status, _, body) = lookup(&scoped, "code=%20S-1a%20").await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert_eq!(body["matches"].as_array().unwrap().len(), 1);
+    assert_eq!(body["matches"][0]["id"], aliquot);
+    assert_eq!(
+        ancestry_names(&body["matches"][0]),
+        ["Glacier A", "FR-1", "S-1"]
+    );
+}
+
+/// Scenario: one isolate cultured from aliquot S-1a and another from a private
+/// sample.
+/// Expected behaviour: the isolate lists the samples between its field record and
+/// itself; public callers do not see the private sample.
+#[tokio::test]
+async fn isolates_resolve_below_the_sample_they_were_cultured_from() {
+    let db = setup_sqlite_db().await;
+    let admin = build_app_with_db(db.clone());
+    let scoped = build_scoped_app_with_db(db);
+    let (fr, _, aliquot) = sample_lineage(&admin).await;
+    let private = create(
+        &admin,
+        "/api/samples",
+        json!({ "name": "S-2", "field_record_id": fr, "is_private": true }),
+    )
+    .await;
+    for (name, sample) in [("ISO-1", &aliquot), ("ISO-2", &private)] {
+        create(
+            &admin,
+            "/api/isolates",
+            json!({ "name": name, "field_record_id": fr, "sample_id": sample }),
+        )
+        .await;
+    }
+
+    let (_, _, body) = lookup(&scoped, "code=ISO-1").await;
+    assert_eq!(
+        ancestry_names(&body["matches"][0]),
+        ["Glacier A", "FR-1", "S-1", "S-1a"]
+    );
+    let (_, _, body) = lookup(&admin, "code=ISO-2").await;
+    assert_eq!(
+        ancestry_names(&body["matches"][0]),
+        ["Glacier A", "FR-1", "S-2"]
+    );
+    let (_, _, body) = lookup(&scoped, "code=ISO-2").await;
+    assert_eq!(ancestry_names(&body["matches"][0]), ["Glacier A", "FR-1"]);
+}
+
+#[tokio::test]
+async fn short_ids_and_redirects_resolve() {
+    let db = setup_sqlite_db().await;
+    let admin = build_app_with_db(db.clone());
+    let scoped = build_scoped_app_with_db(db);
+    let (fr, _, _) = sample_lineage(&admin).await;
+
+    let (status, _, body) = lookup(&scoped, &format!("code={}", &fr[..8])).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    assert!(body["matches"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .any(|m| m["id"] == fr && m["matched_on"] == "short_id"));
+
+    let (status, location, _) = lookup(&scoped, "code=FR-1&redirect=true").await;
+    assert_eq!(status, StatusCode::SEE_OTHER);
+    assert_eq!(location, Some(format!("/api/field_records/{fr}")));
+}
+
+/// Expected behaviour: a sample on a private field record resolves for admins only;
+/// a blank code is 400.
+#[tokio::test]
+async fn private_rows_stay_hidden_from_the_public() {
+    let db = setup_sqlite_db().await;
+    let admin = build_app_with_db(db.clone());
+    let scoped = build_scoped_app_with_db(db);
+    let site_id = create_site(&admin, "Glacier A").await;
+    let private_fr = create(
+        &admin,
+        "/api/field_records",
+        json!({
+            "name": "FR-2", "site_id": site_id, "sample_type": "Snow",
+            "sampling_date": "2025-07-11", "is_private": true
+        }),
+    )
+    .await;
+    create(
+        &admin,
+        "/api/samples",
+        json!({ "name": "S-2", "field_record_id": private_fr }),
+    )
+    .await;
+
+    let (status, _, _) = lookup(&scoped, "code=S-2").await;
+    assert_eq!(status, StatusCode::NOT_FOUND, "private field record");
+    let (status, _, body) = lookup(&admin, "code=S-2").await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+
+    let (status, _, _) = lookup(&scoped, "code=%20").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+/// Scenario: the label of box `Box 7` in `Freezer B / Rack 2` is scanned.
+/// Expected behaviour: admins get the box with its freezer and rack; storage is not
+/// public, so public callers get nothing.
+#[tokio::test]
+async fn box_labels_resolve_to_storage_units() {
+    let db = setup_sqlite_db().await;
+    let admin = build_app_with_db(db.clone());
+    let scoped = build_scoped_app_with_db(db);
+    let freezer = create(
+        &admin,
+        "/api/storage_units",
+        json!({ "name": "Freezer B", "kind": "Freezer" }),
+    )
+    .await;
+    let rack = create(
+        &admin,
+        "/api/storage_units",
+        json!({ "name": "Rack 2", "kind": "Rack", "parent_id": freezer }),
+    )
+    .await;
+    let storage_box = create(
+        &admin,
+        "/api/storage_units",
+        json!({ "name": "Box 7", "kind": "Box", "parent_id": rack, "rows": 9, "columns": 9 }),
+    )
+    .await;
+
+    for code in [storage_box.to_uppercase(), "Box%207".to_string()] {
+        let (status, _, body) = lookup(&admin, &format!("code={code}")).await;
+        assert_eq!(status, StatusCode::OK, "{body}");
+        let found = &body["matches"][0];
+        assert_eq!(found["resource"], "storage_unit");
+        assert_eq!(found["path"], format!("/api/storage_units/{storage_box}"));
+        assert_eq!(ancestry_names(found), ["Freezer B", "Rack 2"]);
+    }
+
+    let (status, _, _) = lookup(&scoped, &format!("code={storage_box}")).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
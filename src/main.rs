@@ -1,25 +1,51 @@
 mod areas;
+mod assemblies;
+mod campaigns;
 mod common;
 mod config;
+mod culture_accessions;
+mod custody_events;
 mod dna;
 #[cfg(test)]
 mod bulk_import_tests;
 #[cfg(test)]
 mod e2e_tests;
 #[cfg(test)]
+mod lookup_tests;
+#[cfg(test)]
 mod smoke_tests;
 mod field_records;
+mod files;
+mod growth_tests;
+mod isolate_images;
 mod isolates;
+mod labels;
+mod lookup;
+mod marker_sequences;
+mod material_requests;
+mod measurement_thresholds;
 mod middleware;
+mod parameter_values;
+mod parameters;
+mod replicate_groups;
+mod sample_type_measurements;
+mod sample_types;
 mod samples;
 mod search;
+mod shipment_items;
+mod shipments;
 mod sites;
+mod storage_positions;
+mod storage_units;
+mod taxa;
+mod temperature_readings;
+mod withdrawals;
 #[cfg(test)]
 mod test_utils;
 
 use axum::{routing::get, Router};
 use axum_keycloak_auth::{instance::KeycloakAuthInstance, instance::KeycloakConfig, Url};
-use config::Config;
+use config::{Config, FileStoreConfig};
 use migration::{Migrator, MigratorTrait};
 use sea_orm::{Database, DatabaseConnection};
 use std::sync::Arc;
@@ -51,6 +77,27 @@ async fn run() {
         .await
         .expect("Failed to run migrations");
 
+    let file_store =
+        files::store::build(&FileStoreConfig::from_env()).expect("Failed to open the file store");
+    let moved = files::services::store_pending(&db, &*file_store)
+        .await
+        .expect("Failed to move migrated photos to the file store");
+    if moved > 0 {
+        println!("Moved {moved} migrated photos to the file store");
+    }
+    let removed = files::services::remove_orphans(&db, &*file_store)
+        .await
+        .expect("Failed to remove orphaned files");
+    if removed > 0 {
+        println!("Removed {removed} files nothing refers to");
+    }
+    let filled = isolates::services::fill_lineages(&db)
+        .await
+        .expect("Failed to derive isolate lineages");
+    if filled > 0 {
+        println!("Derived the lineage of {filled} isolates from their taxonomy");
+    }
+
     println!(
         "Starting server {} ({} deployment) ...",
         config.app_name,
@@ -89,17 +136,65 @@ async fn run() {
         .nest(
             "/api/field_records",
             Router::from(field_records::db::FieldRecord::router(&db.clone()))
+                .merge(field_records::views::router(&db))
                 .layer(axum::middleware::from_fn(middleware::scope_field_records)),
         )
         .nest(
             "/api/samples",
             Router::from(samples::db::Sample::router(&db.clone()))
+                .merge(samples::views::router(&db))
                 .layer(axum::middleware::from_fn(middleware::scope_samples)),
         )
         .nest(
             "/api/isolates",
             Router::from(isolates::db::Isolate::router(&db.clone()))
-                .layer(axum::middleware::from_fn(middleware::scope_isolates)),
+                .merge(isolates::views::router(&db, &file_store))
+                .layer(axum::middleware::from_fn_with_state(
+                    (db.clone(), file_store.clone()),
+                    middleware::remove_orphaned_files,
+                ))
+                .layer(axum::middleware::from_fn_with_state(
+                    db.clone(),
+                    middleware::scope_isolates,
+                )),
+        )
+        .nest(
+            "/api/isolate_images",
+            Router::from(isolate_images::db::IsolateImage::router(&db.clone()))
+                .merge(isolate_images::views::router(&db, &file_store))
+                .layer(axum::middleware::from_fn_with_state(
+                    (db.clone(), file_store.clone()),
+                    middleware::remove_orphaned_files,
+                ))
+                .layer(axum::middleware::from_fn_with_state(
+                    isolate_images::db::Column::IsolateId,
+                    middleware::scope_isolate_child::<isolate_images::db::Column>,
+                )),
+        )
+        .nest(
+            "/api/growth_tests",
+            Router::from(growth_tests::db::GrowthTest::router(&db.clone()))
+                .layer(axum::middleware::from_fn_with_state(
+                    growth_tests::db::Column::IsolateId,
+                    middleware::scope_isolate_child::<growth_tests::db::Column>,
+                )),
+        )
+        .nest(
+            "/api/marker_sequences",
+            Router::from(marker_sequences::db::MarkerSequence::router(&db.clone()))
+                .merge(marker_sequences::views::router(&db))
+                .layer(axum::middleware::from_fn_with_state(
+                    marker_sequences::db::Column::IsolateId,
+                    middleware::scope_isolate_child::<marker_sequences::db::Column>,
+                )),
+        )
+        .nest(
+            "/api/culture_accessions",
+            Router::from(culture_accessions::db::CultureAccession::router(&db.clone()))
+                .layer(axum::middleware::from_fn_with_state(
+                    culture_accessions::db::Column::IsolateId,
+                    middleware::scope_isolate_child::<culture_accessions::db::Column>,
+                )),
         )
         .nest(
             "/api/dna",
@@ -111,7 +206,106 @@ async fn run() {
             Router::from(areas::db::Area::router(&db.clone()))
                 .layer(axum::middleware::from_fn(middleware::scope_areas)),
         )
+        .nest(
+            "/api/campaigns",
+            Router::from(campaigns::db::Campaign::router(&db.clone()))
+                .merge(campaigns::views::router(&db))
+                .layer(axum::middleware::from_fn(middleware::scope_campaigns)),
+        )
+        .nest(
+            "/api/parameters",
+            Router::from(parameters::db::Parameter::router(&db.clone()))
+                .layer(axum::middleware::from_fn(middleware::scope_parameters)),
+        )
+        .nest(
+            "/api/parameter_values",
+            Router::from(parameter_values::db::ParameterValue::router(&db.clone()))
+                .layer(axum::middleware::from_fn(middleware::scope_parameter_values)),
+        )
+        .nest(
+            "/api/replicate_groups",
+            Router::from(replicate_groups::db::ReplicateGroup::router(&db.clone()))
+                .merge(replicate_groups::views::router(&db))
+                .layer(axum::middleware::from_fn(middleware::scope_replicate_groups)),
+        )
+        .nest(
+            "/api/measurement_thresholds",
+            Router::from(measurement_thresholds::db::MeasurementThreshold::router(&db.clone()))
+                .layer(axum::middleware::from_fn(middleware::scope_measurement_thresholds)),
+        )
+        .nest(
+            "/api/sample_types",
+            Router::from(sample_types::db::SampleType::router(&db.clone()))
+                .layer(axum::middleware::from_fn(middleware::scope_sample_types)),
+        )
+        .nest(
+            "/api/sample_type_measurements",
+            Router::from(sample_type_measurements::db::SampleTypeMeasurement::router(&db.clone()))
+                .layer(axum::middleware::from_fn(middleware::scope_sample_types)),
+        )
+        .nest(
+            "/api/storage_units",
+            Router::from(storage_units::db::StorageUnit::router(&db.clone()))
+                .merge(storage_units::views::router(&db))
+                .layer(axum::middleware::from_fn(middleware::admin_only)),
+        )
+        .nest(
+            "/api/storage_positions",
+            Router::from(storage_positions::db::StoragePosition::router(&db.clone()))
+                .merge(storage_positions::views::router(&db))
+                .layer(axum::middleware::from_fn(middleware::admin_only)),
+        )
+        .nest(
+            "/api/material_requests",
+            material_requests::views::router(&db, config.freeze_thaw)
+                .layer(axum::middleware::from_fn(middleware::scope_material_requests)),
+        )
+        .nest(
+            "/api/withdrawals",
+            Router::from(withdrawals::db::Withdrawal::router(&db.clone()))
+                .merge(withdrawals::views::router(&db))
+                .layer(axum::middleware::from_fn(middleware::admin_only)),
+        )
+        .nest(
+            "/api/custody_events",
+            Router::from(custody_events::db::CustodyEvent::router(&db.clone()))
+                .merge(custody_events::views::router(&db))
+                .layer(axum::middleware::from_fn(middleware::admin_only)),
+        )
+        .nest(
+            "/api/shipments",
+            Router::from(shipments::db::Shipment::router(&db.clone()))
+                .merge(shipments::views::router(&db))
+                .layer(axum::middleware::from_fn(middleware::admin_only)),
+        )
+        .nest(
+            "/api/shipment_items",
+            Router::from(shipment_items::db::ShipmentItem::router(&db.clone()))
+                .layer(axum::middleware::from_fn(middleware::admin_only)),
+        )
+        .nest(
+            "/api/taxa",
+            Router::from(taxa::db::Taxon::router(&db.clone()))
+                .merge(taxa::views::router(&db))
+                .layer(axum::middleware::from_fn(middleware::admin_only)),
+        )
+        .nest(
+            "/api/temperature_readings",
+            Router::from(temperature_readings::db::TemperatureReading::router(&db.clone()))
+                .merge(temperature_readings::views::router(&db))
+                .layer(axum::middleware::from_fn(middleware::admin_only)),
+        )
+        .nest(
+            "/api/labels",
+            labels::views::router(&db).layer(axum::middleware::from_fn(middleware::admin_only)),
+        )
         .route("/api/search", get(search::search).with_state(db.clone()))
+        .route(
+            "/api/lookup",
+            get(lookup::lookup)
+                .with_state(db.clone())
+                .layer(axum::middleware::from_fn(middleware::scope_lookup)),
+        )
         .layer(keycloak_pass_layer);
 
     let addr: std::net::SocketAddr = "0.0.0.0:3000".parse().unwrap();
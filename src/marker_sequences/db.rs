@@ -0,0 +1,100 @@
+use crate::common::enums::MarkerGene;
+use chrono::{DateTime, NaiveDate, Utc};
+use crudcrate::validation::{Validatable, ValidationError};
+use crudcrate::{CRUDResource, EntityToModels};
+use sea_orm::entity::prelude::*;
+use sea_orm::Set;
+use uuid::Uuid;
+
+use super::kmers;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+#[sea_orm(table_name = "marker_sequences")]
+#[crudcrate(
+    generate_router,
+    api_struct = "MarkerSequence",
+    name_singular = "marker_sequence",
+    name_plural = "marker_sequences",
+    description = "16S, 18S and ITS barcode sequences of isolates",
+    no_eq,
+    derive_partial_eq,
+    create::one::body = crate::marker_sequences::services::create_sequence,
+    create::many::body = crate::marker_sequences::services::create_sequences
+)]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    #[crudcrate(primary_key, filterable, exclude(update, create), on_create = Uuid::new_v4())]
+    pub id: Uuid,
+    #[crudcrate(sortable, filterable, exclude(update))]
+    pub isolate_id: Uuid,
+    #[crudcrate(sortable, filterable)]
+    pub marker: MarkerGene,
+    /// Nucleotides, stored upper-case as DNA without whitespace; a pasted FASTA
+    /// header line is dropped.
+    pub sequence: String,
+    #[crudcrate(sortable, filterable, exclude(create, update), on_create = 0)]
+    pub length_bp: i32,
+    /// Primers or sequencing method, e.g. `27F/1492R, Sanger`.
+    #[crudcrate(sortable, filterable, fulltext)]
+    pub method: Option<String>,
+    #[crudcrate(sortable, filterable)]
+    pub sequenced_on: Option<NaiveDate>,
+    #[crudcrate(fulltext)]
+    pub notes: Option<String>,
+    #[crudcrate(sortable, filterable, exclude(create, update), on_create = chrono::Utc::now())]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "crate::isolates::db::Entity",
+        from = "Column::IsolateId",
+        to = "crate::isolates::db::Column::Id",
+        on_delete = "Cascade"
+    )]
+    Isolate,
+}
+
+impl Related<crate::isolates::db::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Isolate.def()
+    }
+}
+
+/// Normalises the sequence and counts its bases on every save.
+#[async_trait::async_trait]
+impl ActiveModelBehavior for ActiveModel {
+    async fn before_save<C>(mut self, _db: &C, _insert: bool) -> Result<Self, DbErr>
+    where
+        C: ConnectionTrait,
+    {
+        if let sea_orm::ActiveValue::Set(sequence) = &self.sequence {
+            let sequence = kmers::normalize(sequence).map_err(DbErr::Custom)?;
+            self.length_bp = Set(sequence.len() as i32);
+            self.sequence = Set(sequence);
+        }
+        Ok(self)
+    }
+}
+
+fn check_sequence(sequence: &str) -> Result<(), ValidationError> {
+    kmers::normalize(sequence)
+        .map(|_| ())
+        .map_err(|message| ValidationError::new("sequence", message))
+}
+
+impl Validatable for MarkerSequenceCreate {
+    fn validate(&self) -> Result<(), ValidationError> {
+        check_sequence(&self.sequence)
+    }
+}
+
+impl Validatable for MarkerSequenceUpdate {
+    fn validate(&self) -> Result<(), ValidationError> {
+        match &self.sequence {
+            Some(Some(sequence)) => check_sequence(sequence),
+            _ => Ok(()),
+        }
+    }
+}
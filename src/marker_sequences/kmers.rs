@@ -0,0 +1,107 @@
+//! Alignment-free comparison of marker sequences by their shared 8-mers, the word
+//! length classifiers such as RDP use for 16S.
+
+/// IUPAC nucleotide codes.
+const BASES: &[u8] = b"ACGTRYSWKMBDHVN";
+
+/// Longer than any marker amplicon; whole genomes go to `/api/isolates/{id}/assembly`.
+pub const MAX_LENGTH: usize = 20_000;
+
+pub const K: usize = 8;
+
+/// 2-bit codes of the k-mers fill a bit set of 4^8 bits.
+const WORDS: usize = (1 << (2 * K)) / 64;
+
+/// Upper-case DNA without whitespace, from pasted text or a single FASTA record.
+pub fn normalize(text: &str) -> Result<String, String> {
+    let text = text.trim_start();
+    let body = match text.strip_prefix('>') {
+        Some(record) => record.split_once('\n').map_or("", |(_, body)| body),
+        None => text,
+    };
+    let mut sequence = String::with_capacity(body.len());
+    for c in body.chars().filter(|c| !c.is_whitespace()) {
+        let base = match c.to_ascii_uppercase() {
+            'U' => 'T',
+            '>' => return Err("Give one sequence, not several FASTA records".to_string()),
+            base if base.is_ascii() && BASES.contains(&(base as u8)) => base,
+            _ => return Err(format!("'{c}' is not a nucleotide code")),
+        };
+        sequence.push(base);
+    }
+    if sequence.is_empty() {
+        return Err("The sequence is empty".to_string());
+    }
+    if sequence.len() > MAX_LENGTH {
+        return Err(format!(
+            "Marker sequences are limited to {MAX_LENGTH} bases"
+        ));
+    }
+    Ok(sequence)
+}
+
+fn code(base: u8) -> Option<u32> {
+    match base {
+        b'A' => Some(0),
+        b'C' => Some(1),
+        b'G' => Some(2),
+        b'T' => Some(3),
+        _ => None,
+    }
+}
+
+/// The distinct k-mers of a sequence, each counted with its reverse complement so
+/// that sequences read off either strand compare alike. K-mers spanning an
+/// ambiguous base are skipped.
+pub struct Profile {
+    bits: Vec<u64>,
+    count: u32,
+}
+
+impl Profile {
+    pub fn new(sequence: &str) -> Self {
+        let mask = (1u32 << (2 * K)) - 1;
+        let mut profile = Profile {
+            bits: vec![0; WORDS],
+            count: 0,
+        };
+        let (mut forward, mut reverse, mut valid) = (0u32, 0u32, 0);
+        for base in sequence.bytes() {
+            let Some(code) = code(base) else {
+                valid = 0;
+                continue;
+            };
+            forward = ((forward << 2) | code) & mask;
+            reverse = (reverse >> 2) | ((3 - code) << (2 * (K - 1)));
+            valid += 1;
+            if valid >= K {
+                profile.insert(forward.min(reverse));
+            }
+        }
+        profile
+    }
+
+    fn insert(&mut self, kmer: u32) {
+        let (word, bit) = (kmer as usize / 64, kmer % 64);
+        if self.bits[word] & (1 << bit) == 0 {
+            self.bits[word] |= 1 << bit;
+            self.count += 1;
+        }
+    }
+
+    pub fn len(&self) -> u32 {
+        self.count
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.count == 0
+    }
+
+    pub fn shared(&self, other: &Profile) -> u32 {
+        self.bits
+            .iter()
+            .zip(&other.bits)
+            .map(|(a, b)| (a & b).count_ones())
+            .sum()
+    }
+}
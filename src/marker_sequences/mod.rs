@@ -0,0 +1,7 @@
+pub mod db;
+pub mod kmers;
+pub mod models;
+pub mod services;
+#[cfg(test)]
+mod tests;
+pub mod views;
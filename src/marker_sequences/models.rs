@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use crate::common::enums::MarkerGene;
+
+#[derive(Deserialize, IntoParams, Debug)]
+pub struct SimilarParams {
+    /// Query sequence, as plain nucleotides or one FASTA record.
+    pub sequence: String,
+    /// Only compare with sequences of this marker.
+    pub marker: Option<MarkerGene>,
+    /// Most similar sequences to return; 10 by default, at most 100.
+    pub limit: Option<u64>,
+}
+
+/// A stored sequence sharing 8-mers with the query.
+#[derive(ToSchema, Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct SimilarSequence {
+    pub marker_sequence_id: Uuid,
+    pub isolate_id: Uuid,
+    pub isolate_name: String,
+    pub marker: MarkerGene,
+    pub length_bp: i32,
+    /// Share of the query's distinct 8-mers found in this sequence, from 0 to 1.
+    pub similarity: f64,
+    pub shared_kmers: u32,
+}
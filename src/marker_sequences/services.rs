@@ -0,0 +1,108 @@
+use crudcrate::validation::ValidationError;
+use crudcrate::ApiError;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, Condition, DatabaseConnection, EntityTrait, QueryFilter,
+    QuerySelect, QueryTrait, TransactionTrait,
+};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use super::db::{ActiveModel, Column, Entity, MarkerSequence, MarkerSequenceCreate};
+use super::kmers::{self, Profile};
+use super::models::{SimilarParams, SimilarSequence};
+use crate::common::batch;
+use crate::isolates;
+
+const DEFAULT_LIMIT: u64 = 10;
+const MAX_LIMIT: u64 = 100;
+
+/// Stores a sequence pasted as text or FASTA; saving the active model strips it to
+/// upper-case bases and records their count in `length_bp`.
+pub(super) async fn create_sequence(
+    db: &DatabaseConnection,
+    data: MarkerSequenceCreate,
+) -> Result<MarkerSequence, ApiError> {
+    let model = ActiveModel::from(data).insert(db).await?;
+    Ok(model.into())
+}
+
+/// Stores a batch of sequences in one transaction, so a bad sequence stores none.
+pub(super) async fn create_sequences(
+    db: &DatabaseConnection,
+    data: Vec<MarkerSequenceCreate>,
+) -> Result<Vec<MarkerSequence>, ApiError> {
+    batch::check_limit::<MarkerSequence>("create", data.len())?;
+    let txn = db.begin().await?;
+    let mut result = Vec::with_capacity(data.len());
+    for item in data {
+        result.push(MarkerSequence::from(
+            ActiveModel::from(item).insert(&txn).await?,
+        ));
+    }
+    txn.commit().await?;
+    Ok(result)
+}
+
+/// The stored sequences most similar to the query, among those of isolates matching
+/// `condition`, best first. Sequences sharing no 8-mer with the query are left out.
+pub async fn similar(
+    db: &DatabaseConnection,
+    condition: Condition,
+    params: &SimilarParams,
+) -> Result<Vec<SimilarSequence>, ApiError> {
+    let query = kmers::normalize(&params.sequence)
+        .map_err(|message| ValidationError::new("sequence", message))?;
+    let query = Profile::new(&query);
+    if query.is_empty() {
+        return Err(ValidationError::new(
+            "sequence",
+            format!("Give at least {} unambiguous bases in a row", kmers::K),
+        )
+        .into());
+    }
+    let limit = params.limit.unwrap_or(DEFAULT_LIMIT).clamp(1, MAX_LIMIT) as usize;
+
+    let names: HashMap<Uuid, String> = isolates::db::Entity::find()
+        .select_only()
+        .columns([isolates::db::Column::Id, isolates::db::Column::Name])
+        .filter(condition.clone())
+        .into_tuple()
+        .all(db)
+        .await?
+        .into_iter()
+        .collect();
+    let ids = isolates::db::Entity::find()
+        .select_only()
+        .column(isolates::db::Column::Id)
+        .filter(condition)
+        .into_query();
+    let mut sequences = Entity::find().filter(Column::IsolateId.in_subquery(ids));
+    if let Some(marker) = params.marker {
+        sequences = sequences.filter(Column::Marker.eq(marker));
+    }
+
+    let mut hits: Vec<SimilarSequence> = sequences
+        .all(db)
+        .await?
+        .into_iter()
+        .filter_map(|stored| {
+            let shared_kmers = query.shared(&Profile::new(&stored.sequence));
+            (shared_kmers > 0).then(|| SimilarSequence {
+                marker_sequence_id: stored.id,
+                isolate_name: names.get(&stored.isolate_id).cloned().unwrap_or_default(),
+                isolate_id: stored.isolate_id,
+                marker: stored.marker,
+                length_bp: stored.length_bp,
+                similarity: shared_kmers as f64 / query.len() as f64,
+                shared_kmers,
+            })
+        })
+        .collect();
+    hits.sort_by(|a, b| {
+        b.similarity
+            .total_cmp(&a.similarity)
+            .then_with(|| a.isolate_name.cmp(&b.isolate_name))
+    });
+    hits.truncate(limit);
+    Ok(hits)
+}
@@ -0,0 +1,217 @@
+use axum::http::StatusCode;
+use serde_json::{json, Value};
+
+use crate::test_utils::{
+    build_app_with_db, build_scoped_app_with_db, create, create_field_record, create_json,
+    create_site, get, send, setup_sqlite_db,
+};
+
+/// A reproducible pseudo-random sequence.
+fn sequence(seed: u64, length: usize) -> String {
+    let mut state = seed;
+    (0..length)
+        .map(|_| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            b"ACGT"[(state >> 62) as usize] as char
+        })
+        .collect()
+}
+
+fn reverse_complement(sequence: &str) -> String {
+    sequence
+        .chars()
+        .rev()
+        .map(|base| match base {
+            'A' => 'T',
+            'C' => 'G',
+            'G' => 'C',
+            _ => 'A',
+        })
+        .collect()
+}
+
+fn similar_names(hits: &Value) -> Vec<&str> {
+    hits.as_array()
+        .unwrap()
+        .iter()
+        .map(|hit| hit["isolate_name"].as_str().unwrap())
+        .collect()
+}
+
+/// Isolates `names` from field record FR-8, the ones flagged private.
+async fn isolates(app: &axum::Router, names: &[(&str, bool)]) -> Vec<String> {
+    let site_id = create_site(app, "Glacier H").await;
+    let fr = create_field_record(app, &site_id, "FR-8").await;
+    let mut isolates = Vec::new();
+    for (name, is_private) in names {
+        isolates.push(
+            create(
+                app,
+                "/api/isolates",
+                json!({ "name": name, "field_record_id": fr, "is_private": is_private }),
+            )
+            .await,
+        );
+    }
+    isolates
+}
+
+/// 16S sequences of ISO-A, a close relative and a private isolate, and an ITS
+/// sequence of a fungus; returns ISO-A's sequence and the private one.
+async fn sequenced_isolates(app: &axum::Router) -> (String, String) {
+    let isolates = isolates(
+        app,
+        &[
+            ("ISO-A", false),
+            ("ISO-A-RELATIVE", false),
+            ("ISO-B-PRIVATE", true),
+            ("ISO-FUNGUS", false),
+        ],
+    )
+    .await;
+    let a = sequence(1, 600);
+    // Every 40th base changed: most 8-mers survive.
+    let relative: String = a
+        .chars()
+        .enumerate()
+        .map(|(i, base)| match (i % 40, base) {
+            (0, 'A') => 'C',
+            (0, _) => 'A',
+            (_, base) => base,
+        })
+        .collect();
+    let private = sequence(2, 600);
+    for (isolate, marker, sequence) in [
+        (&isolates[0], "16S", &a),
+        (&isolates[1], "16S", &relative),
+        (&isolates[2], "16S", &private),
+        (&isolates[3], "ITS", &sequence(3, 400)),
+    ] {
+        create(
+            app,
+            "/api/marker_sequences",
+            json!({ "isolate_id": isolate, "marker": marker, "sequence": sequence }),
+        )
+        .await;
+    }
+    (a, private)
+}
+
+/// Scenario: sequences are pasted as FASTA with lowercase bases and uracil, as
+/// letters that are not nucleotides, empty, in a batch with one bad sequence, and
+/// by a public caller.
+/// Expected behaviour: the FASTA is stored normalised with its length; the rest is
+/// refused, and a refused batch stores nothing.
+#[tokio::test]
+async fn sequences_are_stored_normalised() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db.clone());
+    let scoped = build_scoped_app_with_db(db);
+    let isolate = isolates(&app, &[("ISO-A", false)]).await.remove(0);
+    let a = sequence(1, 600);
+
+    for (sequence, problem) in [("ACGTQQ", "not nucleotides"), ("", "empty")] {
+        let (status, _) = send(
+            &app,
+            "POST",
+            "/api/marker_sequences",
+            json!({ "isolate_id": isolate, "marker": "16S", "sequence": sequence }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{problem}");
+    }
+    let (status, _) = send(
+        &app,
+        "POST",
+        "/api/marker_sequences/batch",
+        json!([
+            { "isolate_id": isolate, "marker": "18S", "sequence": "ACGTACGTACGT" },
+            { "isolate_id": isolate, "marker": "ITS", "sequence": "ACGTQQ" }
+        ]),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    let (_, stored) = get(&app, "/api/marker_sequences").await;
+    assert_eq!(stored, json!([]), "a refused batch stores nothing");
+
+    let pasted = format!(
+        ">ISO-A 27F/1492R\n{}\n{}\n",
+        a[..300].to_lowercase(),
+        a[300..].replace('T', "U")
+    );
+    let stored = create_json(
+        &app,
+        "/api/marker_sequences",
+        json!({ "isolate_id": isolate, "marker": "16S", "sequence": pasted, "method": "27F/1492R, Sanger" }),
+    )
+    .await;
+    assert_eq!(stored["sequence"], a);
+    assert_eq!(stored["length_bp"], 600);
+
+    let (status, _) = send(
+        &scoped,
+        "POST",
+        "/api/marker_sequences",
+        json!({ "isolate_id": isolate, "marker": "16S", "sequence": a }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+/// Scenario: part of ISO-A's 16S, read off the other strand, is looked up.
+/// Expected behaviour: the source isolate ranks first and its relative next, other
+/// markers are left out, and queries too short to search are refused.
+#[tokio::test]
+async fn similar_sequences_rank_the_source_then_its_relative() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db);
+    let (a, _) = sequenced_isolates(&app).await;
+
+    let query = reverse_complement(&a[100..500]);
+    let (status, hits) = get(
+        &app,
+        &format!("/api/marker_sequences/similar?sequence={query}&marker=16S"),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(similar_names(&hits)[..2], ["ISO-A", "ISO-A-RELATIVE"]);
+    assert_eq!(hits[0]["similarity"], 1.0);
+    assert!(hits[1]["similarity"].as_f64().unwrap() > 0.5);
+    assert!(similar_names(&hits)
+        .iter()
+        .all(|name| *name != "ISO-FUNGUS"));
+
+    let (status, _) = get(&app, "/api/marker_sequences/similar?sequence=ACGNNACG").await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+/// Scenario: the private isolate's sequence is looked up by an admin and by a
+/// public caller.
+/// Expected behaviour: admins find it; public callers see neither the isolate in
+/// the hits nor its sequence in the list.
+#[tokio::test]
+async fn private_sequences_are_hidden_from_public_callers() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db.clone());
+    let scoped = build_scoped_app_with_db(db);
+    let (_, private) = sequenced_isolates(&app).await;
+
+    let (_, hits) = get(
+        &app,
+        &format!("/api/marker_sequences/similar?sequence={private}&limit=1"),
+    )
+    .await;
+    assert_eq!(similar_names(&hits), ["ISO-B-PRIVATE"]);
+    let (_, hits) = get(
+        &scoped,
+        &format!("/api/marker_sequences/similar?sequence={private}"),
+    )
+    .await;
+    assert!(similar_names(&hits)
+        .iter()
+        .all(|name| *name != "ISO-B-PRIVATE"));
+    let (_, public) = get(&scoped, "/api/marker_sequences").await;
+    assert_eq!(public.as_array().unwrap().len(), 3);
+}
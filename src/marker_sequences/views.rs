@@ -0,0 +1,38 @@
+use axum::extract::{Query, Request, State};
+use axum::{routing::get, Json, Router};
+use crudcrate::ApiError;
+use sea_orm::DatabaseConnection;
+
+use super::models::{SimilarParams, SimilarSequence};
+use crate::middleware;
+
+/// Routes mounted next to the generated CRUD router under `/api/marker_sequences`.
+pub fn router(db: &DatabaseConnection) -> Router {
+    Router::new()
+        .route("/similar", get(get_similar))
+        .with_state(db.clone())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/marker_sequences/similar",
+    params(SimilarParams),
+    responses(
+        (status = OK, description = "Stored sequences by the share of the query's 8-mers they contain, best first", body = [SimilarSequence]),
+        (status = UNPROCESSABLE_ENTITY, description = "The query is not a nucleotide sequence of at least 8 unambiguous bases")
+    )
+)]
+pub async fn get_similar(
+    State(db): State<DatabaseConnection>,
+    Query(params): Query<SimilarParams>,
+    req: Request,
+) -> Result<Json<Vec<SimilarSequence>>, ApiError> {
+    let condition = if middleware::is_scoped(&req) {
+        middleware::isolates_scope()
+    } else {
+        sea_orm::Condition::all()
+    };
+    Ok(Json(
+        super::services::similar(&db, condition, &params).await?,
+    ))
+}
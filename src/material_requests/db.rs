@@ -0,0 +1,126 @@
+use crate::common::enums::MaterialRequestStatus;
+use chrono::{DateTime, Utc};
+use crudcrate::validation::{Validatable, ValidationError};
+use crudcrate::{CRUDResource, EntityToModels};
+use sea_orm::entity::prelude::*;
+use uuid::Uuid;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+#[sea_orm(table_name = "material_requests")]
+#[crudcrate(
+    generate_router,
+    api_struct = "MaterialRequest",
+    name_singular = "material_request",
+    name_plural = "material_requests",
+    description = "Requests from researchers for public samples or isolates, with their approval and shipping history",
+    no_eq,
+    derive_partial_eq
+)]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    #[crudcrate(primary_key, filterable, exclude(update, create), on_create = Uuid::new_v4())]
+    pub id: Uuid,
+    /// Exactly one of `sample_id` and `isolate_id` is set.
+    #[crudcrate(sortable, filterable, exclude(update))]
+    pub sample_id: Option<Uuid>,
+    #[crudcrate(sortable, filterable, exclude(update))]
+    pub isolate_id: Option<Uuid>,
+    #[crudcrate(sortable, filterable, fulltext)]
+    pub requester_name: String,
+    #[crudcrate(sortable, filterable, fulltext)]
+    pub requester_email: String,
+    #[crudcrate(sortable, filterable, fulltext)]
+    pub institution: Option<String>,
+    #[crudcrate(fulltext)]
+    pub purpose: String,
+    pub shipping_address: String,
+    /// Changed only through the approve, reject, ship and return endpoints.
+    #[crudcrate(sortable, filterable, exclude(create, update), on_create = MaterialRequestStatus::Pending)]
+    pub status: MaterialRequestStatus,
+    #[crudcrate(fulltext, exclude(create))]
+    pub admin_note: Option<String>,
+    #[crudcrate(filterable, exclude(create))]
+    pub tracking_number: Option<String>,
+    /// Set when the item had reached the freeze-thaw maximum at filing or approval.
+    #[crudcrate(filterable, exclude(create, update))]
+    pub freeze_thaw_warning: Option<String>,
+    #[crudcrate(sortable, filterable, exclude(create, update), on_create = chrono::Utc::now())]
+    pub created_at: DateTime<Utc>,
+    #[crudcrate(sortable, filterable, exclude(create, update))]
+    pub decided_at: Option<DateTime<Utc>>,
+    #[crudcrate(sortable, filterable, exclude(create, update))]
+    pub shipped_at: Option<DateTime<Utc>>,
+    #[crudcrate(sortable, filterable, exclude(create, update))]
+    pub returned_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "crate::samples::db::Entity",
+        from = "Column::SampleId",
+        to = "crate::samples::db::Column::Id"
+    )]
+    Sample,
+    #[sea_orm(
+        belongs_to = "crate::isolates::db::Entity",
+        from = "Column::IsolateId",
+        to = "crate::isolates::db::Column::Id"
+    )]
+    Isolate,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+fn validate_required(field: &str, value: &str) -> Result<(), ValidationError> {
+    if value.trim().is_empty() {
+        return Err(ValidationError::new(field, "Must not be blank"));
+    }
+    Ok(())
+}
+
+fn validate_email(email: &str) -> Result<(), ValidationError> {
+    let well_formed = email
+        .split_once('@')
+        .is_some_and(|(local, domain)| !local.is_empty() && domain.contains('.'));
+    if !well_formed || email.contains(char::is_whitespace) {
+        return Err(ValidationError::new(
+            "requester_email",
+            "Must be an email address",
+        ));
+    }
+    Ok(())
+}
+
+impl Validatable for MaterialRequestCreate {
+    fn validate(&self) -> Result<(), ValidationError> {
+        if self.sample_id.is_some() == self.isolate_id.is_some() {
+            return Err(ValidationError::new(
+                "sample_id",
+                "Request either one sample or one isolate",
+            ));
+        }
+        validate_required("requester_name", &self.requester_name)?;
+        validate_email(&self.requester_email)?;
+        validate_required("purpose", &self.purpose)?;
+        validate_required("shipping_address", &self.shipping_address)
+    }
+}
+
+impl Validatable for MaterialRequestUpdate {
+    fn validate(&self) -> Result<(), ValidationError> {
+        if let Some(Some(requester_name)) = &self.requester_name {
+            validate_required("requester_name", requester_name)?;
+        }
+        if let Some(Some(requester_email)) = &self.requester_email {
+            validate_email(requester_email)?;
+        }
+        if let Some(Some(purpose)) = &self.purpose {
+            validate_required("purpose", purpose)?;
+        }
+        if let Some(Some(shipping_address)) = &self.shipping_address {
+            validate_required("shipping_address", shipping_address)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,28 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use super::db::MaterialRequest;
+
+/// Optional details recorded with a status change.
+#[derive(ToSchema, Deserialize, Serialize, Debug, Clone, PartialEq, Default)]
+pub struct TransitionRequest {
+    /// Stored as the request's `admin_note`, e.g. the reason for a rejection.
+    pub note: Option<String>,
+    /// Courier tracking number; only used when shipping.
+    pub tracking_number: Option<String>,
+}
+
+/// Open requests, oldest first within each stage.
+#[derive(ToSchema, Serialize, Debug, Clone)]
+pub struct MaterialRequestQueue {
+    pub pending: Vec<MaterialRequest>,
+    pub awaiting_shipment: Vec<MaterialRequest>,
+    pub on_loan: Vec<MaterialRequest>,
+}
+
+#[derive(Deserialize, IntoParams, Debug)]
+pub struct HistoryParams {
+    pub sample_id: Option<Uuid>,
+    pub isolate_id: Option<Uuid>,
+}
@@ -0,0 +1,221 @@
+use crudcrate::validation::ValidationError;
+use crudcrate::{ApiError, CRUDResource};
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait,
+    IntoActiveModel, QueryFilter, Set, TransactionTrait,
+};
+use uuid::Uuid;
+
+use super::db::{Column, Entity, MaterialRequest, MaterialRequestCreate, Model};
+use super::models::TransitionRequest;
+use crate::common::batch;
+use crate::common::enums::MaterialRequestStatus;
+use crate::config::{FreezeThawLimit, FreezeThawPolicy};
+use crate::{isolates, middleware, samples};
+
+/// Current availability of the requested item, or `None` when it does not exist or
+/// is not public. Requests only ever cover public material, whoever files them.
+async fn public_availability(
+    db: &impl ConnectionTrait,
+    sample_id: Option<Uuid>,
+    isolate_id: Option<Uuid>,
+) -> Result<Option<bool>, ApiError> {
+    Ok(match (sample_id, isolate_id) {
+        (Some(id), _) => samples::db::Entity::find_by_id(id)
+            .filter(middleware::samples_scope())
+            .one(db)
+            .await?
+            .map(|sample| sample.is_available),
+        // Isolates have no availability flag of their own; cultures can be
+        // subcultured, so any public isolate can be requested.
+        (_, Some(id)) => isolates::db::Entity::find_by_id(id)
+            .filter(middleware::isolates_scope())
+            .one(db)
+            .await?
+            .map(|_| true),
+        _ => None,
+    })
+}
+
+/// Why the requested item should not be thawed again, if it has reached the
+/// freeze-thaw maximum. Fulfilling a request means thawing the stock once more.
+pub async fn freeze_thaw_warning(
+    db: &impl ConnectionTrait,
+    sample_id: Option<Uuid>,
+    isolate_id: Option<Uuid>,
+    limit: &FreezeThawLimit,
+) -> Result<Option<String>, DbErr> {
+    let cycles = match (sample_id, isolate_id) {
+        (Some(id), _) => samples::db::Entity::find_by_id(id)
+            .one(db)
+            .await?
+            .map(|sample| sample.freeze_thaw_cycles),
+        (_, Some(id)) => isolates::db::Entity::find_by_id(id)
+            .one(db)
+            .await?
+            .map(|isolate| isolate.freeze_thaw_cycles),
+        _ => None,
+    };
+    Ok(cycles.and_then(|cycles| limit.exceeded(cycles)))
+}
+
+/// Refuses requests for material that is unknown, not public or unavailable, and
+/// under the blocking policy for material thawed as often as `limit` allows.
+async fn check_create(
+    db: &DatabaseConnection,
+    data: &MaterialRequestCreate,
+    limit: &FreezeThawLimit,
+) -> Result<(), ApiError> {
+    let field = if data.sample_id.is_some() {
+        "sample_id"
+    } else {
+        "isolate_id"
+    };
+    match public_availability(db, data.sample_id, data.isolate_id).await? {
+        None => Err(ValidationError::new(field, "Unknown or not public").into()),
+        Some(false) => Err(ValidationError::new(field, "Currently not available").into()),
+        Some(true) => {
+            if limit.policy == FreezeThawPolicy::Block {
+                if let Some(reason) =
+                    freeze_thaw_warning(db, data.sample_id, data.isolate_id, limit).await?
+                {
+                    return Err(ValidationError::new(field, reason).into());
+                }
+            }
+            Ok(())
+        }
+    }
+}
+
+/// Stores the freeze-thaw warning on a new request, so the requester sees it in the
+/// response. Under the blocking policy `check_create` has refused the request.
+async fn stamp_freeze_thaw_warning(
+    db: &DatabaseConnection,
+    mut request: MaterialRequest,
+    limit: &FreezeThawLimit,
+) -> Result<MaterialRequest, ApiError> {
+    let warning = freeze_thaw_warning(db, request.sample_id, request.isolate_id, limit).await?;
+    if warning.is_some() {
+        Entity::update_many()
+            .col_expr(Column::FreezeThawWarning, Expr::value(warning.clone()))
+            .filter(Column::Id.eq(request.id))
+            .exec(db)
+            .await?;
+        request.freeze_thaw_warning = warning;
+    }
+    Ok(request)
+}
+
+/// Files a request. The generated create handlers are not mounted, as their hooks
+/// cannot see the configured freeze-thaw limit.
+pub async fn create(
+    db: &DatabaseConnection,
+    data: MaterialRequestCreate,
+    limit: &FreezeThawLimit,
+) -> Result<MaterialRequest, ApiError> {
+    check_create(db, &data, limit).await?;
+    let request = MaterialRequest::create(db, data).await?;
+    stamp_freeze_thaw_warning(db, request, limit).await
+}
+
+/// Files every request or, if any is refused, none.
+pub async fn create_many(
+    db: &DatabaseConnection,
+    data: Vec<MaterialRequestCreate>,
+    limit: &FreezeThawLimit,
+) -> Result<Vec<MaterialRequest>, ApiError> {
+    batch::check_limit::<MaterialRequest>("create", data.len())?;
+    for item in &data {
+        check_create(db, item, limit).await?;
+    }
+    let mut stamped = Vec::with_capacity(data.len());
+    for request in MaterialRequest::create_many(db, data).await? {
+        stamped.push(stamp_freeze_thaw_warning(db, request, limit).await?);
+    }
+    Ok(stamped)
+}
+
+/// Approval reserves a sample; rejecting an approved request or getting the
+/// material back releases it again.
+async fn set_sample_available(
+    db: &impl ConnectionTrait,
+    request: &Model,
+    is_available: bool,
+) -> Result<(), ApiError> {
+    let Some(sample_id) = request.sample_id else {
+        return Ok(());
+    };
+    samples::db::Entity::update_many()
+        .col_expr(samples::db::Column::IsAvailable, Expr::value(is_available))
+        .filter(samples::db::Column::Id.eq(sample_id))
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+pub async fn transition(
+    db: &DatabaseConnection,
+    id: Uuid,
+    to: MaterialRequestStatus,
+    details: TransitionRequest,
+    limit: &FreezeThawLimit,
+) -> Result<Model, ApiError> {
+    use MaterialRequestStatus::{Approved, Pending, Rejected, Returned, Shipped};
+
+    let txn = db.begin().await?;
+    let request = Entity::find_by_id(id)
+        .one(&txn)
+        .await?
+        .ok_or_else(|| ApiError::not_found("material_request", Some(id.to_string())))?;
+
+    let now = chrono::Utc::now();
+    let mut updated = request.clone().into_active_model();
+    match (&request.status, &to) {
+        (Pending, Approved) => {
+            if public_availability(&txn, request.sample_id, request.isolate_id).await? != Some(true)
+            {
+                return Err(ApiError::conflict(
+                    "The requested material is no longer available",
+                ));
+            }
+            // The item may have been thawed again since the request was filed.
+            let warning =
+                freeze_thaw_warning(&txn, request.sample_id, request.isolate_id, limit).await?;
+            if let (Some(reason), FreezeThawPolicy::Block) = (&warning, limit.policy) {
+                return Err(ApiError::conflict(reason.clone()));
+            }
+            updated.freeze_thaw_warning = Set(warning);
+            set_sample_available(&txn, &request, false).await?;
+            updated.decided_at = Set(Some(now));
+        }
+        (Pending, Rejected) => updated.decided_at = Set(Some(now)),
+        (Approved, Rejected) => {
+            set_sample_available(&txn, &request, true).await?;
+            updated.decided_at = Set(Some(now));
+        }
+        (Approved, Shipped) => {
+            updated.shipped_at = Set(Some(now));
+            if details.tracking_number.is_some() {
+                updated.tracking_number = Set(details.tracking_number.clone());
+            }
+        }
+        (Shipped, Returned) => {
+            set_sample_available(&txn, &request, true).await?;
+            updated.returned_at = Set(Some(now));
+        }
+        (from, to) => {
+            return Err(ApiError::conflict(format!(
+                "A {from} request cannot be marked {to}"
+            )))
+        }
+    }
+    updated.status = Set(to);
+    if details.note.is_some() {
+        updated.admin_note = Set(details.note);
+    }
+
+    let updated = updated.update(&txn).await?;
+    txn.commit().await?;
+    Ok(updated)
+}
@@ -0,0 +1,319 @@
+use axum::http::StatusCode;
+use serde_json::{json, Value};
+
+use crate::config::{FreezeThawLimit, FreezeThawPolicy};
+use crate::test_utils::{
+    build_app_with_db, build_app_with_freeze_thaw_limit, build_scoped_app_with_db, create,
+    create_field_record, create_site, get, send, setup_sqlite_db,
+};
+
+fn request_for(item: Value) -> Value {
+    let mut request = json!({
+        "requester_name": "Ada Researcher",
+        "requester_email": "ada@example.org",
+        "institution": "Example University",
+        "purpose": "Cold-adapted enzyme screening",
+        "shipping_address": "1 Lab Road, 1000 Lausanne"
+    });
+    request
+        .as_object_mut()
+        .unwrap()
+        .extend(item.as_object().unwrap().clone());
+    request
+}
+
+/// Scenario: an anonymous researcher requests a public sample, which is approved,
+/// shipped and returned.
+/// Expected behaviour: only the submission is public; approval reserves the sample
+/// until it comes back, and out-of-order transitions are 409.
+#[tokio::test]
+async fn sample_request_moves_through_the_loan_workflow() {
+    let db = setup_sqlite_db().await;
+    let admin = build_app_with_db(db.clone());
+    let public = build_scoped_app_with_db(db);
+
+    let site_id = create_site(&admin, "Glacier A").await;
+    let fr = create_field_record(&admin, &site_id, "FR-1").await;
+    let sample = create(
+        &admin,
+        "/api/samples",
+        json!({ "name": "S-1", "field_record_id": fr }),
+    )
+    .await;
+    let private_sample = create(
+        &admin,
+        "/api/samples",
+        json!({ "name": "S-2", "field_record_id": fr, "is_private": true }),
+    )
+    .await;
+
+    for (payload, reason) in [
+        (json!({ "sample_id": private_sample }), "private"),
+        (json!({}), "no item"),
+        (
+            json!({ "sample_id": sample, "requester_email": "not-an-email" }),
+            "email",
+        ),
+    ] {
+        let (status, body) = send(
+            &public,
+            "POST",
+            "/api/material_requests",
+            request_for(payload),
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{reason}: {body}");
+    }
+    let request = create(
+        &public,
+        "/api/material_requests",
+        request_for(json!({ "sample_id": sample, "status": "Shipped" })),
+    )
+    .await;
+
+    let (status, _) = get(&public, "/api/material_requests").await;
+    assert_eq!(status, StatusCode::FORBIDDEN, "contact details are private");
+    let (status, _) = send(
+        &public,
+        "POST",
+        &format!("/api/material_requests/{request}/approve"),
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (_, queue) = get(&admin, "/api/material_requests/queue").await;
+    assert_eq!(queue["pending"][0]["id"], request);
+    assert_eq!(
+        queue["pending"][0]["status"], "Pending",
+        "status is not client-set"
+    );
+
+    let (status, _) = send(
+        &admin,
+        "POST",
+        &format!("/api/material_requests/{request}/ship"),
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT, "not approved yet");
+
+    let (status, body) = send(
+        &admin,
+        "POST",
+        &format!("/api/material_requests/{request}/approve"),
+        json!({ "note": "Half of the remaining volume" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let (_, stored) = get(&admin, &format!("/api/samples/{sample}")).await;
+    assert_eq!(stored["is_available"], false);
+    let (status, _) = send(
+        &public,
+        "POST",
+        "/api/material_requests",
+        request_for(json!({ "sample_id": sample })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "reserved");
+
+    let (status, body) = send(
+        &admin,
+        "POST",
+        &format!("/api/material_requests/{request}/ship"),
+        json!({ "tracking_number": "1Z999" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["tracking_number"], "1Z999");
+    let (_, queue) = get(&admin, "/api/material_requests/queue").await;
+    assert_eq!(queue["on_loan"].as_array().unwrap().len(), 1);
+
+    let (status, _) = send(
+        &admin,
+        "POST",
+        &format!("/api/material_requests/{request}/return"),
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (_, stored) = get(&admin, &format!("/api/samples/{sample}")).await;
+    assert_eq!(stored["is_available"], true);
+
+    let (status, history) = get(
+        &admin,
+        &format!("/api/material_requests/history?sample_id={sample}"),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{history}");
+    let history = history.as_array().unwrap();
+    assert_eq!(history.len(), 1);
+    assert_eq!(history[0]["status"], "Returned");
+    assert_eq!(history[0]["admin_note"], "Half of the remaining volume");
+    assert!(!history[0]["shipped_at"].is_null());
+}
+
+#[tokio::test]
+async fn rejecting_an_approved_request_releases_the_sample() {
+    let db = setup_sqlite_db().await;
+    let admin = build_app_with_db(db);
+
+    let site_id = create_site(&admin, "Glacier A").await;
+    let fr = create_field_record(&admin, &site_id, "FR-1").await;
+    let sample = create(
+        &admin,
+        "/api/samples",
+        json!({ "name": "S-1", "field_record_id": fr }),
+    )
+    .await;
+    let first = create(
+        &admin,
+        "/api/material_requests",
+        request_for(json!({ "sample_id": sample })),
+    )
+    .await;
+    let second = create(
+        &admin,
+        "/api/material_requests",
+        request_for(json!({ "sample_id": sample })),
+    )
+    .await;
+
+    for (id, expected) in [(&first, StatusCode::OK), (&second, StatusCode::CONFLICT)] {
+        let (status, _) = send(
+            &admin,
+            "POST",
+            &format!("/api/material_requests/{id}/approve"),
+            json!({}),
+        )
+        .await;
+        assert_eq!(status, expected, "one sample, one approval");
+    }
+
+    let (status, _) = send(
+        &admin,
+        "POST",
+        &format!("/api/material_requests/{first}/reject"),
+        json!({ "note": "Requester withdrew" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (_, stored) = get(&admin, &format!("/api/samples/{sample}")).await;
+    assert_eq!(stored["is_available"], true);
+    let (status, _) = send(
+        &admin,
+        "POST",
+        &format!("/api/material_requests/{second}/approve"),
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+/// Scenario: one sample thawed five times, the default maximum, and one never thawed.
+/// Expected behaviour: the counts come from the custody log and can be filtered on;
+/// under the default warn policy the request for the worn sample is filed with a
+/// warning.
+#[tokio::test]
+async fn requests_for_samples_at_the_freeze_thaw_maximum_are_warned() {
+    let db = setup_sqlite_db().await;
+    let admin = build_app_with_db(db);
+
+    let site_id = create_site(&admin, "Glacier A").await;
+    let fr = create_field_record(&admin, &site_id, "FR-1").await;
+    let worn = create(
+        &admin,
+        "/api/samples",
+        json!({ "name": "S-1", "field_record_id": fr }),
+    )
+    .await;
+    let fresh = create(
+        &admin,
+        "/api/samples",
+        json!({ "name": "S-2", "field_record_id": fr }),
+    )
+    .await;
+
+    let thaws: Vec<Value> = (0..5)
+        .map(|_| json!({ "sample_id": worn, "kind": "Thawed", "actor": "A. Keller", "location": "Lab 3" }))
+        .collect();
+    let (status, body) = send(&admin, "POST", "/api/custody_events/batch", json!(thaws)).await;
+    assert_eq!(status, StatusCode::CREATED, "{body}");
+
+    let (_, record) = get(&admin, &format!("/api/samples/{worn}")).await;
+    assert_eq!(record["freeze_thaw_cycles"], 5);
+    let (status, list) = get(
+        &admin,
+        "/api/samples?filter=%7B%22freeze_thaw_cycles_gte%22%3A5%7D",
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{list}");
+    let list = list.as_array().unwrap();
+    assert_eq!(list.len(), 1);
+    assert_eq!(list[0]["id"], worn);
+
+    let (status, request) = send(
+        &admin,
+        "POST",
+        "/api/material_requests",
+        request_for(json!({ "sample_id": worn })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED, "{request}");
+    assert_eq!(
+        request["freeze_thaw_warning"],
+        "Thawed 5 times; the maximum is 5 freeze-thaw cycles"
+    );
+    let (_, request) = send(
+        &admin,
+        "POST",
+        "/api/material_requests",
+        request_for(json!({ "sample_id": fresh })),
+    )
+    .await;
+    assert_eq!(request["freeze_thaw_warning"], Value::Null);
+}
+
+/// Scenario: the startup configuration blocks at two cycles; one sample is thawed
+/// twice.
+/// Expected behaviour: requests for it are refused with the reason, while the app's
+/// own limit, not the environment, decides.
+#[tokio::test]
+async fn blocking_policy_refuses_requests_for_worn_samples() {
+    let db = setup_sqlite_db().await;
+    let admin = build_app_with_freeze_thaw_limit(
+        db,
+        FreezeThawLimit {
+            max_cycles: 2,
+            policy: FreezeThawPolicy::Block,
+        },
+    );
+
+    let site_id = create_site(&admin, "Glacier A").await;
+    let fr = create_field_record(&admin, &site_id, "FR-1").await;
+    let worn = create(
+        &admin,
+        "/api/samples",
+        json!({ "name": "S-1", "field_record_id": fr }),
+    )
+    .await;
+    let thaws: Vec<Value> = (0..2)
+        .map(|_| json!({ "sample_id": worn, "kind": "Thawed", "actor": "A. Keller", "location": "Lab 3" }))
+        .collect();
+    let (status, body) = send(&admin, "POST", "/api/custody_events/batch", json!(thaws)).await;
+    assert_eq!(status, StatusCode::CREATED, "{body}");
+
+    let (status, body) = send(
+        &admin,
+        "POST",
+        "/api/material_requests",
+        request_for(json!({ "sample_id": worn })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{body}");
+    assert!(
+        body.to_string()
+            .contains("Thawed 2 times; the maximum is 2 freeze-thaw cycles"),
+        "{body}"
+    );
+}
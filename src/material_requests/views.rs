@@ -0,0 +1,230 @@
+use axum::extract::{DefaultBodyLimit, Path, Query, State};
+use axum::http::StatusCode;
+use axum::{
+    routing::{get, post},
+    Extension, Json, Router,
+};
+use crudcrate::{ApiError, CRUDResource};
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+use uuid::Uuid;
+
+use super::db::{
+    delete_many_handler, delete_one_handler, get_all_handler, get_one_handler, update_many_handler,
+    update_one_handler, Column, Entity, MaterialRequest, MaterialRequestCreate,
+};
+use super::models::{HistoryParams, MaterialRequestQueue, TransitionRequest};
+use super::services::{self, transition};
+use crate::common::enums::MaterialRequestStatus;
+use crate::config::FreezeThawLimit;
+
+/// Routes under `/api/material_requests`: the generated ones except creation, which
+/// checks new requests against the freeze-thaw `limit`, and the workflow.
+pub fn router(db: &DatabaseConnection, limit: FreezeThawLimit) -> Router {
+    Router::new()
+        .route("/", get(get_all_handler).post(post_request))
+        .route(
+            "/batch",
+            post(post_requests)
+                .patch(update_many_handler)
+                .delete(delete_many_handler),
+        )
+        .route(
+            "/{id}",
+            get(get_one_handler)
+                .put(update_one_handler)
+                .delete(delete_one_handler),
+        )
+        .route("/queue", get(get_queue))
+        .route("/history", get(get_history))
+        .route("/{id}/approve", post(approve))
+        .route("/{id}/reject", post(reject))
+        .route("/{id}/ship", post(ship))
+        .route("/{id}/return", post(mark_returned))
+        .layer(Extension(limit))
+        .layer(DefaultBodyLimit::max(
+            MaterialRequest::security_profile().max_request_body_bytes,
+        ))
+        .with_state(db.clone())
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/material_requests",
+    request_body = MaterialRequestCreate,
+    responses(
+        (status = CREATED, description = "Filed, with a warning if the material has been thawed as often as allowed", body = MaterialRequest),
+        (status = UNPROCESSABLE_ENTITY, description = "The material is unknown, not public or unavailable, or under the blocking policy thawed as often as allowed")
+    )
+)]
+pub async fn post_request(
+    State(db): State<DatabaseConnection>,
+    Extension(limit): Extension<FreezeThawLimit>,
+    Json(data): Json<MaterialRequestCreate>,
+) -> Result<(StatusCode, Json<MaterialRequest>), ApiError> {
+    let request = services::create(&db, data, &limit).await?;
+    Ok((StatusCode::CREATED, Json(request)))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/material_requests/batch",
+    request_body = Vec<MaterialRequestCreate>,
+    responses(
+        (status = CREATED, description = "Every request filed", body = [MaterialRequest]),
+        (status = BAD_REQUEST, description = "More requests than the batch limit"),
+        (status = UNPROCESSABLE_ENTITY, description = "A request was refused, so none were filed")
+    )
+)]
+pub async fn post_requests(
+    State(db): State<DatabaseConnection>,
+    Extension(limit): Extension<FreezeThawLimit>,
+    Json(data): Json<Vec<MaterialRequestCreate>>,
+) -> Result<(StatusCode, Json<Vec<MaterialRequest>>), ApiError> {
+    let requests = services::create_many(&db, data, &limit).await?;
+    Ok((StatusCode::CREATED, Json(requests)))
+}
+
+async fn with_status(
+    db: &DatabaseConnection,
+    status: MaterialRequestStatus,
+) -> Result<Vec<MaterialRequest>, ApiError> {
+    Ok(Entity::find()
+        .filter(Column::Status.eq(status))
+        .order_by_asc(Column::CreatedAt)
+        .all(db)
+        .await?
+        .into_iter()
+        .map(MaterialRequest::from)
+        .collect())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/material_requests/queue",
+    responses(
+        (status = OK, description = "Requests that still need an admin", body = MaterialRequestQueue)
+    )
+)]
+pub async fn get_queue(
+    State(db): State<DatabaseConnection>,
+) -> Result<Json<MaterialRequestQueue>, ApiError> {
+    Ok(Json(MaterialRequestQueue {
+        pending: with_status(&db, MaterialRequestStatus::Pending).await?,
+        awaiting_shipment: with_status(&db, MaterialRequestStatus::Approved).await?,
+        on_loan: with_status(&db, MaterialRequestStatus::Shipped).await?,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/material_requests/history",
+    params(HistoryParams),
+    responses(
+        (status = OK, description = "Every request for the item, newest first", body = Vec<MaterialRequest>),
+        (status = BAD_REQUEST, description = "Neither or both of sample_id and isolate_id given")
+    )
+)]
+pub async fn get_history(
+    State(db): State<DatabaseConnection>,
+    Query(params): Query<HistoryParams>,
+) -> Result<Json<Vec<MaterialRequest>>, ApiError> {
+    let condition = match (params.sample_id, params.isolate_id) {
+        (Some(id), None) => Column::SampleId.eq(id),
+        (None, Some(id)) => Column::IsolateId.eq(id),
+        _ => {
+            return Err(ApiError::bad_request(
+                "Pass exactly one of sample_id and isolate_id",
+            ))
+        }
+    };
+    Ok(Json(
+        Entity::find()
+            .filter(condition)
+            .order_by_desc(Column::CreatedAt)
+            .all(&db)
+            .await?
+            .into_iter()
+            .map(MaterialRequest::from)
+            .collect(),
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/material_requests/{id}/approve",
+    params(("id" = Uuid, Path, description = "Material request id")),
+    request_body = TransitionRequest,
+    responses(
+        (status = OK, description = "Approved; a requested sample is marked unavailable", body = MaterialRequest),
+        (status = CONFLICT, description = "Not pending, or the material is no longer available")
+    )
+)]
+pub async fn approve(
+    State(db): State<DatabaseConnection>,
+    Extension(limit): Extension<FreezeThawLimit>,
+    Path(id): Path<Uuid>,
+    Json(details): Json<TransitionRequest>,
+) -> Result<Json<MaterialRequest>, ApiError> {
+    let request = transition(&db, id, MaterialRequestStatus::Approved, details, &limit).await?;
+    Ok(Json(request.into()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/material_requests/{id}/reject",
+    params(("id" = Uuid, Path, description = "Material request id")),
+    request_body = TransitionRequest,
+    responses(
+        (status = OK, description = "Rejected; an approved sample is released again", body = MaterialRequest),
+        (status = CONFLICT, description = "Already shipped or decided")
+    )
+)]
+pub async fn reject(
+    State(db): State<DatabaseConnection>,
+    Extension(limit): Extension<FreezeThawLimit>,
+    Path(id): Path<Uuid>,
+    Json(details): Json<TransitionRequest>,
+) -> Result<Json<MaterialRequest>, ApiError> {
+    let request = transition(&db, id, MaterialRequestStatus::Rejected, details, &limit).await?;
+    Ok(Json(request.into()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/material_requests/{id}/ship",
+    params(("id" = Uuid, Path, description = "Material request id")),
+    request_body = TransitionRequest,
+    responses(
+        (status = OK, description = "Marked as shipped", body = MaterialRequest),
+        (status = CONFLICT, description = "Not approved")
+    )
+)]
+pub async fn ship(
+    State(db): State<DatabaseConnection>,
+    Extension(limit): Extension<FreezeThawLimit>,
+    Path(id): Path<Uuid>,
+    Json(details): Json<TransitionRequest>,
+) -> Result<Json<MaterialRequest>, ApiError> {
+    let request = transition(&db, id, MaterialRequestStatus::Shipped, details, &limit).await?;
+    Ok(Json(request.into()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/material_requests/{id}/return",
+    params(("id" = Uuid, Path, description = "Material request id")),
+    request_body = TransitionRequest,
+    responses(
+        (status = OK, description = "Loan returned; a sample becomes available again", body = MaterialRequest),
+        (status = CONFLICT, description = "Not shipped")
+    )
+)]
+pub async fn mark_returned(
+    State(db): State<DatabaseConnection>,
+    Extension(limit): Extension<FreezeThawLimit>,
+    Path(id): Path<Uuid>,
+    Json(details): Json<TransitionRequest>,
+) -> Result<Json<MaterialRequest>, ApiError> {
+    let request = transition(&db, id, MaterialRequestStatus::Returned, details, &limit).await?;
+    Ok(Json(request.into()))
+}
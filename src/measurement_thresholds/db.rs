@@ -0,0 +1,81 @@
+use chrono::{DateTime, Utc};
+use crudcrate::validation::{Validatable, ValidationError};
+use crudcrate::{CRUDResource, EntityToModels};
+use sea_orm::entity::prelude::*;
+use uuid::Uuid;
+
+use crate::field_records::services::measurement_rule;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+#[sea_orm(table_name = "measurement_thresholds")]
+#[crudcrate(
+    generate_router,
+    api_struct = "MeasurementThreshold",
+    name_singular = "measurement_threshold",
+    name_plural = "measurement_thresholds",
+    description = "Admin overrides for the plausibility ranges applied to field record measurements",
+    no_eq,
+    derive_partial_eq
+)]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    #[crudcrate(primary_key, filterable, exclude(update, create), on_create = Uuid::new_v4())]
+    pub id: Uuid,
+    #[sea_orm(unique)]
+    #[crudcrate(sortable, filterable, fulltext)]
+    pub column_name: String,
+    #[crudcrate(sortable, filterable)]
+    pub min_value: Option<f64>,
+    #[crudcrate(sortable, filterable)]
+    pub max_value: Option<f64>,
+    #[crudcrate(sortable, filterable, fulltext)]
+    pub description: Option<String>,
+    #[crudcrate(sortable, filterable, exclude(create, update), on_create = chrono::Utc::now())]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+fn validate_column_name(column_name: &str) -> Result<(), ValidationError> {
+    if measurement_rule(column_name).is_none() {
+        return Err(ValidationError::new(
+            "column_name",
+            format!("'{column_name}' is not a field record measurement"),
+        ));
+    }
+    Ok(())
+}
+
+fn validate_bounds(min_value: Option<f64>, max_value: Option<f64>) -> Result<(), ValidationError> {
+    if let (Some(min), Some(max)) = (min_value, max_value) {
+        if min > max {
+            return Err(ValidationError::new(
+                "max_value",
+                "Must not be below min_value",
+            ));
+        }
+    }
+    Ok(())
+}
+
+impl Validatable for MeasurementThresholdCreate {
+    fn validate(&self) -> Result<(), ValidationError> {
+        validate_column_name(&self.column_name)?;
+        validate_bounds(self.min_value, self.max_value)
+    }
+}
+
+impl Validatable for MeasurementThresholdUpdate {
+    fn validate(&self) -> Result<(), ValidationError> {
+        if let Some(Some(column_name)) = &self.column_name {
+            validate_column_name(column_name)?;
+        }
+        if let (Some(min_value), Some(max_value)) = (self.min_value, self.max_value) {
+            validate_bounds(min_value, max_value)?;
+        }
+        Ok(())
+    }
+}
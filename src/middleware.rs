@@ -1,15 +1,17 @@
 use axum::{
-    extract::{Query, Request},
+    extract::{Query, Request, State},
     http::{Method, StatusCode},
     middleware::Next,
     response::{IntoResponse, Response},
 };
 use crudcrate::ScopeCondition;
-use sea_orm::{sea_query::Expr, ColumnTrait, Condition};
+use object_store::ObjectStore;
+use sea_orm::sea_query::{self, Expr, SelectStatement};
+use sea_orm::{ColumnTrait, Condition, DatabaseConnection};
 use std::collections::HashMap;
+use std::sync::Arc;
 
 use crate::common::auth::Role;
-use crate::common::enums::SampleType;
 
 type AuthStatus =
     axum_keycloak_auth::KeycloakAuthStatus<Role, axum_keycloak_auth::decode::ProfileAndEmail>;
@@ -21,6 +23,22 @@ pub fn is_admin(req: &Request) -> bool {
         .unwrap_or(false)
 }
 
+/// True once a scope middleware has restricted this request to public rows. Custom
+/// handlers nested beside a generated router follow the same decision as the
+/// generated ones instead of re-deriving it from the auth status.
+pub fn is_scoped(req: &Request) -> bool {
+    req.extensions().get::<ScopeCondition>().is_some()
+}
+
+/// The condition a scope middleware attached to this request, or one matching
+/// everything.
+pub fn scope_condition(req: &Request) -> Condition {
+    req.extensions()
+        .get::<ScopeCondition>()
+        .map(|scope| scope.condition.clone())
+        .unwrap_or_else(Condition::all)
+}
+
 /// Block writes for non-admin, return early if unauthorized/forbidden write attempt.
 fn check_write_access(req: &Request) -> Option<Response> {
     if *req.method() != Method::GET && *req.method() != Method::HEAD && !is_admin(req) {
@@ -41,6 +59,14 @@ const FIELD_RECORD_SUBQUERY: &str = "\
         AND (a.id IS NULL OR a.is_private = false)\
     )";
 
+/// Operator suffixes understood by `?param.<name><suffix>=` on field records. A bare
+/// name compares for equality.
+pub const PARAMETER_FILTER_SUFFIXES: &[&str] = &["_gte", "_lte", "_gt", "_lt", "_neq"];
+
+pub fn campaigns_scope() -> Condition {
+    Condition::all().add(crate::campaigns::db::Column::IsPrivate.eq(false))
+}
+
 pub fn areas_scope() -> Condition {
     Condition::all().add(crate::areas::db::Column::IsPrivate.eq(false))
 }
@@ -53,23 +79,41 @@ pub fn sites_scope() -> Condition {
         ))
 }
 
+/// Aliquots inherit privacy: anything split from a private sample, at any depth,
+/// stays hidden even if its own flag is false.
+const PRIVATE_SAMPLE_LINEAGE_SUBQUERY: &str = "\
+    samples.id NOT IN (\
+        WITH RECURSIVE private_lineage(id) AS (\
+            SELECT id FROM samples WHERE is_private = true \
+            UNION SELECT s.id FROM samples s \
+            JOIN private_lineage p ON s.parent_sample_id = p.id\
+        ) \
+        SELECT id FROM private_lineage\
+    )";
+
+const PUBLIC_SITE_SUBQUERY: &str = "\
+    site_id IN (\
+        SELECT s.id FROM sites s \
+        LEFT JOIN areas a ON s.area_id = a.id \
+        WHERE s.is_private = false \
+        AND (a.id IS NULL OR a.is_private = false)\
+    )";
+
 pub fn field_records_scope() -> Condition {
     Condition::all()
         .add(crate::field_records::db::Column::IsPrivate.eq(false))
-        .add(Expr::cust(
-            "site_id IN (\
-                SELECT s.id FROM sites s \
-                LEFT JOIN areas a ON s.area_id = a.id \
-                WHERE s.is_private = false \
-                AND (a.id IS NULL OR a.is_private = false)\
-            )",
-        ))
+        .add(Expr::cust(PUBLIC_SITE_SUBQUERY))
+}
+
+pub fn replicate_groups_scope() -> Condition {
+    Condition::all().add(Expr::cust(PUBLIC_SITE_SUBQUERY))
 }
 
 pub fn samples_scope() -> Condition {
     Condition::all()
         .add(crate::samples::db::Column::IsPrivate.eq(false))
         .add(Expr::cust(FIELD_RECORD_SUBQUERY))
+        .add(Expr::cust(PRIVATE_SAMPLE_LINEAGE_SUBQUERY))
 }
 
 pub fn isolates_scope() -> Condition {
@@ -84,6 +128,26 @@ pub fn dna_scope() -> Condition {
         .add(Expr::cust(FIELD_RECORD_SUBQUERY))
 }
 
+/// Ids of the isolates visible under `isolates_scope`, for resources hanging off an
+/// isolate.
+fn public_isolate_ids() -> SelectStatement {
+    sea_query::Query::select()
+        .column(crate::isolates::db::Column::Id)
+        .from(crate::isolates::db::Entity)
+        .cond_where(isolates_scope())
+        .to_owned()
+}
+
+/// Resources hanging off an isolate (images, growth tests, marker sequences, culture
+/// accessions): the isolate is public, matched on the resource's `isolate_id`.
+pub fn isolate_child_scope(isolate_id: impl ColumnTrait) -> Condition {
+    Condition::all().add(isolate_id.in_subquery(public_isolate_ids()))
+}
+
+pub fn parameter_values_scope() -> Condition {
+    Condition::all().add(Expr::cust(FIELD_RECORD_SUBQUERY))
+}
+
 /// Areas: `is_private = false`
 pub async fn scope_areas(mut req: Request, next: Next) -> Response {
     if let Some(r) = check_write_access(&req) {
@@ -96,6 +160,18 @@ pub async fn scope_areas(mut req: Request, next: Next) -> Response {
     next.run(req).await
 }
 
+/// Campaigns: `is_private = false`
+pub async fn scope_campaigns(mut req: Request, next: Next) -> Response {
+    if let Some(r) = check_write_access(&req) {
+        return r;
+    }
+    if !is_admin(&req) {
+        req.extensions_mut()
+            .insert(ScopeCondition::new(campaigns_scope()));
+    }
+    next.run(req).await
+}
+
 /// Sites: `is_private = false AND (area_id IS NULL OR area not private)`
 pub async fn scope_sites(mut req: Request, next: Next) -> Response {
     if let Some(r) = check_write_access(&req) {
@@ -108,14 +184,111 @@ pub async fn scope_sites(mut req: Request, next: Next) -> Response {
     next.run(req).await
 }
 
-/// Field records: `is_private = false AND site is public (with area check)`
+/// Field records: `is_private = false AND site is public (with area check)`, plus
+/// optional `?param.<name>[_gte|_lte|_gt|_lt|_neq]=` filters on custom parameters.
 pub async fn scope_field_records(mut req: Request, next: Next) -> Response {
     if let Some(r) = check_write_access(&req) {
         return r;
     }
+
+    let parameter_filters = match parameter_filter_params(&req) {
+        Ok(filters) => filters,
+        Err(rejection) => return rejection.into_response(),
+    };
+
+    let mut condition = Condition::all();
+    let mut apply = false;
+
+    if !is_admin(&req) {
+        condition = condition.add(field_records_scope());
+        apply = true;
+    }
+    for filter in parameter_filters {
+        condition = condition.add(filter);
+        apply = true;
+    }
+
+    if apply {
+        req.extensions_mut().insert(ScopeCondition::new(condition));
+    }
+    next.run(req).await
+}
+
+/// Custom parameter values live in `parameter_values`, so each filter becomes a subquery
+/// on the field record id, the same way the isolate habitat filter works. Numeric
+/// comparisons need a numeric value; equality matches `value_number` when the value
+/// parses as a number and `value_text` otherwise. The subquery is built with sea-query
+/// rather than `Expr::cust_with_values` so placeholders render for both backends.
+fn parameter_filter_params(req: &Request) -> Result<Vec<Condition>, (StatusCode, String)> {
+    use crate::parameter_values::db::{Column as ValueColumn, Entity as ValueEntity};
+    use crate::parameters::db::{Column as ParameterColumn, Entity as ParameterEntity};
+
+    let Ok(Query(params)) = Query::<HashMap<String, String>>::try_from_uri(req.uri()) else {
+        return Ok(vec![]);
+    };
+
+    let mut filters = vec![];
+    for (key, raw) in &params {
+        let Some(key) = key.strip_prefix("param.") else {
+            continue;
+        };
+        let (name, operator) = PARAMETER_FILTER_SUFFIXES
+            .iter()
+            .find_map(|suffix| key.strip_suffix(suffix).map(|name| (name, *suffix)))
+            .unwrap_or((key, ""));
+        if name.is_empty() {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                format!("missing parameter name in 'param.{key}'"),
+            ));
+        }
+
+        let number = raw.parse::<f64>().ok().filter(|n| n.is_finite());
+        let value_filter = match (operator, number) {
+            ("_gte", Some(n)) => ValueColumn::ValueNumber.gte(n),
+            ("_lte", Some(n)) => ValueColumn::ValueNumber.lte(n),
+            ("_gt", Some(n)) => ValueColumn::ValueNumber.gt(n),
+            ("_lt", Some(n)) => ValueColumn::ValueNumber.lt(n),
+            ("_neq", Some(n)) => ValueColumn::ValueNumber.ne(n),
+            ("", Some(n)) => ValueColumn::ValueNumber.eq(n),
+            ("_neq", None) => ValueColumn::ValueText.ne(raw.as_str()),
+            ("", None) => ValueColumn::ValueText.eq(raw.as_str()),
+            (_, None) => {
+                return Err((
+                    StatusCode::BAD_REQUEST,
+                    format!("'param.{key}' expects a number, got '{raw}'"),
+                ))
+            }
+            _ => unreachable!("suffixes come from PARAMETER_FILTER_SUFFIXES"),
+        };
+
+        let matching_records = sea_orm::sea_query::Query::select()
+            .column((ValueEntity, ValueColumn::FieldRecordId))
+            .from(ValueEntity)
+            .inner_join(
+                ParameterEntity,
+                Expr::col((ParameterEntity, ParameterColumn::Id))
+                    .equals((ValueEntity, ValueColumn::ParameterId)),
+            )
+            .and_where(ParameterColumn::Name.eq(name))
+            .and_where(value_filter)
+            .to_owned();
+        filters.push(
+            Condition::all()
+                .add(crate::field_records::db::Column::Id.in_subquery(matching_records)),
+        );
+    }
+    Ok(filters)
+}
+
+/// Replicate groups: site (with area check) is public
+pub async fn scope_replicate_groups(mut req: Request, next: Next) -> Response {
+    if let Some(r) = check_write_access(&req) {
+        return r;
+    }
     if !is_admin(&req) {
         req.extensions_mut()
-            .insert(ScopeCondition::new(field_records_scope()));
+            .insert(ScopeCondition::new(replicate_groups_scope()));
     }
     next.run(req).await
 }
@@ -133,16 +306,32 @@ pub async fn scope_samples(mut req: Request, next: Next) -> Response {
 }
 
 /// Isolates: `is_private = false AND field_record/site/area chain is public`, plus an
-/// optional `?sample_type=` habitat filter.
-pub async fn scope_isolates(mut req: Request, next: Next) -> Response {
+/// optional `?sample_type=` habitat filter, case-insensitive rank filters such as
+/// `?phylum=` and `?genus=`, growth filters such as `?grows_at=4&no_growth_at=25`, and
+/// culture collection filters `?accession=` and `?collection=`.
+pub async fn scope_isolates(
+    State(db): State<DatabaseConnection>,
+    mut req: Request,
+    next: Next,
+) -> Response {
     if let Some(r) = check_write_access(&req) {
         return r;
     }
 
-    let sample_type = match sample_type_param(&req) {
+    let mut params = Query::<HashMap<String, String>>::try_from_uri(req.uri())
+        .map(|Query(params)| params)
+        .unwrap_or_default();
+    let sample_type = match sample_type_param(&db, params.remove("sample_type")).await {
         Ok(t) => t,
         Err(rejection) => return rejection.into_response(),
     };
+    let rank_filters = rank_filter_params(&params);
+    let growth_filters = match crate::growth_tests::services::isolate_filters(&params) {
+        Ok(filters) => filters,
+        Err(message) => return (StatusCode::BAD_REQUEST, message).into_response(),
+    };
+
+    let accession_filters = crate::culture_accessions::services::isolate_filters(&params);
 
     let mut condition = Condition::all();
     let mut apply = false;
@@ -155,6 +344,14 @@ pub async fn scope_isolates(mut req: Request, next: Next) -> Response {
         condition = condition.add(field_record_sample_type_scope(&sample_type));
         apply = true;
     }
+    for filter in rank_filters
+        .into_iter()
+        .chain(growth_filters)
+        .chain(accession_filters)
+    {
+        condition = condition.add(filter);
+        apply = true;
+    }
 
     if apply {
         req.extensions_mut().insert(ScopeCondition::new(condition));
@@ -165,31 +362,148 @@ pub async fn scope_isolates(mut req: Request, next: Next) -> Response {
 /// Habitat lives on the parent field record, not the isolate. Expressing it as a
 /// subquery keeps the client from having to enumerate matching field record ids in the
 /// URL, which overflowed the request header limit once the database filled up.
-fn field_record_sample_type_scope(sample_type: &SampleType) -> Condition {
-    Condition::all().add(Expr::cust_with_values(
-        "field_record_id IN (SELECT id FROM field_records WHERE sample_type = $1)",
-        [sample_type.to_string()],
-    ))
+fn field_record_sample_type_scope(sample_type: &str) -> Condition {
+    use crate::field_records::db::{Column as FieldRecordColumn, Entity as FieldRecordEntity};
+
+    let matching_records = sea_orm::sea_query::Query::select()
+        .column(FieldRecordColumn::Id)
+        .from(FieldRecordEntity)
+        .and_where(FieldRecordColumn::SampleType.eq(sample_type))
+        .to_owned();
+    Condition::all().add(crate::isolates::db::Column::FieldRecordId.in_subquery(matching_records))
 }
 
-/// Read and validate `?sample_type=`. An unparseable value is a client error rather
-/// than a silently unfiltered list.
-fn sample_type_param(req: &Request) -> Result<Option<SampleType>, (StatusCode, String)> {
-    let Ok(Query(params)) = Query::<HashMap<String, String>>::try_from_uri(req.uri()) else {
-        return Ok(None);
-    };
-    let Some(raw) = params.get("sample_type") else {
+/// `?<rank>=<name>` filters on the isolate lineage, one per rank in
+/// `isolates::lineage::RANKS`.
+fn rank_filter_params(params: &HashMap<String, String>) -> Vec<Condition> {
+    use crate::isolates::db::Column;
+    use sea_orm::sea_query::Func;
+    use std::str::FromStr;
+
+    crate::isolates::lineage::RANKS
+        .iter()
+        .filter_map(|rank| {
+            let name = params.get(*rank)?;
+            let column = Column::from_str(rank).ok()?;
+            Some(
+                Condition::all()
+                    .add(Expr::expr(Func::lower(Expr::col(column))).eq(name.trim().to_lowercase())),
+            )
+        })
+        .collect()
+}
+
+/// Read and validate `?sample_type=` against the habitat catalogue. An unknown value
+/// is a client error rather than a silently empty list.
+async fn sample_type_param(
+    db: &DatabaseConnection,
+    raw: Option<String>,
+) -> Result<Option<String>, (StatusCode, String)> {
+    let Some(raw) = raw else {
         return Ok(None);
     };
 
-    match raw.as_str() {
-        "Snow" => Ok(Some(SampleType::Snow)),
-        "Soil" => Ok(Some(SampleType::Soil)),
-        other => Err((
+    match crate::sample_types::services::exists(db, &raw).await {
+        Ok(true) => Ok(Some(raw)),
+        Ok(false) => Err((
             StatusCode::BAD_REQUEST,
-            format!("unknown sample_type '{other}', expected Snow or Soil"),
+            format!("unknown sample_type '{raw}'"),
         )),
+        Err(_) => Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            "could not load sample types".to_string(),
+        )),
+    }
+}
+
+/// Storage units, storage positions, labels, withdrawals, custody events, shipments,
+/// temperature readings and taxa: freezer locations and monitoring, label printing,
+/// the consumption and custody logs, outgoing shipments and the reference taxonomy
+/// are internal, so unlike the `exclude(scoped)` fields on items the whole resource
+/// is admin-only.
+pub async fn admin_only(req: Request, next: Next) -> Response {
+    if !is_admin(&req) {
+        return StatusCode::FORBIDDEN.into_response();
+    }
+    next.run(req).await
+}
+
+/// Material requests: anyone may file one (`POST /`), but requests carry the
+/// requester's contact details, so reading and deciding them is admin-only.
+pub async fn scope_material_requests(req: Request, next: Next) -> Response {
+    let is_submission = *req.method() == Method::POST && req.uri().path() == "/";
+    if !is_submission && !is_admin(&req) {
+        return StatusCode::FORBIDDEN.into_response();
     }
+    next.run(req).await
+}
+
+/// Isolates and their images: once a delete succeeds, removes the files nothing
+/// refers to any more. A sweep that fails leaves them for the next one.
+pub async fn remove_orphaned_files(
+    State((db, store)): State<(DatabaseConnection, Arc<dyn ObjectStore>)>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let is_delete = *req.method() == Method::DELETE;
+    let response = next.run(req).await;
+    if is_delete && response.status().is_success() {
+        if let Err(err) = crate::files::services::remove_orphans(&db, &*store).await {
+            eprintln!("Removing orphaned files failed: {err:?}");
+        }
+    }
+    response
+}
+
+/// Marks a `/api/lookup` request as coming from a public caller.
+#[derive(Clone, Copy)]
+pub struct PublicLookup;
+
+/// Lookup: marks public requests so `/api/lookup` resolves codes against the same
+/// rows the resource lists show. Each resource applies its own scope.
+pub async fn scope_lookup(mut req: Request, next: Next) -> Response {
+    if !is_admin(&req) {
+        req.extensions_mut().insert(PublicLookup);
+    }
+    next.run(req).await
+}
+
+/// Sample types and the measurements reserved for them: the habitat catalogue is
+/// public; only writes are restricted.
+pub async fn scope_sample_types(req: Request, next: Next) -> Response {
+    if let Some(r) = check_write_access(&req) {
+        return r;
+    }
+    next.run(req).await
+}
+
+/// Parameters: the catalogue is public; only writes are restricted.
+pub async fn scope_parameters(req: Request, next: Next) -> Response {
+    if let Some(r) = check_write_access(&req) {
+        return r;
+    }
+    next.run(req).await
+}
+
+/// Measurement thresholds: public like the parameter catalogue; only writes are
+/// restricted.
+pub async fn scope_measurement_thresholds(req: Request, next: Next) -> Response {
+    if let Some(r) = check_write_access(&req) {
+        return r;
+    }
+    next.run(req).await
+}
+
+/// Parameter values: parent field_record/site/area chain is public
+pub async fn scope_parameter_values(mut req: Request, next: Next) -> Response {
+    if let Some(r) = check_write_access(&req) {
+        return r;
+    }
+    if !is_admin(&req) {
+        req.extensions_mut()
+            .insert(ScopeCondition::new(parameter_values_scope()));
+    }
+    next.run(req).await
 }
 
 /// DNA: `is_private = false AND field_record/site/area chain is public`
@@ -204,6 +518,26 @@ pub async fn scope_dna(mut req: Request, next: Next) -> Response {
     next.run(req).await
 }
 
+/// Resources hanging off an isolate: the isolate is public (with its field
+/// record/site/area chain). The state is the resource's `isolate_id` column.
+pub async fn scope_isolate_child<C>(
+    State(isolate_id): State<C>,
+    mut req: Request,
+    next: Next,
+) -> Response
+where
+    C: ColumnTrait + Clone + Send + Sync + 'static,
+{
+    if let Some(r) = check_write_access(&req) {
+        return r;
+    }
+    if !is_admin(&req) {
+        req.extensions_mut()
+            .insert(ScopeCondition::new(isolate_child_scope(isolate_id)));
+    }
+    next.run(req).await
+}
+
 #[cfg(test)]
 mod tests {
     use axum::{
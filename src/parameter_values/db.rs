@@ -0,0 +1,65 @@
+use chrono::{DateTime, Utc};
+use crudcrate::{CRUDResource, EntityToModels};
+use sea_orm::entity::prelude::*;
+use uuid::Uuid;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+#[sea_orm(table_name = "parameter_values")]
+#[crudcrate(
+    generate_router,
+    api_struct = "ParameterValue",
+    name_singular = "parameter_value",
+    name_plural = "parameter_values",
+    description = "Values of admin-defined parameters, one per field record and parameter",
+    no_eq,
+    derive_partial_eq,
+    create::one::pre = crate::parameter_values::services::check_create,
+    create::many::pre = crate::parameter_values::services::check_create_many,
+    update::one::pre = crate::parameter_values::services::check_update,
+    update::many::pre = crate::parameter_values::services::check_update_many
+)]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    #[crudcrate(primary_key, filterable, exclude(update, create), on_create = Uuid::new_v4())]
+    pub id: Uuid,
+    #[crudcrate(sortable, filterable, exclude(update))]
+    pub field_record_id: Uuid,
+    #[crudcrate(sortable, filterable, exclude(update))]
+    pub parameter_id: Uuid,
+    #[crudcrate(sortable, filterable)]
+    pub value_number: Option<f64>,
+    #[crudcrate(sortable, filterable, fulltext)]
+    pub value_text: Option<String>,
+    #[crudcrate(sortable, filterable, exclude(create, update), on_create = chrono::Utc::now())]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "crate::field_records::db::Entity",
+        from = "Column::FieldRecordId",
+        to = "crate::field_records::db::Column::Id"
+    )]
+    FieldRecord,
+    #[sea_orm(
+        belongs_to = "crate::parameters::db::Entity",
+        from = "Column::ParameterId",
+        to = "crate::parameters::db::Column::Id"
+    )]
+    Parameter,
+}
+
+impl Related<crate::field_records::db::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::FieldRecord.def()
+    }
+}
+
+impl Related<crate::parameters::db::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Parameter.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,148 @@
+use crudcrate::validation::{validators::validate_range, ValidationError};
+use crudcrate::ApiError;
+use sea_orm::{DatabaseConnection, EntityTrait};
+use uuid::Uuid;
+
+use super::db::{Entity, ParameterValueCreate, ParameterValueUpdate};
+use crate::common::enums::ParameterValueType;
+use crate::{field_records, parameters};
+
+/// Check a value against its catalogue entry: the storage column must match the
+/// parameter type, numbers must fall in the configured range, and habitat-specific
+/// parameters only attach to field records of that habitat.
+async fn check_value(
+    db: &DatabaseConnection,
+    field_record_id: Uuid,
+    parameter_id: Uuid,
+    value_number: Option<f64>,
+    value_text: Option<&str>,
+) -> Result<(), ApiError> {
+    let Some(parameter) = parameters::db::Entity::find_by_id(parameter_id)
+        .one(db)
+        .await?
+    else {
+        return Err(ValidationError::new("parameter_id", "Unknown parameter").into());
+    };
+    let Some(field_record) = field_records::db::Entity::find_by_id(field_record_id)
+        .one(db)
+        .await?
+    else {
+        return Err(ValidationError::new("field_record_id", "Unknown field record").into());
+    };
+
+    if let Some(sample_type) = &parameter.sample_type {
+        if *sample_type != field_record.sample_type {
+            return Err(ValidationError::new(
+                "parameter_id",
+                format!(
+                    "'{}' only applies to {sample_type} field records",
+                    parameter.name
+                ),
+            )
+            .into());
+        }
+    }
+
+    if parameter.value_type.is_numeric() {
+        if value_text.is_some() {
+            return Err(ValidationError::new(
+                "value_text",
+                format!("'{}' is numeric, use value_number", parameter.name),
+            )
+            .into());
+        }
+        let Some(value) = value_number else {
+            return Err(ValidationError::new("value_number", "A value is required").into());
+        };
+        if !value.is_finite() {
+            return Err(ValidationError::new("value_number", "Must be a finite number").into());
+        }
+        if parameter.value_type == ParameterValueType::Integer && value.fract() != 0.0 {
+            return Err(ValidationError::new("value_number", "Must be a whole number").into());
+        }
+        validate_range(
+            "value_number",
+            value,
+            parameter.min_value,
+            parameter.max_value,
+        )?;
+    } else {
+        if value_number.is_some() {
+            return Err(ValidationError::new(
+                "value_number",
+                format!("'{}' is text, use value_text", parameter.name),
+            )
+            .into());
+        }
+        if value_text.is_none_or(|text| text.trim().is_empty()) {
+            return Err(ValidationError::new("value_text", "A value is required").into());
+        }
+    }
+
+    Ok(())
+}
+
+pub async fn check_create(
+    db: &DatabaseConnection,
+    data: &ParameterValueCreate,
+) -> Result<(), ApiError> {
+    check_value(
+        db,
+        data.field_record_id,
+        data.parameter_id,
+        data.value_number,
+        data.value_text.as_deref(),
+    )
+    .await
+}
+
+pub async fn check_create_many(
+    db: &DatabaseConnection,
+    data: &[ParameterValueCreate],
+) -> Result<(), ApiError> {
+    for item in data {
+        check_create(db, item).await?;
+    }
+    Ok(())
+}
+
+/// Updates only carry the value columns, so the stored row supplies the parameter
+/// and field record; absent columns keep their stored value.
+pub async fn check_update(
+    db: &DatabaseConnection,
+    id: Uuid,
+    data: &ParameterValueUpdate,
+) -> Result<(), ApiError> {
+    let existing = Entity::find_by_id(id)
+        .one(db)
+        .await?
+        .ok_or_else(|| ApiError::not_found("parameter_value", Some(id.to_string())))?;
+
+    let value_number = match data.value_number {
+        Some(value) => value,
+        None => existing.value_number,
+    };
+    let value_text = match &data.value_text {
+        Some(value) => value.clone(),
+        None => existing.value_text,
+    };
+
+    check_value(
+        db,
+        existing.field_record_id,
+        existing.parameter_id,
+        value_number,
+        value_text.as_deref(),
+    )
+    .await
+}
+
+pub async fn check_update_many(
+    db: &DatabaseConnection,
+    updates: &[(Uuid, ParameterValueUpdate)],
+) -> Result<(), ApiError> {
+    for (id, data) in updates {
+        check_update(db, *id, data).await?;
+    }
+    Ok(())
+}
@@ -0,0 +1,339 @@
+use axum::http::StatusCode;
+use serde_json::{json, Value};
+
+use crate::test_utils::{
+    build_app_with_db, build_scoped_app_with_db, create, create_site, get, names, send,
+    setup_sqlite_db,
+};
+
+async fn create_field_record(
+    app: &axum::Router,
+    site_id: &str,
+    name: &str,
+    sample_type: &str,
+) -> String {
+    create(
+        app,
+        "/api/field_records",
+        json!({
+            "name": name, "site_id": site_id, "sample_type": sample_type,
+            "sampling_date": "2025-07-10"
+        }),
+    )
+    .await
+}
+
+fn sorted_names(list: &Value) -> Vec<&str> {
+    let mut names = names(list);
+    names.sort_unstable();
+    names
+}
+
+#[tokio::test]
+async fn parameter_name_must_be_usable_as_filter_key() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db);
+
+    for name in ["Chlorophyll A", "depth_gte", "1st_layer"] {
+        let (status, body) = send(
+            &app,
+            "POST",
+            "/api/parameters",
+            json!({ "name": name, "value_type": "Number" }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{name}: {body}");
+    }
+
+    let (status, _) = send(
+        &app,
+        "POST",
+        "/api/parameters",
+        json!({ "name": "ice_type", "value_type": "Text", "min_value": 0.0 }),
+    )
+    .await;
+    assert_eq!(
+        status,
+        StatusCode::UNPROCESSABLE_ENTITY,
+        "text parameters have no range"
+    );
+
+    create(
+        &app,
+        "/api/parameters",
+        json!({ "name": "chlorophyll_a", "value_type": "Number", "unit": "µg/L" }),
+    )
+    .await;
+}
+
+/// Scenario: a Snow-only integer parameter with a range, and a text parameter.
+/// Expected behaviour: values that disagree with the catalogue entry are 422, valid
+/// ones are stored and show up on the field record.
+#[tokio::test]
+async fn values_are_checked_against_the_catalogue() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db);
+
+    let site_id = create_site(&app, "Glacier A").await;
+    let snow = create_field_record(&app, &site_id, "FR-SNOW", "Snow").await;
+    let soil = create_field_record(&app, &site_id, "FR-SOIL", "Soil").await;
+
+    let algae = create(
+        &app,
+        "/api/parameters",
+        json!({
+            "name": "algae_count", "value_type": "Integer", "min_value": 0.0,
+            "max_value": 1000.0, "sample_type": "Snow"
+        }),
+    )
+    .await;
+    let colour = create(
+        &app,
+        "/api/parameters",
+        json!({ "name": "snow_colour", "value_type": "Text" }),
+    )
+    .await;
+
+    for (payload, reason) in [
+        (
+            json!({ "field_record_id": soil, "parameter_id": algae, "value_number": 5.0 }),
+            "Snow only",
+        ),
+        (
+            json!({ "field_record_id": snow, "parameter_id": algae, "value_number": 2.5 }),
+            "integer",
+        ),
+        (
+            json!({ "field_record_id": snow, "parameter_id": algae, "value_number": 5000.0 }),
+            "range",
+        ),
+        (
+            json!({ "field_record_id": snow, "parameter_id": algae, "value_text": "many" }),
+            "numeric",
+        ),
+        (
+            json!({ "field_record_id": snow, "parameter_id": colour, "value_number": 1.0 }),
+            "text",
+        ),
+        (
+            json!({ "field_record_id": snow, "parameter_id": colour, "value_text": "  " }),
+            "blank",
+        ),
+    ] {
+        let (status, body) = send(&app, "POST", "/api/parameter_values", payload).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{reason}: {body}");
+    }
+
+    let value_id = create(
+        &app,
+        "/api/parameter_values",
+        json!({ "field_record_id": snow, "parameter_id": algae, "value_number": 42.0 }),
+    )
+    .await;
+    create(
+        &app,
+        "/api/parameter_values",
+        json!({ "field_record_id": snow, "parameter_id": colour, "value_text": "red" }),
+    )
+    .await;
+
+    let (status, _) = send(
+        &app,
+        "PUT",
+        &format!("/api/parameter_values/{value_id}"),
+        json!({ "value_number": -1.0 }),
+    )
+    .await;
+    assert_eq!(
+        status,
+        StatusCode::UNPROCESSABLE_ENTITY,
+        "update stays in range"
+    );
+
+    let (status, record) = get(&app, &format!("/api/field_records/{snow}")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(record["parameter_values"].as_array().unwrap().len(), 2);
+}
+
+/// Scenario: three snow records with different algae counts and colours.
+/// Expected behaviour: `param.` filters narrow the field record list, and the values
+/// list sorts by the numeric value.
+#[tokio::test]
+async fn field_records_filter_and_values_sort_by_parameter() {
+    let db = setup_sqlite_db().await;
+    let admin = build_app_with_db(db.clone());
+    let scoped = build_scoped_app_with_db(db);
+
+    let site_id = create_site(&admin, "Glacier A").await;
+    let algae = create(
+        &admin,
+        "/api/parameters",
+        json!({ "name": "algae_count", "value_type": "Integer" }),
+    )
+    .await;
+    let colour = create(
+        &admin,
+        "/api/parameters",
+        json!({ "name": "snow_colour", "value_type": "Text" }),
+    )
+    .await;
+
+    for (name, count, snow_colour) in [
+        ("FR-1", 10.0, "red"),
+        ("FR-2", 250.0, "green"),
+        ("FR-3", 90.0, "red"),
+    ] {
+        let fr = create_field_record(&admin, &site_id, name, "Snow").await;
+        create(
+            &admin,
+            "/api/parameter_values",
+            json!({ "field_record_id": fr, "parameter_id": algae, "value_number": count }),
+        )
+        .await;
+        create(
+            &admin,
+            "/api/parameter_values",
+            json!({ "field_record_id": fr, "parameter_id": colour, "value_text": snow_colour }),
+        )
+        .await;
+    }
+
+    let (status, list) = get(&scoped, "/api/field_records?param.algae_count_gte=50").await;
+    assert_eq!(status, StatusCode::OK, "{list}");
+    assert_eq!(sorted_names(&list), ["FR-2", "FR-3"]);
+
+    let (_, list) = get(
+        &scoped,
+        "/api/field_records?param.algae_count_lt=200&param.snow_colour=red",
+    )
+    .await;
+    assert_eq!(sorted_names(&list), ["FR-1", "FR-3"]);
+
+    let (_, list) = get(&scoped, "/api/field_records?param.snow_colour_neq=red").await;
+    assert_eq!(sorted_names(&list), ["FR-2"]);
+
+    let (status, _) = get(&scoped, "/api/field_records?param.algae_count_gt=lots").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, values) = get(
+        &scoped,
+        &format!(
+            "/api/parameter_values?filter=%7B%22parameter_id%22%3A%22{algae}%22%7D\
+             &sort=%5B%22value_number%22%2C%22DESC%22%5D"
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{values}");
+    let counts: Vec<f64> = values
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|v| v["value_number"].as_f64().unwrap())
+        .collect();
+    assert_eq!(counts, [250.0, 90.0, 10.0]);
+}
+
+/// Scenario: snow records with algae counts 10, 250 and 90, and one never counted.
+/// Expected behaviour: `/api/field_records/sorted?parameter=algae_count` orders by the
+/// count in either direction with the uncounted record last, one page at a time; text
+/// parameters sort alphabetically.
+#[tokio::test]
+async fn field_records_sort_by_parameter() {
+    let db = setup_sqlite_db().await;
+    let admin = build_app_with_db(db.clone());
+    let scoped = build_scoped_app_with_db(db);
+
+    let site_id = create_site(&admin, "Glacier A").await;
+    let algae = create(
+        &admin,
+        "/api/parameters",
+        json!({ "name": "algae_count", "value_type": "Integer" }),
+    )
+    .await;
+    let colour = create(
+        &admin,
+        "/api/parameters",
+        json!({ "name": "snow_colour", "value_type": "Text" }),
+    )
+    .await;
+    for (name, count, snow_colour) in [
+        ("FR-1", 10.0, "red"),
+        ("FR-2", 250.0, "green"),
+        ("FR-3", 90.0, "orange"),
+    ] {
+        let fr = create_field_record(&admin, &site_id, name, "Snow").await;
+        create(
+            &admin,
+            "/api/parameter_values",
+            json!({ "field_record_id": fr, "parameter_id": algae, "value_number": count }),
+        )
+        .await;
+        create(
+            &admin,
+            "/api/parameter_values",
+            json!({ "field_record_id": fr, "parameter_id": colour, "value_text": snow_colour }),
+        )
+        .await;
+    }
+    create_field_record(&admin, &site_id, "FR-4", "Snow").await;
+
+    for (query, expected) in [
+        (
+            "parameter=algae_count&order=ASC",
+            &["FR-1", "FR-3", "FR-2", "FR-4"][..],
+        ),
+        (
+            "parameter=algae_count&order=DESC",
+            &["FR-2", "FR-3", "FR-1", "FR-4"],
+        ),
+        ("parameter=snow_colour", &["FR-2", "FR-3", "FR-1", "FR-4"]),
+        ("parameter=algae_count&page=2&per_page=3", &["FR-4"]),
+    ] {
+        let (status, list) = get(&scoped, &format!("/api/field_records/sorted?{query}")).await;
+        assert_eq!(status, StatusCode::OK, "{list}");
+        assert_eq!(names(&list), expected, "{query}");
+    }
+}
+
+#[tokio::test]
+async fn values_of_private_field_records_hidden_from_public() {
+    let db = setup_sqlite_db().await;
+    let admin = build_app_with_db(db.clone());
+    let scoped = build_scoped_app_with_db(db);
+
+    let site_id = create_site(&admin, "Glacier A").await;
+    let fr = create(
+        &admin,
+        "/api/field_records",
+        json!({
+            "name": "FR-PRIVATE", "site_id": site_id, "sample_type": "Snow",
+            "sampling_date": "2025-07-10", "is_private": true
+        }),
+    )
+    .await;
+    let parameter = create(
+        &admin,
+        "/api/parameters",
+        json!({ "name": "algae_count", "value_type": "Integer" }),
+    )
+    .await;
+    let value_id = create(
+        &admin,
+        "/api/parameter_values",
+        json!({ "field_record_id": fr, "parameter_id": parameter, "value_number": 3.0 }),
+    )
+    .await;
+
+    let (status, _) = get(&scoped, &format!("/api/parameter_values/{value_id}")).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _) = get(&scoped, &format!("/api/parameters/{parameter}")).await;
+    assert_eq!(status, StatusCode::OK, "catalogue stays public");
+    let (status, _) = send(
+        &scoped,
+        "POST",
+        "/api/parameters",
+        json!({ "name": "ph_field", "value_type": "Number" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
@@ -0,0 +1,131 @@
+use crate::common::enums::ParameterValueType;
+use chrono::{DateTime, Utc};
+use crudcrate::validation::{Validatable, ValidationError};
+use crudcrate::{CRUDResource, EntityToModels};
+use sea_orm::entity::prelude::*;
+use uuid::Uuid;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+#[sea_orm(table_name = "parameters")]
+#[crudcrate(
+    generate_router,
+    api_struct = "Parameter",
+    name_singular = "parameter",
+    name_plural = "parameters",
+    description = "Admin-defined measurement parameters recorded per field record without a schema change",
+    no_eq,
+    derive_partial_eq,
+    create::one::pre = crate::parameters::services::check_create,
+    create::many::pre = crate::parameters::services::check_create_many,
+    update::one::pre = crate::parameters::services::check_update,
+    update::many::pre = crate::parameters::services::check_update_many
+)]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    #[crudcrate(primary_key, filterable, exclude(update, create), on_create = Uuid::new_v4())]
+    pub id: Uuid,
+    #[sea_orm(unique)]
+    #[crudcrate(sortable, filterable, fulltext)]
+    pub name: String,
+    #[crudcrate(sortable, filterable, fulltext)]
+    pub label: Option<String>,
+    #[crudcrate(sortable, filterable)]
+    pub value_type: ParameterValueType,
+    #[crudcrate(sortable, filterable)]
+    pub unit: Option<String>,
+    #[crudcrate(sortable, filterable)]
+    pub min_value: Option<f64>,
+    #[crudcrate(sortable, filterable)]
+    pub max_value: Option<f64>,
+    /// Habitat the parameter applies to; `None` applies to every field record.
+    #[crudcrate(sortable, filterable)]
+    pub sample_type: Option<String>,
+    #[crudcrate(sortable, filterable, fulltext)]
+    pub description: Option<String>,
+    #[crudcrate(sortable, filterable, exclude(create, update), on_create = chrono::Utc::now())]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "crate::parameter_values::db::Entity")]
+    ParameterValues,
+}
+
+impl Related<crate::parameter_values::db::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ParameterValues.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+// Names double as query keys (`?param.<name>_gte=`), so they are restricted to
+// lowercase snake case and may not end in one of the filter operator suffixes.
+fn validate_name(name: &str) -> Result<(), ValidationError> {
+    let well_formed = name.chars().next().is_some_and(|c| c.is_ascii_lowercase())
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_');
+    if !well_formed {
+        return Err(ValidationError::new(
+            "name",
+            "Must be lowercase snake_case starting with a letter",
+        ));
+    }
+    if let Some(suffix) = crate::middleware::PARAMETER_FILTER_SUFFIXES
+        .iter()
+        .find(|suffix| name.ends_with(*suffix))
+    {
+        return Err(ValidationError::new(
+            "name",
+            format!("Must not end in the filter suffix '{suffix}'"),
+        ));
+    }
+    Ok(())
+}
+
+fn validate_range_bounds(
+    value_type: &ParameterValueType,
+    min_value: Option<f64>,
+    max_value: Option<f64>,
+) -> Result<(), ValidationError> {
+    if !value_type.is_numeric() && (min_value.is_some() || max_value.is_some()) {
+        return Err(ValidationError::new(
+            "min_value",
+            "Ranges only apply to Number and Integer parameters",
+        ));
+    }
+    if let (Some(min), Some(max)) = (min_value, max_value) {
+        if min > max {
+            return Err(ValidationError::new(
+                "max_value",
+                "Must not be below min_value",
+            ));
+        }
+    }
+    Ok(())
+}
+
+impl Validatable for ParameterCreate {
+    fn validate(&self) -> Result<(), ValidationError> {
+        validate_name(&self.name)?;
+        validate_range_bounds(&self.value_type, self.min_value, self.max_value)
+    }
+}
+
+impl Validatable for ParameterUpdate {
+    fn validate(&self) -> Result<(), ValidationError> {
+        if let Some(Some(name)) = &self.name {
+            validate_name(name)?;
+        }
+        // Bounds are only cross-checked when the request carries the full picture;
+        // the table's CHECK constraint covers the rest.
+        if let (Some(Some(value_type)), Some(min_value), Some(max_value)) =
+            (&self.value_type, self.min_value, self.max_value)
+        {
+            validate_range_bounds(value_type, min_value, max_value)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,44 @@
+use crudcrate::ApiError;
+use sea_orm::DatabaseConnection;
+use uuid::Uuid;
+
+use super::db::{ParameterCreate, ParameterUpdate};
+use crate::sample_types;
+
+pub async fn check_create(db: &DatabaseConnection, data: &ParameterCreate) -> Result<(), ApiError> {
+    if let Some(sample_type) = &data.sample_type {
+        sample_types::services::check_known(db, sample_type).await?;
+    }
+    Ok(())
+}
+
+pub async fn check_create_many(
+    db: &DatabaseConnection,
+    data: &[ParameterCreate],
+) -> Result<(), ApiError> {
+    for item in data {
+        check_create(db, item).await?;
+    }
+    Ok(())
+}
+
+pub async fn check_update(
+    db: &DatabaseConnection,
+    _id: Uuid,
+    data: &ParameterUpdate,
+) -> Result<(), ApiError> {
+    if let Some(Some(sample_type)) = &data.sample_type {
+        sample_types::services::check_known(db, sample_type).await?;
+    }
+    Ok(())
+}
+
+pub async fn check_update_many(
+    db: &DatabaseConnection,
+    updates: &[(Uuid, ParameterUpdate)],
+) -> Result<(), ApiError> {
+    for (id, data) in updates {
+        check_update(db, *id, data).await?;
+    }
+    Ok(())
+}
@@ -0,0 +1,92 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use crudcrate::validation::{Validatable, ValidationError};
+use crudcrate::{CRUDResource, EntityToModels};
+use sea_orm::entity::prelude::*;
+use uuid::Uuid;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, EntityToModels)]
+#[sea_orm(table_name = "replicate_groups")]
+#[crudcrate(
+    generate_router,
+    api_struct = "ReplicateGroup",
+    name_singular = "replicate_group",
+    name_plural = "replicate_groups",
+    description = "Replicate field records taken at one site on one date under one treatment",
+    derive_partial_eq,
+    update::one::pre = crate::replicate_groups::services::check_update,
+    update::many::pre = crate::replicate_groups::services::check_update_many
+)]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    #[crudcrate(primary_key, filterable, exclude(update, create), on_create = Uuid::new_v4())]
+    pub id: Uuid,
+    #[crudcrate(sortable, filterable, fulltext)]
+    pub name: String,
+    #[crudcrate(sortable, filterable)]
+    pub site_id: Uuid,
+    #[crudcrate(sortable, filterable)]
+    pub sampling_date: NaiveDate,
+    /// `None` is the untreated control.
+    #[crudcrate(sortable, filterable, fulltext)]
+    pub treatment: Option<String>,
+    #[crudcrate(sortable, filterable, on_create = 3)]
+    pub expected_replicates: i32,
+    #[crudcrate(sortable, filterable, fulltext)]
+    pub description: Option<String>,
+    #[crudcrate(sortable, filterable, exclude(create, update), on_create = chrono::Utc::now())]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "crate::sites::db::Entity",
+        from = "Column::SiteId",
+        to = "crate::sites::db::Column::Id"
+    )]
+    Site,
+    #[sea_orm(has_many = "crate::field_records::db::Entity")]
+    FieldRecords,
+}
+
+impl Related<crate::sites::db::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Site.def()
+    }
+}
+
+impl Related<crate::field_records::db::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::FieldRecords.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+fn validate_expected_replicates(expected_replicates: i32) -> Result<(), ValidationError> {
+    if expected_replicates < 1 {
+        return Err(ValidationError::new(
+            "expected_replicates",
+            "Must be at least 1",
+        ));
+    }
+    Ok(())
+}
+
+impl Validatable for ReplicateGroupCreate {
+    fn validate(&self) -> Result<(), ValidationError> {
+        if let Some(expected_replicates) = self.expected_replicates {
+            validate_expected_replicates(expected_replicates)?;
+        }
+        Ok(())
+    }
+}
+
+impl Validatable for ReplicateGroupUpdate {
+    fn validate(&self) -> Result<(), ValidationError> {
+        if let Some(Some(expected_replicates)) = self.expected_replicates {
+            validate_expected_replicates(expected_replicates)?;
+        }
+        Ok(())
+    }
+}
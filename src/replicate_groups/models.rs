@@ -0,0 +1,38 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Mean and sample standard deviation of one measurement across a group's field
+/// records. Records without a value are left out of `n`; `standard_deviation` is
+/// `None` below two values.
+#[derive(ToSchema, Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct MeasurementStatistics {
+    pub name: String,
+    pub unit: Option<String>,
+    pub n: u64,
+    pub mean: f64,
+    pub standard_deviation: Option<f64>,
+}
+
+/// Group-level view of a replicate set: built-in measurement columns first, then
+/// numeric custom parameters.
+#[derive(ToSchema, Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct ReplicateGroupStatistics {
+    pub replicate_group_id: Uuid,
+    pub expected_replicates: i32,
+    pub field_record_count: u64,
+    pub measurements: Vec<MeasurementStatistics>,
+}
+
+#[derive(ToSchema, Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct IncompleteReplicateGroup {
+    pub id: Uuid,
+    pub name: String,
+    pub site_id: Uuid,
+    pub sampling_date: NaiveDate,
+    pub treatment: Option<String>,
+    pub expected_replicates: i32,
+    pub field_record_count: u64,
+    pub missing_replicates: u64,
+}
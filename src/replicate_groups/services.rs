@@ -0,0 +1,116 @@
+use chrono::NaiveDate;
+use crudcrate::validation::ValidationError;
+use crudcrate::ApiError;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use uuid::Uuid;
+
+use super::db::{Entity, ReplicateGroupUpdate};
+use crate::field_records;
+
+/// Treatments are free text; surrounding whitespace and blank values should not
+/// split a group.
+fn normalise_treatment(treatment: Option<&str>) -> Option<&str> {
+    treatment.map(str::trim).filter(|t| !t.is_empty())
+}
+
+/// The design shared by every member of a group.
+struct Design<'a> {
+    site_id: Uuid,
+    sampling_date: NaiveDate,
+    treatment: Option<&'a str>,
+}
+
+impl Design<'_> {
+    /// Name of the first field that differs from the record, if any.
+    fn mismatch(&self, record: &field_records::db::Model) -> Option<&'static str> {
+        if record.site_id != self.site_id {
+            Some("site_id")
+        } else if record.sampling_date != self.sampling_date {
+            Some("sampling_date")
+        } else if normalise_treatment(record.treatment.as_deref())
+            != normalise_treatment(self.treatment)
+        {
+            Some("treatment")
+        } else {
+            None
+        }
+    }
+}
+
+/// A field record may only join a group whose site, date and treatment it shares.
+pub async fn check_membership(
+    db: &DatabaseConnection,
+    record: &field_records::db::Model,
+) -> Result<(), ApiError> {
+    let Some(group_id) = record.replicate_group_id else {
+        return Ok(());
+    };
+    let Some(group) = Entity::find_by_id(group_id).one(db).await? else {
+        return Err(ValidationError::new("replicate_group_id", "Unknown replicate group").into());
+    };
+
+    let design = Design {
+        site_id: group.site_id,
+        sampling_date: group.sampling_date,
+        treatment: group.treatment.as_deref(),
+    };
+    if let Some(field) = design.mismatch(record) {
+        return Err(ValidationError::new(
+            field,
+            format!("Must match replicate group '{}'", group.name),
+        )
+        .into());
+    }
+    Ok(())
+}
+
+/// Changing a group's design must not strand the field records already in it.
+pub async fn check_update(
+    db: &DatabaseConnection,
+    id: Uuid,
+    data: &ReplicateGroupUpdate,
+) -> Result<(), ApiError> {
+    let existing = Entity::find_by_id(id)
+        .one(db)
+        .await?
+        .ok_or_else(|| ApiError::not_found("replicate_group", Some(id.to_string())))?;
+
+    let treatment = match &data.treatment {
+        Some(treatment) => treatment.clone(),
+        None => existing.treatment,
+    };
+    let design = Design {
+        site_id: match data.site_id {
+            Some(Some(site_id)) => site_id,
+            _ => existing.site_id,
+        },
+        sampling_date: match data.sampling_date {
+            Some(Some(sampling_date)) => sampling_date,
+            _ => existing.sampling_date,
+        },
+        treatment: treatment.as_deref(),
+    };
+
+    let members = field_records::db::Entity::find()
+        .filter(field_records::db::Column::ReplicateGroupId.eq(id))
+        .all(db)
+        .await?;
+    if let Some(field) = members.iter().find_map(|record| design.mismatch(record)) {
+        return Err(ValidationError::new(
+            field,
+            "Field records in this group would no longer match",
+        )
+        .into());
+    }
+    Ok(())
+}
+
+pub async fn check_update_many(
+    db: &DatabaseConnection,
+    updates: &[(Uuid, ReplicateGroupUpdate)],
+) -> Result<(), ApiError> {
+    for (id, data) in updates {
+        check_update(db, *id, data).await?;
+    }
+    Ok(())
+}
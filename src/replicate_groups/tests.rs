@@ -0,0 +1,271 @@
+use axum::http::StatusCode;
+use serde_json::{json, Value};
+
+use crate::test_utils::{
+    build_app_with_db, build_scoped_app_with_db, create, create_site, get, send, setup_sqlite_db,
+};
+
+fn measurement<'a>(statistics: &'a Value, name: &str) -> &'a Value {
+    statistics["measurements"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|m| m["name"] == name)
+        .unwrap_or_else(|| panic!("no {name} in {statistics}"))
+}
+
+#[tokio::test]
+async fn field_records_must_share_the_group_design() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db);
+
+    let site_id = create_site(&app, "Glacier A").await;
+    let other_site_id = create_site(&app, "Glacier B").await;
+    let group_id = create(
+        &app,
+        "/api/replicate_groups",
+        json!({
+            "name": "Glacier A warming", "site_id": site_id,
+            "sampling_date": "2025-07-10", "treatment": "warming"
+        }),
+    )
+    .await;
+
+    for (extra, reason) in [
+        (json!({ "site_id": other_site_id }), "site"),
+        (json!({ "sampling_date": "2025-07-11" }), "date"),
+        (json!({ "treatment": "control" }), "treatment"),
+        (json!({ "treatment": null }), "untreated"),
+    ] {
+        let mut payload = json!({
+            "name": format!("FR-{reason}"), "site_id": site_id, "sample_type": "Soil",
+            "sampling_date": "2025-07-10", "treatment": "warming", "replicate_group_id": group_id
+        });
+        payload
+            .as_object_mut()
+            .unwrap()
+            .extend(extra.as_object().unwrap().clone());
+        let (status, body) = send(&app, "POST", "/api/field_records", payload).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{reason}: {body}");
+    }
+
+    let fr = create(
+        &app,
+        "/api/field_records",
+        json!({
+            "name": "FR-1", "site_id": site_id, "sample_type": "Soil",
+            "sampling_date": "2025-07-10", "treatment": " warming ", "replicate_group_id": group_id
+        }),
+    )
+    .await;
+
+    let (status, _) = send(
+        &app,
+        "PUT",
+        &format!("/api/field_records/{fr}"),
+        json!({ "sampling_date": "2025-07-12" }),
+    )
+    .await;
+    assert_eq!(
+        status,
+        StatusCode::UNPROCESSABLE_ENTITY,
+        "member moved out of design"
+    );
+
+    let (status, _) = send(
+        &app,
+        "PUT",
+        &format!("/api/replicate_groups/{group_id}"),
+        json!({ "treatment": "shading" }),
+    )
+    .await;
+    assert_eq!(
+        status,
+        StatusCode::UNPROCESSABLE_ENTITY,
+        "group would strand FR-1"
+    );
+
+    let (status, _) = send(
+        &app,
+        "PUT",
+        &format!("/api/replicate_groups/{group_id}"),
+        json!({ "expected_replicates": 4 }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+/// Scenario: a triplicate with pH 5.0, 6.0 and 7.0 and a custom numeric parameter
+/// on two of the three replicates, one of which is private.
+/// Expected behaviour: admins get mean 6 and sd 1 for pH; the public statistics only
+/// include the public replicates.
+#[tokio::test]
+async fn statistics_report_mean_and_standard_deviation() {
+    let db = setup_sqlite_db().await;
+    let admin = build_app_with_db(db.clone());
+    let scoped = build_scoped_app_with_db(db);
+
+    let site_id = create_site(&admin, "Glacier A").await;
+    let group_id = create(
+        &admin,
+        "/api/replicate_groups",
+        json!({ "name": "Glacier A control", "site_id": site_id, "sampling_date": "2025-07-10" }),
+    )
+    .await;
+    let chlorophyll = create(
+        &admin,
+        "/api/parameters",
+        json!({ "name": "chlorophyll_a", "value_type": "Number", "unit": "µg/L" }),
+    )
+    .await;
+
+    for (name, ph, chlorophyll_a, is_private) in [
+        ("FR-1", 5.0, Some(1.5), false),
+        ("FR-2", 6.0, Some(2.5), false),
+        ("FR-3", 7.0, None, true),
+    ] {
+        let fr = create(
+            &admin,
+            "/api/field_records",
+            json!({
+                "name": name, "site_id": site_id, "sample_type": "Soil", "sampling_date": "2025-07-10",
+                "ph": ph, "replicate_group_id": group_id, "is_private": is_private
+            }),
+        )
+        .await;
+        if let Some(value) = chlorophyll_a {
+            create(
+                &admin,
+                "/api/parameter_values",
+                json!({ "field_record_id": fr, "parameter_id": chlorophyll, "value_number": value }),
+            )
+            .await;
+        }
+    }
+
+    let (status, statistics) = get(
+        &admin,
+        &format!("/api/replicate_groups/{group_id}/statistics"),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{statistics}");
+    assert_eq!(statistics["field_record_count"], 3);
+    let ph = measurement(&statistics, "ph");
+    assert_eq!(ph["n"], 3);
+    assert_eq!(ph["mean"].as_f64().unwrap(), 6.0);
+    assert!((ph["standard_deviation"].as_f64().unwrap() - 1.0).abs() < 1e-9);
+    let chlorophyll_a = measurement(&statistics, "chlorophyll_a");
+    assert_eq!(chlorophyll_a["unit"], "µg/L");
+    assert_eq!(chlorophyll_a["mean"].as_f64().unwrap(), 2.0);
+    assert!(
+        statistics["measurements"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .all(|m| m["name"] != "snow_depth_cm"),
+        "columns without values are left out"
+    );
+
+    let (status, statistics) = get(
+        &scoped,
+        &format!("/api/replicate_groups/{group_id}/statistics"),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{statistics}");
+    assert_eq!(statistics["field_record_count"], 2);
+    let ph = measurement(&statistics, "ph");
+    assert_eq!(ph["n"], 2);
+    assert_eq!(ph["mean"].as_f64().unwrap(), 5.5);
+}
+
+#[tokio::test]
+async fn incomplete_groups_are_flagged() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db);
+
+    let site_id = create_site(&app, "Glacier A").await;
+    let mut groups = vec![];
+    for (treatment, replicates) in [("control", 3), ("warming", 1)] {
+        let group_id = create(
+            &app,
+            "/api/replicate_groups",
+            json!({
+                "name": format!("Glacier A {treatment}"), "site_id": site_id,
+                "sampling_date": "2025-07-10", "treatment": treatment
+            }),
+        )
+        .await;
+        for i in 0..replicates {
+            create(
+                &app,
+                "/api/field_records",
+                json!({
+                    "name": format!("FR-{treatment}-{i}"), "site_id": site_id, "sample_type": "Soil",
+                    "sampling_date": "2025-07-10", "treatment": treatment, "replicate_group_id": group_id
+                }),
+            )
+            .await;
+        }
+        groups.push(group_id);
+    }
+
+    let (status, incomplete) = get(&app, "/api/replicate_groups/incomplete").await;
+    assert_eq!(status, StatusCode::OK, "{incomplete}");
+    let incomplete = incomplete.as_array().unwrap();
+    assert_eq!(incomplete.len(), 1, "{incomplete:?}");
+    assert_eq!(incomplete[0]["id"], groups[1]);
+    assert_eq!(incomplete[0]["field_record_count"], 1);
+    assert_eq!(incomplete[0]["missing_replicates"], 2);
+}
+
+/// Scenario: a group expecting two replicates holds one public and one private
+/// record; another expecting three holds a single public record.
+/// Expected behaviour: admins see only the second as incomplete; public callers see
+/// it with its public count and do not see the first flagged for the hidden record.
+#[tokio::test]
+async fn public_incomplete_groups_count_public_records_only() {
+    let db = setup_sqlite_db().await;
+    let admin = build_app_with_db(db.clone());
+    let scoped = build_scoped_app_with_db(db);
+
+    let site_id = create_site(&admin, "Glacier A").await;
+    let mut groups = vec![];
+    for (treatment, expected, private) in [
+        ("control", 2, vec![false, true]),
+        ("warming", 3, vec![false]),
+    ] {
+        let group_id = create(
+            &admin,
+            "/api/replicate_groups",
+            json!({
+                "name": format!("Glacier A {treatment}"), "site_id": site_id,
+                "sampling_date": "2025-07-10", "treatment": treatment,
+                "expected_replicates": expected
+            }),
+        )
+        .await;
+        for (i, is_private) in private.into_iter().enumerate() {
+            create(
+                &admin,
+                "/api/field_records",
+                json!({
+                    "name": format!("FR-{treatment}-{i}"), "site_id": site_id, "sample_type": "Soil",
+                    "sampling_date": "2025-07-10", "treatment": treatment,
+                    "replicate_group_id": group_id, "is_private": is_private
+                }),
+            )
+            .await;
+        }
+        groups.push(group_id);
+    }
+
+    for app in [&admin, &scoped] {
+        let (status, incomplete) = get(app, "/api/replicate_groups/incomplete").await;
+        assert_eq!(status, StatusCode::OK, "{incomplete}");
+        let incomplete = incomplete.as_array().unwrap();
+        assert_eq!(incomplete.len(), 1, "{incomplete:?}");
+        assert_eq!(incomplete[0]["id"], groups[1]);
+        assert_eq!(incomplete[0]["field_record_count"], 1);
+        assert_eq!(incomplete[0]["missing_replicates"], 2);
+    }
+}
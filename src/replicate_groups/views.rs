@@ -0,0 +1,195 @@
+use axum::extract::{Path, Request, State};
+use axum::{routing::get, Json, Router};
+use crudcrate::ApiError;
+use sea_orm::{
+    ColumnTrait, Condition, DatabaseConnection, EntityTrait, IdenStatic, ModelTrait, QueryFilter,
+    QueryOrder, QuerySelect,
+};
+use std::collections::{BTreeMap, HashMap};
+use uuid::Uuid;
+
+use super::models::{IncompleteReplicateGroup, MeasurementStatistics, ReplicateGroupStatistics};
+use crate::field_records::services::{as_f64, MEASUREMENT_RULES};
+use crate::{field_records, middleware, parameter_values, parameters};
+
+/// Routes mounted next to the generated CRUD router under `/api/replicate_groups`.
+pub fn router(db: &DatabaseConnection) -> Router {
+    Router::new()
+        .route("/incomplete", get(get_incomplete_replicate_groups))
+        .route("/{id}/statistics", get(get_replicate_group_statistics))
+        .with_state(db.clone())
+}
+
+fn summarise(name: String, unit: Option<String>, values: &[f64]) -> MeasurementStatistics {
+    let n = values.len() as f64;
+    let mean = values.iter().sum::<f64>() / n;
+    let standard_deviation = (values.len() > 1).then(|| {
+        let squares: f64 = values.iter().map(|v| (v - mean).powi(2)).sum();
+        (squares / (n - 1.0)).sqrt()
+    });
+    MeasurementStatistics {
+        name,
+        unit,
+        n: values.len() as u64,
+        mean,
+        standard_deviation,
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/replicate_groups/{id}/statistics",
+    params(("id" = Uuid, Path, description = "Replicate group id")),
+    responses(
+        (status = OK, description = "Per-measurement mean and standard deviation", body = ReplicateGroupStatistics),
+        (status = NOT_FOUND, description = "Replicate group not found")
+    )
+)]
+pub async fn get_replicate_group_statistics(
+    State(db): State<DatabaseConnection>,
+    Path(id): Path<Uuid>,
+    req: Request,
+) -> Result<Json<ReplicateGroupStatistics>, ApiError> {
+    let scope_public = middleware::is_scoped(&req);
+
+    let mut group = super::db::Entity::find_by_id(id);
+    if scope_public {
+        group = group.filter(middleware::replicate_groups_scope());
+    }
+    let Some(group) = group.one(&db).await? else {
+        return Err(ApiError::not_found("replicate_group", Some(id.to_string())));
+    };
+
+    let mut records = field_records::db::Entity::find()
+        .filter(field_records::db::Column::ReplicateGroupId.eq(id));
+    if scope_public {
+        records = records.filter(middleware::field_records_scope());
+    }
+    let records = records.all(&db).await?;
+
+    let mut measurements: Vec<MeasurementStatistics> = MEASUREMENT_RULES
+        .iter()
+        .filter_map(|rule| {
+            let values: Vec<f64> = records
+                .iter()
+                .filter_map(|record| as_f64(record.get(rule.column)))
+                .collect();
+            (!values.is_empty()).then(|| summarise(rule.column.as_str().to_string(), None, &values))
+        })
+        .collect();
+
+    let record_ids: Vec<Uuid> = records.iter().map(|record| record.id).collect();
+    if !record_ids.is_empty() {
+        let values = parameter_values::db::Entity::find()
+            .filter(parameter_values::db::Column::FieldRecordId.is_in(record_ids))
+            .filter(parameter_values::db::Column::ValueNumber.is_not_null())
+            .find_also_related(parameters::db::Entity)
+            .all(&db)
+            .await?;
+
+        // Keyed by name so custom parameters come out in a stable order.
+        let mut per_parameter: BTreeMap<String, (Option<String>, Vec<f64>)> = BTreeMap::new();
+        for (value, parameter) in values {
+            let (Some(number), Some(parameter)) = (value.value_number, parameter) else {
+                continue;
+            };
+            per_parameter
+                .entry(parameter.name)
+                .or_insert_with(|| (parameter.unit, vec![]))
+                .1
+                .push(number);
+        }
+        measurements.extend(
+            per_parameter
+                .into_iter()
+                .map(|(name, (unit, values))| summarise(name, unit, &values)),
+        );
+    }
+
+    Ok(Json(ReplicateGroupStatistics {
+        replicate_group_id: group.id,
+        expected_replicates: group.expected_replicates,
+        field_record_count: records.len() as u64,
+        measurements,
+    }))
+}
+
+/// Field records per replicate group, within `scope` when given.
+async fn field_record_counts(
+    db: &DatabaseConnection,
+    scope: Option<Condition>,
+) -> Result<HashMap<Uuid, i64>, ApiError> {
+    let mut counts = field_records::db::Entity::find()
+        .select_only()
+        .column(field_records::db::Column::ReplicateGroupId)
+        .column_as(field_records::db::Column::Id.count(), "field_record_count")
+        .filter(field_records::db::Column::ReplicateGroupId.is_not_null())
+        .group_by(field_records::db::Column::ReplicateGroupId);
+    if let Some(scope) = scope {
+        counts = counts.filter(scope);
+    }
+    Ok(counts
+        .into_tuple::<(Option<Uuid>, i64)>()
+        .all(db)
+        .await?
+        .into_iter()
+        .filter_map(|(group_id, count)| group_id.map(|id| (id, count)))
+        .collect())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/replicate_groups/incomplete",
+    responses(
+        (status = OK, description = "Groups with fewer field records than expected", body = Vec<IncompleteReplicateGroup>)
+    )
+)]
+pub async fn get_incomplete_replicate_groups(
+    State(db): State<DatabaseConnection>,
+    req: Request,
+) -> Result<Json<Vec<IncompleteReplicateGroup>>, ApiError> {
+    let scope_public = middleware::is_scoped(&req);
+
+    let mut groups = super::db::Entity::find()
+        .order_by_desc(super::db::Column::SamplingDate)
+        .order_by_asc(super::db::Column::Name);
+    if scope_public {
+        groups = groups.filter(middleware::replicate_groups_scope());
+    }
+    let groups = groups.all(&db).await?;
+
+    let counts = field_record_counts(&db, None).await?;
+    // Public callers get counts of public records only. A group that also holds
+    // private records would look incomplete to them, so it is left out.
+    let public_counts = if scope_public {
+        Some(field_record_counts(&db, Some(middleware::field_records_scope())).await?)
+    } else {
+        None
+    };
+
+    let incomplete = groups
+        .into_iter()
+        .filter_map(|group| {
+            let field_record_count = counts.get(&group.id).copied().unwrap_or_default() as u64;
+            if let Some(public_counts) = &public_counts {
+                let public_count = public_counts.get(&group.id).copied().unwrap_or_default();
+                if public_count as u64 != field_record_count {
+                    return None;
+                }
+            }
+            let expected = u64::try_from(group.expected_replicates).unwrap_or_default();
+            (field_record_count < expected).then(|| IncompleteReplicateGroup {
+                missing_replicates: expected - field_record_count,
+                field_record_count,
+                id: group.id,
+                name: group.name,
+                site_id: group.site_id,
+                sampling_date: group.sampling_date,
+                treatment: group.treatment,
+                expected_replicates: group.expected_replicates,
+            })
+        })
+        .collect();
+
+    Ok(Json(incomplete))
+}
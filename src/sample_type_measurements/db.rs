@@ -0,0 +1,74 @@
+use chrono::{DateTime, Utc};
+use crudcrate::validation::{Validatable, ValidationError};
+use crudcrate::{CRUDResource, EntityToModels};
+use sea_orm::entity::prelude::*;
+use uuid::Uuid;
+
+use crate::field_records::services::measurement_rule;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+#[sea_orm(table_name = "sample_type_measurements")]
+#[crudcrate(
+    generate_router,
+    api_struct = "SampleTypeMeasurement",
+    name_singular = "sample_type_measurement",
+    name_plural = "sample_type_measurements",
+    description = "Measurement columns reserved for some habitats: a column listed for any sample type may only be filled on field records of the types listing it",
+    no_eq,
+    derive_partial_eq
+)]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    #[crudcrate(primary_key, filterable, exclude(update, create), on_create = Uuid::new_v4())]
+    pub id: Uuid,
+    #[crudcrate(sortable, filterable)]
+    pub sample_type_id: Uuid,
+    #[crudcrate(sortable, filterable, fulltext)]
+    pub column_name: String,
+    #[crudcrate(sortable, filterable, exclude(create, update), on_create = chrono::Utc::now())]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "crate::sample_types::db::Entity",
+        from = "Column::SampleTypeId",
+        to = "crate::sample_types::db::Column::Id",
+        on_delete = "Cascade"
+    )]
+    SampleType,
+}
+
+impl Related<crate::sample_types::db::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::SampleType.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+fn validate_column_name(column_name: &str) -> Result<(), ValidationError> {
+    if measurement_rule(column_name).is_none() {
+        return Err(ValidationError::new(
+            "column_name",
+            format!("'{column_name}' is not a field record measurement"),
+        ));
+    }
+    Ok(())
+}
+
+impl Validatable for SampleTypeMeasurementCreate {
+    fn validate(&self) -> Result<(), ValidationError> {
+        validate_column_name(&self.column_name)
+    }
+}
+
+impl Validatable for SampleTypeMeasurementUpdate {
+    fn validate(&self) -> Result<(), ValidationError> {
+        if let Some(Some(column_name)) = &self.column_name {
+            validate_column_name(column_name)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,85 @@
+use chrono::{DateTime, Utc};
+use crudcrate::validation::{Validatable, ValidationError};
+use crudcrate::{CRUDResource, EntityToModels};
+use sea_orm::entity::prelude::*;
+use uuid::Uuid;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+#[sea_orm(table_name = "sample_types")]
+#[crudcrate(
+    generate_router,
+    api_struct = "SampleType",
+    name_singular = "sample_type",
+    name_plural = "sample_types",
+    description = "Admin-managed habitat catalogue that field records and parameters refer to by name",
+    no_eq,
+    derive_partial_eq
+)]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    #[crudcrate(primary_key, filterable, exclude(update, create), on_create = Uuid::new_v4())]
+    pub id: Uuid,
+    /// Referenced by `field_records.sample_type`; renames cascade to the records.
+    #[sea_orm(unique)]
+    #[crudcrate(sortable, filterable, fulltext)]
+    pub name: String,
+    #[crudcrate(sortable, filterable, fulltext)]
+    pub description: Option<String>,
+    /// Environment Ontology term, e.g. `ENVO:00001998`.
+    #[crudcrate(sortable, filterable)]
+    pub envo_id: Option<String>,
+    #[crudcrate(sortable, filterable, fulltext)]
+    pub envo_label: Option<String>,
+    #[crudcrate(sortable, filterable, exclude(create, update), on_create = chrono::Utc::now())]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+fn validate_name(name: &str) -> Result<(), ValidationError> {
+    if name.trim().is_empty() || name.trim() != name {
+        return Err(ValidationError::new(
+            "name",
+            "Must not be blank or padded with whitespace",
+        ));
+    }
+    Ok(())
+}
+
+fn validate_envo_id(envo_id: &str) -> Result<(), ValidationError> {
+    let well_formed = envo_id
+        .strip_prefix("ENVO:")
+        .is_some_and(|digits| digits.len() == 8 && digits.chars().all(|c| c.is_ascii_digit()));
+    if !well_formed {
+        return Err(ValidationError::new(
+            "envo_id",
+            "Must be an ENVO CURIE such as ENVO:00001998",
+        ));
+    }
+    Ok(())
+}
+
+impl Validatable for SampleTypeCreate {
+    fn validate(&self) -> Result<(), ValidationError> {
+        validate_name(&self.name)?;
+        if let Some(envo_id) = &self.envo_id {
+            validate_envo_id(envo_id)?;
+        }
+        Ok(())
+    }
+}
+
+impl Validatable for SampleTypeUpdate {
+    fn validate(&self) -> Result<(), ValidationError> {
+        if let Some(Some(name)) = &self.name {
+            validate_name(name)?;
+        }
+        if let Some(Some(envo_id)) = &self.envo_id {
+            validate_envo_id(envo_id)?;
+        }
+        Ok(())
+    }
+}
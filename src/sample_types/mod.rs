@@ -0,0 +1,4 @@
+pub mod db;
+pub mod services;
+#[cfg(test)]
+mod tests;
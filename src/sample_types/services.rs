@@ -0,0 +1,37 @@
+use crudcrate::validation::ValidationError;
+use crudcrate::ApiError;
+use sea_orm::{
+    ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter, QueryOrder,
+};
+
+use super::db::{Column, Entity};
+
+/// Catalogue names in display order.
+pub async fn names(db: &DatabaseConnection) -> Result<Vec<String>, ApiError> {
+    Ok(Entity::find()
+        .order_by_asc(Column::Name)
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|sample_type| sample_type.name)
+        .collect())
+}
+
+pub async fn exists(db: &DatabaseConnection, name: &str) -> Result<bool, ApiError> {
+    Ok(Entity::find()
+        .filter(Column::Name.eq(name))
+        .count(db)
+        .await?
+        > 0)
+}
+
+/// Reject a `sample_type` that is not in the catalogue with a 422 instead of the
+/// foreign key's 409.
+pub async fn check_known(db: &DatabaseConnection, name: &str) -> Result<(), ApiError> {
+    if !exists(db, name).await? {
+        return Err(
+            ValidationError::new("sample_type", format!("Unknown sample type '{name}'")).into(),
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,165 @@
+use axum::http::StatusCode;
+use serde_json::json;
+
+use super::services::names;
+use crate::test_utils::{
+    build_app_with_db, build_scoped_app_with_db, create, create_site, get, send, setup_sqlite_db,
+};
+
+async fn add_cryoconite(app: &axum::Router) -> String {
+    create(
+        app,
+        "/api/sample_types",
+        json!({
+            "name": "Cryoconite", "description": "Sediment in meltwater holes on glacier ice",
+            "envo_id": "ENVO:00000000", "envo_label": "cryoconite"
+        }),
+    )
+    .await
+}
+
+/// Scenario: an admin adds a Cryoconite habitat.
+/// Expected behaviour: malformed ENVO ids are 422, public callers cannot add
+/// habitats, and `/api/config` lists it next to the seeded Snow and Soil.
+#[tokio::test]
+async fn admins_extend_the_catalogue() {
+    let db = setup_sqlite_db().await;
+    let admin = build_app_with_db(db.clone());
+    let scoped = build_scoped_app_with_db(db.clone());
+
+    let (status, _) = send(
+        &admin,
+        "POST",
+        "/api/sample_types",
+        json!({ "name": "Cryoconite", "envo_id": "cryoconite hole" }),
+    )
+    .await;
+    assert_eq!(
+        status,
+        StatusCode::UNPROCESSABLE_ENTITY,
+        "malformed ENVO id"
+    );
+    let (status, _) = send(
+        &scoped,
+        "POST",
+        "/api/sample_types",
+        json!({ "name": "Cryoconite" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    add_cryoconite(&admin).await;
+
+    // `/api/config` reports the same list.
+    assert_eq!(names(&db).await.unwrap(), ["Cryoconite", "Snow", "Soil"]);
+}
+
+/// Scenario: field records are saved as Cryoconite and as an unknown Lava.
+/// Expected behaviour: the catalogued habitat is accepted; Lava is 422 on create
+/// and on update.
+#[tokio::test]
+async fn field_records_take_catalogued_habitats_only() {
+    let db = setup_sqlite_db().await;
+    let admin = build_app_with_db(db);
+    add_cryoconite(&admin).await;
+    let site_id = create_site(&admin, "Glacier A").await;
+
+    let (status, body) = send(
+        &admin,
+        "POST",
+        "/api/field_records",
+        json!({ "name": "FR-LAVA", "site_id": site_id, "sample_type": "Lava", "sampling_date": "2025-07-10" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{body}");
+    let fr = create(
+        &admin,
+        "/api/field_records",
+        json!({ "name": "FR-1", "site_id": site_id, "sample_type": "Cryoconite", "sampling_date": "2025-07-10" }),
+    )
+    .await;
+    let (status, _) = send(
+        &admin,
+        "PUT",
+        &format!("/api/field_records/{fr}"),
+        json!({ "sample_type": "Lava" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+/// Scenario: a parameter and an isolate filter name a habitat.
+/// Expected behaviour: Cryoconite is accepted, Lava is 422 for the parameter and
+/// 400 for the filter.
+#[tokio::test]
+async fn parameters_and_filters_take_catalogued_habitats_only() {
+    let db = setup_sqlite_db().await;
+    let admin = build_app_with_db(db.clone());
+    let scoped = build_scoped_app_with_db(db);
+    add_cryoconite(&admin).await;
+
+    let (status, _) = send(
+        &admin,
+        "POST",
+        "/api/parameters",
+        json!({ "name": "hole_depth", "value_type": "Number", "sample_type": "Lava" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    create(
+        &admin,
+        "/api/parameters",
+        json!({ "name": "hole_depth", "value_type": "Number", "sample_type": "Cryoconite" }),
+    )
+    .await;
+
+    let (status, _) = get(&scoped, "/api/isolates?sample_type=Lava").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = get(&scoped, "/api/isolates?sample_type=Cryoconite").await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+/// Scenario: snow depth is reserved for Snow; an admin also allows it on
+/// Cryoconite.
+/// Expected behaviour: Cryoconite records then take a snow depth while Soil records
+/// still do not; unknown columns are 422 and public callers cannot reserve one.
+#[tokio::test]
+async fn measurements_are_reserved_per_habitat() {
+    let db = setup_sqlite_db().await;
+    let admin = build_app_with_db(db.clone());
+    let scoped = build_scoped_app_with_db(db);
+    let cryoconite = add_cryoconite(&admin).await;
+    let site_id = create_site(&admin, "Glacier A").await;
+    let record = |sample_type: &str| {
+        json!({
+            "name": format!("FR-{sample_type}"), "site_id": site_id, "sample_type": sample_type,
+            "sampling_date": "2025-07-10", "snow_depth_cm": 12.0
+        })
+    };
+
+    let (status, body) = send(&admin, "POST", "/api/field_records", record("Cryoconite")).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{body}");
+
+    for (app, column_name, expected) in [
+        (&admin, "ice_depth_cm", StatusCode::UNPROCESSABLE_ENTITY),
+        (&scoped, "snow_depth_cm", StatusCode::FORBIDDEN),
+        (&admin, "snow_depth_cm", StatusCode::CREATED),
+    ] {
+        let (status, body) = send(
+            app,
+            "POST",
+            "/api/sample_type_measurements",
+            json!({ "sample_type_id": cryoconite, "column_name": column_name }),
+        )
+        .await;
+        assert_eq!(status, expected, "{column_name}: {body}");
+    }
+
+    create(&admin, "/api/field_records", record("Cryoconite")).await;
+    let (status, body) = send(&admin, "POST", "/api/field_records", record("Soil")).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{body}");
+    assert!(
+        body.to_string()
+            .contains("Only applies to Cryoconite, Snow field records"),
+        "{body}"
+    );
+}
@@ -3,7 +3,9 @@ use crudcrate::{CRUDResource, EntityToModels};
 use sea_orm::entity::prelude::*;
 use uuid::Uuid;
 
-#[derive(Clone, Debug, PartialEq, DeriveEntityModel, Eq, EntityToModels)]
+use super::models::SampleIsolate;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
 #[sea_orm(table_name = "samples")]
 #[crudcrate(
     generate_router,
@@ -11,7 +13,15 @@ use uuid::Uuid;
     name_singular = "sample",
     name_plural = "samples",
     description = "Sample collection records from field records with associated DNA",
-    derive_partial_eq
+    no_eq,
+    derive_partial_eq,
+    create::one::pre = crate::samples::services::check_create,
+    create::many::pre = crate::samples::services::check_create_many,
+    update::one::pre = crate::samples::services::check_update,
+    update::many::pre = crate::samples::services::check_update_many,
+    update::one::post = crate::samples::services::propagate_availability,
+    update::many::post = crate::samples::services::propagate_availability_many,
+    read::one::transform = crate::samples::services::with_isolates
 )]
 pub struct Model {
     #[sea_orm(primary_key, auto_increment = false)]
@@ -22,17 +32,35 @@ pub struct Model {
     #[sea_orm(unique)]
     #[crudcrate(sortable, filterable, fulltext)]
     pub name: String,
+    /// Sample this one was aliquoted from; `None` for a sample taken in the field.
+    #[crudcrate(sortable, filterable)]
+    pub parent_sample_id: Option<Uuid>,
+    /// Amount held in this tube. An aliquot's quantity is drawn from its parent's.
+    #[crudcrate(sortable, filterable)]
+    pub quantity: Option<f64>,
+    #[crudcrate(sortable, filterable)]
+    pub quantity_unit: Option<String>,
 
     #[crudcrate(sortable, filterable, on_create = true)]
     pub is_available: bool,
+    /// Free-text location from before `storage_positions`; kept for items that have
+    /// not been placed in a box yet.
     #[crudcrate(sortable, filterable, fulltext, exclude(scoped))]
     pub storage_location: Option<String>,
     #[crudcrate(sortable, filterable, fulltext)]
     pub description: Option<String>,
+    /// Times thawed, counted from `Thawed` custody events.
+    #[crudcrate(sortable, filterable, exclude(create, update), on_create = 0)]
+    pub freeze_thaw_cycles: i32,
     #[crudcrate(filterable, exclude(scoped), on_create = false)]
     pub is_private: bool,
     #[crudcrate(sortable, filterable, exclude(create, update), on_create = chrono::Utc::now())]
     pub created_at: DateTime<Utc>,
+    /// Isolates cultured from this sample. Left out for public callers, who find
+    /// them in the aliquot tree.
+    #[sea_orm(ignore)]
+    #[crudcrate(non_db_attr, exclude(create, update, scoped))]
+    pub isolates: Vec<SampleIsolate>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
@@ -43,6 +71,12 @@ pub enum Relation {
         to = "crate::field_records::db::Column::Id"
     )]
     FieldRecord,
+    #[sea_orm(
+        belongs_to = "Entity",
+        from = "Column::ParentSampleId",
+        to = "Column::Id"
+    )]
+    ParentSample,
 }
 
 impl Related<crate::field_records::db::Entity> for Entity {
@@ -1,3 +1,6 @@
 pub mod db;
+pub mod models;
+pub mod services;
 #[cfg(test)]
 mod tests;
+pub mod views;
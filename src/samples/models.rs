@@ -0,0 +1,27 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// One sample in an aliquot tree. `remaining_quantity` is the sample's quantity
+/// minus what its direct aliquots hold and what has been withdrawn from it.
+#[derive(ToSchema, Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct AliquotNode {
+    pub id: Uuid,
+    pub name: String,
+    pub quantity: Option<f64>,
+    pub quantity_unit: Option<String>,
+    pub remaining_quantity: Option<f64>,
+    pub is_available: bool,
+    /// Isolates cultured from this sample itself, not from its aliquots.
+    pub isolates: Vec<SampleIsolate>,
+    #[schema(no_recursion)]
+    pub aliquots: Vec<AliquotNode>,
+}
+
+/// An isolate cultured from a sample.
+#[derive(ToSchema, Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct SampleIsolate {
+    pub id: Uuid,
+    pub name: String,
+    pub species: Option<String>,
+}
@@ -0,0 +1,330 @@
+use crudcrate::traits::MergeIntoActiveModel;
+use crudcrate::validation::ValidationError;
+use crudcrate::ApiError;
+use sea_orm::entity::prelude::*;
+use sea_orm::sea_query::Expr;
+use sea_orm::{DatabaseConnection, IntoActiveModel, Iterable, QueryOrder, TryIntoModel};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use super::db::{ActiveModel, Column, Entity, Model, Sample, SampleCreate, SampleUpdate};
+use super::models::SampleIsolate;
+use crate::{isolates, middleware, withdrawals};
+
+/// Every sample taken from one field record. Aliquots stay with their parent's
+/// field record, so a whole lineage is always within this set.
+pub async fn field_record_samples(
+    db: &DatabaseConnection,
+    field_record_id: Uuid,
+) -> Result<Vec<Model>, ApiError> {
+    Ok(Entity::find()
+        .filter(Column::FieldRecordId.eq(field_record_id))
+        .all(db)
+        .await?)
+}
+
+pub fn children(samples: &[Model], id: Uuid) -> impl Iterator<Item = &Model> {
+    samples
+        .iter()
+        .filter(move |sample| sample.parent_sample_id == Some(id))
+}
+
+/// Ids below `id` in the lineage, depth first.
+pub fn descendant_ids(samples: &[Model], id: Uuid) -> Vec<Uuid> {
+    let mut ids = vec![];
+    let mut stack = vec![id];
+    while let Some(current) = stack.pop() {
+        for child in children(samples, current) {
+            // Guards against a cycle slipping past validation.
+            if child.id != id && !ids.contains(&child.id) {
+                ids.push(child.id);
+                stack.push(child.id);
+            }
+        }
+    }
+    ids
+}
+
+/// Quantity not yet handed out to aliquots or withdrawn. `None` when the sample's
+/// own quantity is not recorded.
+pub fn remaining_quantity(sample: &Model, samples: &[Model], withdrawn: f64) -> Option<f64> {
+    let aliquoted: f64 = children(samples, sample.id)
+        .filter_map(|child| child.quantity)
+        .sum();
+    sample
+        .quantity
+        .map(|quantity| quantity - aliquoted - withdrawn)
+}
+
+/// Isolates cultured from each of the given samples, by name. `scope_public` leaves
+/// out those hidden from public callers.
+pub async fn isolates_from_samples(
+    db: &DatabaseConnection,
+    sample_ids: Vec<Uuid>,
+    scope_public: bool,
+) -> Result<HashMap<Uuid, Vec<SampleIsolate>>, ApiError> {
+    let mut query = isolates::db::Entity::find()
+        .filter(isolates::db::Column::SampleId.is_in(sample_ids))
+        .order_by_asc(isolates::db::Column::Name);
+    if scope_public {
+        query = query.filter(middleware::isolates_scope());
+    }
+    let mut by_sample: HashMap<Uuid, Vec<SampleIsolate>> = HashMap::new();
+    for isolate in query.all(db).await? {
+        let Some(sample_id) = isolate.sample_id else {
+            continue;
+        };
+        by_sample.entry(sample_id).or_default().push(SampleIsolate {
+            id: isolate.id,
+            name: isolate.name,
+            species: isolate.species,
+        });
+    }
+    Ok(by_sample)
+}
+
+/// Adds the isolates cultured from the sample to its detail response.
+pub async fn with_isolates(
+    db: &DatabaseConnection,
+    mut sample: Sample,
+) -> Result<Sample, ApiError> {
+    sample.isolates = isolates_from_samples(db, vec![sample.id], false)
+        .await?
+        .remove(&sample.id)
+        .unwrap_or_default();
+    Ok(sample)
+}
+
+fn invalid(field: &str, message: String) -> ApiError {
+    ValidationError::new(field, message).into()
+}
+
+/// Checks the link to the parent. `relinked` is false for updates that keep the
+/// existing parent, so editing an aliquot of a since-used-up sample still works.
+async fn check_parent(
+    db: &DatabaseConnection,
+    record: &Model,
+    relinked: bool,
+) -> Result<(), ApiError> {
+    let Some(parent_id) = record.parent_sample_id else {
+        return Ok(());
+    };
+    let Some(parent) = Entity::find_by_id(parent_id).one(db).await? else {
+        return Err(invalid(
+            "parent_sample_id",
+            "Unknown parent sample".to_string(),
+        ));
+    };
+
+    if parent.field_record_id != record.field_record_id {
+        return Err(invalid(
+            "field_record_id",
+            format!("Must match parent sample '{}'", parent.name),
+        ));
+    }
+    let lineage = field_record_samples(db, record.field_record_id).await?;
+    if parent.id == record.id || descendant_ids(&lineage, record.id).contains(&parent.id) {
+        return Err(invalid(
+            "parent_sample_id",
+            "Would make the sample its own ancestor".to_string(),
+        ));
+    }
+    if relinked && !parent.is_available {
+        return Err(invalid(
+            "parent_sample_id",
+            format!("Parent sample '{}' is not available", parent.name),
+        ));
+    }
+
+    let Some(parent_quantity) = parent.quantity else {
+        return Ok(());
+    };
+    let Some(quantity) = record.quantity else {
+        return Err(invalid(
+            "quantity",
+            "Required when the parent sample has a quantity".to_string(),
+        ));
+    };
+    if record.quantity_unit != parent.quantity_unit {
+        return Err(invalid(
+            "quantity_unit",
+            format!("Must match parent sample '{}'", parent.name),
+        ));
+    }
+    let siblings: f64 = children(&lineage, parent.id)
+        .filter(|sibling| sibling.id != record.id)
+        .filter_map(|sibling| sibling.quantity)
+        .sum();
+    let drawn = siblings + withdrawals::services::withdrawn_from_sample(db, parent.id).await?;
+    if drawn + quantity > parent_quantity {
+        return Err(invalid(
+            "quantity",
+            format!(
+                "Exceeds the {} remaining in parent sample '{}'",
+                parent_quantity - drawn,
+                parent.name
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// An existing sample must keep covering the aliquots and withdrawals already drawn
+/// from it.
+async fn check_aliquots(db: &DatabaseConnection, record: &Model) -> Result<(), ApiError> {
+    let withdrawn = withdrawals::services::withdrawn_from_sample(db, record.id).await?;
+    if withdrawn > 0.0 {
+        let existing = Entity::find_by_id(record.id).one(db).await?;
+        if existing.is_some_and(|existing| existing.quantity_unit != record.quantity_unit) {
+            return Err(invalid(
+                "quantity_unit",
+                "Withdrawals are recorded in the current unit".to_string(),
+            ));
+        }
+    }
+    let aliquots = Entity::find()
+        .filter(Column::ParentSampleId.eq(record.id))
+        .all(db)
+        .await?;
+    if aliquots.is_empty() {
+        return check_covers(record, 0.0, withdrawn);
+    }
+
+    if aliquots
+        .iter()
+        .any(|aliquot| aliquot.field_record_id != record.field_record_id)
+    {
+        return Err(invalid(
+            "field_record_id",
+            "Aliquots must stay with their parent's field record".to_string(),
+        ));
+    }
+    let measured: Vec<&Model> = aliquots.iter().filter(|a| a.quantity.is_some()).collect();
+    if measured.is_empty() {
+        return check_covers(record, 0.0, withdrawn);
+    }
+    if measured
+        .iter()
+        .any(|aliquot| aliquot.quantity_unit != record.quantity_unit)
+    {
+        return Err(invalid(
+            "quantity_unit",
+            "Must match the unit of existing aliquots".to_string(),
+        ));
+    }
+    let aliquoted: f64 = measured.iter().filter_map(|aliquot| aliquot.quantity).sum();
+    check_covers(record, aliquoted, withdrawn)
+}
+
+fn check_covers(record: &Model, aliquoted: f64, withdrawn: f64) -> Result<(), ApiError> {
+    if aliquoted == 0.0 && withdrawn == 0.0 {
+        return Ok(());
+    }
+    let Some(quantity) = record.quantity else {
+        return if withdrawn > 0.0 {
+            Err(invalid(
+                "quantity",
+                "Required once material has been withdrawn".to_string(),
+            ))
+        } else {
+            Ok(())
+        };
+    };
+    if quantity < aliquoted + withdrawn {
+        return Err(invalid(
+            "quantity",
+            format!("Must cover the {aliquoted} already aliquoted and {withdrawn} withdrawn"),
+        ));
+    }
+    Ok(())
+}
+
+pub async fn check_create(db: &DatabaseConnection, data: &SampleCreate) -> Result<(), ApiError> {
+    let record = ActiveModel::from(data.clone()).try_into_model()?;
+    check_parent(db, &record, true).await
+}
+
+pub async fn check_create_many(
+    db: &DatabaseConnection,
+    data: &[SampleCreate],
+) -> Result<(), ApiError> {
+    for item in data {
+        check_create(db, item).await?;
+    }
+    Ok(())
+}
+
+pub async fn check_update(
+    db: &DatabaseConnection,
+    id: Uuid,
+    data: &SampleUpdate,
+) -> Result<(), ApiError> {
+    let existing = Entity::find_by_id(id)
+        .one(db)
+        .await?
+        .ok_or_else(|| ApiError::not_found("sample", Some(id.to_string())))?;
+    // Fields absent from the request come back `NotSet`; fill them from the stored row.
+    let mut merged = data
+        .clone()
+        .merge_into_activemodel(existing.clone().into_active_model())?;
+    for column in Column::iter() {
+        if merged.get(column).is_not_set() {
+            merged.set(column, existing.get(column));
+        }
+    }
+    let record = merged.try_into_model()?;
+
+    if record.field_record_id != existing.field_record_id
+        && !isolates_from_samples(db, vec![id], false).await?.is_empty()
+    {
+        return Err(invalid(
+            "field_record_id",
+            "Isolates cultured from the sample must stay with its field record".to_string(),
+        ));
+    }
+    let relinked = record.parent_sample_id != existing.parent_sample_id;
+    check_parent(db, &record, relinked).await?;
+    check_aliquots(db, &record).await
+}
+
+pub async fn check_update_many(
+    db: &DatabaseConnection,
+    updates: &[(Uuid, SampleUpdate)],
+) -> Result<(), ApiError> {
+    for (id, data) in updates {
+        check_update(db, *id, data).await?;
+    }
+    Ok(())
+}
+
+/// A sample marked unavailable takes its aliquots with it (e.g. a contaminated or
+/// discarded lineage). Making it available again leaves them as they are.
+pub async fn propagate_availability(
+    db: &DatabaseConnection,
+    sample: &Sample,
+) -> Result<(), ApiError> {
+    if sample.is_available {
+        return Ok(());
+    }
+    let lineage = field_record_samples(db, sample.field_record_id).await?;
+    let descendants = descendant_ids(&lineage, sample.id);
+    if descendants.is_empty() {
+        return Ok(());
+    }
+    Entity::update_many()
+        .col_expr(Column::IsAvailable, Expr::value(false))
+        .filter(Column::Id.is_in(descendants))
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+pub async fn propagate_availability_many(
+    db: &DatabaseConnection,
+    samples: &[Sample],
+) -> Result<(), ApiError> {
+    for sample in samples {
+        propagate_availability(db, sample).await?;
+    }
+    Ok(())
+}
@@ -2,10 +2,13 @@ use axum::{
     body::{to_bytes, Body},
     http::{Request, StatusCode},
 };
-use serde_json::json;
+use serde_json::{json, Value};
 use tower::ServiceExt;
 
-use crate::test_utils::{build_app_with_db, setup_clean_db};
+use crate::test_utils::{
+    build_app_with_db, build_scoped_app_with_db, create, create_field_record, create_site, get,
+    names, send, setup_clean_db, setup_sqlite_db,
+};
 
 #[tokio::test]
 #[ignore]
@@ -198,3 +201,315 @@ async fn test_samples_invalid_and_duplicate() {
     let response = app.oneshot(request).await.unwrap();
     assert_eq!(response.status(), StatusCode::CONFLICT);
 }
+
+/// Two field records on one public site.
+async fn create_field_records(app: &axum::Router) -> (String, String) {
+    let site_id = create_site(app, "Glacier A").await;
+    (
+        create_field_record(app, &site_id, "FR-1").await,
+        create_field_record(app, &site_id, "FR-2").await,
+    )
+}
+
+/// Scenario: 10 mL of meltwater split into 4 mL and 6 mL aliquots.
+/// Expected behaviour: aliquots cannot overdraw the parent, switch unit or field
+/// record, or loop back on themselves; the tree reports what is left.
+#[tokio::test]
+async fn aliquots_draw_from_parent_quantity() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db);
+    let (fr, other_fr) = create_field_records(&app).await;
+
+    let parent = create(
+        &app,
+        "/api/samples",
+        json!({ "name": "S-1", "field_record_id": fr, "quantity": 10.0, "quantity_unit": "mL" }),
+    )
+    .await;
+    let first = create(
+        &app,
+        "/api/samples",
+        json!({
+            "name": "S-1a", "field_record_id": fr, "parent_sample_id": parent,
+            "quantity": 4.0, "quantity_unit": "mL"
+        }),
+    )
+    .await;
+
+    for (payload, reason) in [
+        (
+            json!({ "quantity": 7.0, "quantity_unit": "mL" }),
+            "overdraws",
+        ),
+        (json!({ "quantity": 6.0, "quantity_unit": "µL" }), "unit"),
+        (json!({}), "no quantity"),
+        (
+            json!({ "quantity": 1.0, "quantity_unit": "mL", "field_record_id": other_fr }),
+            "field record",
+        ),
+    ] {
+        let mut aliquot = json!({
+            "name": "S-1b", "field_record_id": fr, "parent_sample_id": parent
+        });
+        aliquot
+            .as_object_mut()
+            .unwrap()
+            .extend(payload.as_object().unwrap().clone());
+        let (status, body) = send(&app, "POST", "/api/samples", aliquot).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{reason}: {body}");
+    }
+
+    let second = create(
+        &app,
+        "/api/samples",
+        json!({
+            "name": "S-1b", "field_record_id": fr, "parent_sample_id": parent,
+            "quantity": 6.0, "quantity_unit": "mL"
+        }),
+    )
+    .await;
+
+    let (status, _) = send(
+        &app,
+        "PUT",
+        &format!("/api/samples/{parent}"),
+        json!({ "quantity": 5.0 }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "below aliquoted");
+    let (status, _) = send(
+        &app,
+        "PUT",
+        &format!("/api/samples/{parent}"),
+        json!({ "parent_sample_id": first }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "cycle");
+
+    let (status, tree) = get(&app, &format!("/api/samples/{second}/aliquots")).await;
+    assert_eq!(status, StatusCode::OK, "{tree}");
+    assert_eq!(tree["id"], parent, "rooted at the original sample");
+    assert_eq!(tree["remaining_quantity"].as_f64().unwrap(), 0.0);
+    assert_eq!(tree["aliquots"].as_array().unwrap().len(), 2);
+}
+
+/// Scenario: S-1 -> S-1a -> S-1a-i, then S-1a is made private and S-1 unavailable.
+/// Expected behaviour: the public surface loses S-1a and everything below it; the
+/// whole lineage becomes unavailable and can no longer be aliquoted.
+#[tokio::test]
+async fn privacy_and_availability_propagate_down_the_tree() {
+    let db = setup_sqlite_db().await;
+    let admin = build_app_with_db(db.clone());
+    let scoped = build_scoped_app_with_db(db);
+    let (fr, _) = create_field_records(&admin).await;
+
+    let root = create(
+        &admin,
+        "/api/samples",
+        json!({ "name": "S-1", "field_record_id": fr }),
+    )
+    .await;
+    let child = create(
+        &admin,
+        "/api/samples",
+        json!({ "name": "S-1a", "field_record_id": fr, "parent_sample_id": root }),
+    )
+    .await;
+    let grandchild = create(
+        &admin,
+        "/api/samples",
+        json!({ "name": "S-1a-i", "field_record_id": fr, "parent_sample_id": child }),
+    )
+    .await;
+
+    let (status, _) = send(
+        &admin,
+        "PUT",
+        &format!("/api/samples/{child}"),
+        json!({ "is_private": true }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (_, list) = get(&scoped, "/api/samples").await;
+    let names: Vec<&str> = list
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|s| s["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["S-1"]);
+    let (status, _) = get(&scoped, &format!("/api/samples/{grandchild}")).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (_, tree) = get(&scoped, &format!("/api/samples/{root}/aliquots")).await;
+    assert!(tree["aliquots"].as_array().unwrap().is_empty(), "{tree}");
+
+    let (status, _) = send(
+        &admin,
+        "PUT",
+        &format!("/api/samples/{root}"),
+        json!({ "is_available": false }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (_, tree) = get(&admin, &format!("/api/samples/{root}/aliquots")).await;
+    assert_eq!(tree["aliquots"][0]["is_available"], false);
+    assert_eq!(tree["aliquots"][0]["aliquots"][0]["is_available"], false);
+
+    let (status, _) = send(
+        &admin,
+        "POST",
+        "/api/samples",
+        json!({ "name": "S-1b", "field_record_id": fr, "parent_sample_id": root }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+}
+
+/// Sample S-2 with aliquot S-2a on FR-1, and public isolate ISO-S2A and private
+/// isolate ISO-S2A-PRIVATE cultured from the aliquot. Returns the other field record,
+/// the sample, the aliquot and the public isolate.
+async fn cultured_aliquot(app: &axum::Router) -> (String, String, String, String) {
+    let (fr, other_fr) = create_field_records(app).await;
+    let root = create(
+        app,
+        "/api/samples",
+        json!({ "name": "S-2", "field_record_id": fr }),
+    )
+    .await;
+    let aliquot = create(
+        app,
+        "/api/samples",
+        json!({ "name": "S-2a", "field_record_id": fr, "parent_sample_id": root }),
+    )
+    .await;
+    let public = create(
+        app,
+        "/api/isolates",
+        json!({ "name": "ISO-S2A", "field_record_id": fr, "sample_id": aliquot }),
+    )
+    .await;
+    create(
+        app,
+        "/api/isolates",
+        json!({ "name": "ISO-S2A-PRIVATE", "field_record_id": fr, "sample_id": aliquot, "is_private": true }),
+    )
+    .await;
+    (other_fr, root, aliquot, public)
+}
+
+/// Expected behaviour: isolates cannot name a sample from another field record, nor
+/// can the sample move to another field record while isolates come from it.
+#[tokio::test]
+async fn isolates_come_from_a_sample_of_their_field_record() {
+    let db = setup_sqlite_db().await;
+    let admin = build_app_with_db(db);
+    let (other_fr, _, aliquot, public) = cultured_aliquot(&admin).await;
+
+    let (status, _) = send(
+        &admin,
+        "POST",
+        "/api/isolates",
+        json!({ "name": "ISO-ELSEWHERE", "field_record_id": other_fr, "sample_id": aliquot }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    let (status, _) = send(
+        &admin,
+        "PUT",
+        &format!("/api/isolates/{public}"),
+        json!({ "field_record_id": other_fr }),
+    )
+    .await;
+    assert_eq!(
+        status,
+        StatusCode::UNPROCESSABLE_ENTITY,
+        "leaves the sample behind"
+    );
+    let (status, _) = send(
+        &admin,
+        "PUT",
+        &format!("/api/samples/{aliquot}"),
+        json!({ "field_record_id": other_fr, "parent_sample_id": null }),
+    )
+    .await;
+    assert_eq!(
+        status,
+        StatusCode::UNPROCESSABLE_ENTITY,
+        "takes isolates along"
+    );
+}
+
+/// Expected behaviour: the sample detail lists its isolates for admins and the
+/// aliquot tree shows them under S-2a, without the private isolate for public
+/// callers.
+#[tokio::test]
+async fn samples_list_the_isolates_cultured_from_them() {
+    let db = setup_sqlite_db().await;
+    let admin = build_app_with_db(db.clone());
+    let scoped = build_scoped_app_with_db(db);
+    let (_, root, aliquot, _) = cultured_aliquot(&admin).await;
+
+    let (_, detail) = get(&admin, &format!("/api/samples/{aliquot}")).await;
+    assert_eq!(names(&detail["isolates"]), ["ISO-S2A", "ISO-S2A-PRIVATE"]);
+    let (_, tree) = get(&admin, &format!("/api/samples/{aliquot}/aliquots")).await;
+    assert!(tree["isolates"].as_array().unwrap().is_empty());
+    assert_eq!(
+        names(&tree["aliquots"][0]["isolates"]),
+        ["ISO-S2A", "ISO-S2A-PRIVATE"]
+    );
+    let (_, tree) = get(&scoped, &format!("/api/samples/{root}/aliquots")).await;
+    assert_eq!(names(&tree["aliquots"][0]["isolates"]), ["ISO-S2A"]);
+    let (_, detail) = get(&scoped, &format!("/api/samples/{aliquot}")).await;
+    assert_eq!(detail.get("isolates"), None);
+}
+
+/// Scenario: a public isolate cultured from a private sample.
+/// Expected behaviour: public callers see the isolate without its `sample_id`.
+#[tokio::test]
+async fn public_isolates_do_not_expose_their_sample() {
+    let db = setup_sqlite_db().await;
+    let admin = build_app_with_db(db.clone());
+    let scoped = build_scoped_app_with_db(db);
+    let (fr, _) = create_field_records(&admin).await;
+    let sample = create(
+        &admin,
+        "/api/samples",
+        json!({ "name": "S-3", "field_record_id": fr, "is_private": true }),
+    )
+    .await;
+    let isolate = create(
+        &admin,
+        "/api/isolates",
+        json!({ "name": "ISO-S3", "field_record_id": fr, "sample_id": sample }),
+    )
+    .await;
+
+    let (_, detail) = get(&admin, &format!("/api/isolates/{isolate}")).await;
+    assert_eq!(detail["sample_id"], sample);
+    let (status, detail) = get(&scoped, &format!("/api/isolates/{isolate}")).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(detail.get("sample_id"), None, "{detail}");
+    let (_, listed) = get(&scoped, "/api/isolates").await;
+    assert_eq!(names(&listed), ["ISO-S3"]);
+    assert_eq!(listed[0].get("sample_id"), None, "{listed}");
+}
+
+/// Expected behaviour: deleting the sample unlinks its isolates.
+#[tokio::test]
+async fn deleting_a_sample_unlinks_its_isolates() {
+    let db = setup_sqlite_db().await;
+    let admin = build_app_with_db(db);
+    let (_, _, aliquot, public) = cultured_aliquot(&admin).await;
+
+    let (status, _) = send(
+        &admin,
+        "DELETE",
+        &format!("/api/samples/{aliquot}"),
+        Value::Null,
+    )
+    .await;
+    assert!(status.is_success());
+    let (_, isolate) = get(&admin, &format!("/api/isolates/{public}")).await;
+    assert_eq!(isolate["sample_id"], Value::Null);
+}
@@ -0,0 +1,95 @@
+use axum::extract::{Path, Request, State};
+use axum::{routing::get, Json, Router};
+use crudcrate::ApiError;
+use sea_orm::{DatabaseConnection, EntityTrait, QueryFilter};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use super::db::Model;
+use super::models::{AliquotNode, SampleIsolate};
+use super::services::{children, field_record_samples, isolates_from_samples, remaining_quantity};
+use crate::{middleware, withdrawals};
+
+/// Routes mounted next to the generated CRUD router under `/api/samples`.
+pub fn router(db: &DatabaseConnection) -> Router {
+    Router::new()
+        .route("/{id}/aliquots", get(get_aliquot_tree))
+        .with_state(db.clone())
+}
+
+/// Private samples are left out together with everything aliquoted from them.
+fn build_node(
+    sample: &Model,
+    lineage: &[Model],
+    withdrawn: &HashMap<Uuid, f64>,
+    isolates: &HashMap<Uuid, Vec<SampleIsolate>>,
+    scope_public: bool,
+) -> AliquotNode {
+    AliquotNode {
+        id: sample.id,
+        name: sample.name.clone(),
+        quantity: sample.quantity,
+        quantity_unit: sample.quantity_unit.clone(),
+        remaining_quantity: remaining_quantity(
+            sample,
+            lineage,
+            withdrawn.get(&sample.id).copied().unwrap_or_default(),
+        ),
+        is_available: sample.is_available,
+        isolates: isolates.get(&sample.id).cloned().unwrap_or_default(),
+        aliquots: children(lineage, sample.id)
+            .filter(|child| !(scope_public && child.is_private))
+            .map(|child| build_node(child, lineage, withdrawn, isolates, scope_public))
+            .collect(),
+    }
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/samples/{id}/aliquots",
+    params(("id" = Uuid, Path, description = "Any sample in the lineage")),
+    responses(
+        (status = OK, description = "Aliquot tree from the original field sample down, with the isolates cultured from each sample", body = AliquotNode),
+        (status = NOT_FOUND, description = "Sample not found")
+    )
+)]
+pub async fn get_aliquot_tree(
+    State(db): State<DatabaseConnection>,
+    Path(id): Path<Uuid>,
+    req: Request,
+) -> Result<Json<AliquotNode>, ApiError> {
+    let scope_public = middleware::is_scoped(&req);
+
+    let mut sample = super::db::Entity::find_by_id(id);
+    if scope_public {
+        sample = sample.filter(middleware::samples_scope());
+    }
+    let Some(sample) = sample.one(&db).await? else {
+        return Err(ApiError::not_found("sample", Some(id.to_string())));
+    };
+
+    let lineage = field_record_samples(&db, sample.field_record_id).await?;
+    let mut root = &sample;
+    let mut seen = vec![root.id];
+    while let Some(parent) = root
+        .parent_sample_id
+        .and_then(|parent_id| lineage.iter().find(|s| s.id == parent_id))
+    {
+        if seen.contains(&parent.id) {
+            break;
+        }
+        seen.push(parent.id);
+        root = parent;
+    }
+
+    let ids: Vec<Uuid> = lineage.iter().map(|sample| sample.id).collect();
+    let withdrawn = withdrawals::services::withdrawn_from_samples(&db, ids.clone()).await?;
+    let isolates = isolates_from_samples(&db, ids, scope_public).await?;
+    Ok(Json(build_node(
+        root,
+        &lineage,
+        &withdrawn,
+        &isolates,
+        scope_public,
+    )))
+}
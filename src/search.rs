@@ -83,37 +83,53 @@ pub async fn search(
 
     let scope_public = !middleware::is_admin(&req);
     let area_scope = scope_public.then(middleware::areas_scope);
+    let campaign_scope = scope_public.then(middleware::campaigns_scope);
     let site_scope = scope_public.then(middleware::sites_scope);
     let field_record_scope = scope_public.then(middleware::field_records_scope);
     let isolate_scope = scope_public.then(middleware::isolates_scope);
     let sample_scope = scope_public.then(middleware::samples_scope);
     let dna_scope = scope_public.then(middleware::dna_scope);
+    let accession_scope = scope_public
+        .then(|| middleware::isolate_child_scope(culture_accessions::db::Column::IsolateId));
 
-    use crate::{areas, dna, field_records, isolates, samples, sites};
+    use crate::{
+        areas, campaigns, culture_accessions, dna, field_records, isolates, samples, sites,
+    };
 
-    let (areas, sites, field_records, isolates, samples, dna) = tokio::join!(
+    let (areas, campaigns, sites, field_records, isolates, samples, dna, accessions) = tokio::join!(
         search_resource::<areas::db::Area>(&query, backend, &db, area_scope),
+        search_resource::<campaigns::db::Campaign>(&query, backend, &db, campaign_scope),
         search_resource::<sites::db::Site>(&query, backend, &db, site_scope),
         search_resource::<field_records::db::FieldRecord>(&query, backend, &db, field_record_scope),
         search_resource::<isolates::db::Isolate>(&query, backend, &db, isolate_scope),
         search_resource::<samples::db::Sample>(&query, backend, &db, sample_scope),
         search_resource::<dna::db::DNA>(&query, backend, &db, dna_scope),
+        search_resource::<culture_accessions::db::CultureAccession>(
+            &query,
+            backend,
+            &db,
+            accession_scope
+        ),
     );
 
     let total = areas.len()
+        + campaigns.len()
         + sites.len()
         + field_records.len()
         + isolates.len()
         + samples.len()
-        + dna.len();
+        + dna.len()
+        + accessions.len();
 
     let results = HashMap::from([
         ("areas".to_string(), areas),
+        ("campaigns".to_string(), campaigns),
         ("sites".to_string(), sites),
         ("field_records".to_string(), field_records),
         ("isolates".to_string(), isolates),
         ("samples".to_string(), samples),
         ("dna".to_string(), dna),
+        ("culture_accessions".to_string(), accessions),
     ]);
 
     Ok(Json(SearchResponse {
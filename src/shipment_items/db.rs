@@ -0,0 +1,96 @@
+use chrono::{DateTime, Utc};
+use crudcrate::validation::{Validatable, ValidationError};
+use crudcrate::{CRUDResource, EntityToModels};
+use sea_orm::entity::prelude::*;
+use uuid::Uuid;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+#[sea_orm(table_name = "shipment_items")]
+#[crudcrate(
+    generate_router,
+    api_struct = "ShipmentItem",
+    name_singular = "shipment_item",
+    name_plural = "shipment_items",
+    description = "Samples, isolates and DNA extracts packed into a shipment",
+    no_eq,
+    derive_partial_eq,
+    create::one::pre = crate::shipment_items::services::check_create,
+    create::many::pre = crate::shipment_items::services::check_create_many,
+    delete::one::pre = crate::shipment_items::services::check_delete,
+    delete::many::pre = crate::shipment_items::services::check_delete_many
+)]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    #[crudcrate(primary_key, filterable, exclude(update, create), on_create = Uuid::new_v4())]
+    pub id: Uuid,
+    #[crudcrate(sortable, filterable, exclude(update))]
+    pub shipment_id: Uuid,
+    /// Exactly one of the item ids is set. Items are swapped by deleting the entry
+    /// and adding the new one.
+    #[crudcrate(sortable, filterable, exclude(update))]
+    pub sample_id: Option<Uuid>,
+    #[crudcrate(sortable, filterable, exclude(update))]
+    pub isolate_id: Option<Uuid>,
+    #[crudcrate(sortable, filterable, exclude(update))]
+    pub dna_id: Option<Uuid>,
+    #[crudcrate(sortable, filterable, exclude(create, update), on_create = chrono::Utc::now())]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "crate::shipments::db::Entity",
+        from = "Column::ShipmentId",
+        to = "crate::shipments::db::Column::Id"
+    )]
+    Shipment,
+    #[sea_orm(
+        belongs_to = "crate::samples::db::Entity",
+        from = "Column::SampleId",
+        to = "crate::samples::db::Column::Id"
+    )]
+    Sample,
+    #[sea_orm(
+        belongs_to = "crate::isolates::db::Entity",
+        from = "Column::IsolateId",
+        to = "crate::isolates::db::Column::Id"
+    )]
+    Isolate,
+    #[sea_orm(
+        belongs_to = "crate::dna::db::Entity",
+        from = "Column::DnaId",
+        to = "crate::dna::db::Column::Id"
+    )]
+    Dna,
+}
+
+impl Related<crate::shipments::db::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Shipment.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Validatable for ShipmentItemCreate {
+    fn validate(&self) -> Result<(), ValidationError> {
+        let items = [self.sample_id, self.isolate_id, self.dna_id]
+            .iter()
+            .filter(|id| id.is_some())
+            .count();
+        if items != 1 {
+            return Err(ValidationError::new(
+                "sample_id",
+                "Pack exactly one sample, isolate or DNA extract per entry",
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Validatable for ShipmentItemUpdate {
+    fn validate(&self) -> Result<(), ValidationError> {
+        Ok(())
+    }
+}
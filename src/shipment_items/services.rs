@@ -0,0 +1,142 @@
+use crudcrate::validation::ValidationError;
+use crudcrate::ApiError;
+use sea_orm::sea_query::SimpleExpr;
+use sea_orm::{ColumnTrait, ConnectionTrait, DatabaseConnection, DbErr, EntityTrait, QueryFilter};
+use uuid::Uuid;
+
+use super::db::{Column, Entity, ShipmentItemCreate};
+use crate::{samples, shipments, storage_positions};
+
+/// Items can only be packed into or taken out of a shipment that has not left.
+pub async fn check_open(db: &DatabaseConnection, shipment_id: Uuid) -> Result<(), ApiError> {
+    let Some(shipment) = shipments::db::Entity::find_by_id(shipment_id)
+        .one(db)
+        .await?
+    else {
+        return Err(ValidationError::new("shipment_id", "Unknown shipment").into());
+    };
+    if shipment.shipped_at.is_some() {
+        return Err(ApiError::conflict(format!(
+            "Shipment '{}' has already been shipped",
+            shipment.name
+        )));
+    }
+    Ok(())
+}
+
+fn item_condition(
+    sample_id: Option<Uuid>,
+    isolate_id: Option<Uuid>,
+    dna_id: Option<Uuid>,
+) -> Option<SimpleExpr> {
+    match (sample_id, isolate_id, dna_id) {
+        (Some(id), _, _) => Some(Column::SampleId.eq(id)),
+        (_, Some(id), _) => Some(Column::IsolateId.eq(id)),
+        (_, _, Some(id)) => Some(Column::DnaId.eq(id)),
+        _ => None,
+    }
+}
+
+/// Why the item cannot go out with shipment `shipment_id`, if it cannot: a sample
+/// that is reserved or used up, or an item already packed into another shipment
+/// that has not left. Checked when packing and again when shipping, since a request
+/// or withdrawal can take the item in between.
+pub async fn unavailable(
+    db: &impl ConnectionTrait,
+    shipment_id: Uuid,
+    sample_id: Option<Uuid>,
+    isolate_id: Option<Uuid>,
+    dna_id: Option<Uuid>,
+) -> Result<Option<String>, DbErr> {
+    if let Some(id) = sample_id {
+        if let Some(sample) = samples::db::Entity::find_by_id(id).one(db).await? {
+            if !sample.is_available {
+                return Ok(Some(format!("Sample '{}' is not available", sample.name)));
+            }
+        }
+    }
+    let Some(condition) = item_condition(sample_id, isolate_id, dna_id) else {
+        return Ok(None);
+    };
+    let elsewhere = Entity::find()
+        .filter(condition)
+        .filter(Column::ShipmentId.ne(shipment_id))
+        .find_also_related(shipments::db::Entity)
+        .filter(shipments::db::Column::ShippedAt.is_null())
+        .one(db)
+        .await?;
+    Ok(elsewhere
+        .and_then(|(_, shipment)| shipment)
+        .map(|shipment| format!("Already packed in shipment '{}'", shipment.name)))
+}
+
+pub async fn check_create(
+    db: &DatabaseConnection,
+    data: &ShipmentItemCreate,
+) -> Result<(), ApiError> {
+    check_open(db, data.shipment_id).await?;
+    storage_positions::services::check_item_exists(
+        db,
+        data.sample_id,
+        data.isolate_id,
+        data.dna_id,
+    )
+    .await?;
+    let Some(condition) = item_condition(data.sample_id, data.isolate_id, data.dna_id) else {
+        return Ok(());
+    };
+    if let Some(reason) = unavailable(
+        db,
+        data.shipment_id,
+        data.sample_id,
+        data.isolate_id,
+        data.dna_id,
+    )
+    .await?
+    {
+        return Err(ApiError::conflict(reason));
+    }
+    if Entity::find()
+        .filter(Column::ShipmentId.eq(data.shipment_id))
+        .filter(condition)
+        .one(db)
+        .await?
+        .is_some()
+    {
+        return Err(ApiError::conflict("Already packed in this shipment"));
+    }
+    Ok(())
+}
+
+pub async fn check_create_many(
+    db: &DatabaseConnection,
+    data: &[ShipmentItemCreate],
+) -> Result<(), ApiError> {
+    for (i, item) in data.iter().enumerate() {
+        check_create(db, item).await?;
+        let repeated = data[..i].iter().any(|earlier| {
+            earlier.shipment_id == item.shipment_id
+                && earlier.sample_id == item.sample_id
+                && earlier.isolate_id == item.isolate_id
+                && earlier.dna_id == item.dna_id
+        });
+        if repeated {
+            return Err(ApiError::conflict("The same item is packed twice"));
+        }
+    }
+    Ok(())
+}
+
+pub async fn check_delete(db: &DatabaseConnection, id: Uuid) -> Result<(), ApiError> {
+    if let Some(item) = Entity::find_by_id(id).one(db).await? {
+        check_open(db, item.shipment_id).await?;
+    }
+    Ok(())
+}
+
+pub async fn check_delete_many(db: &DatabaseConnection, ids: &[Uuid]) -> Result<(), ApiError> {
+    for id in ids {
+        check_delete(db, *id).await?;
+    }
+    Ok(())
+}
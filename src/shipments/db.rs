@@ -0,0 +1,85 @@
+use chrono::{DateTime, Utc};
+use crudcrate::validation::{Validatable, ValidationError};
+use crudcrate::{CRUDResource, EntityToModels};
+use sea_orm::entity::prelude::*;
+use uuid::Uuid;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+#[sea_orm(table_name = "shipments")]
+#[crudcrate(
+    generate_router,
+    api_struct = "Shipment",
+    name_singular = "shipment",
+    name_plural = "shipments",
+    description = "Outgoing shipments of samples, isolates and DNA to partner labs",
+    no_eq,
+    derive_partial_eq,
+    delete::one::pre = crate::shipments::services::check_delete,
+    delete::many::pre = crate::shipments::services::check_delete_many
+)]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    #[crudcrate(primary_key, filterable, exclude(update, create), on_create = Uuid::new_v4())]
+    pub id: Uuid,
+    /// Reference printed on the manifest, e.g. `SHIP-2025-07`.
+    #[sea_orm(unique)]
+    #[crudcrate(sortable, filterable, fulltext)]
+    pub name: String,
+    /// Receiving lab; becomes the location of the items' `Shipped` custody events.
+    #[crudcrate(sortable, filterable, fulltext)]
+    pub destination: String,
+    pub address: Option<String>,
+    #[crudcrate(sortable, filterable)]
+    pub carrier: Option<String>,
+    #[crudcrate(filterable)]
+    pub tracking_number: Option<String>,
+    #[crudcrate(fulltext)]
+    pub note: Option<String>,
+    /// Set by the ship endpoint; the item list is fixed from then on.
+    #[crudcrate(sortable, filterable, exclude(create, update))]
+    pub shipped_by: Option<String>,
+    #[crudcrate(sortable, filterable, exclude(create, update))]
+    pub shipped_at: Option<DateTime<Utc>>,
+    #[crudcrate(sortable, filterable, exclude(create, update), on_create = chrono::Utc::now())]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(has_many = "crate::shipment_items::db::Entity")]
+    Items,
+}
+
+impl Related<crate::shipment_items::db::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Items.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+fn validate_required(field: &str, value: &str) -> Result<(), ValidationError> {
+    if value.trim().is_empty() {
+        return Err(ValidationError::new(field, "Must not be blank"));
+    }
+    Ok(())
+}
+
+impl Validatable for ShipmentCreate {
+    fn validate(&self) -> Result<(), ValidationError> {
+        validate_required("name", &self.name)?;
+        validate_required("destination", &self.destination)
+    }
+}
+
+impl Validatable for ShipmentUpdate {
+    fn validate(&self) -> Result<(), ValidationError> {
+        if let Some(Some(name)) = &self.name {
+            validate_required("name", name)?;
+        }
+        if let Some(Some(destination)) = &self.destination {
+            validate_required("destination", destination)?;
+        }
+        Ok(())
+    }
+}
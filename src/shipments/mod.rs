@@ -0,0 +1,7 @@
+pub mod db;
+pub mod models;
+pub mod render;
+pub mod services;
+#[cfg(test)]
+mod tests;
+pub mod views;
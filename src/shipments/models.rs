@@ -0,0 +1,74 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use super::db::Shipment;
+
+#[derive(ToSchema, Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum ManifestFormat {
+    #[default]
+    Json,
+    Csv,
+    /// Printable packing list.
+    Pdf,
+}
+
+#[derive(Deserialize, IntoParams, Debug)]
+pub struct ManifestParams {
+    /// `json` (default), `csv` or `pdf`.
+    pub format: Option<ManifestFormat>,
+}
+
+#[derive(ToSchema, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ItemKind {
+    Sample,
+    Isolate,
+    Dna,
+}
+
+impl std::fmt::Display for ItemKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ItemKind::Sample => write!(f, "Sample"),
+            ItemKind::Isolate => write!(f, "Isolate"),
+            ItemKind::Dna => write!(f, "DNA"),
+        }
+    }
+}
+
+/// One line of a manifest. `amount` is a sample's quantity or an extract's volume;
+/// isolates have none.
+#[derive(ToSchema, Serialize, Debug, Clone)]
+pub struct ManifestItem {
+    pub kind: ItemKind,
+    pub id: Uuid,
+    pub name: String,
+    /// Habitat of the origin field record.
+    pub sample_type: String,
+    pub amount: Option<f64>,
+    pub unit: Option<String>,
+    pub field_record: String,
+    pub site: String,
+}
+
+#[derive(ToSchema, Serialize, Debug, Clone)]
+pub struct Manifest {
+    pub shipment: Shipment,
+    /// In the order they were packed.
+    pub items: Vec<ManifestItem>,
+}
+
+#[derive(ToSchema, Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct ShipRequest {
+    /// Recorded as the actor of the items' `Shipped` custody events.
+    pub shipped_by: String,
+    pub tracking_number: Option<String>,
+}
+
+#[derive(ToSchema, Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct AddBoxRequest {
+    /// Box whose stored items are all packed.
+    pub storage_unit_id: Uuid,
+}
@@ -0,0 +1,152 @@
+//! CSV manifest and PDF packing list for a shipment.
+
+use pdf_writer::Content;
+
+use super::db::Shipment;
+use super::models::ManifestItem;
+use crate::common::csv;
+use crate::common::pdf::{self, truncate, AVERAGE_GLYPH_WIDTH, BOLD, POINTS_PER_MM, REGULAR};
+
+const MARGIN_MM: f64 = 10.0;
+const TITLE_PT: f64 = 14.0;
+const TEXT_PT: f64 = 9.0;
+const ROW_MM: f64 = 6.0;
+/// Packed checkbox, then the manifest columns, with their widths in millimetres.
+const COLUMNS: [(&str, f64); 7] = [
+    ("", 6.0),
+    ("Type", 14.0),
+    ("Name", 38.0),
+    ("Sample type", 22.0),
+    ("Amount", 22.0),
+    ("Field record", 40.0),
+    ("Site", 48.0),
+];
+
+fn amount(item: &ManifestItem) -> String {
+    match (item.amount, &item.unit) {
+        (Some(amount), Some(unit)) => format!("{amount} {unit}"),
+        (Some(amount), None) => amount.to_string(),
+        (None, _) => String::new(),
+    }
+}
+
+fn cells(item: &ManifestItem) -> [String; 6] {
+    [
+        item.kind.to_string(),
+        item.name.clone(),
+        item.sample_type.clone(),
+        amount(item),
+        item.field_record.clone(),
+        item.site.clone(),
+    ]
+}
+
+pub fn csv(items: &[ManifestItem]) -> String {
+    let mut out = csv::record(&[
+        "type",
+        "name",
+        "sample_type",
+        "amount",
+        "unit",
+        "field_record",
+        "site",
+        "id",
+    ]);
+    for item in items {
+        let fields = [
+            item.kind.to_string(),
+            item.name.clone(),
+            item.sample_type.clone(),
+            item.amount
+                .map(|amount| amount.to_string())
+                .unwrap_or_default(),
+            item.unit.clone().unwrap_or_default(),
+            item.field_record.clone(),
+            item.site.clone(),
+            item.id.to_string(),
+        ];
+        out.push_str(&csv::record(&fields));
+    }
+    out
+}
+
+fn header_row(page: &mut Content, top: f64) {
+    let mut left = MARGIN_MM;
+    for (title, width) in COLUMNS {
+        pdf::text(page, BOLD, TEXT_PT, left, top, title);
+        left += width;
+    }
+}
+
+/// A4 packing list: the shipment details, then one row per item with a box to tick
+/// when it is packed. The table continues on further pages with its header repeated.
+pub fn pdf(shipment: &Shipment, items: &[ManifestItem]) -> Vec<u8> {
+    let font_mm = TEXT_PT / POINTS_PER_MM;
+    let bottom = pdf::A4_MM.1 - MARGIN_MM;
+    let mut pages = vec![];
+    let mut page = Content::new();
+
+    let mut top = MARGIN_MM + TITLE_PT / POINTS_PER_MM;
+    pdf::text(
+        &mut page,
+        BOLD,
+        TITLE_PT,
+        MARGIN_MM,
+        top,
+        &format!("Packing list {}", shipment.name),
+    );
+    top += ROW_MM + 2.0;
+    let details = [
+        ("Destination", Some(shipment.destination.clone())),
+        ("Address", shipment.address.clone()),
+        ("Carrier", shipment.carrier.clone()),
+        ("Tracking number", shipment.tracking_number.clone()),
+        (
+            "Shipped",
+            shipment.shipped_at.map(|at| {
+                let by = shipment.shipped_by.clone().unwrap_or_default();
+                format!("{} by {by}", at.format("%Y-%m-%d %H:%M UTC"))
+            }),
+        ),
+        ("Items", Some(items.len().to_string())),
+    ];
+    for (label, value) in details {
+        if let Some(value) = value {
+            pdf::text(&mut page, BOLD, TEXT_PT, MARGIN_MM, top, label);
+            // Addresses are entered on several lines but printed on one.
+            let value = value.split_whitespace().collect::<Vec<_>>().join(" ");
+            pdf::text(&mut page, REGULAR, TEXT_PT, MARGIN_MM + 30.0, top, &value);
+            top += ROW_MM;
+        }
+    }
+    top += ROW_MM;
+    header_row(&mut page, top);
+
+    for item in items {
+        top += ROW_MM;
+        if top > bottom {
+            pages.push(std::mem::replace(&mut page, Content::new()));
+            top = MARGIN_MM + font_mm;
+            header_row(&mut page, top);
+            top += ROW_MM;
+        }
+        let (x, y) = pdf::position(MARGIN_MM, top);
+        let side = (font_mm * POINTS_PER_MM) as f32;
+        page.rect(x, y, side, side).stroke();
+        let mut left = MARGIN_MM + COLUMNS[0].1;
+        for (value, (_, width)) in cells(item).iter().zip(&COLUMNS[1..]) {
+            let max_chars = ((width - 1.0) / (font_mm * AVERAGE_GLYPH_WIDTH)) as usize;
+            pdf::text(
+                &mut page,
+                REGULAR,
+                TEXT_PT,
+                left,
+                top,
+                &truncate(value, max_chars),
+            );
+            left += width;
+        }
+    }
+    pages.push(page);
+    pdf::document(pages)
+}
@@ -0,0 +1,353 @@
+use chrono::Utc;
+use crudcrate::validation::ValidationError;
+use crudcrate::ApiError;
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, IntoActiveModel, QueryFilter,
+    QueryOrder, Set, TransactionTrait,
+};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use super::db::{Entity, Model};
+use super::models::{ItemKind, ManifestItem, ShipRequest};
+use crate::common::enums::{CustodyEventKind, StorageUnitKind};
+use crate::shipment_items::db::{self as items, ShipmentItem};
+use crate::{
+    custody_events, dna, field_records, isolates, samples, shipment_items, sites,
+    storage_positions, storage_units,
+};
+
+pub async fn find(db: &DatabaseConnection, id: Uuid) -> Result<Model, ApiError> {
+    Entity::find_by_id(id)
+        .one(db)
+        .await?
+        .ok_or_else(|| ApiError::not_found("shipment", Some(id.to_string())))
+}
+
+async fn packed(db: &DatabaseConnection, shipment_id: Uuid) -> Result<Vec<items::Model>, ApiError> {
+    Ok(items::Entity::find()
+        .filter(items::Column::ShipmentId.eq(shipment_id))
+        .order_by_asc(items::Column::CreatedAt)
+        .all(db)
+        .await?)
+}
+
+/// A shipment that has left stays on record, like the custody events it produced.
+pub async fn check_delete(db: &DatabaseConnection, id: Uuid) -> Result<(), ApiError> {
+    if let Some(shipment) = Entity::find_by_id(id).one(db).await? {
+        if shipment.shipped_at.is_some() {
+            return Err(ApiError::conflict(format!(
+                "Shipment '{}' has been shipped and cannot be deleted",
+                shipment.name
+            )));
+        }
+    }
+    Ok(())
+}
+
+pub async fn check_delete_many(db: &DatabaseConnection, ids: &[Uuid]) -> Result<(), ApiError> {
+    for id in ids {
+        check_delete(db, *id).await?;
+    }
+    Ok(())
+}
+
+/// Manifest lines with each item's origin field record and site.
+pub async fn manifest(
+    db: &DatabaseConnection,
+    shipment_id: Uuid,
+) -> Result<Vec<ManifestItem>, ApiError> {
+    let packed = packed(db, shipment_id).await?;
+    let ids = |pick: fn(&items::Model) -> Option<Uuid>| {
+        packed.iter().filter_map(pick).collect::<Vec<_>>()
+    };
+
+    let samples: HashMap<Uuid, samples::db::Model> = samples::db::Entity::find()
+        .filter(samples::db::Column::Id.is_in(ids(|item| item.sample_id)))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|sample| (sample.id, sample))
+        .collect();
+    let isolates: HashMap<Uuid, isolates::db::Model> = isolates::db::Entity::find()
+        .filter(isolates::db::Column::Id.is_in(ids(|item| item.isolate_id)))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|isolate| (isolate.id, isolate))
+        .collect();
+    let extracts: HashMap<Uuid, dna::db::Model> = dna::db::Entity::find()
+        .filter(dna::db::Column::Id.is_in(ids(|item| item.dna_id)))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|extract| (extract.id, extract))
+        .collect();
+
+    // (kind, id, name, amount, unit, field record) per packed item.
+    let lines: Vec<_> = packed
+        .iter()
+        .filter_map(
+            |item| match (item.sample_id, item.isolate_id, item.dna_id) {
+                (Some(id), _, _) => samples.get(&id).map(|s| {
+                    let unit = s.quantity_unit.clone();
+                    (
+                        ItemKind::Sample,
+                        id,
+                        s.name.clone(),
+                        s.quantity,
+                        unit,
+                        s.field_record_id,
+                    )
+                }),
+                (_, Some(id), _) => isolates.get(&id).map(|i| {
+                    (
+                        ItemKind::Isolate,
+                        id,
+                        i.name.clone(),
+                        None,
+                        None,
+                        i.field_record_id,
+                    )
+                }),
+                (_, _, Some(id)) => extracts.get(&id).map(|d| {
+                    (
+                        ItemKind::Dna,
+                        id,
+                        d.name.clone(),
+                        d.volume,
+                        None,
+                        d.field_record_id,
+                    )
+                }),
+                _ => None,
+            },
+        )
+        .collect();
+
+    let records: HashMap<Uuid, field_records::db::Model> = field_records::db::Entity::find()
+        .filter(
+            field_records::db::Column::Id
+                .is_in(lines.iter().map(|line| line.5).collect::<Vec<_>>()),
+        )
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|record| (record.id, record))
+        .collect();
+    let site_names: HashMap<Uuid, String> = sites::db::Entity::find()
+        .filter(
+            sites::db::Column::Id.is_in(
+                records
+                    .values()
+                    .map(|record| record.site_id)
+                    .collect::<Vec<_>>(),
+            ),
+        )
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|site| (site.id, site.name))
+        .collect();
+
+    Ok(lines
+        .into_iter()
+        .map(|(kind, id, name, amount, unit, field_record_id)| {
+            let record = records.get(&field_record_id);
+            ManifestItem {
+                kind,
+                id,
+                name,
+                sample_type: record.map(|r| r.sample_type.clone()).unwrap_or_default(),
+                amount,
+                unit,
+                field_record: record.map(|r| r.name.clone()).unwrap_or_default(),
+                site: record
+                    .and_then(|r| site_names.get(&r.site_id).cloned())
+                    .unwrap_or_default(),
+            }
+        })
+        .collect())
+}
+
+/// Packs every item stored in a box, skipping those already in the shipment. The
+/// whole box is refused if any other item in it cannot go out.
+pub async fn add_box(
+    db: &DatabaseConnection,
+    shipment_id: Uuid,
+    storage_unit_id: Uuid,
+) -> Result<Vec<ShipmentItem>, ApiError> {
+    find(db, shipment_id).await?;
+    shipment_items::services::check_open(db, shipment_id).await?;
+    let unit = storage_units::db::Entity::find_by_id(storage_unit_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| ApiError::not_found("storage_unit", Some(storage_unit_id.to_string())))?;
+    if unit.kind != StorageUnitKind::Box {
+        return Err(ValidationError::new(
+            "storage_unit_id",
+            format!("'{}' is a {}, not a box", unit.name, unit.kind),
+        )
+        .into());
+    }
+
+    let already = packed(db, shipment_id).await?;
+    let is_packed = |position: &storage_positions::db::Model| {
+        already.iter().any(|item| {
+            (position.sample_id.is_some() && item.sample_id == position.sample_id)
+                || (position.isolate_id.is_some() && item.isolate_id == position.isolate_id)
+                || (position.dna_id.is_some() && item.dna_id == position.dna_id)
+        })
+    };
+
+    let txn = db.begin().await?;
+    let mut added = vec![];
+    for position in storage_positions::db::Entity::find()
+        .filter(storage_positions::db::Column::StorageUnitId.eq(storage_unit_id))
+        .order_by_asc(storage_positions::db::Column::Row)
+        .order_by_asc(storage_positions::db::Column::Column)
+        .all(&txn)
+        .await?
+    {
+        if is_packed(&position) {
+            continue;
+        }
+        if let Some(reason) = shipment_items::services::unavailable(
+            &txn,
+            shipment_id,
+            position.sample_id,
+            position.isolate_id,
+            position.dna_id,
+        )
+        .await?
+        {
+            return Err(ApiError::conflict(reason));
+        }
+        let item = items::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            shipment_id: Set(shipment_id),
+            sample_id: Set(position.sample_id),
+            isolate_id: Set(position.isolate_id),
+            dna_id: Set(position.dna_id),
+            created_at: Set(Utc::now()),
+        }
+        .insert(&txn)
+        .await?;
+        added.push(ShipmentItem::from(item));
+    }
+    txn.commit().await?;
+    Ok(added)
+}
+
+/// Records the shipment against its items: each gets a `Shipped` custody event at
+/// the destination, shipped samples become unavailable, and the items' box slots
+/// are freed.
+pub async fn ship(
+    db: &DatabaseConnection,
+    id: Uuid,
+    request: ShipRequest,
+) -> Result<Model, ApiError> {
+    let shipped_by = request.shipped_by.trim().to_string();
+    if shipped_by.is_empty() {
+        return Err(ValidationError::new("shipped_by", "Must not be blank").into());
+    }
+
+    let txn = db.begin().await?;
+    let shipment = Entity::find_by_id(id)
+        .one(&txn)
+        .await?
+        .ok_or_else(|| ApiError::not_found("shipment", Some(id.to_string())))?;
+    if shipment.shipped_at.is_some() {
+        return Err(ApiError::conflict(format!(
+            "Shipment '{}' has already been shipped",
+            shipment.name
+        )));
+    }
+    let packed = items::Entity::find()
+        .filter(items::Column::ShipmentId.eq(id))
+        .all(&txn)
+        .await?;
+    if packed.is_empty() {
+        return Err(ApiError::conflict("Nothing has been packed"));
+    }
+    for item in &packed {
+        if let Some(reason) = shipment_items::services::unavailable(
+            &txn,
+            id,
+            item.sample_id,
+            item.isolate_id,
+            item.dna_id,
+        )
+        .await?
+        {
+            return Err(ApiError::conflict(reason));
+        }
+    }
+
+    let now = Utc::now();
+    for item in &packed {
+        custody_events::db::ActiveModel {
+            id: Set(Uuid::new_v4()),
+            sample_id: Set(item.sample_id),
+            isolate_id: Set(item.isolate_id),
+            dna_id: Set(item.dna_id),
+            kind: Set(CustodyEventKind::Shipped),
+            actor: Set(shipped_by.clone()),
+            location: Set(shipment.destination.clone()),
+            occurred_at: Set(now),
+            recorded_at: Set(now),
+            note: Set(Some(format!("Shipment {}", shipment.name))),
+        }
+        .insert(&txn)
+        .await?;
+    }
+
+    let sample_ids: Vec<Uuid> = packed.iter().filter_map(|item| item.sample_id).collect();
+    samples::db::Entity::update_many()
+        .col_expr(samples::db::Column::IsAvailable, Expr::value(false))
+        .filter(samples::db::Column::Id.is_in(sample_ids.clone()))
+        .exec(&txn)
+        .await?;
+    let stored: Vec<Uuid> = storage_positions::db::Entity::find()
+        .filter(
+            sea_orm::Condition::any()
+                .add(storage_positions::db::Column::SampleId.is_in(sample_ids))
+                .add(
+                    storage_positions::db::Column::IsolateId.is_in(
+                        packed
+                            .iter()
+                            .filter_map(|item| item.isolate_id)
+                            .collect::<Vec<_>>(),
+                    ),
+                )
+                .add(
+                    storage_positions::db::Column::DnaId.is_in(
+                        packed
+                            .iter()
+                            .filter_map(|item| item.dna_id)
+                            .collect::<Vec<_>>(),
+                    ),
+                ),
+        )
+        .all(&txn)
+        .await?
+        .into_iter()
+        .map(|position| position.id)
+        .collect();
+    storage_positions::db::Entity::delete_many()
+        .filter(storage_positions::db::Column::Id.is_in(stored.clone()))
+        .exec(&txn)
+        .await?;
+    storage_positions::services::record_removals(&txn, &stored).await?;
+
+    let mut updated = shipment.into_active_model();
+    updated.shipped_by = Set(Some(shipped_by));
+    updated.shipped_at = Set(Some(now));
+    if request.tracking_number.is_some() {
+        updated.tracking_number = Set(request.tracking_number);
+    }
+    let updated = updated.update(&txn).await?;
+    txn.commit().await?;
+    Ok(updated)
+}
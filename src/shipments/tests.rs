@@ -0,0 +1,320 @@
+use axum::{
+    body::{to_bytes, Body},
+    http::{Request, StatusCode},
+};
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+use crate::test_utils::{
+    build_app_with_db, build_scoped_app_with_db, create, create_field_record, create_site, get,
+    send, setup_sqlite_db,
+};
+
+/// Status and raw body.
+async fn get_raw(app: &axum::Router, uri: &str) -> (StatusCode, Vec<u8>) {
+    let req = Request::builder()
+        .method("GET")
+        .uri(uri)
+        .body(Body::empty())
+        .unwrap();
+    let resp = app.clone().oneshot(req).await.unwrap();
+    let status = resp.status();
+    let body = to_bytes(resp.into_body(), 1024 * 1024).await.unwrap();
+    (status, body.to_vec())
+}
+
+/// Box `Box A` in `Freezer B / Rack 1`, holding sample S-1 (2 mL) in slot 1/1 and
+/// DNA extract `D-1, pooled` (50 µL) in slot 1/2, both from snow field record FR-1
+/// at Glacier A.
+struct StoredBox {
+    box_id: String,
+    sample: String,
+    extract: String,
+}
+
+async fn stored_box(app: &axum::Router) -> StoredBox {
+    let freezer = create(
+        app,
+        "/api/storage_units",
+        json!({ "kind": "Freezer", "name": "Freezer B" }),
+    )
+    .await;
+    let rack = create(
+        app,
+        "/api/storage_units",
+        json!({ "kind": "Rack", "name": "Rack 1", "parent_id": freezer }),
+    )
+    .await;
+    let box_id = create(
+        app,
+        "/api/storage_units",
+        json!({ "kind": "Box", "name": "Box A", "parent_id": rack, "rows": 2, "columns": 2 }),
+    )
+    .await;
+    let site_id = create_site(app, "Glacier A").await;
+    let fr = create_field_record(app, &site_id, "FR-1").await;
+    let sample = create(
+        app,
+        "/api/samples",
+        json!({ "name": "S-1", "field_record_id": fr, "quantity": 2.0, "quantity_unit": "mL" }),
+    )
+    .await;
+    let extract = create(
+        app,
+        "/api/dna",
+        json!({ "name": "D-1, pooled", "field_record_id": fr, "volume": 50.0 }),
+    )
+    .await;
+    for (column, item) in [(1, ("sample_id", &sample)), (2, ("dna_id", &extract))] {
+        create(
+            app,
+            "/api/storage_positions",
+            json!({ "storage_unit_id": box_id, "row": 1, "column": column, item.0: item.1 }),
+        )
+        .await;
+    }
+    StoredBox {
+        box_id,
+        sample,
+        extract,
+    }
+}
+
+async fn create_shipment(app: &axum::Router, name: &str) -> String {
+    create(
+        app,
+        "/api/shipments",
+        json!({ "name": name, "destination": "Partner Lab Bern", "carrier": "ColdChain" }),
+    )
+    .await
+}
+
+async fn pack_box(app: &axum::Router, shipment: &str, box_id: &str) -> (StatusCode, Value) {
+    send(
+        app,
+        "POST",
+        &format!("/api/shipments/{shipment}/boxes"),
+        json!({ "storage_unit_id": box_id }),
+    )
+    .await
+}
+
+async fn ship(app: &axum::Router, shipment: &str) -> (StatusCode, Value) {
+    send(
+        app,
+        "POST",
+        &format!("/api/shipments/{shipment}/ship"),
+        json!({ "shipped_by": "A. Keller", "tracking_number": "CC-123" }),
+    )
+    .await
+}
+
+/// Scenario: a box holding a sample and a DNA extract is packed twice into one
+/// shipment, and its sample once more on its own.
+/// Expected behaviour: each item is packed once; the manifest lists both with their
+/// origin in JSON, CSV and PDF, for admins only.
+#[tokio::test]
+async fn packed_box_is_listed_in_the_manifest() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db.clone());
+    let scoped = build_scoped_app_with_db(db);
+    let stored = stored_box(&app).await;
+    let shipment = create_shipment(&app, "SHIP-1").await;
+
+    let (status, added) = pack_box(&app, &shipment, &stored.box_id).await;
+    assert_eq!(status, StatusCode::OK, "{added}");
+    assert_eq!(added.as_array().unwrap().len(), 2);
+    let (_, added) = pack_box(&app, &shipment, &stored.box_id).await;
+    assert_eq!(added.as_array().unwrap().len(), 0, "already packed");
+    let (status, _) = send(
+        &app,
+        "POST",
+        "/api/shipment_items",
+        json!({ "shipment_id": shipment, "sample_id": stored.sample }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let manifest = format!("/api/shipments/{shipment}/manifest");
+    let (status, body) = get(&app, &manifest).await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let first = &body["items"][0];
+    assert_eq!(first["kind"], "sample");
+    assert_eq!(first["amount"].as_f64(), Some(2.0));
+    assert_eq!(first["field_record"], "FR-1");
+    assert_eq!(first["site"], "Glacier A");
+    assert_eq!(first["sample_type"], "Snow");
+
+    let (_, csv) = get_raw(&app, &format!("{manifest}?format=csv")).await;
+    let csv = String::from_utf8(csv).unwrap();
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert!(lines[1].starts_with("Sample,S-1,Snow,2,mL,FR-1,Glacier A,"));
+    assert!(lines[2].starts_with("DNA,\"D-1, pooled\",Snow,50,,FR-1,Glacier A,"));
+    let (_, pdf) = get_raw(&app, &format!("{manifest}?format=pdf")).await;
+    assert!(pdf.starts_with(b"%PDF-"));
+    assert!(pdf.windows(20).any(|w| w == b"(Packing list SHIP-1"));
+
+    let (status, _) = get(&scoped, &manifest).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+/// Scenario: a packed box is shipped.
+/// Expected behaviour: shipping marks the sample unavailable, frees the slots, logs
+/// `Shipped` custody events at the destination and freezes the shipment.
+#[tokio::test]
+async fn shipping_records_custody_and_frees_the_slots() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db);
+    let stored = stored_box(&app).await;
+    let shipment = create_shipment(&app, "SHIP-1").await;
+    pack_box(&app, &shipment, &stored.box_id).await;
+
+    let (status, _) = send(
+        &app,
+        "POST",
+        &format!("/api/shipments/{shipment}/ship"),
+        json!({ "shipped_by": " " }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    let (status, shipped) = ship(&app, &shipment).await;
+    assert_eq!(status, StatusCode::OK, "{shipped}");
+    assert!(shipped["shipped_at"].is_string());
+    assert_eq!(shipped["tracking_number"], "CC-123");
+
+    let (_, record) = get(&app, &format!("/api/samples/{}", stored.sample)).await;
+    assert_eq!(record["is_available"], false);
+    let (_, grid) = get(&app, &format!("/api/storage_units/{}/grid", stored.box_id)).await;
+    assert!(
+        grid.to_string().find(&stored.sample).is_none(),
+        "slot freed: {grid}"
+    );
+    let (_, timeline) = get(
+        &app,
+        &format!("/api/custody_events/timeline?dna_id={}", stored.extract),
+    )
+    .await;
+    assert_eq!(timeline["events"][0]["kind"], "Shipped");
+    assert_eq!(timeline["location"], "Partner Lab Bern");
+
+    let (status, _) = ship(&app, &shipment).await;
+    assert_eq!(status, StatusCode::CONFLICT, "shipped twice");
+    let (status, _) = pack_box(&app, &shipment, &stored.box_id).await;
+    assert_eq!(status, StatusCode::CONFLICT, "packing after shipping");
+    let (status, _) = send(
+        &app,
+        "DELETE",
+        &format!("/api/shipments/{shipment}"),
+        Value::Null,
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let empty = create_shipment(&app, "SHIP-2").await;
+    let (status, _) = ship(&app, &empty).await;
+    assert_eq!(status, StatusCode::CONFLICT, "nothing packed");
+}
+
+/// Scenario: the box is packed into SHIP-1, then its items are offered to SHIP-2,
+/// and a sample that is not available is offered to SHIP-3.
+/// Expected behaviour: items waiting in another shipment and unavailable samples
+/// are refused, singly and as part of a box; once SHIP-1 has left, the extract can
+/// go out again.
+#[tokio::test]
+async fn unavailable_and_already_packed_items_are_refused() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db);
+    let stored = stored_box(&app).await;
+    let first = create_shipment(&app, "SHIP-1").await;
+    let second = create_shipment(&app, "SHIP-2").await;
+    pack_box(&app, &first, &stored.box_id).await;
+
+    let (status, body) = send(
+        &app,
+        "POST",
+        "/api/shipment_items",
+        json!({ "shipment_id": second, "dna_id": stored.extract }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT, "{body}");
+    assert!(body.to_string().contains("SHIP-1"), "{body}");
+    let (status, _) = send(
+        &app,
+        "POST",
+        "/api/shipment_items/batch",
+        json!([{ "shipment_id": second, "sample_id": stored.sample }]),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    let (status, _) = pack_box(&app, &second, &stored.box_id).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let site_id = create_site(&app, "Glacier B").await;
+    let fr = create_field_record(&app, &site_id, "FR-2").await;
+    let reserved = create(
+        &app,
+        "/api/samples",
+        json!({ "name": "S-2", "field_record_id": fr }),
+    )
+    .await;
+    let (status, _) = send(
+        &app,
+        "PUT",
+        &format!("/api/samples/{reserved}"),
+        json!({ "is_available": false }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let third = create_shipment(&app, "SHIP-3").await;
+    let (status, body) = send(
+        &app,
+        "POST",
+        "/api/shipment_items",
+        json!({ "shipment_id": third, "sample_id": reserved }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT, "{body}");
+    assert!(body.to_string().contains("not available"), "{body}");
+
+    let (status, _) = ship(&app, &first).await;
+    assert_eq!(status, StatusCode::OK);
+    create(
+        &app,
+        "/api/shipment_items",
+        json!({ "shipment_id": second, "dna_id": stored.extract }),
+    )
+    .await;
+}
+
+/// Scenario: a packed sample becomes unavailable before the shipment leaves.
+/// Expected behaviour: shipping is refused and nothing is recorded against the items.
+#[tokio::test]
+async fn shipping_rechecks_availability() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db);
+    let stored = stored_box(&app).await;
+    let shipment = create_shipment(&app, "SHIP-1").await;
+    pack_box(&app, &shipment, &stored.box_id).await;
+
+    let (status, _) = send(
+        &app,
+        "PUT",
+        &format!("/api/samples/{}", stored.sample),
+        json!({ "is_available": false }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, body) = ship(&app, &shipment).await;
+    assert_eq!(status, StatusCode::CONFLICT, "{body}");
+    assert!(body.to_string().contains("S-1"), "{body}");
+
+    let (_, shipment) = get(&app, &format!("/api/shipments/{shipment}")).await;
+    assert_eq!(shipment["shipped_at"], Value::Null);
+    let (_, timeline) = get(
+        &app,
+        &format!("/api/custody_events/timeline?dna_id={}", stored.extract),
+    )
+    .await;
+    assert_eq!(timeline["events"].as_array().unwrap().len(), 0);
+}
@@ -0,0 +1,98 @@
+use axum::extract::{Path, Query, State};
+use axum::http::header;
+use axum::response::{IntoResponse, Response};
+use axum::{
+    routing::{get, post},
+    Json, Router,
+};
+use crudcrate::ApiError;
+use sea_orm::DatabaseConnection;
+use uuid::Uuid;
+
+use super::db::Shipment;
+use super::models::{AddBoxRequest, Manifest, ManifestFormat, ManifestParams, ShipRequest};
+use super::{render, services};
+use crate::shipment_items::db::ShipmentItem;
+
+/// Routes mounted next to the generated CRUD router under `/api/shipments`.
+pub fn router(db: &DatabaseConnection) -> Router {
+    Router::new()
+        .route("/{id}/manifest", get(get_manifest))
+        .route("/{id}/boxes", post(add_box))
+        .route("/{id}/ship", post(ship))
+        .with_state(db.clone())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/shipments/{id}/manifest",
+    params(("id" = Uuid, Path, description = "Shipment id"), ManifestParams),
+    responses(
+        (status = OK, description = "Manifest as JSON or CSV, or a PDF packing list", body = Manifest),
+        (status = NOT_FOUND, description = "Shipment not found")
+    )
+)]
+pub async fn get_manifest(
+    State(db): State<DatabaseConnection>,
+    Path(id): Path<Uuid>,
+    Query(params): Query<ManifestParams>,
+) -> Result<Response, ApiError> {
+    let shipment = Shipment::from(services::find(&db, id).await?);
+    let items = services::manifest(&db, id).await?;
+
+    Ok(match params.format.unwrap_or_default() {
+        ManifestFormat::Json => Json(Manifest { shipment, items }).into_response(),
+        ManifestFormat::Csv => (
+            [(header::CONTENT_TYPE, "text/csv; charset=utf-8")],
+            render::csv(&items),
+        )
+            .into_response(),
+        ManifestFormat::Pdf => (
+            [(header::CONTENT_TYPE, "application/pdf")],
+            render::pdf(&shipment, &items),
+        )
+            .into_response(),
+    })
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/shipments/{id}/boxes",
+    params(("id" = Uuid, Path, description = "Shipment id")),
+    request_body = AddBoxRequest,
+    responses(
+        (status = OK, description = "Items of the box newly packed into the shipment", body = Vec<ShipmentItem>),
+        (status = NOT_FOUND, description = "Shipment or storage unit not found"),
+        (status = CONFLICT, description = "The shipment has already been shipped, or an item in the box is unavailable or packed elsewhere"),
+        (status = UNPROCESSABLE_ENTITY, description = "The storage unit is not a box")
+    )
+)]
+pub async fn add_box(
+    State(db): State<DatabaseConnection>,
+    Path(id): Path<Uuid>,
+    Json(request): Json<AddBoxRequest>,
+) -> Result<Json<Vec<ShipmentItem>>, ApiError> {
+    Ok(Json(
+        services::add_box(&db, id, request.storage_unit_id).await?,
+    ))
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/shipments/{id}/ship",
+    params(("id" = Uuid, Path, description = "Shipment id")),
+    request_body = ShipRequest,
+    responses(
+        (status = OK, description = "Shipment recorded against its items", body = Shipment),
+        (status = NOT_FOUND, description = "Shipment not found"),
+        (status = CONFLICT, description = "Already shipped, nothing packed, or an item is no longer available"),
+        (status = UNPROCESSABLE_ENTITY, description = "shipped_by is blank")
+    )
+)]
+pub async fn ship(
+    State(db): State<DatabaseConnection>,
+    Path(id): Path<Uuid>,
+    Json(request): Json<ShipRequest>,
+) -> Result<Json<Shipment>, ApiError> {
+    Ok(Json(services::ship(&db, id, request).await?.into()))
+}
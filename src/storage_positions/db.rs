@@ -0,0 +1,111 @@
+use chrono::{DateTime, Utc};
+use crudcrate::validation::{Validatable, ValidationError};
+use crudcrate::{CRUDResource, EntityToModels};
+use sea_orm::entity::prelude::*;
+use uuid::Uuid;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+#[sea_orm(table_name = "storage_positions")]
+#[crudcrate(
+    generate_router,
+    api_struct = "StoragePosition",
+    name_singular = "storage_position",
+    name_plural = "storage_positions",
+    description = "Occupied box slots, each holding one sample, isolate or DNA extract",
+    no_eq,
+    derive_partial_eq,
+    create::one::pre = crate::storage_positions::services::check_create,
+    create::many::pre = crate::storage_positions::services::check_create_many,
+    create::one::post = crate::storage_positions::services::record_placement,
+    create::many::post = crate::storage_positions::services::record_placements,
+    update::one::pre = crate::storage_positions::services::check_update,
+    update::many::pre = crate::storage_positions::services::check_update_many,
+    update::one::post = crate::storage_positions::services::record_placement,
+    update::many::post = crate::storage_positions::services::record_placements,
+    delete::one::post = crate::storage_positions::services::record_removal,
+    delete::many::post = crate::storage_positions::services::record_removals
+)]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    #[crudcrate(primary_key, filterable, exclude(update, create), on_create = Uuid::new_v4())]
+    pub id: Uuid,
+    /// The box; positions are 1-based `row` and `column` within its grid.
+    #[crudcrate(sortable, filterable)]
+    pub storage_unit_id: Uuid,
+    #[crudcrate(sortable, filterable)]
+    pub row: i32,
+    #[crudcrate(sortable, filterable)]
+    pub column: i32,
+    /// Exactly one of the item ids is set. Items are swapped by deleting the
+    /// position and storing the new one.
+    #[crudcrate(sortable, filterable, exclude(update))]
+    pub sample_id: Option<Uuid>,
+    #[crudcrate(sortable, filterable, exclude(update))]
+    pub isolate_id: Option<Uuid>,
+    #[crudcrate(sortable, filterable, exclude(update))]
+    pub dna_id: Option<Uuid>,
+    #[crudcrate(sortable, filterable, exclude(create, update), on_create = chrono::Utc::now())]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "crate::storage_units::db::Entity",
+        from = "Column::StorageUnitId",
+        to = "crate::storage_units::db::Column::Id"
+    )]
+    StorageUnit,
+}
+
+impl Related<crate::storage_units::db::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::StorageUnit.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+pub(crate) fn validate_slot(row: i32, column: i32) -> Result<(), ValidationError> {
+    if row < 1 {
+        return Err(ValidationError::new("row", "Must be at least 1"));
+    }
+    if column < 1 {
+        return Err(ValidationError::new("column", "Must be at least 1"));
+    }
+    Ok(())
+}
+
+pub(crate) fn validate_item(
+    sample_id: Option<Uuid>,
+    isolate_id: Option<Uuid>,
+    dna_id: Option<Uuid>,
+) -> Result<(), ValidationError> {
+    let items = [sample_id, isolate_id, dna_id]
+        .iter()
+        .filter(|id| id.is_some())
+        .count();
+    if items != 1 {
+        return Err(ValidationError::new(
+            "sample_id",
+            "Exactly one of sample_id, isolate_id or dna_id is required",
+        ));
+    }
+    Ok(())
+}
+
+impl Validatable for StoragePositionCreate {
+    fn validate(&self) -> Result<(), ValidationError> {
+        validate_slot(self.row, self.column)?;
+        validate_item(self.sample_id, self.isolate_id, self.dna_id)
+    }
+}
+
+impl Validatable for StoragePositionUpdate {
+    fn validate(&self) -> Result<(), ValidationError> {
+        validate_slot(
+            self.row.flatten().unwrap_or(1),
+            self.column.flatten().unwrap_or(1),
+        )
+    }
+}
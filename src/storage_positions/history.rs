@@ -0,0 +1,27 @@
+use chrono::{DateTime, Utc};
+use sea_orm::entity::prelude::*;
+use uuid::Uuid;
+
+/// A slot an item occupied, from `stored_at` until `removed_at`; open while the item
+/// is still there. Written alongside every change to `storage_positions`.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "storage_position_history")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    /// The position, which may since have been deleted.
+    pub storage_position_id: Uuid,
+    pub storage_unit_id: Uuid,
+    pub row: i32,
+    pub column: i32,
+    pub sample_id: Option<Uuid>,
+    pub isolate_id: Option<Uuid>,
+    pub dna_id: Option<Uuid>,
+    pub stored_at: DateTime<Utc>,
+    pub removed_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
@@ -0,0 +1,5 @@
+pub mod db;
+pub mod history;
+pub mod models;
+pub mod services;
+pub mod views;
@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+
+/// Put one item into a box slot, whether or not it is already stored somewhere.
+#[derive(ToSchema, Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct MoveRequest {
+    pub sample_id: Option<Uuid>,
+    pub isolate_id: Option<Uuid>,
+    pub dna_id: Option<Uuid>,
+    pub storage_unit_id: Uuid,
+    pub row: i32,
+    pub column: i32,
+}
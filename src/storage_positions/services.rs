@@ -0,0 +1,237 @@
+use chrono::Utc;
+use crudcrate::validation::ValidationError;
+use crudcrate::ApiError;
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait,
+    IntoActiveModel, QueryFilter, Set,
+};
+use uuid::Uuid;
+
+use super::db::{
+    Column, Entity, Model, StoragePosition, StoragePositionCreate, StoragePositionUpdate,
+};
+use super::history;
+use crate::common::enums::StorageUnitKind;
+use crate::{dna, isolates, samples, storage_units};
+
+fn invalid(field: &str, message: impl Into<String>) -> ApiError {
+    ValidationError::new(field, message.into()).into()
+}
+
+/// The slot has to be inside a box's grid and empty. `position_id` is the position
+/// being moved, which may keep its own slot.
+pub async fn check_slot(
+    db: &impl ConnectionTrait,
+    storage_unit_id: Uuid,
+    row: i32,
+    column: i32,
+    position_id: Option<Uuid>,
+) -> Result<(), ApiError> {
+    let Some(unit) = storage_units::db::Entity::find_by_id(storage_unit_id)
+        .one(db)
+        .await?
+    else {
+        return Err(invalid("storage_unit_id", "Unknown storage unit"));
+    };
+    if unit.kind != StorageUnitKind::Box {
+        return Err(invalid(
+            "storage_unit_id",
+            format!("Items are stored in boxes, not in a {}", unit.kind),
+        ));
+    }
+    if let Some(rows) = unit.rows.filter(|rows| row > *rows) {
+        return Err(invalid("row", format!("'{}' has {rows} rows", unit.name)));
+    }
+    if let Some(columns) = unit.columns.filter(|columns| column > *columns) {
+        return Err(invalid(
+            "column",
+            format!("'{}' has {columns} columns", unit.name),
+        ));
+    }
+
+    let mut occupant = Entity::find()
+        .filter(Column::StorageUnitId.eq(storage_unit_id))
+        .filter(Column::Row.eq(row))
+        .filter(Column::Column.eq(column));
+    if let Some(position_id) = position_id {
+        occupant = occupant.filter(Column::Id.ne(position_id));
+    }
+    if occupant.one(db).await?.is_some() {
+        return Err(ApiError::conflict(format!(
+            "Row {row}, column {column} of '{}' is already taken",
+            unit.name
+        )));
+    }
+    Ok(())
+}
+
+/// The stored position of whichever item is given.
+pub async fn find_for_item(
+    db: &impl ConnectionTrait,
+    sample_id: Option<Uuid>,
+    isolate_id: Option<Uuid>,
+    dna_id: Option<Uuid>,
+) -> Result<Option<Model>, ApiError> {
+    let condition = match (sample_id, isolate_id, dna_id) {
+        (Some(id), _, _) => Column::SampleId.eq(id),
+        (_, Some(id), _) => Column::IsolateId.eq(id),
+        (_, _, Some(id)) => Column::DnaId.eq(id),
+        _ => return Ok(None),
+    };
+    Ok(Entity::find().filter(condition).one(db).await?)
+}
+
+pub async fn check_item_exists(
+    db: &impl ConnectionTrait,
+    sample_id: Option<Uuid>,
+    isolate_id: Option<Uuid>,
+    dna_id: Option<Uuid>,
+) -> Result<(), ApiError> {
+    let (field, exists) = match (sample_id, isolate_id, dna_id) {
+        (Some(id), _, _) => (
+            "sample_id",
+            samples::db::Entity::find_by_id(id).one(db).await?.is_some(),
+        ),
+        (_, Some(id), _) => (
+            "isolate_id",
+            isolates::db::Entity::find_by_id(id)
+                .one(db)
+                .await?
+                .is_some(),
+        ),
+        (_, _, Some(id)) => (
+            "dna_id",
+            dna::db::Entity::find_by_id(id).one(db).await?.is_some(),
+        ),
+        _ => return Ok(()),
+    };
+    if !exists {
+        return Err(invalid(field, "Unknown item"));
+    }
+    Ok(())
+}
+
+pub async fn check_create(
+    db: &DatabaseConnection,
+    data: &StoragePositionCreate,
+) -> Result<(), ApiError> {
+    check_item_exists(db, data.sample_id, data.isolate_id, data.dna_id).await?;
+    if let Some(position) = find_for_item(db, data.sample_id, data.isolate_id, data.dna_id).await? {
+        return Err(ApiError::conflict(format!(
+            "Already stored in position {}; move it instead",
+            position.id
+        )));
+    }
+    check_slot(db, data.storage_unit_id, data.row, data.column, None).await
+}
+
+pub async fn check_create_many(
+    db: &DatabaseConnection,
+    data: &[StoragePositionCreate],
+) -> Result<(), ApiError> {
+    for item in data {
+        check_create(db, item).await?;
+    }
+    Ok(())
+}
+
+pub async fn check_update(
+    db: &DatabaseConnection,
+    id: Uuid,
+    data: &StoragePositionUpdate,
+) -> Result<(), ApiError> {
+    let existing = Entity::find_by_id(id)
+        .one(db)
+        .await?
+        .ok_or_else(|| ApiError::not_found("storage_position", Some(id.to_string())))?;
+    check_slot(
+        db,
+        data.storage_unit_id
+            .flatten()
+            .unwrap_or(existing.storage_unit_id),
+        data.row.flatten().unwrap_or(existing.row),
+        data.column.flatten().unwrap_or(existing.column),
+        Some(id),
+    )
+    .await
+}
+
+pub async fn check_update_many(
+    db: &DatabaseConnection,
+    updates: &[(Uuid, StoragePositionUpdate)],
+) -> Result<(), ApiError> {
+    for (id, data) in updates {
+        check_update(db, *id, data).await?;
+    }
+    Ok(())
+}
+
+/// Opens a history entry for the position's slot, closing the previous entry if the
+/// position has moved.
+pub async fn record_placement(
+    db: &impl ConnectionTrait,
+    position: &StoragePosition,
+) -> Result<(), ApiError> {
+    let open = history::Entity::find()
+        .filter(history::Column::StoragePositionId.eq(position.id))
+        .filter(history::Column::RemovedAt.is_null())
+        .one(db)
+        .await?;
+    let stored_at = match open {
+        Some(open)
+            if (open.storage_unit_id, open.row, open.column)
+                == (position.storage_unit_id, position.row, position.column) =>
+        {
+            return Ok(());
+        }
+        Some(open) => {
+            let now = Utc::now();
+            let mut open = open.into_active_model();
+            open.removed_at = Set(Some(now));
+            open.update(db).await?;
+            now
+        }
+        None => position.created_at,
+    };
+    history::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        storage_position_id: Set(position.id),
+        storage_unit_id: Set(position.storage_unit_id),
+        row: Set(position.row),
+        column: Set(position.column),
+        sample_id: Set(position.sample_id),
+        isolate_id: Set(position.isolate_id),
+        dna_id: Set(position.dna_id),
+        stored_at: Set(stored_at),
+        removed_at: Set(None),
+    }
+    .insert(db)
+    .await?;
+    Ok(())
+}
+
+pub async fn record_placements(
+    db: &DatabaseConnection,
+    positions: &[StoragePosition],
+) -> Result<(), ApiError> {
+    for position in positions {
+        record_placement(db, position).await?;
+    }
+    Ok(())
+}
+
+/// Closes the history entries of deleted positions.
+pub async fn record_removals(db: &impl ConnectionTrait, ids: &[Uuid]) -> Result<(), ApiError> {
+    history::Entity::update_many()
+        .col_expr(history::Column::RemovedAt, Expr::value(Utc::now()))
+        .filter(history::Column::StoragePositionId.is_in(ids.iter().copied()))
+        .filter(history::Column::RemovedAt.is_null())
+        .exec(db)
+        .await?;
+    Ok(())
+}
+
+pub async fn record_removal(db: &DatabaseConnection, id: Uuid) -> Result<(), ApiError> {
+    record_removals(db, &[id]).await
+}
@@ -0,0 +1,86 @@
+use axum::extract::State;
+use axum::{routing::post, Json, Router};
+use crudcrate::ApiError;
+use sea_orm::{
+    ActiveModelTrait, DatabaseConnection, IntoActiveModel, Set, SqlErr, TransactionTrait,
+};
+use uuid::Uuid;
+
+use super::db::{validate_item, validate_slot, ActiveModel, StoragePosition};
+use super::models::MoveRequest;
+use super::services::{check_item_exists, check_slot, find_for_item, record_placement};
+
+/// Routes mounted next to the generated CRUD router under `/api/storage_positions`.
+pub fn router(db: &DatabaseConnection) -> Router {
+    Router::new()
+        .route("/move", post(move_item))
+        .with_state(db.clone())
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/storage_positions/move",
+    request_body = MoveRequest,
+    responses(
+        (status = OK, description = "The item's position after the move", body = StoragePosition),
+        (status = CONFLICT, description = "Target slot is taken"),
+        (status = UNPROCESSABLE_ENTITY, description = "Unknown item or slot outside a box")
+    )
+)]
+pub async fn move_item(
+    State(db): State<DatabaseConnection>,
+    Json(request): Json<MoveRequest>,
+) -> Result<Json<StoragePosition>, ApiError> {
+    validate_slot(request.row, request.column)?;
+    validate_item(request.sample_id, request.isolate_id, request.dna_id)?;
+
+    // Checking the slot and taking it happen in one transaction, and a concurrent
+    // move that takes the slot first still surfaces as a conflict.
+    let txn = db.begin().await?;
+    check_item_exists(&txn, request.sample_id, request.isolate_id, request.dna_id).await?;
+    let existing =
+        find_for_item(&txn, request.sample_id, request.isolate_id, request.dna_id).await?;
+    check_slot(
+        &txn,
+        request.storage_unit_id,
+        request.row,
+        request.column,
+        existing.as_ref().map(|position| position.id),
+    )
+    .await?;
+
+    let position = match existing {
+        Some(position) => {
+            let mut position = position.into_active_model();
+            position.storage_unit_id = Set(request.storage_unit_id);
+            position.row = Set(request.row);
+            position.column = Set(request.column);
+            position.update(&txn).await
+        }
+        None => {
+            ActiveModel {
+                id: Set(Uuid::new_v4()),
+                storage_unit_id: Set(request.storage_unit_id),
+                row: Set(request.row),
+                column: Set(request.column),
+                sample_id: Set(request.sample_id),
+                isolate_id: Set(request.isolate_id),
+                dna_id: Set(request.dna_id),
+                created_at: Set(chrono::Utc::now()),
+            }
+            .insert(&txn)
+            .await
+        }
+    }
+    .map_err(|err| match err.sql_err() {
+        Some(SqlErr::UniqueConstraintViolation(_)) => ApiError::conflict(format!(
+            "Row {}, column {} is already taken",
+            request.row, request.column
+        )),
+        _ => err.into(),
+    })?;
+    let position = StoragePosition::from(position);
+    record_placement(&txn, &position).await?;
+    txn.commit().await?;
+    Ok(Json(position))
+}
@@ -0,0 +1,140 @@
+use crate::common::enums::StorageUnitKind;
+use chrono::{DateTime, Utc};
+use crudcrate::validation::{Validatable, ValidationError};
+use crudcrate::{CRUDResource, EntityToModels};
+use sea_orm::entity::prelude::*;
+use uuid::Uuid;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+#[sea_orm(table_name = "storage_units")]
+#[crudcrate(
+    generate_router,
+    api_struct = "StorageUnit",
+    name_singular = "storage_unit",
+    name_plural = "storage_units",
+    description = "Freezers, racks and boxes that samples, isolates and DNA are stored in",
+    no_eq,
+    derive_partial_eq,
+    create::one::pre = crate::storage_units::services::check_create,
+    create::many::pre = crate::storage_units::services::check_create_many,
+    update::one::pre = crate::storage_units::services::check_update,
+    update::many::pre = crate::storage_units::services::check_update_many
+)]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    #[crudcrate(primary_key, filterable, exclude(update, create), on_create = Uuid::new_v4())]
+    pub id: Uuid,
+    /// Freezer a rack sits in, or rack a box sits in. `None` for freezers.
+    #[crudcrate(sortable, filterable)]
+    pub parent_id: Option<Uuid>,
+    #[crudcrate(sortable, filterable, exclude(update))]
+    pub kind: StorageUnitKind,
+    #[crudcrate(sortable, filterable, fulltext)]
+    pub name: String,
+    /// Maximum number of racks in a freezer or boxes in a rack.
+    #[crudcrate(sortable, filterable)]
+    pub capacity: Option<i32>,
+    /// Grid size of a box.
+    #[crudcrate(sortable, filterable)]
+    pub rows: Option<i32>,
+    #[crudcrate(sortable, filterable)]
+    pub columns: Option<i32>,
+    /// Readings above this are excursions, e.g. -70 for a -80 °C freezer. Units
+    /// without one use their nearest ancestor's.
+    #[crudcrate(sortable, filterable)]
+    pub max_temperature_celsius: Option<f64>,
+    #[crudcrate(sortable, filterable, fulltext)]
+    pub description: Option<String>,
+    #[crudcrate(sortable, filterable, exclude(create, update), on_create = chrono::Utc::now())]
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(belongs_to = "Entity", from = "Column::ParentId", to = "Column::Id")]
+    Parent,
+    #[sea_orm(has_many = "crate::storage_positions::db::Entity")]
+    StoragePositions,
+}
+
+impl Related<crate::storage_positions::db::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::StoragePositions.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+/// Largest box side; real boxes are at most 10 x 10, so anything past this is a typo.
+pub const MAX_GRID_SIDE: i32 = 100;
+
+fn validate_positive(field: &str, value: Option<i32>) -> Result<(), ValidationError> {
+    if value.is_some_and(|v| v < 1) {
+        return Err(ValidationError::new(field, "Must be at least 1"));
+    }
+    Ok(())
+}
+
+fn validate_grid_side(field: &str, value: Option<i32>) -> Result<(), ValidationError> {
+    validate_positive(field, value)?;
+    if value.is_some_and(|v| v > MAX_GRID_SIDE) {
+        return Err(ValidationError::new(
+            field,
+            format!("Must be at most {MAX_GRID_SIDE}"),
+        ));
+    }
+    Ok(())
+}
+
+/// Boxes are sized by their grid; freezers and racks by how many children they hold.
+fn validate_shape(
+    kind: &StorageUnitKind,
+    capacity: Option<i32>,
+    rows: Option<i32>,
+    columns: Option<i32>,
+) -> Result<(), ValidationError> {
+    validate_positive("capacity", capacity)?;
+    validate_grid_side("rows", rows)?;
+    validate_grid_side("columns", columns)?;
+    match kind {
+        StorageUnitKind::Box => {
+            if rows.is_none() || columns.is_none() {
+                return Err(ValidationError::new(
+                    "rows",
+                    "Boxes need both rows and columns",
+                ));
+            }
+            if capacity.is_some() {
+                return Err(ValidationError::new(
+                    "capacity",
+                    "A box holds rows x columns positions",
+                ));
+            }
+        }
+        _ => {
+            if rows.is_some() || columns.is_some() {
+                return Err(ValidationError::new(
+                    "rows",
+                    format!("Only boxes have a grid, not a {kind}"),
+                ));
+            }
+        }
+    }
+    Ok(())
+}
+
+impl Validatable for StorageUnitCreate {
+    fn validate(&self) -> Result<(), ValidationError> {
+        validate_shape(&self.kind, self.capacity, self.rows, self.columns)
+    }
+}
+
+impl Validatable for StorageUnitUpdate {
+    fn validate(&self) -> Result<(), ValidationError> {
+        // The kind is fixed after creation, so the shape is checked against the
+        // stored row in the update hook.
+        validate_positive("capacity", self.capacity.flatten())?;
+        validate_grid_side("rows", self.rows.flatten())?;
+        validate_grid_side("columns", self.columns.flatten())
+    }
+}
@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+/// One cell of a box grid. The item fields are all `None` for a free slot.
+#[derive(ToSchema, Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct GridSlot {
+    pub row: i32,
+    pub column: i32,
+    pub storage_position_id: Option<Uuid>,
+    pub sample_id: Option<Uuid>,
+    pub isolate_id: Option<Uuid>,
+    pub dna_id: Option<Uuid>,
+    /// Name of the stored sample, isolate or DNA extract.
+    pub item_name: Option<String>,
+}
+
+/// Occupancy of a box, with `slots` in row-major order covering the whole grid.
+#[derive(ToSchema, Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct BoxGrid {
+    pub id: Uuid,
+    pub path: String,
+    pub rows: i32,
+    pub columns: i32,
+    pub occupied: u64,
+    pub free: u64,
+    pub slots: Vec<GridSlot>,
+}
+
+#[derive(ToSchema, Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct FreeSlot {
+    pub storage_unit_id: Uuid,
+    pub path: String,
+    pub row: i32,
+    pub column: i32,
+}
+
+#[derive(Deserialize, IntoParams, Debug)]
+pub struct FreeSlotParams {
+    /// Only search boxes in this freezer, rack or box.
+    pub within: Option<Uuid>,
+    /// Maximum number of slots returned (default 20, at most 500).
+    pub limit: Option<u64>,
+}
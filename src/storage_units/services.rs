@@ -0,0 +1,234 @@
+use crudcrate::validation::ValidationError;
+use crudcrate::ApiError;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, PaginatorTrait, QueryFilter};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+use super::db::{Column, Entity, Model, StorageUnitCreate, StorageUnitUpdate};
+use crate::common::enums::StorageUnitKind;
+use crate::{dna, isolates, samples, storage_positions};
+
+fn invalid(field: &str, message: impl Into<String>) -> ApiError {
+    ValidationError::new(field, message.into()).into()
+}
+
+/// "Freezer B / Rack 2 / Box 7" for any unit, given every unit keyed by id.
+pub fn path(units: &HashMap<Uuid, Model>, id: Uuid) -> String {
+    let mut names = vec![];
+    let mut current = units.get(&id);
+    while let Some(unit) = current {
+        // The kind hierarchy caps the depth at three; the guard only matters if the
+        // table was edited by hand.
+        if names.len() > 3 {
+            break;
+        }
+        names.push(unit.name.as_str());
+        current = unit.parent_id.and_then(|parent_id| units.get(&parent_id));
+    }
+    names.reverse();
+    names.join(" / ")
+}
+
+/// Whether `id` is `ancestor` itself or sits somewhere inside it.
+pub fn is_within(units: &HashMap<Uuid, Model>, mut id: Uuid, ancestor: Uuid) -> bool {
+    for _ in 0..3 {
+        if id == ancestor {
+            return true;
+        }
+        match units.get(&id).and_then(|unit| unit.parent_id) {
+            Some(parent_id) => id = parent_id,
+            None => return false,
+        }
+    }
+    id == ancestor
+}
+
+/// Names of the stored items, keyed by item id. Each item is given as its
+/// `(sample_id, isolate_id, dna_id)`.
+pub async fn item_names(
+    db: &DatabaseConnection,
+    items: impl IntoIterator<Item = (Option<Uuid>, Option<Uuid>, Option<Uuid>)>,
+) -> Result<HashMap<Uuid, String>, ApiError> {
+    let (mut sample_ids, mut isolate_ids, mut dna_ids) = (vec![], vec![], vec![]);
+    for (sample_id, isolate_id, dna_id) in items {
+        sample_ids.extend(sample_id);
+        isolate_ids.extend(isolate_id);
+        dna_ids.extend(dna_id);
+    }
+
+    let mut names = HashMap::new();
+    if !sample_ids.is_empty() {
+        for sample in samples::db::Entity::find()
+            .filter(samples::db::Column::Id.is_in(sample_ids))
+            .all(db)
+            .await?
+        {
+            names.insert(sample.id, sample.name);
+        }
+    }
+    if !isolate_ids.is_empty() {
+        for isolate in isolates::db::Entity::find()
+            .filter(isolates::db::Column::Id.is_in(isolate_ids))
+            .all(db)
+            .await?
+        {
+            names.insert(isolate.id, isolate.name);
+        }
+    }
+    if !dna_ids.is_empty() {
+        for extract in dna::db::Entity::find()
+            .filter(dna::db::Column::Id.is_in(dna_ids))
+            .all(db)
+            .await?
+        {
+            names.insert(extract.id, extract.name);
+        }
+    }
+    Ok(names)
+}
+
+pub async fn all_units(db: &DatabaseConnection) -> Result<HashMap<Uuid, Model>, ApiError> {
+    Ok(Entity::find()
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|unit| (unit.id, unit))
+        .collect())
+}
+
+/// Racks go in freezers and boxes in racks, up to the parent's capacity.
+async fn check_parent(
+    db: &DatabaseConnection,
+    id: Option<Uuid>,
+    kind: &StorageUnitKind,
+    parent_id: Option<Uuid>,
+) -> Result<(), ApiError> {
+    let (expected, parent_id) = match (kind.parent_kind(), parent_id) {
+        (None, None) => return Ok(()),
+        (None, Some(_)) => {
+            return Err(invalid(
+                "parent_id",
+                "Freezers are not stored inside anything",
+            ))
+        }
+        (Some(expected), None) => {
+            return Err(invalid(
+                "parent_id",
+                format!("A {kind} must sit in a {expected}"),
+            ))
+        }
+        (Some(expected), Some(parent_id)) => (expected, parent_id),
+    };
+
+    let Some(parent) = Entity::find_by_id(parent_id).one(db).await? else {
+        return Err(invalid("parent_id", "Unknown storage unit"));
+    };
+    if parent.kind != expected {
+        return Err(invalid(
+            "parent_id",
+            format!("A {kind} must sit in a {expected}, not a {}", parent.kind),
+        ));
+    }
+    if let Some(capacity) = parent.capacity {
+        let mut siblings = Entity::find().filter(Column::ParentId.eq(parent.id));
+        if let Some(id) = id {
+            siblings = siblings.filter(Column::Id.ne(id));
+        }
+        if siblings.count(db).await? >= capacity as u64 {
+            return Err(invalid(
+                "parent_id",
+                format!("'{}' is full ({capacity} places)", parent.name),
+            ));
+        }
+    }
+    Ok(())
+}
+
+pub async fn check_create(
+    db: &DatabaseConnection,
+    data: &StorageUnitCreate,
+) -> Result<(), ApiError> {
+    check_parent(db, None, &data.kind, data.parent_id).await
+}
+
+pub async fn check_create_many(
+    db: &DatabaseConnection,
+    data: &[StorageUnitCreate],
+) -> Result<(), ApiError> {
+    for item in data {
+        check_create(db, item).await?;
+    }
+    Ok(())
+}
+
+/// Moving a unit re-checks its new parent; shrinking one must keep room for what is
+/// already inside.
+pub async fn check_update(
+    db: &DatabaseConnection,
+    id: Uuid,
+    data: &StorageUnitUpdate,
+) -> Result<(), ApiError> {
+    let existing = Entity::find_by_id(id)
+        .one(db)
+        .await?
+        .ok_or_else(|| ApiError::not_found("storage_unit", Some(id.to_string())))?;
+
+    let parent_id = data.parent_id.unwrap_or(existing.parent_id);
+    if parent_id != existing.parent_id {
+        check_parent(db, Some(id), &existing.kind, parent_id).await?;
+    }
+
+    let capacity = data.capacity.unwrap_or(existing.capacity);
+    let rows = data.rows.unwrap_or(existing.rows);
+    let columns = data.columns.unwrap_or(existing.columns);
+    if existing.kind == StorageUnitKind::Box {
+        let (Some(rows), Some(columns)) = (rows, columns) else {
+            return Err(invalid("rows", "Boxes need both rows and columns"));
+        };
+        if capacity.is_some() {
+            return Err(invalid("capacity", "A box holds rows x columns positions"));
+        }
+        let outside = storage_positions::db::Entity::find()
+            .filter(storage_positions::db::Column::StorageUnitId.eq(id))
+            .filter(
+                storage_positions::db::Column::Row
+                    .gt(rows)
+                    .or(storage_positions::db::Column::Column.gt(columns)),
+            )
+            .count(db)
+            .await?;
+        if outside > 0 {
+            return Err(invalid(
+                "rows",
+                format!("{outside} stored item(s) would fall outside a {rows} x {columns} grid"),
+            ));
+        }
+    } else {
+        if rows.is_some() || columns.is_some() {
+            return Err(invalid(
+                "rows",
+                format!("Only boxes have a grid, not a {}", existing.kind),
+            ));
+        }
+        if let Some(capacity) = capacity {
+            let children = Entity::find()
+                .filter(Column::ParentId.eq(id))
+                .count(db)
+                .await?;
+            if children > capacity as u64 {
+                return Err(invalid("capacity", format!("Already holds {children}")));
+            }
+        }
+    }
+    Ok(())
+}
+
+pub async fn check_update_many(
+    db: &DatabaseConnection,
+    updates: &[(Uuid, StorageUnitUpdate)],
+) -> Result<(), ApiError> {
+    for (id, data) in updates {
+        check_update(db, *id, data).await?;
+    }
+    Ok(())
+}
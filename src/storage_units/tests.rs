@@ -0,0 +1,242 @@
+use axum::http::StatusCode;
+use serde_json::json;
+
+use crate::test_utils::{
+    build_app_with_db, build_scoped_app_with_db, create, create_field_record, create_site, get,
+    send, setup_sqlite_db,
+};
+
+#[tokio::test]
+async fn hierarchy_enforces_kinds_and_capacity() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db);
+
+    let freezer = create(
+        &app,
+        "/api/storage_units",
+        json!({ "kind": "Freezer", "name": "Freezer B", "capacity": 1 }),
+    )
+    .await;
+    let rack = create(
+        &app,
+        "/api/storage_units",
+        json!({ "kind": "Rack", "name": "Rack 1", "parent_id": freezer }),
+    )
+    .await;
+
+    for (payload, reason) in [
+        (
+            json!({ "kind": "Rack", "name": "Rack 2", "parent_id": freezer }),
+            "freezer full",
+        ),
+        (
+            json!({ "kind": "Box", "name": "Box 1", "parent_id": freezer, "rows": 9, "columns": 9 }),
+            "box in freezer",
+        ),
+        (
+            json!({ "kind": "Box", "name": "Box 1", "parent_id": rack }),
+            "box without grid",
+        ),
+        (
+            json!({ "kind": "Rack", "name": "Rack 2", "rows": 2, "columns": 2 }),
+            "rack with grid",
+        ),
+        (
+            json!({ "kind": "Freezer", "name": "Freezer C", "parent_id": freezer }),
+            "nested freezer",
+        ),
+        (
+            json!({ "kind": "Box", "name": "Box 1", "parent_id": rack, "rows": 9, "columns": 900 }),
+            "grid too large",
+        ),
+    ] {
+        let (status, body) = send(&app, "POST", "/api/storage_units", payload).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{reason}: {body}");
+    }
+
+    let storage_box = create(
+        &app,
+        "/api/storage_units",
+        json!({ "kind": "Box", "name": "Box 1", "parent_id": rack, "rows": 9, "columns": 9 }),
+    )
+    .await;
+    for (unit, payload) in [
+        (&rack, json!({ "capacity": 0 })),
+        (&storage_box, json!({ "rows": 100_000 })),
+    ] {
+        let (status, _) = send(&app, "PUT", &format!("/api/storage_units/{unit}"), payload).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    }
+}
+
+/// Scenario: a 2 x 2 box and a 1 x 1 box in one rack; a sample, isolate and DNA
+/// extract are stored and moved around.
+/// Expected behaviour: slots hold one item and each item sits in one slot; the grid
+/// and free-slot search reflect every move, and none of it is public.
+#[tokio::test]
+async fn items_are_stored_moved_and_found() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db.clone());
+    let scoped = build_scoped_app_with_db(db);
+
+    let freezer = create(
+        &app,
+        "/api/storage_units",
+        json!({ "kind": "Freezer", "name": "Freezer B" }),
+    )
+    .await;
+    let rack = create(
+        &app,
+        "/api/storage_units",
+        json!({ "kind": "Rack", "name": "Rack 1", "parent_id": freezer }),
+    )
+    .await;
+    let box_a = create(
+        &app,
+        "/api/storage_units",
+        json!({ "kind": "Box", "name": "Box A", "parent_id": rack, "rows": 2, "columns": 2 }),
+    )
+    .await;
+    let box_b = create(
+        &app,
+        "/api/storage_units",
+        json!({ "kind": "Box", "name": "Box B", "parent_id": rack, "rows": 1, "columns": 1 }),
+    )
+    .await;
+
+    let site_id = create_site(&app, "Glacier A").await;
+    let fr = create_field_record(&app, &site_id, "FR-1").await;
+    let sample = create(
+        &app,
+        "/api/samples",
+        json!({ "name": "S-1", "field_record_id": fr }),
+    )
+    .await;
+    let isolate = create(
+        &app,
+        "/api/isolates",
+        json!({ "name": "I-1", "field_record_id": fr }),
+    )
+    .await;
+    let dna = create(
+        &app,
+        "/api/dna",
+        json!({ "name": "D-1", "field_record_id": fr }),
+    )
+    .await;
+
+    create(
+        &app,
+        "/api/storage_positions",
+        json!({ "storage_unit_id": box_a, "row": 1, "column": 1, "sample_id": sample }),
+    )
+    .await;
+    for (payload, expected, reason) in [
+        (
+            json!({ "storage_unit_id": box_a, "row": 1, "column": 2, "sample_id": sample }),
+            StatusCode::CONFLICT,
+            "sample already stored",
+        ),
+        (
+            json!({ "storage_unit_id": box_a, "row": 1, "column": 1, "isolate_id": isolate }),
+            StatusCode::CONFLICT,
+            "slot taken",
+        ),
+        (
+            json!({ "storage_unit_id": box_a, "row": 3, "column": 1, "isolate_id": isolate }),
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "outside grid",
+        ),
+        (
+            json!({ "storage_unit_id": rack, "row": 1, "column": 1, "isolate_id": isolate }),
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "not a box",
+        ),
+        (
+            json!({ "storage_unit_id": box_a, "row": 1, "column": 2, "isolate_id": isolate, "dna_id": dna }),
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "two items",
+        ),
+    ] {
+        let (status, body) = send(&app, "POST", "/api/storage_positions", payload).await;
+        assert_eq!(status, expected, "{reason}: {body}");
+    }
+    create(
+        &app,
+        "/api/storage_positions",
+        json!({ "storage_unit_id": box_a, "row": 2, "column": 2, "dna_id": dna }),
+    )
+    .await;
+
+    let (status, grid) = get(&app, &format!("/api/storage_units/{box_a}/grid")).await;
+    assert_eq!(status, StatusCode::OK, "{grid}");
+    assert_eq!(grid["path"], "Freezer B / Rack 1 / Box A");
+    assert_eq!(grid["occupied"], 2);
+    assert_eq!(grid["slots"].as_array().unwrap().len(), 4);
+    assert_eq!(grid["slots"][0]["item_name"], "S-1");
+    assert_eq!(grid["slots"][3]["item_name"], "D-1");
+
+    let (status, free) = get(
+        &app,
+        &format!("/api/storage_units/free_slots?within={rack}"),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{free}");
+    let free: Vec<(&str, i64, i64)> = free
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|slot| {
+            (
+                slot["path"].as_str().unwrap(),
+                slot["row"].as_i64().unwrap(),
+                slot["column"].as_i64().unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        free,
+        [
+            ("Freezer B / Rack 1 / Box A", 1, 2),
+            ("Freezer B / Rack 1 / Box A", 2, 1),
+            ("Freezer B / Rack 1 / Box B", 1, 1),
+        ]
+    );
+
+    let (status, body) = send(
+        &app,
+        "POST",
+        "/api/storage_positions/move",
+        json!({ "sample_id": sample, "storage_unit_id": box_b, "row": 1, "column": 1 }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    let (status, _) = send(
+        &app,
+        "POST",
+        "/api/storage_positions/move",
+        json!({ "isolate_id": isolate, "storage_unit_id": box_b, "row": 1, "column": 1 }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    let (_, grid) = get(&app, &format!("/api/storage_units/{box_a}/grid")).await;
+    assert_eq!(grid["occupied"], 1);
+
+    let (status, _) = send(
+        &app,
+        "PUT",
+        &format!("/api/storage_units/{box_a}"),
+        json!({ "rows": 1, "columns": 1 }),
+    )
+    .await;
+    assert_eq!(
+        status,
+        StatusCode::UNPROCESSABLE_ENTITY,
+        "D-1 would fall outside"
+    );
+
+    let (status, _) = get(&scoped, "/api/storage_units").await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = get(&scoped, &format!("/api/storage_units/{box_a}/grid")).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
@@ -0,0 +1,181 @@
+use axum::extract::{Path, Query, State};
+use axum::{routing::get, Json, Router};
+use crudcrate::ApiError;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+use super::db::MAX_GRID_SIDE;
+use super::models::{BoxGrid, FreeSlot, FreeSlotParams, GridSlot};
+use super::services::{all_units, is_within, item_names, path};
+use crate::common::enums::StorageUnitKind;
+use crate::storage_positions;
+
+const DEFAULT_FREE_SLOTS: u64 = 20;
+const MAX_FREE_SLOTS: u64 = 500;
+
+/// Routes mounted next to the generated CRUD router under `/api/storage_units`.
+pub fn router(db: &DatabaseConnection) -> Router {
+    Router::new()
+        .route("/free_slots", get(get_free_slots))
+        .route("/{id}/grid", get(get_box_grid))
+        .with_state(db.clone())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/storage_units/{id}/grid",
+    params(("id" = Uuid, Path, description = "Box id")),
+    responses(
+        (status = OK, description = "Every slot of the box and what it holds", body = BoxGrid),
+        (status = BAD_REQUEST, description = "Storage unit is not a box"),
+        (status = NOT_FOUND, description = "Storage unit not found")
+    )
+)]
+pub async fn get_box_grid(
+    State(db): State<DatabaseConnection>,
+    Path(id): Path<Uuid>,
+) -> Result<Json<BoxGrid>, ApiError> {
+    let units = all_units(&db).await?;
+    let Some(unit) = units.get(&id) else {
+        return Err(ApiError::not_found("storage_unit", Some(id.to_string())));
+    };
+    let (StorageUnitKind::Box, Some(rows), Some(columns)) = (&unit.kind, unit.rows, unit.columns)
+    else {
+        return Err(ApiError::bad_request(format!(
+            "'{}' is a {}, not a box",
+            unit.name, unit.kind
+        )));
+    };
+
+    // Sides are capped on save; rows written around the API still must not size
+    // the allocation below.
+    let Some(size) = rows
+        .checked_mul(columns)
+        .and_then(|size| usize::try_from(size).ok())
+        .filter(|&size| size <= (MAX_GRID_SIDE * MAX_GRID_SIDE) as usize)
+    else {
+        return Err(ApiError::internal(
+            format!("'{}' has an invalid {rows} x {columns} grid", unit.name),
+            None,
+        ));
+    };
+
+    let positions = storage_positions::db::Entity::find()
+        .filter(storage_positions::db::Column::StorageUnitId.eq(id))
+        .all(&db)
+        .await?;
+    let names = item_names(
+        &db,
+        positions
+            .iter()
+            .map(|p| (p.sample_id, p.isolate_id, p.dna_id)),
+    )
+    .await?;
+    let by_slot: HashMap<(i32, i32), &storage_positions::db::Model> = positions
+        .iter()
+        .map(|position| ((position.row, position.column), position))
+        .collect();
+
+    let mut slots = Vec::with_capacity(size);
+    for row in 1..=rows {
+        for column in 1..=columns {
+            let position = by_slot.get(&(row, column));
+            slots.push(GridSlot {
+                row,
+                column,
+                storage_position_id: position.map(|p| p.id),
+                sample_id: position.and_then(|p| p.sample_id),
+                isolate_id: position.and_then(|p| p.isolate_id),
+                dna_id: position.and_then(|p| p.dna_id),
+                item_name: position
+                    .and_then(|p| p.sample_id.or(p.isolate_id).or(p.dna_id))
+                    .and_then(|item_id| names.get(&item_id).cloned()),
+            });
+        }
+    }
+
+    let occupied = positions.len() as u64;
+    Ok(Json(BoxGrid {
+        id,
+        path: path(&units, id),
+        rows,
+        columns,
+        occupied,
+        free: size as u64 - occupied.min(size as u64),
+        slots,
+    }))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/storage_units/free_slots",
+    params(FreeSlotParams),
+    responses(
+        (status = OK, description = "Empty box slots, box by box in path order", body = Vec<FreeSlot>),
+        (status = NOT_FOUND, description = "`within` storage unit not found")
+    )
+)]
+pub async fn get_free_slots(
+    State(db): State<DatabaseConnection>,
+    Query(params): Query<FreeSlotParams>,
+) -> Result<Json<Vec<FreeSlot>>, ApiError> {
+    let limit = params
+        .limit
+        .unwrap_or(DEFAULT_FREE_SLOTS)
+        .min(MAX_FREE_SLOTS) as usize;
+    let units = all_units(&db).await?;
+    if let Some(within) = params.within {
+        if !units.contains_key(&within) {
+            return Err(ApiError::not_found(
+                "storage_unit",
+                Some(within.to_string()),
+            ));
+        }
+    }
+
+    let in_scope = |id: Uuid| {
+        params
+            .within
+            .is_none_or(|within| is_within(&units, id, within))
+    };
+    let mut boxes: Vec<(String, &super::db::Model)> = units
+        .values()
+        .filter(|unit| unit.kind == StorageUnitKind::Box && in_scope(unit.id))
+        .map(|unit| (path(&units, unit.id), unit))
+        .collect();
+    boxes.sort_by(|a, b| a.0.cmp(&b.0));
+
+    let box_ids: Vec<Uuid> = boxes.iter().map(|(_, unit)| unit.id).collect();
+    let occupied: HashSet<(Uuid, i32, i32)> = if box_ids.is_empty() {
+        HashSet::new()
+    } else {
+        storage_positions::db::Entity::find()
+            .filter(storage_positions::db::Column::StorageUnitId.is_in(box_ids))
+            .all(&db)
+            .await?
+            .into_iter()
+            .map(|p| (p.storage_unit_id, p.row, p.column))
+            .collect()
+    };
+
+    let mut free = vec![];
+    'boxes: for (box_path, unit) in boxes {
+        for row in 1..=unit.rows.unwrap_or_default() {
+            for column in 1..=unit.columns.unwrap_or_default() {
+                if free.len() >= limit {
+                    break 'boxes;
+                }
+                if !occupied.contains(&(unit.id, row, column)) {
+                    free.push(FreeSlot {
+                        storage_unit_id: unit.id,
+                        path: box_path.clone(),
+                        row,
+                        column,
+                    });
+                }
+            }
+        }
+    }
+    Ok(Json(free))
+}
@@ -0,0 +1,41 @@
+use crate::common::enums::TaxonomySource;
+use crudcrate::{CRUDResource, EntityToModels};
+use sea_orm::entity::prelude::*;
+use uuid::Uuid;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+#[sea_orm(table_name = "taxa")]
+#[crudcrate(
+    generate_router,
+    api_struct = "Taxon",
+    name_singular = "taxon",
+    name_plural = "taxa",
+    description = "Names of a reference taxonomy, loaded from an NCBI taxdump or a GTDB taxonomy file",
+    no_eq,
+    derive_partial_eq
+)]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    #[crudcrate(primary_key, filterable, exclude(update, create), on_create = Uuid::new_v4())]
+    pub id: Uuid,
+    #[crudcrate(sortable, filterable)]
+    pub source: TaxonomySource,
+    /// NCBI tax id, or the rank-prefixed GTDB name.
+    #[crudcrate(sortable, filterable)]
+    pub taxon_id: String,
+    #[crudcrate(sortable, filterable, fulltext)]
+    pub name: String,
+    /// Rank as the source writes it, e.g. `genus` or `no rank`.
+    #[crudcrate(sortable, filterable)]
+    pub rank: String,
+    #[crudcrate(filterable)]
+    pub parent_taxon_id: Option<String>,
+    /// Another name of the taxon whose accepted name has the same `taxon_id`.
+    #[crudcrate(filterable)]
+    pub is_synonym: bool,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
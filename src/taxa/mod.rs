@@ -0,0 +1,8 @@
+pub mod db;
+pub mod models;
+pub mod parse;
+pub mod services;
+pub mod staging;
+#[cfg(test)]
+mod tests;
+pub mod views;
@@ -0,0 +1,49 @@
+use crate::common::enums::TaxonomySource;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+#[derive(Deserialize, IntoParams, Debug)]
+pub struct ImportParams {
+    /// `NCBI` for a taxdump sent as `nodes` and `names` fields, `GTDB` for a
+    /// taxonomy file sent as a `taxonomy` field.
+    pub source: TaxonomySource,
+}
+
+#[derive(ToSchema, Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct ImportSummary {
+    pub source: TaxonomySource,
+    /// Accepted names stored; they replace the previous load of the source.
+    pub taxa: u64,
+    pub synonyms: u64,
+}
+
+#[derive(Deserialize, IntoParams, Debug)]
+pub struct SuggestionParams {
+    /// A possibly misspelt taxon name.
+    pub name: String,
+}
+
+#[derive(ToSchema, Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnresolvedReason {
+    /// The name matches no taxon.
+    NotFound,
+    /// The taxon the name resolved to is no longer in the taxonomy.
+    Removed,
+    /// The taxon is still there under another accepted name.
+    Renamed,
+}
+
+/// An isolate whose name does not resolve against the loaded taxonomies.
+#[derive(ToSchema, Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct UnresolvedIsolate {
+    pub isolate_id: Uuid,
+    pub isolate_name: String,
+    /// The lowest rank of the isolate's lineage, or its taxonomy.
+    pub taxon_name: String,
+    pub taxon_source: Option<TaxonomySource>,
+    pub taxon_id: Option<String>,
+    pub reason: UnresolvedReason,
+    /// The current accepted name when renamed, otherwise close spellings.
+    pub suggestions: Vec<String>,
+}
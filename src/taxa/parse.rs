@@ -0,0 +1,191 @@
+//! Readers for reference taxonomy files.
+//!
+//! An NCBI taxdump is read from its `nodes.dmp` and `names.dmp`, whose fields are
+//! separated by `\t|\t`. A GTDB taxonomy file (`bac120_taxonomy.tsv`,
+//! `ar53_taxonomy.tsv`) holds one rank-prefixed lineage per genome; every prefix of
+//! a lineage becomes a taxon named by its prefixed name. Files are read one line at
+//! a time as the upload streams in.
+
+use crudcrate::ApiError;
+use std::collections::{HashMap, HashSet};
+
+/// NCBI name classes kept as synonyms of the scientific name.
+const NCBI_SYNONYM_CLASSES: [&str; 3] = ["synonym", "equivalent name", "genbank synonym"];
+
+/// GTDB prefixes and the ranks they stand for, from the top down.
+const GTDB_RANKS: [(&str, &str); 7] = [
+    ("d", "domain"),
+    ("p", "phylum"),
+    ("c", "class"),
+    ("o", "order"),
+    ("f", "family"),
+    ("g", "genus"),
+    ("s", "species"),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    pub taxon_id: String,
+    pub name: String,
+    pub rank: String,
+    pub parent_taxon_id: Option<String>,
+    pub is_synonym: bool,
+}
+
+/// Fields of a `.dmp` line, without the trailing `\t|`.
+fn dmp_fields(line: &str) -> Vec<&str> {
+    line.trim_end_matches(['\n', '\r'])
+        .trim_end_matches("\t|")
+        .split("\t|\t")
+        .map(str::trim)
+        .collect()
+}
+
+/// Parents and ranks of an NCBI taxdump's nodes, read from `nodes.dmp` one line at a
+/// time before any of its names.
+#[derive(Default)]
+pub struct NcbiNodes {
+    nodes: HashMap<String, (Option<String>, String)>,
+}
+
+impl NcbiNodes {
+    pub fn read_line(&mut self, number: usize, line: &str) -> Result<(), ApiError> {
+        if line.trim().is_empty() {
+            return Ok(());
+        }
+        let fields = dmp_fields(line);
+        let [taxon_id, parent, rank, ..] = fields.as_slice() else {
+            return Err(ApiError::bad_request(format!(
+                "nodes.dmp line {number}: expected tax id, parent tax id and rank"
+            )));
+        };
+        if taxon_id.is_empty() || rank.is_empty() {
+            return Err(ApiError::bad_request(format!(
+                "nodes.dmp line {number}: missing tax id or rank"
+            )));
+        }
+        // The root is its own parent.
+        let parent = (!parent.is_empty() && parent != taxon_id).then(|| parent.to_string());
+        self.nodes
+            .insert(taxon_id.to_string(), (parent, rank.to_string()));
+        Ok(())
+    }
+
+    /// A reader for `names.dmp`, once every node has been read.
+    pub fn names(self) -> Result<NcbiNames, ApiError> {
+        if self.nodes.is_empty() {
+            return Err(ApiError::bad_request("nodes.dmp contains no taxa"));
+        }
+        Ok(NcbiNames {
+            nodes: self.nodes,
+            named: HashSet::new(),
+        })
+    }
+}
+
+/// Taxa of an NCBI taxdump's `names.dmp`: one accepted entry per node, named by its
+/// scientific name, and one synonym entry per synonym. Names of unknown nodes are
+/// ignored. A synonym may come before its node's scientific name, so synonyms of
+/// nodes that turn out to have none are left for the import to drop.
+pub struct NcbiNames {
+    nodes: HashMap<String, (Option<String>, String)>,
+    named: HashSet<String>,
+}
+
+impl NcbiNames {
+    pub fn read_line(&mut self, number: usize, line: &str) -> Result<Option<Entry>, ApiError> {
+        if line.trim().is_empty() {
+            return Ok(None);
+        }
+        let fields = dmp_fields(line);
+        let [taxon_id, name, _unique_name, class, ..] = fields.as_slice() else {
+            return Err(ApiError::bad_request(format!(
+                "names.dmp line {number}: expected tax id, name, unique name and name class"
+            )));
+        };
+        let Some((parent, rank)) = self.nodes.get(*taxon_id) else {
+            return Ok(None);
+        };
+        let is_synonym = match *class {
+            "scientific name" => false,
+            class if NCBI_SYNONYM_CLASSES.contains(&class) => true,
+            _ => return Ok(None),
+        };
+        if !is_synonym && !self.named.insert(taxon_id.to_string()) {
+            return Err(ApiError::bad_request(format!(
+                "names.dmp line {number}: tax id {taxon_id} has a second scientific name"
+            )));
+        }
+        Ok(Some(Entry {
+            taxon_id: taxon_id.to_string(),
+            name: name.to_string(),
+            rank: rank.clone(),
+            parent_taxon_id: parent.clone(),
+            is_synonym,
+        }))
+    }
+
+    pub fn finish(&self) -> Result<(), ApiError> {
+        if self.named.is_empty() {
+            return Err(ApiError::bad_request(
+                "names.dmp contains no scientific names for the nodes",
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Taxa of a GTDB taxonomy file, read one line at a time. Lines may hold the lineage
+/// alone or after the genome accession and a tab.
+#[derive(Default)]
+pub struct Gtdb {
+    seen: HashSet<String>,
+}
+
+impl Gtdb {
+    /// Taxa of the line's lineage that earlier lines did not name.
+    pub fn read_line(&mut self, number: usize, line: &str) -> Result<Vec<Entry>, ApiError> {
+        let mut entries = Vec::new();
+        if line.trim().is_empty() || line.starts_with('#') {
+            return Ok(entries);
+        }
+        let lineage = line.rsplit('\t').next().unwrap_or_default();
+        let mut parent: Option<String> = None;
+        for part in lineage.split(';').map(str::trim) {
+            let rank = part.split_once("__").and_then(|(prefix, name)| {
+                let (_, rank) = GTDB_RANKS.iter().find(|(p, _)| *p == prefix)?;
+                Some((*rank, name.trim()))
+            });
+            let Some((rank, name)) = rank else {
+                return Err(ApiError::bad_request(format!(
+                    "Line {number}: expected a lineage such as d__Bacteria;p__Pseudomonadota, got '{part}'"
+                )));
+            };
+            // GTDB leaves ranks without a name as a bare prefix.
+            if name.is_empty() {
+                continue;
+            }
+            let taxon_id = part.to_string();
+            if self.seen.insert(taxon_id.clone()) {
+                entries.push(Entry {
+                    taxon_id: taxon_id.clone(),
+                    name: name.to_string(),
+                    rank: rank.to_string(),
+                    parent_taxon_id: parent.clone(),
+                    is_synonym: false,
+                });
+            }
+            parent = Some(taxon_id);
+        }
+        Ok(entries)
+    }
+
+    pub fn finish(&self) -> Result<(), ApiError> {
+        if self.seen.is_empty() {
+            return Err(ApiError::bad_request(
+                "The taxonomy file contains no lineages",
+            ));
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,430 @@
+use crudcrate::validation::ValidationError;
+use crudcrate::ApiError;
+use sea_orm::sea_query::{Alias, Expr, Func, Query};
+use sea_orm::{
+    ActiveValue, ColumnTrait, Condition, ConnectionTrait, DatabaseConnection, EntityTrait,
+    PaginatorTrait, QueryFilter, QuerySelect, Set, TransactionTrait,
+};
+use uuid::Uuid;
+
+use super::db::{Column, Entity, Model};
+use super::models::{ImportSummary, UnresolvedIsolate, UnresolvedReason};
+use super::parse::Entry;
+use super::staging;
+use crate::common::enums::TaxonomySource;
+use crate::isolates::{self, lineage};
+
+/// Rows per INSERT, well under the bind parameter limits.
+const INSERT_CHUNK: usize = 500;
+
+/// Rows staged per transaction while an upload is read.
+const STAGE_BATCH: usize = 10 * INSERT_CHUNK;
+
+/// Close spellings offered for a name that does not resolve.
+const SUGGESTIONS: usize = 5;
+
+/// Parent links followed before giving up on a malformed taxonomy.
+const MAX_DEPTH: usize = 100;
+
+/// Position in `lineage::RANKS` of a rank as NCBI or GTDB write it.
+fn rank_index(rank: &str) -> Option<usize> {
+    match rank {
+        "superkingdom" | "domain" => Some(0),
+        "order" => Some(3),
+        rank => lineage::RANKS.iter().position(|r| *r == rank),
+    }
+}
+
+/// A taxonomy load in progress. Entries are staged in `taxa_staging` and committed
+/// in batches as the upload is read; `replace` then swaps them in for the source's
+/// taxa in one transaction, so isolates resolve against either the old or the new
+/// load.
+pub struct Import<'a> {
+    db: &'a DatabaseConnection,
+    source: TaxonomySource,
+    id: Uuid,
+    pending: Vec<Entry>,
+}
+
+impl<'a> Import<'a> {
+    pub fn new(db: &'a DatabaseConnection, source: TaxonomySource) -> Self {
+        Self {
+            db,
+            source,
+            id: Uuid::new_v4(),
+            pending: Vec::new(),
+        }
+    }
+
+    pub async fn push(&mut self, entry: Entry) -> Result<(), ApiError> {
+        self.pending.push(entry);
+        if self.pending.len() >= STAGE_BATCH {
+            self.flush().await?;
+        }
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> Result<(), ApiError> {
+        let txn = self.db.begin().await?;
+        for chunk in self.pending.chunks(INSERT_CHUNK) {
+            let models = chunk.iter().map(|entry| staging::ActiveModel {
+                id: Set(Uuid::new_v4()),
+                import_id: Set(self.id),
+                taxon_id: Set(entry.taxon_id.clone()),
+                name: Set(entry.name.clone()),
+                rank: Set(entry.rank.clone()),
+                parent_taxon_id: Set(entry.parent_taxon_id.clone()),
+                is_synonym: Set(entry.is_synonym),
+            });
+            staging::Entity::insert_many(models).exec(&txn).await?;
+        }
+        txn.commit().await?;
+        self.pending.clear();
+        Ok(())
+    }
+
+    /// Replaces the taxa of the source with the staged ones. Synonyms of taxa
+    /// without an accepted name cannot be resolved to and are dropped.
+    pub async fn replace(&mut self) -> Result<ImportSummary, ApiError> {
+        self.flush().await?;
+        let accepted = Alias::new("accepted");
+        let has_accepted_name = Query::select()
+            .expr(Expr::val(1))
+            .from_as(staging::Entity, accepted.clone())
+            .and_where(Expr::col((accepted.clone(), staging::Column::ImportId)).eq(self.id))
+            .and_where(
+                Expr::col((accepted.clone(), staging::Column::TaxonId))
+                    .equals((staging::Entity, staging::Column::TaxonId)),
+            )
+            .and_where(Expr::col((accepted, staging::Column::IsSynonym)).eq(false))
+            .to_owned();
+        let staged = Query::select()
+            .column(staging::Column::Id)
+            .expr(Expr::val(self.source))
+            .columns([
+                staging::Column::TaxonId,
+                staging::Column::Name,
+                staging::Column::Rank,
+                staging::Column::ParentTaxonId,
+                staging::Column::IsSynonym,
+            ])
+            .from(staging::Entity)
+            .and_where(staging::Column::ImportId.eq(self.id))
+            .cond_where(
+                Condition::any()
+                    .add(staging::Column::IsSynonym.eq(false))
+                    .add(Expr::exists(has_accepted_name)),
+            )
+            .to_owned();
+        let insert = Query::insert()
+            .into_table(Entity)
+            .columns([
+                Column::Id,
+                Column::Source,
+                Column::TaxonId,
+                Column::Name,
+                Column::Rank,
+                Column::ParentTaxonId,
+                Column::IsSynonym,
+            ])
+            .select_from(staged)
+            .map_err(|err| ApiError::internal(err.to_string(), None))?
+            .to_owned();
+
+        let txn = self.db.begin().await?;
+        Entity::delete_many()
+            .filter(Column::Source.eq(self.source))
+            .exec(&txn)
+            .await?;
+        txn.execute(txn.get_database_backend().build(&insert))
+            .await?;
+        self.discard_in(&txn).await?;
+        let count = |is_synonym: bool| {
+            Entity::find()
+                .filter(Column::Source.eq(self.source))
+                .filter(Column::IsSynonym.eq(is_synonym))
+                .count(&txn)
+        };
+        let taxa = count(false).await?;
+        let synonyms = count(true).await?;
+        txn.commit().await?;
+
+        Ok(ImportSummary {
+            source: self.source,
+            taxa,
+            synonyms,
+        })
+    }
+
+    /// Drops whatever is still staged, after a failed or finished import.
+    pub async fn discard(&self) -> Result<(), ApiError> {
+        self.discard_in(self.db).await
+    }
+
+    async fn discard_in<C: ConnectionTrait>(&self, db: &C) -> Result<(), ApiError> {
+        staging::Entity::delete_many()
+            .filter(staging::Column::ImportId.eq(self.id))
+            .exec(db)
+            .await?;
+        Ok(())
+    }
+}
+
+async fn is_loaded<C: ConnectionTrait>(db: &C) -> Result<bool, ApiError> {
+    Ok(Entity::find().one(db).await?.is_some())
+}
+
+/// Taxa with this name, accepted or synonym, in any loaded source.
+async fn named<C: ConnectionTrait>(db: &C, name: &str) -> Result<Vec<Model>, ApiError> {
+    Ok(Entity::find()
+        .filter(Expr::expr(Func::lower(Expr::col(Column::Name))).eq(name.trim().to_lowercase()))
+        .all(db)
+        .await?)
+}
+
+async fn accepted<C: ConnectionTrait>(
+    db: &C,
+    source: TaxonomySource,
+    taxon_id: &str,
+) -> Result<Option<Model>, ApiError> {
+    Ok(Entity::find()
+        .filter(Column::Source.eq(source))
+        .filter(Column::TaxonId.eq(taxon_id))
+        .filter(Column::IsSynonym.eq(false))
+        .one(db)
+        .await?)
+}
+
+/// Accepted names of the taxon and its ancestors at the ranks isolates record.
+async fn lineage_of<C: ConnectionTrait>(
+    db: &C,
+    taxon: &Model,
+) -> Result<lineage::Lineage, ApiError> {
+    let mut names = lineage::Lineage::default();
+    let mut current = Some(taxon.clone());
+    for _ in 0..MAX_DEPTH {
+        let Some(taxon) = current else {
+            break;
+        };
+        if let Some(rank) = rank_index(&taxon.rank) {
+            names[rank].get_or_insert(taxon.name.clone());
+        }
+        current = match &taxon.parent_taxon_id {
+            Some(parent) => accepted(db, taxon.source, parent).await?,
+            None => None,
+        };
+    }
+    Ok(names)
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(ca != *cb);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(diagonal + 1);
+        }
+    }
+    row[b.len()]
+}
+
+/// Names within a few edits of `name`, closest first. Only names sharing the first
+/// letter are considered, which keeps the search to a slice of a full taxdump.
+pub async fn suggest<C: ConnectionTrait>(db: &C, name: &str) -> Result<Vec<String>, ApiError> {
+    let name = name.trim().to_lowercase();
+    let Some(first) = name.chars().next() else {
+        return Ok(Vec::new());
+    };
+    let length = name.chars().count();
+    let max_distance = (length / 4).clamp(1, 3);
+    let candidates: Vec<String> = Entity::find()
+        .select_only()
+        .column(Column::Name)
+        .distinct()
+        .filter(Expr::expr(Func::lower(Expr::col(Column::Name))).like(format!("{first}%")))
+        .filter(
+            Expr::expr(Func::char_length(Expr::col(Column::Name))).between(
+                length.saturating_sub(max_distance) as i64,
+                (length + max_distance) as i64,
+            ),
+        )
+        .into_tuple()
+        .all(db)
+        .await?;
+
+    let mut scored: Vec<(usize, String)> = candidates
+        .into_iter()
+        .map(|candidate| (edit_distance(&name, &candidate.to_lowercase()), candidate))
+        .filter(|(distance, _)| *distance <= max_distance)
+        .collect();
+    scored.sort();
+    scored.dedup_by(|a, b| a.1 == b.1);
+    Ok(scored
+        .into_iter()
+        .take(SUGGESTIONS)
+        .map(|(_, name)| name)
+        .collect())
+}
+
+fn unresolved_message(name: &str, suggestions: &[String]) -> String {
+    if suggestions.is_empty() {
+        format!("'{name}' is not in the loaded taxonomy")
+    } else {
+        format!(
+            "'{name}' is not in the loaded taxonomy; did you mean {}?",
+            suggestions.join(", ")
+        )
+    }
+}
+
+fn current(value: &ActiveValue<Option<String>>) -> Option<String> {
+    match value {
+        ActiveValue::Set(value) | ActiveValue::Unchanged(value) => value.clone(),
+        ActiveValue::NotSet => None,
+    }
+}
+
+/// The name an isolate is resolved by: its lowest rank, or else its taxonomy.
+/// Returns the name with the rank it was read from.
+fn isolate_name(
+    ranks: &lineage::Lineage,
+    taxonomy: Option<&str>,
+) -> Option<(String, Option<usize>)> {
+    if let Some(rank) = ranks.iter().rposition(Option::is_some) {
+        return Some((ranks[rank].clone().unwrap(), Some(rank)));
+    }
+    let taxonomy = taxonomy?.trim();
+    (!taxonomy.is_empty()).then(|| (taxonomy.to_string(), None))
+}
+
+/// How well a candidate taxon fits an isolate, compared field by field: rank
+/// matches, other ranks agreeing, accepted name, from NCBI.
+type Score = (bool, usize, bool, bool);
+
+/// Validates the name of an isolate being saved against the loaded taxonomies and
+/// normalises it: the ranks take the accepted names of the resolved taxon's
+/// lineage and the taxon is stored with the isolate. Among homonyms and names
+/// found in both sources, the taxon whose lineage agrees with more of the
+/// isolate's other ranks wins, then accepted names over synonyms, then NCBI.
+///
+/// Nothing is checked until a taxonomy is loaded, or when neither the taxonomy
+/// nor a rank is being saved.
+pub async fn resolve_isolate<C: ConnectionTrait>(
+    db: &C,
+    model: &mut isolates::db::ActiveModel,
+) -> Result<(), ApiError> {
+    let changed = model.taxonomy.is_set() || lineage::ranks(model).iter().any(|r| r.is_set());
+    if !changed || !is_loaded(db).await? {
+        return Ok(());
+    }
+    let ranks: lineage::Lineage = lineage::ranks(model).map(|rank| current(rank));
+    let taxonomy = current(&model.taxonomy);
+    let Some((name, rank)) = isolate_name(&ranks, taxonomy.as_deref()) else {
+        model.taxon_source = Set(None);
+        model.taxon_id = Set(None);
+        return Ok(());
+    };
+
+    let mut best: Option<(Score, Model, lineage::Lineage)> = None;
+    for candidate in named(db, &name).await? {
+        let taxon = if candidate.is_synonym {
+            match accepted(db, candidate.source, &candidate.taxon_id).await? {
+                Some(taxon) => taxon,
+                None => continue,
+            }
+        } else {
+            candidate.clone()
+        };
+        let names = lineage_of(db, &taxon).await?;
+        let agreeing = (0..lineage::RANKS.len())
+            .filter(|other| Some(*other) != rank)
+            .filter(|other| match (&ranks[*other], &names[*other]) {
+                (Some(ours), Some(theirs)) => ours.eq_ignore_ascii_case(theirs),
+                _ => false,
+            })
+            .count();
+        let score = (
+            rank.is_none() || rank_index(&taxon.rank) == rank,
+            agreeing,
+            !candidate.is_synonym,
+            taxon.source == TaxonomySource::Ncbi,
+        );
+        if best.as_ref().is_none_or(|(best, _, _)| score > *best) {
+            best = Some((score, taxon, names));
+        }
+    }
+    let Some((_, taxon, names)) = best else {
+        let suggestions = suggest(db, &name).await?;
+        let field = rank.map_or("taxonomy", |rank| lineage::RANKS[rank]);
+        return Err(ValidationError::new(field, unresolved_message(&name, &suggestions)).into());
+    };
+
+    for (field, accepted) in lineage::ranks(model).into_iter().zip(names) {
+        if accepted.is_some() {
+            *field = Set(accepted);
+        }
+    }
+    if let Some(rank) = rank {
+        *lineage::ranks(model)[rank] = Set(Some(taxon.name.clone()));
+    }
+    if taxonomy.is_some_and(|taxonomy| taxonomy.trim().eq_ignore_ascii_case(&name)) {
+        model.taxonomy = Set(Some(taxon.name.clone()));
+    }
+    model.taxon_source = Set(Some(taxon.source));
+    model.taxon_id = Set(Some(taxon.taxon_id));
+    Ok(())
+}
+
+/// Isolates whose name does not resolve against the loaded taxonomies: names
+/// that match nothing, and resolved taxa that were dropped or renamed by a later
+/// load. Empty until a taxonomy is loaded.
+pub async fn unresolved(db: &DatabaseConnection) -> Result<Vec<UnresolvedIsolate>, ApiError> {
+    if !is_loaded(db).await? {
+        return Ok(Vec::new());
+    }
+    let mut report = Vec::new();
+    for isolate in isolates::db::Entity::find().all(db).await? {
+        let ranks = [
+            isolate.domain.clone(),
+            isolate.phylum.clone(),
+            isolate.class.clone(),
+            isolate.taxonomic_order.clone(),
+            isolate.family.clone(),
+            isolate.genus.clone(),
+            isolate.species.clone(),
+        ];
+        let Some((name, _)) = isolate_name(&ranks, isolate.taxonomy.as_deref()) else {
+            continue;
+        };
+        let (reason, suggestions) = match (isolate.taxon_source, &isolate.taxon_id) {
+            (Some(source), Some(taxon_id)) => match accepted(db, source, taxon_id).await? {
+                None => (UnresolvedReason::Removed, suggest(db, &name).await?),
+                Some(taxon)
+                    if taxon.name != name && isolate.taxonomy.as_ref() != Some(&taxon.name) =>
+                {
+                    (UnresolvedReason::Renamed, vec![taxon.name])
+                }
+                Some(_) => continue,
+            },
+            _ if named(db, &name).await?.is_empty() => {
+                (UnresolvedReason::NotFound, suggest(db, &name).await?)
+            }
+            _ => continue,
+        };
+        report.push(UnresolvedIsolate {
+            isolate_id: isolate.id,
+            isolate_name: isolate.name,
+            taxon_name: name,
+            taxon_source: isolate.taxon_source,
+            taxon_id: isolate.taxon_id,
+            reason,
+            suggestions,
+        });
+    }
+    report.sort_by(|a, b| a.isolate_name.cmp(&b.isolate_name));
+    Ok(report)
+}
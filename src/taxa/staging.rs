@@ -0,0 +1,21 @@
+use sea_orm::entity::prelude::*;
+use uuid::Uuid;
+
+/// A taxon of an import that is still being read; see `services::Import`.
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel)]
+#[sea_orm(table_name = "taxa_staging")]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    pub id: Uuid,
+    pub import_id: Uuid,
+    pub taxon_id: String,
+    pub name: String,
+    pub rank: String,
+    pub parent_taxon_id: Option<String>,
+    pub is_synonym: bool,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {}
+
+impl ActiveModelBehavior for ActiveModel {}
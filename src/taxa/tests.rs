@@ -0,0 +1,330 @@
+use axum::http::StatusCode;
+use sea_orm::{EntityTrait, PaginatorTrait};
+use serde_json::{json, Value};
+
+use crate::test_utils::{
+    build_app_with_db, build_scoped_app_with_db, create_field_record, create_json, create_site,
+    get, json_body, send, send_raw, setup_sqlite_db,
+};
+
+const NODES: &str = "1\t|\t1\t|\tno rank\t|\t\t|
+2\t|\t1\t|\tsuperkingdom\t|\t\t|
+1224\t|\t2\t|\tphylum\t|\t\t|
+1236\t|\t1224\t|\tclass\t|\t\t|
+72274\t|\t1236\t|\torder\t|\t\t|
+135621\t|\t72274\t|\tfamily\t|\t\t|
+286\t|\t135621\t|\tgenus\t|\t\t|
+294\t|\t286\t|\tspecies\t|\t\t|
+1386\t|\t2\t|\tgenus\t|\t\t|
+2759\t|\t1\t|\tsuperkingdom\t|\t\t|
+55087\t|\t2759\t|\tgenus\t|\t\t|
+";
+
+const NAMES: &str = "1\t|\troot\t|\t\t|\tscientific name\t|
+2\t|\tBacteria\t|\tBacteria <bacteria>\t|\tscientific name\t|
+1224\t|\tPseudomonadota\t|\t\t|\tscientific name\t|
+1224\t|\tProteobacteria\t|\t\t|\tsynonym\t|
+1236\t|\tGammaproteobacteria\t|\t\t|\tscientific name\t|
+72274\t|\tPseudomonadales\t|\t\t|\tscientific name\t|
+135621\t|\tPseudomonadaceae\t|\t\t|\tscientific name\t|
+286\t|\tPseudomonas\t|\t\t|\tscientific name\t|
+286\t|\tPseudomonas Migula 1894\t|\t\t|\tauthority\t|
+294\t|\tPseudomonas fluorescens\t|\t\t|\tscientific name\t|
+1386\t|\tBacillus\t|\tBacillus <firmicutes>\t|\tscientific name\t|
+2759\t|\tEukaryota\t|\t\t|\tscientific name\t|
+55087\t|\tBacillus\t|\tBacillus <walking sticks>\t|\tscientific name\t|
+";
+
+const GTDB: &str = "GB_GCA_000009045.1\td__Bacteria;p__Bacillota;c__Bacilli;o__Bacillales;f__Bacillaceae;g__Bacillus;s__Bacillus subtilis
+RS_GCF_000789015.1\td__Bacteria;p__Bacillota;c__Bacilli;o__Bacillales;f__Bacillaceae;g__Bacillus;s__
+";
+
+async fn import(app: &axum::Router, source: &str, files: &[(&str, &str)]) -> (StatusCode, Value) {
+    let boundary = "taxonomy-files";
+    let mut body = String::new();
+    for (name, text) in files {
+        body.push_str(&format!(
+            "--{boundary}\r\nContent-Disposition: form-data; name=\"{name}\"; filename=\"{name}.dmp\"\r\n\r\n{text}\r\n"
+        ));
+    }
+    body.push_str(&format!("--{boundary}--\r\n"));
+    let resp = send_raw(
+        app,
+        "POST",
+        &format!("/api/taxa/import?source={source}"),
+        &[(
+            "Content-Type",
+            &format!("multipart/form-data; boundary={boundary}"),
+        )],
+        body.into_bytes(),
+    )
+    .await;
+    (resp.status(), json_body(resp).await)
+}
+
+/// The test taxdump, loaded.
+async fn load_ncbi(app: &axum::Router) {
+    let (status, summary) = import(app, "NCBI", &[("nodes", NODES), ("names", NAMES)]).await;
+    assert_eq!(status, StatusCode::OK, "{summary}");
+}
+
+/// A field record FR-1 at Glacier A for the isolates of a test.
+async fn field_record(app: &axum::Router) -> String {
+    let site_id = create_site(app, "Glacier A").await;
+    create_field_record(app, &site_id, "FR-1").await
+}
+
+/// An isolate payload on `fr` with the given taxonomy fields.
+fn isolate(fr: &str, name: &str, fields: Value) -> Value {
+    let mut payload = json!({ "name": name, "field_record_id": fr });
+    payload
+        .as_object_mut()
+        .unwrap()
+        .extend(fields.as_object().unwrap().clone());
+    payload
+}
+
+/// Scenario: taxdumps with a missing, malformed or out-of-order file are sent, then
+/// a valid taxdump and a GTDB file large enough to be staged in several batches.
+/// Expected behaviour: the bad uploads are refused without touching the loaded
+/// taxa; the good ones replace the source's taxa, count accepted names and
+/// synonyms, and leave nothing staged. Public callers cannot reach taxa.
+#[tokio::test]
+async fn taxonomies_load_in_batches_and_bad_uploads_change_nothing() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db.clone());
+    let scoped = build_scoped_app_with_db(db.clone());
+
+    let (status, body) = import(&app, "NCBI", &[("nodes", NODES)]).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{body}");
+    let (status, body) = import(&app, "NCBI", &[("names", NAMES), ("nodes", NODES)]).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{body}");
+    let (status, summary) = import(&app, "NCBI", &[("nodes", NODES), ("names", NAMES)]).await;
+    assert_eq!(status, StatusCode::OK, "{summary}");
+    assert_eq!(
+        summary,
+        json!({ "source": "NCBI", "taxa": 11, "synonyms": 1 })
+    );
+
+    let (status, body) = import(&app, "NCBI", &[("nodes", "1\t|\n"), ("names", NAMES)]).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body.to_string().contains("nodes.dmp line 1"), "{body}");
+    let broken_names = format!("{NAMES}286\t|\tPseudomonas\n");
+    let (status, body) = import(&app, "NCBI", &[("nodes", NODES), ("names", &broken_names)]).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body.to_string().contains("names.dmp line 14"), "{body}");
+    let (_, suggestions) = get(&app, "/api/taxa/suggestions?name=Pseudomonass").await;
+    assert_eq!(suggestions, json!(["Pseudomonas"]), "the first load stays");
+
+    let (status, summary) = import(&app, "GTDB", &[("taxonomy", GTDB)]).await;
+    assert_eq!(status, StatusCode::OK, "{summary}");
+    assert_eq!(
+        summary,
+        json!({ "source": "GTDB", "taxa": 7, "synonyms": 0 })
+    );
+    let genomes: String = (0..6000)
+        .map(|i| format!("RS_{i}\td__Bacteria;p__Bacillota;g__Bacillus;s__Bacillus sp{i}\n"))
+        .collect();
+    let (status, summary) = import(&app, "GTDB", &[("taxonomy", &genomes)]).await;
+    assert_eq!(status, StatusCode::OK, "{summary}");
+    assert_eq!(
+        summary,
+        json!({ "source": "GTDB", "taxa": 6003, "synonyms": 0 })
+    );
+    let (status, _) = import(&app, "GTDB", &[("taxonomy", "# no lineages\n")]).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(
+        crate::taxa::staging::Entity::find()
+            .count(&db)
+            .await
+            .unwrap(),
+        0
+    );
+
+    let (status, _) = get(&scoped, "/api/taxa/unresolved").await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = get(&scoped, "/api/taxa").await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+/// Scenario: once a taxdump and a GTDB file are loaded, isolates are recorded by
+/// lowercase names, synonyms, homonyms and misspellings.
+/// Expected behaviour: names are normalised to accepted names with their lineage
+/// and taxon, homonyms follow the other ranks, and misspellings are refused with
+/// suggestions.
+#[tokio::test]
+async fn isolate_names_resolve_to_accepted_names() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db);
+    let fr = field_record(&app).await;
+    load_ncbi(&app).await;
+    import(&app, "GTDB", &[("taxonomy", GTDB)]).await;
+
+    let (status, body) = send(
+        &app,
+        "POST",
+        "/api/isolates",
+        isolate(&fr, "ISO-TYPO", json!({ "taxonomy": "Pseudomnas" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert!(
+        body.to_string().contains("did you mean Pseudomonas?"),
+        "{body}"
+    );
+
+    let lowercase = create_json(
+        &app,
+        "/api/isolates",
+        isolate(
+            &fr,
+            "ISO-LOWER",
+            json!({ "taxonomy": "pseudomonas fluorescens" }),
+        ),
+    )
+    .await;
+    assert_eq!(lowercase["taxonomy"], "Pseudomonas fluorescens");
+    assert_eq!(lowercase["species"], "Pseudomonas fluorescens");
+    assert_eq!(lowercase["domain"], "Bacteria");
+    assert_eq!(lowercase["phylum"], "Pseudomonadota");
+    assert_eq!(lowercase["taxonomic_order"], "Pseudomonadales");
+    assert_eq!(lowercase["taxon_source"], "NCBI");
+    assert_eq!(lowercase["taxon_id"], "294");
+
+    let synonym = create_json(
+        &app,
+        "/api/isolates",
+        isolate(&fr, "ISO-SYNONYM", json!({ "phylum": "Proteobacteria" })),
+    )
+    .await;
+    assert_eq!(synonym["phylum"], "Pseudomonadota");
+    assert_eq!(synonym["domain"], "Bacteria");
+    assert_eq!(synonym["taxon_id"], "1224");
+
+    let bacterium = create_json(
+        &app,
+        "/api/isolates",
+        isolate(
+            &fr,
+            "ISO-BACILLUS",
+            json!({ "domain": "Bacteria", "genus": "Bacillus" }),
+        ),
+    )
+    .await;
+    assert_eq!(bacterium["taxon_id"], "1386");
+    let insect = create_json(
+        &app,
+        "/api/isolates",
+        isolate(
+            &fr,
+            "ISO-STICK",
+            json!({ "domain": "Eukaryota", "genus": "bacillus" }),
+        ),
+    )
+    .await;
+    assert_eq!(insect["taxon_id"], "55087");
+    assert_eq!(insect["genus"], "Bacillus");
+
+    let subtilis = create_json(
+        &app,
+        "/api/isolates",
+        isolate(&fr, "ISO-GTDB", json!({ "taxonomy": "Bacillus subtilis" })),
+    )
+    .await;
+    assert_eq!(subtilis["taxon_source"], "GTDB");
+    assert_eq!(subtilis["taxon_id"], "s__Bacillus subtilis");
+    assert_eq!(subtilis["phylum"], "Bacillota");
+}
+
+/// Scenario: an isolate is recorded with a misspelt name before any taxonomy is
+/// loaded and fixed after the load; a later taxdump renames one taxon and drops
+/// another.
+/// Expected behaviour: nothing is checked before a load; the report lists the
+/// misspelt isolate with suggestions, then the renamed and dropped ones.
+#[tokio::test]
+async fn unresolved_report_follows_the_loaded_taxonomy() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db);
+    let fr = field_record(&app).await;
+
+    let misspelt = create_json(
+        &app,
+        "/api/isolates",
+        isolate(
+            &fr,
+            "ISO-MISSPELT",
+            json!({ "taxonomy": "Pseudomnas fluorescens" }),
+        ),
+    )
+    .await;
+    assert_eq!(misspelt["taxon_id"], Value::Null);
+    assert_eq!(get(&app, "/api/taxa/unresolved").await.1, json!([]));
+
+    load_ncbi(&app).await;
+    let (_, report) = get(&app, "/api/taxa/unresolved").await;
+    assert_eq!(report.as_array().unwrap().len(), 1);
+    assert_eq!(report[0]["isolate_name"], "ISO-MISSPELT");
+    assert_eq!(report[0]["reason"], "NotFound");
+    assert_eq!(report[0]["suggestions"], json!(["Pseudomonas fluorescens"]));
+
+    let misspelt = format!("/api/isolates/{}", misspelt["id"].as_str().unwrap());
+    let (status, fixed) = send(
+        &app,
+        "PUT",
+        &misspelt,
+        json!({ "taxonomy": "Pseudomonas fluorescens" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{fixed}");
+    assert_eq!(fixed["taxon_id"], "294");
+    let (status, _) = send(
+        &app,
+        "PUT",
+        &misspelt,
+        json!({ "genus": "Pseudomonas", "species": "Pseudomonas fluorscens" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(get(&app, "/api/taxa/unresolved").await.1, json!([]));
+    create_json(
+        &app,
+        "/api/isolates",
+        isolate(
+            &fr,
+            "ISO-STICK",
+            json!({ "domain": "Eukaryota", "genus": "bacillus" }),
+        ),
+    )
+    .await;
+
+    let without_insect = |text: &str| -> String {
+        text.lines()
+            .filter(|line| !line.starts_with("55087"))
+            .map(|line| format!("{line}\n"))
+            .collect()
+    };
+    let nodes = without_insect(NODES);
+    let names = without_insect(NAMES).replace("Pseudomonas fluorescens", "Pseudomonas lactis");
+    let (status, _) = import(&app, "NCBI", &[("nodes", &nodes), ("names", &names)]).await;
+    assert_eq!(status, StatusCode::OK);
+    let (_, report) = get(&app, "/api/taxa/unresolved").await;
+    let reasons: Vec<(&str, &str, &Value)> = report
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|entry| {
+            (
+                entry["isolate_name"].as_str().unwrap(),
+                entry["reason"].as_str().unwrap(),
+                &entry["suggestions"],
+            )
+        })
+        .collect();
+    assert_eq!(
+        reasons,
+        [
+            ("ISO-MISSPELT", "Renamed", &json!(["Pseudomonas lactis"])),
+            ("ISO-STICK", "Removed", &json!(["Bacillus"])),
+        ]
+    );
+}
@@ -0,0 +1,218 @@
+use axum::extract::multipart::{Field, MultipartError};
+use axum::extract::{DefaultBodyLimit, Multipart, Query, State};
+use axum::{
+    routing::{get, post},
+    Json, Router,
+};
+use crudcrate::validation::ValidationError;
+use crudcrate::ApiError;
+use sea_orm::DatabaseConnection;
+
+use super::models::{ImportParams, ImportSummary, SuggestionParams, UnresolvedIsolate};
+use super::parse::{Gtdb, NcbiNodes};
+use super::services;
+use crate::common::enums::TaxonomySource;
+
+/// A full NCBI taxdump's `nodes.dmp` and `names.dmp` come to about 450 MB. Uploads
+/// are read as they stream in, so this only bounds how long an import runs.
+const MAX_IMPORT_BYTES: usize = 512 * 1024 * 1024;
+
+/// Routes mounted next to the generated CRUD router under `/api/taxa`.
+pub fn router(db: &DatabaseConnection) -> Router {
+    Router::new()
+        .route(
+            "/import",
+            post(import).layer(DefaultBodyLimit::max(MAX_IMPORT_BYTES)),
+        )
+        .route("/suggestions", get(get_suggestions))
+        .route("/unresolved", get(get_unresolved))
+        .with_state(db.clone())
+}
+
+fn multipart_error(err: MultipartError) -> ApiError {
+    ApiError::custom(err.status(), err.body_text(), None)
+}
+
+/// The lines of an uploaded file, read as its chunks arrive.
+struct FieldLines<'a> {
+    field: Field<'a>,
+    name: &'static str,
+    buffer: Vec<u8>,
+    /// Bytes of `buffer` already searched for a line break.
+    searched: usize,
+    number: usize,
+    finished: bool,
+}
+
+impl<'a> FieldLines<'a> {
+    fn new(field: Field<'a>, name: &'static str) -> Self {
+        Self {
+            field,
+            name,
+            buffer: Vec::new(),
+            searched: 0,
+            number: 0,
+            finished: false,
+        }
+    }
+
+    /// The next line and its number, without the line break.
+    async fn next(&mut self) -> Result<Option<(usize, String)>, ApiError> {
+        loop {
+            let end = self.buffer[self.searched..]
+                .iter()
+                .position(|byte| *byte == b'\n')
+                .map(|offset| self.searched + offset + 1);
+            let end = match end {
+                Some(end) => end,
+                None if !self.finished => {
+                    self.searched = self.buffer.len();
+                    match self.field.chunk().await.map_err(multipart_error)? {
+                        Some(chunk) => self.buffer.extend_from_slice(&chunk),
+                        None => self.finished = true,
+                    }
+                    continue;
+                }
+                None if self.buffer.is_empty() => return Ok(None),
+                None => self.buffer.len(),
+            };
+            let line: Vec<u8> = self.buffer.drain(..end).collect();
+            self.searched = 0;
+            self.number += 1;
+            let line = String::from_utf8(line).map_err(|_| {
+                ApiError::bad_request(format!(
+                    "{} line {} is not UTF-8 text",
+                    self.name, self.number
+                ))
+            })?;
+            let line = line.trim_end_matches(['\n', '\r']).to_string();
+            return Ok(Some((self.number, line)));
+        }
+    }
+}
+
+fn missing(field: &str) -> ApiError {
+    ApiError::from(ValidationError::new(
+        field,
+        format!("No `{field}` file in the upload"),
+    ))
+}
+
+/// Reads the upload into `import`, checking the files as they stream in.
+async fn stage(
+    import: &mut services::Import<'_>,
+    source: TaxonomySource,
+    multipart: &mut Multipart,
+) -> Result<(), ApiError> {
+    let mut nodes = Some(NcbiNodes::default());
+    let (mut nodes_read, mut names_read) = (false, false);
+    let mut gtdb = Gtdb::default();
+    let mut taxonomy_read = false;
+    while let Some(field) = multipart.next_field().await.map_err(multipart_error)? {
+        match (source, field.name().unwrap_or_default()) {
+            (TaxonomySource::Ncbi, "nodes") => {
+                let Some(nodes) = nodes.as_mut() else {
+                    return Err(ApiError::from(ValidationError::new(
+                        "nodes",
+                        "Send `nodes` before `names`",
+                    )));
+                };
+                let mut lines = FieldLines::new(field, "nodes.dmp");
+                while let Some((number, line)) = lines.next().await? {
+                    nodes.read_line(number, &line)?;
+                }
+                nodes_read = true;
+            }
+            (TaxonomySource::Ncbi, "names") => {
+                let Some(nodes) = nodes.take().filter(|_| nodes_read) else {
+                    return Err(ApiError::from(ValidationError::new(
+                        "names",
+                        "Send `nodes` before `names`, and `names` once",
+                    )));
+                };
+                let mut names = nodes.names()?;
+                let mut lines = FieldLines::new(field, "names.dmp");
+                while let Some((number, line)) = lines.next().await? {
+                    if let Some(entry) = names.read_line(number, &line)? {
+                        import.push(entry).await?;
+                    }
+                }
+                names.finish()?;
+                names_read = true;
+            }
+            (TaxonomySource::Gtdb, "taxonomy") => {
+                let mut lines = FieldLines::new(field, "The taxonomy file");
+                while let Some((number, line)) = lines.next().await? {
+                    for entry in gtdb.read_line(number, &line)? {
+                        import.push(entry).await?;
+                    }
+                }
+                gtdb.finish()?;
+                taxonomy_read = true;
+            }
+            _ => continue,
+        }
+    }
+    match source {
+        TaxonomySource::Ncbi if !nodes_read => Err(missing("nodes")),
+        TaxonomySource::Ncbi if !names_read => Err(missing("names")),
+        TaxonomySource::Gtdb if !taxonomy_read => Err(missing("taxonomy")),
+        _ => Ok(()),
+    }
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/taxa/import",
+    params(ImportParams),
+    request_body(
+        content_type = "multipart/form-data",
+        description = "For NCBI, the taxdump's nodes.dmp and names.dmp in fields named `nodes` and `names`, in that order; for GTDB, a taxonomy file such as bac120_taxonomy.tsv in a field named `taxonomy`"
+    ),
+    responses(
+        (status = OK, description = "Taxa stored in place of the source's previous load", body = ImportSummary),
+        (status = BAD_REQUEST, description = "A file could not be read; the message names the line"),
+        (status = UNPROCESSABLE_ENTITY, description = "A file the source needs is missing or out of order")
+    )
+)]
+pub async fn import(
+    State(db): State<DatabaseConnection>,
+    Query(params): Query<ImportParams>,
+    mut multipart: Multipart,
+) -> Result<Json<ImportSummary>, ApiError> {
+    let mut import = services::Import::new(&db, params.source);
+    let summary = match stage(&mut import, params.source, &mut multipart).await {
+        Ok(()) => import.replace().await,
+        Err(err) => Err(err),
+    };
+    import.discard().await?;
+    Ok(Json(summary?))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/taxa/suggestions",
+    params(SuggestionParams),
+    responses(
+        (status = OK, description = "Loaded names close to the given one, closest first", body = [String])
+    )
+)]
+pub async fn get_suggestions(
+    State(db): State<DatabaseConnection>,
+    Query(params): Query<SuggestionParams>,
+) -> Result<Json<Vec<String>>, ApiError> {
+    Ok(Json(services::suggest(&db, &params.name).await?))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/taxa/unresolved",
+    responses(
+        (status = OK, description = "Isolates whose names do not resolve against the loaded taxonomies, by isolate name", body = [UnresolvedIsolate])
+    )
+)]
+pub async fn get_unresolved(
+    State(db): State<DatabaseConnection>,
+) -> Result<Json<Vec<UnresolvedIsolate>>, ApiError> {
+    Ok(Json(services::unresolved(&db).await?))
+}
@@ -0,0 +1,73 @@
+use chrono::{DateTime, Utc};
+use crudcrate::validation::{Validatable, ValidationError};
+use crudcrate::{CRUDResource, EntityToModels};
+use sea_orm::entity::prelude::*;
+use uuid::Uuid;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+#[sea_orm(table_name = "temperature_readings")]
+#[crudcrate(
+    generate_router,
+    api_struct = "TemperatureReading",
+    name_singular = "temperature_reading",
+    name_plural = "temperature_readings",
+    description = "Freezer temperature logger readings, usually imported from the logger's CSV export",
+    no_eq,
+    derive_partial_eq
+)]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    #[crudcrate(primary_key, filterable, exclude(update, create), on_create = Uuid::new_v4())]
+    pub id: Uuid,
+    /// The monitored freezer, fridge or room.
+    #[crudcrate(sortable, filterable, exclude(update))]
+    pub storage_unit_id: Uuid,
+    #[crudcrate(sortable, filterable, exclude(update))]
+    pub recorded_at: DateTime<Utc>,
+    #[crudcrate(sortable, filterable)]
+    pub temperature_celsius: f64,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "crate::storage_units::db::Entity",
+        from = "Column::StorageUnitId",
+        to = "crate::storage_units::db::Column::Id"
+    )]
+    StorageUnit,
+}
+
+impl Related<crate::storage_units::db::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::StorageUnit.def()
+    }
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+impl Validatable for TemperatureReadingCreate {
+    fn validate(&self) -> Result<(), ValidationError> {
+        if !self.temperature_celsius.is_finite() {
+            return Err(ValidationError::new(
+                "temperature_celsius",
+                "Temperature must be a number",
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl Validatable for TemperatureReadingUpdate {
+    fn validate(&self) -> Result<(), ValidationError> {
+        if let Some(Some(temperature)) = self.temperature_celsius {
+            if !temperature.is_finite() {
+                return Err(ValidationError::new(
+                    "temperature_celsius",
+                    "Temperature must be a number",
+                ));
+            }
+        }
+        Ok(())
+    }
+}
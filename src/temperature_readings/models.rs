@@ -0,0 +1,68 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+#[derive(Deserialize, IntoParams, Debug)]
+pub struct ImportParams {
+    /// The unit the logger monitors.
+    pub storage_unit_id: Uuid,
+    /// Offset from UTC of timestamps without one, e.g. 60 for CET (default 0).
+    pub utc_offset_minutes: Option<i32>,
+}
+
+#[derive(ToSchema, Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct ImportSummary {
+    pub storage_unit_id: Uuid,
+    /// Data rows read from the export.
+    pub rows: u64,
+    pub inserted: u64,
+    /// Rows already imported, or repeated within the export.
+    pub duplicates: u64,
+    pub first_reading_at: DateTime<Utc>,
+    pub last_reading_at: DateTime<Utc>,
+}
+
+#[derive(Deserialize, IntoParams, Debug)]
+pub struct ExcursionParams {
+    /// Only report this unit and the units inside it.
+    pub storage_unit_id: Option<Uuid>,
+    /// Only consider readings at or after this time.
+    pub from: Option<DateTime<Utc>>,
+    /// Only consider readings at or before this time.
+    pub to: Option<DateTime<Utc>>,
+}
+
+/// A sample, isolate or DNA extract stored in the unit during an excursion, in the
+/// slot it occupied then.
+#[derive(ToSchema, Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct AffectedItem {
+    /// The box holding the item.
+    pub storage_unit_id: Uuid,
+    pub path: String,
+    pub row: i32,
+    pub column: i32,
+    pub sample_id: Option<Uuid>,
+    pub isolate_id: Option<Uuid>,
+    pub dna_id: Option<Uuid>,
+    pub item_name: Option<String>,
+    pub stored_at: DateTime<Utc>,
+    /// When the item left the slot; `None` while it is still there.
+    pub removed_at: Option<DateTime<Utc>>,
+}
+
+/// A run of consecutive readings above the unit's threshold.
+#[derive(ToSchema, Deserialize, Serialize, Debug, Clone, PartialEq)]
+pub struct Excursion {
+    pub storage_unit_id: Uuid,
+    pub path: String,
+    pub threshold_celsius: f64,
+    /// First reading above the threshold.
+    pub started_at: DateTime<Utc>,
+    /// First reading back at or below the threshold; `None` while ongoing.
+    pub ended_at: Option<DateTime<Utc>>,
+    pub peak_celsius: f64,
+    /// Number of readings above the threshold.
+    pub readings: u64,
+    pub items: Vec<AffectedItem>,
+}
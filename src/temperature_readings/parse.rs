@@ -0,0 +1,193 @@
+//! Reader for temperature logger CSV exports.
+//!
+//! Logger software differs in delimiter, decimal mark, date format and the
+//! preamble it writes above the data, so the header row is found by its column
+//! names: one column naming the time (or separate date and time columns) and
+//! one naming the temperature.
+
+use chrono::{DateTime, Duration, NaiveDateTime, SubsecRound, Utc};
+use crudcrate::ApiError;
+
+/// Lines searched for the header row.
+const MAX_PREAMBLE_LINES: usize = 50;
+
+/// Formats of timestamps without an offset. Month-first dates are ambiguous and
+/// not accepted.
+const NAIVE_FORMATS: [&str; 6] = [
+    "%Y-%m-%d %H:%M:%S%.f",
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%dT%H:%M:%S%.f",
+    "%Y-%m-%dT%H:%M",
+    "%d.%m.%Y %H:%M:%S%.f",
+    "%d.%m.%Y %H:%M",
+];
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Reading {
+    pub recorded_at: DateTime<Utc>,
+    pub temperature_celsius: f64,
+}
+
+struct Header {
+    delimiter: u8,
+    date: usize,
+    /// Set when date and time are separate columns.
+    time: Option<usize>,
+    temperature: usize,
+    fahrenheit: bool,
+}
+
+fn reader(text: &str, delimiter: u8) -> csv::Reader<&[u8]> {
+    csv::ReaderBuilder::new()
+        .delimiter(delimiter)
+        .has_headers(false)
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(text.as_bytes())
+}
+
+/// Fields of one line, honouring double quotes.
+fn split(line: &str, delimiter: u8) -> Vec<String> {
+    reader(line, delimiter)
+        .records()
+        .next()
+        .and_then(Result::ok)
+        .map(|record| record.iter().map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+fn delimiter(line: &str) -> u8 {
+    if line.contains('\t') {
+        b'\t'
+    } else if line.contains(';') {
+        b';'
+    } else {
+        b','
+    }
+}
+
+fn header(line: &str) -> Option<Header> {
+    let delimiter = delimiter(line);
+    let names: Vec<String> = split(line, delimiter)
+        .iter()
+        .map(|name| name.to_lowercase())
+        .collect();
+    let position = |matches: &dyn Fn(&str) -> bool| names.iter().position(|name| matches(name));
+
+    let temperature = position(&|name| {
+        name.contains("temp") || name.contains("celsius") || name.contains("fahrenheit")
+    })?;
+    let (date, time) = match position(&|name| {
+        name.contains("timestamp") || (name.contains("date") && name.contains("time"))
+    }) {
+        Some(column) => (column, None),
+        None => match (
+            position(&|name| name.contains("date")),
+            position(&|name| name.contains("time")),
+        ) {
+            (Some(date), Some(time)) => (date, Some(time)),
+            (Some(column), None) | (None, Some(column)) => (column, None),
+            (None, None) => return None,
+        },
+    };
+    let unit = &names[temperature];
+    let fahrenheit = unit.contains("°f") || unit.contains("fahrenheit") || unit.contains("(f)");
+    Some(Header {
+        delimiter,
+        date,
+        time,
+        temperature,
+        fahrenheit,
+    })
+}
+
+fn timestamp(value: &str, utc_offset_minutes: i32) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    if let Ok(parsed) = DateTime::parse_from_rfc3339(value) {
+        return Some(parsed.with_timezone(&Utc));
+    }
+    let value = value.replace('/', "-");
+    NAIVE_FORMATS
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(&value, format).ok())
+        .map(|naive| naive.and_utc() - Duration::minutes(utc_offset_minutes.into()))
+}
+
+/// A temperature in °C. Accepts decimal commas, the Unicode minus sign and a
+/// trailing unit.
+fn temperature(value: &str, fahrenheit: bool) -> Option<f64> {
+    let value = value.trim().replace('\u{2212}', "-").replace(',', ".");
+    let upper = value.to_uppercase();
+    let (number, fahrenheit) = if let Some(number) = upper.strip_suffix('F') {
+        (number, true)
+    } else if let Some(number) = upper.strip_suffix('C') {
+        (number, false)
+    } else {
+        (upper.as_str(), fahrenheit)
+    };
+    let number: f64 = number.trim().trim_end_matches('°').trim().parse().ok()?;
+    if !number.is_finite() {
+        return None;
+    }
+    Some(if fahrenheit {
+        (number - 32.0) * 5.0 / 9.0
+    } else {
+        number
+    })
+}
+
+/// Readings in file order. Timestamps without an offset are taken to be
+/// `utc_offset_minutes` ahead of UTC and are kept to microsecond precision, as
+/// stored by the database.
+pub fn readings(text: &str, utc_offset_minutes: i32) -> Result<Vec<Reading>, ApiError> {
+    let lines: Vec<&str> = text.trim_start_matches('\u{feff}').lines().collect();
+    let (start, header) = lines
+        .iter()
+        .take(MAX_PREAMBLE_LINES)
+        .enumerate()
+        .find_map(|(index, line)| header(line).map(|header| (index + 1, header)))
+        .ok_or_else(|| {
+            ApiError::bad_request("No header row with a time and a temperature column found")
+        })?;
+
+    let body = lines[start..].join("\n");
+    let number = |position: Option<&csv::Position>| {
+        start + position.map_or(0, |position| position.line() as usize)
+    };
+    let mut readings = Vec::new();
+    for record in reader(&body, header.delimiter).records() {
+        let record = record.map_err(|err| {
+            ApiError::bad_request(format!("Line {}: {err}", number(err.position())))
+        })?;
+        let number = number(record.position());
+        let field = |column: usize| record.get(column).unwrap_or("");
+        let when = match header.time {
+            Some(time) => format!("{} {}", field(header.date), field(time)),
+            None => field(header.date).to_string(),
+        };
+        if when.trim().is_empty() && field(header.temperature).is_empty() {
+            continue;
+        }
+        let recorded_at = timestamp(&when, utc_offset_minutes).ok_or_else(|| {
+            ApiError::bad_request(format!(
+                "Line {number}: cannot read the timestamp '{}'",
+                when.trim()
+            ))
+        })?;
+        let temperature_celsius = temperature(field(header.temperature), header.fahrenheit)
+            .ok_or_else(|| {
+                ApiError::bad_request(format!(
+                    "Line {number}: cannot read the temperature '{}'",
+                    field(header.temperature)
+                ))
+            })?;
+        readings.push(Reading {
+            recorded_at: recorded_at.trunc_subsecs(6),
+            temperature_celsius,
+        });
+    }
+    if readings.is_empty() {
+        return Err(ApiError::bad_request("The export contains no readings"));
+    }
+    Ok(readings)
+}
@@ -0,0 +1,224 @@
+use chrono::{DateTime, Utc};
+use crudcrate::validation::ValidationError;
+use crudcrate::ApiError;
+use sea_orm::{
+    ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder, Set, TransactionTrait,
+};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+use super::db::{ActiveModel, Column, Entity, Model};
+use super::models::{AffectedItem, Excursion, ExcursionParams, ImportSummary};
+use super::parse;
+use crate::storage_positions;
+use crate::storage_units::{self, services::is_within};
+
+/// Rows per INSERT, well under the bind parameter limits.
+const INSERT_CHUNK: usize = 500;
+
+/// Largest offset from UTC in use, in minutes (UTC+14).
+const MAX_UTC_OFFSET_MINUTES: i32 = 14 * 60;
+
+/// The unit's own `max_temperature_celsius`, or its nearest ancestor's.
+fn threshold(units: &HashMap<Uuid, storage_units::db::Model>, mut id: Uuid) -> Option<f64> {
+    for _ in 0..4 {
+        let unit = units.get(&id)?;
+        if let Some(max) = unit.max_temperature_celsius {
+            return Some(max);
+        }
+        id = unit.parent_id?;
+    }
+    None
+}
+
+/// Stores the readings of a logger export, skipping those already on record so
+/// overlapping exports can be imported as they come.
+pub async fn import(
+    db: &DatabaseConnection,
+    storage_unit_id: Uuid,
+    utc_offset_minutes: i32,
+    text: &str,
+) -> Result<ImportSummary, ApiError> {
+    if !(-MAX_UTC_OFFSET_MINUTES..=MAX_UTC_OFFSET_MINUTES).contains(&utc_offset_minutes) {
+        return Err(ValidationError::new(
+            "utc_offset_minutes",
+            format!("Offset must be within ±{MAX_UTC_OFFSET_MINUTES} minutes"),
+        )
+        .into());
+    }
+    storage_units::db::Entity::find_by_id(storage_unit_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| ApiError::not_found("storage_unit", Some(storage_unit_id.to_string())))?;
+
+    let readings = parse::readings(text, utc_offset_minutes)?;
+    let first_reading_at = readings.iter().map(|r| r.recorded_at).min().unwrap();
+    let last_reading_at = readings.iter().map(|r| r.recorded_at).max().unwrap();
+
+    let mut seen: HashSet<DateTime<Utc>> = Entity::find()
+        .filter(Column::StorageUnitId.eq(storage_unit_id))
+        .filter(Column::RecordedAt.between(first_reading_at, last_reading_at))
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|reading| reading.recorded_at)
+        .collect();
+    let new: Vec<ActiveModel> = readings
+        .iter()
+        .filter(|reading| seen.insert(reading.recorded_at))
+        .map(|reading| ActiveModel {
+            id: Set(Uuid::new_v4()),
+            storage_unit_id: Set(storage_unit_id),
+            recorded_at: Set(reading.recorded_at),
+            temperature_celsius: Set(reading.temperature_celsius),
+        })
+        .collect();
+    let inserted = new.len() as u64;
+
+    let txn = db.begin().await?;
+    for chunk in new.chunks(INSERT_CHUNK) {
+        Entity::insert_many(chunk.to_vec()).exec(&txn).await?;
+    }
+    txn.commit().await?;
+
+    Ok(ImportSummary {
+        storage_unit_id,
+        rows: readings.len() as u64,
+        inserted,
+        duplicates: readings.len() as u64 - inserted,
+        first_reading_at,
+        last_reading_at,
+    })
+}
+
+/// Splits one unit's readings, in time order, into runs above `threshold`.
+fn runs(readings: &[Model], threshold: f64) -> Vec<(Vec<&Model>, Option<DateTime<Utc>>)> {
+    let mut runs = Vec::new();
+    let mut current: Vec<&Model> = Vec::new();
+    for reading in readings {
+        if reading.temperature_celsius > threshold {
+            current.push(reading);
+        } else if !current.is_empty() {
+            runs.push((std::mem::take(&mut current), Some(reading.recorded_at)));
+        }
+    }
+    if !current.is_empty() {
+        runs.push((current, None));
+    }
+    runs
+}
+
+/// Excursions of every monitored unit in scope, oldest first, each with the
+/// items stored beneath the unit at some point during it, from the position
+/// history. Items since moved or shipped are listed in the slot they had then;
+/// free-text `storage_location`s are not matched.
+pub async fn excursions(
+    db: &DatabaseConnection,
+    params: &ExcursionParams,
+) -> Result<Vec<Excursion>, ApiError> {
+    let units = storage_units::services::all_units(db).await?;
+    if let Some(id) = params.storage_unit_id {
+        if !units.contains_key(&id) {
+            return Err(ApiError::not_found("storage_unit", Some(id.to_string())));
+        }
+    }
+    let thresholds: HashMap<Uuid, f64> = units
+        .keys()
+        .filter(|id| {
+            params
+                .storage_unit_id
+                .is_none_or(|scope| is_within(&units, **id, scope))
+        })
+        .filter_map(|id| threshold(&units, *id).map(|max| (*id, max)))
+        .collect();
+    if params.storage_unit_id.is_some() && thresholds.is_empty() {
+        return Err(ValidationError::new(
+            "storage_unit_id",
+            "Set max_temperature_celsius on the storage unit or one of its parents",
+        )
+        .into());
+    }
+
+    let mut query = Entity::find().filter(Column::StorageUnitId.is_in(thresholds.keys().copied()));
+    if let Some(from) = params.from {
+        query = query.filter(Column::RecordedAt.gte(from));
+    }
+    if let Some(to) = params.to {
+        query = query.filter(Column::RecordedAt.lte(to));
+    }
+    let mut by_unit: HashMap<Uuid, Vec<Model>> = HashMap::new();
+    for reading in query.order_by_asc(Column::RecordedAt).all(db).await? {
+        by_unit
+            .entry(reading.storage_unit_id)
+            .or_default()
+            .push(reading);
+    }
+
+    let monitored: Vec<Uuid> = units
+        .keys()
+        .filter(|id| thresholds.keys().any(|unit| is_within(&units, **id, *unit)))
+        .copied()
+        .collect();
+    let placements = storage_positions::history::Entity::find()
+        .filter(storage_positions::history::Column::StorageUnitId.is_in(monitored))
+        .all(db)
+        .await?;
+    let names = storage_units::services::item_names(
+        db,
+        placements
+            .iter()
+            .map(|p| (p.sample_id, p.isolate_id, p.dna_id)),
+    )
+    .await?;
+    let now = Utc::now();
+
+    let mut excursions = Vec::new();
+    for (unit_id, readings) in &by_unit {
+        let threshold_celsius = thresholds[unit_id];
+        let path = storage_units::services::path(&units, *unit_id);
+        for (above, ended_at) in runs(readings, threshold_celsius) {
+            let started_at = above[0].recorded_at;
+            let until = ended_at.unwrap_or(now);
+            let mut items: Vec<AffectedItem> = placements
+                .iter()
+                .filter(|p| {
+                    p.stored_at <= until
+                        && p.removed_at
+                            .is_none_or(|removed_at| removed_at > started_at)
+                        && is_within(&units, p.storage_unit_id, *unit_id)
+                })
+                .map(|p| AffectedItem {
+                    storage_unit_id: p.storage_unit_id,
+                    path: storage_units::services::path(&units, p.storage_unit_id),
+                    row: p.row,
+                    column: p.column,
+                    sample_id: p.sample_id,
+                    isolate_id: p.isolate_id,
+                    dna_id: p.dna_id,
+                    item_name: [p.sample_id, p.isolate_id, p.dna_id]
+                        .into_iter()
+                        .flatten()
+                        .find_map(|id| names.get(&id).cloned()),
+                    stored_at: p.stored_at,
+                    removed_at: p.removed_at,
+                })
+                .collect();
+            items.sort_by(|a, b| (&a.path, a.row, a.column).cmp(&(&b.path, b.row, b.column)));
+            excursions.push(Excursion {
+                storage_unit_id: *unit_id,
+                path: path.clone(),
+                threshold_celsius,
+                started_at,
+                ended_at,
+                peak_celsius: above
+                    .iter()
+                    .map(|r| r.temperature_celsius)
+                    .fold(f64::NEG_INFINITY, f64::max),
+                readings: above.len() as u64,
+                items,
+            });
+        }
+    }
+    excursions.sort_by(|a, b| (a.started_at, &a.path).cmp(&(b.started_at, &b.path)));
+    Ok(excursions)
+}
@@ -0,0 +1,217 @@
+use axum::{
+    body::{to_bytes, Body},
+    http::{Request, StatusCode},
+};
+use chrono::{Duration, Utc};
+use serde_json::{json, Value};
+use tower::ServiceExt;
+
+use crate::test_utils::{
+    build_app_with_db, build_scoped_app_with_db, create, create_field_record, create_site, get,
+    send, setup_sqlite_db,
+};
+
+/// Readings every 10 minutes over the past two hours, in CET: two excursions above
+/// -70 °C, one that ended about 80 minutes ago and one still ongoing.
+const TEMPERATURES: [&str; 12] = [
+    "-80,1",
+    "-79,8",
+    "-68,5",
+    "-64,0",
+    "-79,0",
+    "-80,2",
+    "-80,0",
+    "-80,3",
+    "-80,1",
+    "-79,9",
+    "-75,0",
+    "\u{2212}69,5 °C",
+];
+
+/// A logger export with a preamble, semicolons, decimal commas and local CET
+/// timestamps.
+fn logger_export() -> String {
+    let now = Utc::now();
+    let mut csv =
+        String::from("\u{feff}Logger;FRZ-C-01\nSerial;12345\n\nDate/Time (CET);Temperature (°C)\n");
+    for (index, temperature) in TEMPERATURES.iter().enumerate() {
+        let at = now - Duration::minutes(10 * (12 - index as i64)) + Duration::minutes(60);
+        csv.push_str(&format!(
+            "{};{temperature}\n",
+            at.format("%d.%m.%Y %H:%M:%S")
+        ));
+    }
+    csv
+}
+
+async fn import(app: &axum::Router, storage_unit_id: &str, csv: String) -> (StatusCode, Value) {
+    let req = Request::builder()
+        .method("POST")
+        .uri(format!(
+            "/api/temperature_readings/import?storage_unit_id={storage_unit_id}&utc_offset_minutes=60"
+        ))
+        .header("Content-Type", "text/csv")
+        .body(Body::from(csv))
+        .unwrap();
+    let resp = app.clone().oneshot(req).await.unwrap();
+    let status = resp.status();
+    let body = to_bytes(resp.into_body(), 1024 * 1024).await.unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+/// `<name> / Rack 1 / Box A`, a 2 x 2 box, and the ids of the freezer, rack and box.
+async fn freezer_with_box(
+    app: &axum::Router,
+    name: &str,
+    max_temperature_celsius: Option<f64>,
+) -> (String, String, String) {
+    let freezer = create(
+        app,
+        "/api/storage_units",
+        json!({ "kind": "Freezer", "name": name, "max_temperature_celsius": max_temperature_celsius }),
+    )
+    .await;
+    let rack = create(
+        app,
+        "/api/storage_units",
+        json!({ "kind": "Rack", "name": "Rack 1", "parent_id": freezer }),
+    )
+    .await;
+    let box_id = create(
+        app,
+        "/api/storage_units",
+        json!({ "kind": "Box", "name": "Box A", "parent_id": rack, "rows": 2, "columns": 2 }),
+    )
+    .await;
+    (freezer, rack, box_id)
+}
+
+/// Sample S-1 from snow field record FR-1, stored in slot 1/1 of `box_id`.
+async fn stored_sample(app: &axum::Router, box_id: &str) -> String {
+    let site_id = create_site(app, "Glacier A").await;
+    let fr = create_field_record(app, &site_id, "FR-1").await;
+    let sample = create(
+        app,
+        "/api/samples",
+        json!({ "name": "S-1", "field_record_id": fr }),
+    )
+    .await;
+    create(
+        app,
+        "/api/storage_positions",
+        json!({ "storage_unit_id": box_id, "row": 1, "column": 1, "sample_id": sample }),
+    )
+    .await;
+    sample
+}
+
+async fn excursions(app: &axum::Router, storage_unit_id: &str) -> Vec<Value> {
+    let (status, body) = get(
+        app,
+        &format!("/api/temperature_readings/excursions?storage_unit_id={storage_unit_id}"),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+    body.as_array().unwrap().clone()
+}
+
+/// Scenario: the same logger export is imported twice, then a file that is not one.
+/// Expected behaviour: the readings import once, re-importing skips them all, and
+/// the unreadable file is a 400.
+#[tokio::test]
+async fn logger_exports_import_each_reading_once() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db);
+    let (freezer, _, _) = freezer_with_box(&app, "Freezer C", Some(-70.0)).await;
+
+    let (status, summary) = import(&app, &freezer, logger_export()).await;
+    assert_eq!(status, StatusCode::OK, "{summary}");
+    assert_eq!(summary["rows"], 12);
+    assert_eq!(summary["inserted"], 12);
+    let (_, summary) = import(&app, &freezer, logger_export()).await;
+    assert_eq!(summary["inserted"], 0);
+    assert_eq!(summary["duplicates"], 12);
+    let (status, _) = import(
+        &app,
+        &freezer,
+        "Time,Temp\n2026-10-01 12:00,warm\n".to_string(),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+/// Scenario: a -80 °C freezer (threshold -70 °C) logs an excursion that ended
+/// before a sample was boxed and one still ongoing.
+/// Expected behaviour: the report lists the sample only against the ongoing
+/// excursion; racks inherit the freezer's threshold and none of it is public.
+#[tokio::test]
+async fn excursions_list_the_items_stored_during_them() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db.clone());
+    let scoped = build_scoped_app_with_db(db);
+    let (freezer, rack, box_a) = freezer_with_box(&app, "Freezer C", Some(-70.0)).await;
+    import(&app, &freezer, logger_export()).await;
+    let sample = stored_sample(&app, &box_a).await;
+
+    // The rack inherits the freezer's threshold.
+    excursions(&app, &rack).await;
+    let report = excursions(&app, &freezer).await;
+    assert_eq!(report.len(), 2, "{report:?}");
+
+    let ended = &report[0];
+    assert_eq!(ended["path"], "Freezer C");
+    assert_eq!(ended["threshold_celsius"].as_f64(), Some(-70.0));
+    assert_eq!(ended["readings"], 2);
+    assert_eq!(ended["peak_celsius"].as_f64(), Some(-64.0));
+    assert!(ended["ended_at"].is_string());
+    assert!(ended["items"].as_array().unwrap().is_empty(), "boxed later");
+
+    let ongoing = &report[1];
+    assert!(ongoing["ended_at"].is_null());
+    assert_eq!(ongoing["peak_celsius"].as_f64(), Some(-69.5));
+    let items = ongoing["items"].as_array().unwrap();
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0]["sample_id"], sample.as_str());
+    assert_eq!(items[0]["item_name"], "S-1");
+    assert_eq!(items[0]["path"], "Freezer C / Rack 1 / Box A");
+    assert!(items[0]["removed_at"].is_null());
+
+    let (status, _) = get(&scoped, "/api/temperature_readings/excursions").await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+/// Scenario: during the ongoing excursion the sample is moved to another freezer.
+/// Expected behaviour: the excursion still lists it in the slot it had, with the
+/// time it left; the other freezer's box shows it now.
+#[tokio::test]
+async fn items_moved_away_during_an_excursion_are_still_listed() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db);
+    let (freezer, _, box_a) = freezer_with_box(&app, "Freezer C", Some(-70.0)).await;
+    let (_, _, box_d) = freezer_with_box(&app, "Freezer D", None).await;
+    import(&app, &freezer, logger_export()).await;
+    let sample = stored_sample(&app, &box_a).await;
+
+    let (status, body) = send(
+        &app,
+        "POST",
+        "/api/storage_positions/move",
+        json!({ "sample_id": sample, "storage_unit_id": box_d, "row": 2, "column": 2 }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{body}");
+
+    let report = excursions(&app, &freezer).await;
+    let items = report[1]["items"].as_array().unwrap();
+    assert_eq!(items.len(), 1, "{report:?}");
+    assert_eq!(items[0]["sample_id"], sample.as_str());
+    assert_eq!(items[0]["path"], "Freezer C / Rack 1 / Box A");
+    assert_eq!(
+        (items[0]["row"].as_i64(), items[0]["column"].as_i64()),
+        (Some(1), Some(1))
+    );
+    assert!(items[0]["removed_at"].is_string());
+
+    let (_, grid) = get(&app, &format!("/api/storage_units/{box_d}/grid")).await;
+    assert_eq!(grid["slots"][3]["item_name"], "S-1");
+}
@@ -0,0 +1,63 @@
+use axum::extract::{Query, State};
+use axum::{
+    routing::{get, post},
+    Json, Router,
+};
+use crudcrate::ApiError;
+use sea_orm::DatabaseConnection;
+
+use super::models::{Excursion, ExcursionParams, ImportParams, ImportSummary};
+use super::services;
+
+/// Routes mounted next to the generated CRUD router under `/api/temperature_readings`.
+pub fn router(db: &DatabaseConnection) -> Router {
+    Router::new()
+        .route("/import", post(import))
+        .route("/excursions", get(get_excursions))
+        .with_state(db.clone())
+}
+
+#[utoipa::path(
+    post,
+    path = "/api/temperature_readings/import",
+    params(ImportParams),
+    request_body(content = String, content_type = "text/csv", description = "Temperature logger CSV export"),
+    responses(
+        (status = OK, description = "Readings stored, with those already on record skipped", body = ImportSummary),
+        (status = BAD_REQUEST, description = "The export could not be read; the message names the line"),
+        (status = NOT_FOUND, description = "Storage unit not found"),
+        (status = UNPROCESSABLE_ENTITY, description = "utc_offset_minutes is out of range")
+    )
+)]
+pub async fn import(
+    State(db): State<DatabaseConnection>,
+    Query(params): Query<ImportParams>,
+    body: String,
+) -> Result<Json<ImportSummary>, ApiError> {
+    Ok(Json(
+        services::import(
+            &db,
+            params.storage_unit_id,
+            params.utc_offset_minutes.unwrap_or(0),
+            &body,
+        )
+        .await?,
+    ))
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/temperature_readings/excursions",
+    params(ExcursionParams),
+    responses(
+        (status = OK, description = "Excursions above the configured thresholds, oldest first, with the items stored in the unit", body = Vec<Excursion>),
+        (status = NOT_FOUND, description = "Storage unit not found"),
+        (status = UNPROCESSABLE_ENTITY, description = "No threshold is set for the storage unit")
+    )
+)]
+pub async fn get_excursions(
+    State(db): State<DatabaseConnection>,
+    Query(params): Query<ExcursionParams>,
+) -> Result<Json<Vec<Excursion>>, ApiError> {
+    Ok(Json(services::excursions(&db, &params).await?))
+}
@@ -1,14 +1,42 @@
 use crate::{
-    areas::db::Area as area_views, common::views as common_views, config::Config,
-    dna::db::DNA as dna_views, field_records::db::FieldRecord as fr_views,
-    isolates::db::Isolate as iso_views, middleware, samples::db::Sample as samp_views,
-    sites::db::Site as sites_views,
+    areas::db::Area as area_views, campaigns, campaigns::db::Campaign as campaign_views,
+    common::views as common_views, config::{Config, FreezeThawLimit},
+    culture_accessions, culture_accessions::db::CultureAccession as accession_views, custody_events,
+    custody_events::db::CustodyEvent as custody_views,
+    dna::db::DNA as dna_views, field_records, field_records::db::FieldRecord as fr_views,
+    growth_tests, growth_tests::db::GrowthTest as growth_views,
+    isolate_images, isolate_images::db::IsolateImage as image_views, isolates,
+    isolates::db::Isolate as iso_views, labels, marker_sequences,
+    marker_sequences::db::MarkerSequence as marker_views, material_requests,
+    measurement_thresholds::db::MeasurementThreshold as threshold_views, middleware,
+    parameter_values::db::ParameterValue as pv_views, parameters::db::Parameter as param_views,
+    replicate_groups, replicate_groups::db::ReplicateGroup as rg_views,
+    sample_type_measurements::db::SampleTypeMeasurement as reserved_measurement_views,
+    sample_types::db::SampleType as sample_type_views,
+    shipment_items::db::ShipmentItem as shipment_item_views, shipments,
+    shipments::db::Shipment as shipment_views,
+    samples, samples::db::Sample as samp_views,
+    sites::db::Site as sites_views, storage_positions,
+    storage_positions::db::StoragePosition as position_views, storage_units,
+    storage_units::db::StorageUnit as storage_unit_views, taxa, taxa::db::Taxon as taxon_views,
+    temperature_readings,
+    temperature_readings::db::TemperatureReading as temperature_views, withdrawals,
+    withdrawals::db::Withdrawal as withdrawal_views,
 };
-use axum::{routing::get, Router};
+use axum::body::{to_bytes, Body};
+use axum::http::{Request, StatusCode};
+use axum::{routing, Router};
 use axum_keycloak_auth::{instance::KeycloakAuthInstance, instance::KeycloakConfig, Url};
 use migration::{Migrator, MigratorTrait};
-use sea_orm::{ConnectionTrait, Database, DatabaseConnection, DbBackend, Schema, Statement};
-use std::sync::Arc;
+use object_store::memory::InMemory;
+use object_store::ObjectStore;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, Database, DatabaseConnection, DbBackend,
+    EntityTrait, QueryFilter, Schema, Set, Statement,
+};
+use serde_json::{json, Value};
+use std::sync::{Arc, OnceLock};
+use tower::ServiceExt;
 
 pub async fn setup_clean_db() -> DatabaseConnection {
     let config = Config::from_env();
@@ -19,7 +47,7 @@ pub async fn setup_clean_db() -> DatabaseConnection {
 
     let truncate_query = Statement::from_string(
         DbBackend::Postgres,
-        "TRUNCATE TABLE samples, isolates, field_records, dna, sites, areas RESTART IDENTITY CASCADE;"
+        "TRUNCATE TABLE samples, isolates, field_records, dna, sites, areas, campaigns, replicate_groups, parameter_values, parameters, measurement_thresholds, sample_type_measurements, storage_positions, storage_position_history, storage_units, material_requests, withdrawals, custody_events, shipments, shipment_items, temperature_readings, isolate_images, file_objects, taxa, taxa_staging, growth_tests, marker_sequences, culture_accessions RESTART IDENTITY CASCADE;"
             .to_owned(),
     );
     db.execute(truncate_query).await.unwrap();
@@ -39,12 +67,35 @@ pub async fn setup_sqlite_db() -> DatabaseConnection {
     let schema = Schema::new(backend);
 
     let tables: Vec<sea_orm::sea_query::TableCreateStatement> = vec![
+        schema.create_table_from_entity(crate::sample_types::db::Entity),
+        schema.create_table_from_entity(crate::sample_type_measurements::db::Entity),
         schema.create_table_from_entity(crate::areas::db::Entity),
         schema.create_table_from_entity(crate::sites::db::Entity),
+        schema.create_table_from_entity(crate::campaigns::db::Entity),
+        schema.create_table_from_entity(crate::replicate_groups::db::Entity),
         schema.create_table_from_entity(crate::field_records::db::Entity),
         schema.create_table_from_entity(crate::samples::db::Entity),
+        schema.create_table_from_entity(crate::files::db::Entity),
         schema.create_table_from_entity(crate::isolates::db::Entity),
+        schema.create_table_from_entity(crate::isolate_images::db::Entity),
+        schema.create_table_from_entity(crate::growth_tests::db::Entity),
+        schema.create_table_from_entity(crate::marker_sequences::db::Entity),
+        schema.create_table_from_entity(crate::culture_accessions::db::Entity),
         schema.create_table_from_entity(crate::dna::db::Entity),
+        schema.create_table_from_entity(crate::parameters::db::Entity),
+        schema.create_table_from_entity(crate::parameter_values::db::Entity),
+        schema.create_table_from_entity(crate::measurement_thresholds::db::Entity),
+        schema.create_table_from_entity(crate::storage_units::db::Entity),
+        schema.create_table_from_entity(crate::storage_positions::db::Entity),
+        schema.create_table_from_entity(crate::storage_positions::history::Entity),
+        schema.create_table_from_entity(crate::material_requests::db::Entity),
+        schema.create_table_from_entity(crate::withdrawals::db::Entity),
+        schema.create_table_from_entity(crate::custody_events::db::Entity),
+        schema.create_table_from_entity(crate::shipments::db::Entity),
+        schema.create_table_from_entity(crate::shipment_items::db::Entity),
+        schema.create_table_from_entity(crate::temperature_readings::db::Entity),
+        schema.create_table_from_entity(crate::taxa::db::Entity),
+        schema.create_table_from_entity(crate::taxa::staging::Entity),
     ];
 
     for stmt in tables {
@@ -53,10 +104,57 @@ pub async fn setup_sqlite_db() -> DatabaseConnection {
             .expect("Failed to create table");
     }
 
+    // The migration seeds these on Postgres; most tests record Snow and Soil.
+    for name in ["Snow", "Soil"] {
+        crate::sample_types::db::ActiveModel {
+            id: Set(uuid::Uuid::new_v4()),
+            name: Set(name.to_string()),
+            description: Set(None),
+            envo_id: Set(None),
+            envo_label: Set(None),
+            created_at: Set(chrono::Utc::now()),
+        }
+        .insert(&db)
+        .await
+        .expect("Failed to seed sample types");
+    }
+    let snow = crate::sample_types::db::Entity::find()
+        .filter(crate::sample_types::db::Column::Name.eq("Snow"))
+        .one(&db)
+        .await
+        .unwrap()
+        .unwrap();
+    for column_name in ["snow_depth_cm", "snow_temperature_celsius"] {
+        crate::sample_type_measurements::db::ActiveModel {
+            id: Set(uuid::Uuid::new_v4()),
+            sample_type_id: Set(snow.id),
+            column_name: Set(column_name.to_string()),
+            created_at: Set(chrono::Utc::now()),
+        }
+        .insert(&db)
+        .await
+        .expect("Failed to seed sample type measurements");
+    }
+
     db
 }
 
+/// One in-memory file store for the test process, so the admin and public apps
+/// built on a database see the same files.
+fn test_file_store() -> Arc<dyn ObjectStore> {
+    static STORE: OnceLock<Arc<dyn ObjectStore>> = OnceLock::new();
+    STORE.get_or_init(|| Arc::new(InMemory::new())).clone()
+}
+
 pub fn build_app_with_db(db: DatabaseConnection) -> Router {
+    build_app_with_freeze_thaw_limit(db, FreezeThawLimit::default())
+}
+
+pub fn build_app_with_freeze_thaw_limit(
+    db: DatabaseConnection,
+    freeze_thaw: FreezeThawLimit,
+) -> Router {
+    let store = test_file_store();
     Router::new()
         .route("/healthz", axum::routing::get(common_views::healthz))
         .route(
@@ -67,12 +165,150 @@ pub fn build_app_with_db(db: DatabaseConnection) -> Router {
         .nest("/api/sites", sites_views::router(&db).split_for_parts().0)
         .nest(
             "/api/field_records",
-            fr_views::router(&db).split_for_parts().0,
+            fr_views::router(&db)
+                .split_for_parts()
+                .0
+                .merge(field_records::views::router(&db)),
         )
         .nest("/api/dna", dna_views::router(&db).split_for_parts().0)
-        .nest("/api/isolates", iso_views::router(&db).split_for_parts().0)
-        .nest("/api/samples", samp_views::router(&db).split_for_parts().0)
+        .nest(
+            "/api/isolates",
+            iso_views::router(&db)
+                .split_for_parts()
+                .0
+                .merge(isolates::views::router(&db, &store))
+                .layer(axum::middleware::from_fn_with_state(
+                    (db.clone(), store.clone()),
+                    middleware::remove_orphaned_files,
+                )),
+        )
+        .nest(
+            "/api/isolate_images",
+            image_views::router(&db)
+                .split_for_parts()
+                .0
+                .merge(isolate_images::views::router(&db, &store))
+                .layer(axum::middleware::from_fn_with_state(
+                    (db.clone(), store.clone()),
+                    middleware::remove_orphaned_files,
+                )),
+        )
+        .nest(
+            "/api/growth_tests",
+            growth_views::router(&db).split_for_parts().0,
+        )
+        .nest(
+            "/api/culture_accessions",
+            accession_views::router(&db).split_for_parts().0,
+        )
+        .nest(
+            "/api/marker_sequences",
+            marker_views::router(&db)
+                .split_for_parts()
+                .0
+                .merge(marker_sequences::views::router(&db)),
+        )
+        .nest(
+            "/api/samples",
+            samp_views::router(&db)
+                .split_for_parts()
+                .0
+                .merge(samples::views::router(&db)),
+        )
         .nest("/api/areas", area_views::router(&db).split_for_parts().0)
+        .nest(
+            "/api/campaigns",
+            campaign_views::router(&db)
+                .split_for_parts()
+                .0
+                .merge(campaigns::views::router(&db)),
+        )
+        .nest("/api/parameters", param_views::router(&db).split_for_parts().0)
+        .nest(
+            "/api/parameter_values",
+            pv_views::router(&db).split_for_parts().0,
+        )
+        .nest(
+            "/api/replicate_groups",
+            rg_views::router(&db)
+                .split_for_parts()
+                .0
+                .merge(replicate_groups::views::router(&db)),
+        )
+        .nest(
+            "/api/measurement_thresholds",
+            threshold_views::router(&db).split_for_parts().0,
+        )
+        .nest(
+            "/api/sample_types",
+            sample_type_views::router(&db).split_for_parts().0,
+        )
+        .nest(
+            "/api/sample_type_measurements",
+            reserved_measurement_views::router(&db).split_for_parts().0,
+        )
+        .nest(
+            "/api/storage_units",
+            storage_unit_views::router(&db)
+                .split_for_parts()
+                .0
+                .merge(storage_units::views::router(&db)),
+        )
+        .nest(
+            "/api/storage_positions",
+            position_views::router(&db)
+                .split_for_parts()
+                .0
+                .merge(storage_positions::views::router(&db)),
+        )
+        .nest(
+            "/api/material_requests",
+            material_requests::views::router(&db, freeze_thaw),
+        )
+        .nest(
+            "/api/withdrawals",
+            withdrawal_views::router(&db)
+                .split_for_parts()
+                .0
+                .merge(withdrawals::views::router(&db)),
+        )
+        .nest(
+            "/api/custody_events",
+            custody_views::router(&db)
+                .split_for_parts()
+                .0
+                .merge(custody_events::views::router(&db)),
+        )
+        .nest(
+            "/api/shipments",
+            shipment_views::router(&db)
+                .split_for_parts()
+                .0
+                .merge(shipments::views::router(&db)),
+        )
+        .nest(
+            "/api/shipment_items",
+            shipment_item_views::router(&db).split_for_parts().0,
+        )
+        .nest(
+            "/api/taxa",
+            taxon_views::router(&db)
+                .split_for_parts()
+                .0
+                .merge(taxa::views::router(&db)),
+        )
+        .nest(
+            "/api/temperature_readings",
+            temperature_views::router(&db)
+                .split_for_parts()
+                .0
+                .merge(temperature_readings::views::router(&db)),
+        )
+        .nest("/api/labels", labels::views::router(&db))
+        .route(
+            "/api/lookup",
+            routing::get(crate::lookup::lookup).with_state(db.clone()),
+        )
 }
 
 // --- Keycloak-backed helpers (e2e stack) ------------------------------------
@@ -177,10 +413,11 @@ pub async fn build_app_with_keycloak(db: DatabaseConnection) -> Router {
             .expected_audiences(vec![String::from("account")])
             .required_roles(vec![crate::common::auth::Role::Administrator])
             .build();
+    let store = test_file_store();
 
     Router::new()
-        .route("/healthz", get(common_views::healthz))
-        .route("/api/config", get(common_views::get_ui_config))
+        .route("/healthz", routing::get(common_views::healthz))
+        .route("/api/config", routing::get(common_views::get_ui_config))
         .with_state(db.clone())
         .nest(
             "/api/sites",
@@ -190,17 +427,65 @@ pub async fn build_app_with_keycloak(db: DatabaseConnection) -> Router {
         .nest(
             "/api/field_records",
             Router::from(fr_views::router(&db))
+                .merge(field_records::views::router(&db))
                 .layer(axum::middleware::from_fn(middleware::scope_field_records)),
         )
         .nest(
             "/api/samples",
             Router::from(samp_views::router(&db))
+                .merge(samples::views::router(&db))
                 .layer(axum::middleware::from_fn(middleware::scope_samples)),
         )
         .nest(
             "/api/isolates",
             Router::from(iso_views::router(&db))
-                .layer(axum::middleware::from_fn(middleware::scope_isolates)),
+                .merge(isolates::views::router(&db, &store))
+                .layer(axum::middleware::from_fn_with_state(
+                    (db.clone(), store.clone()),
+                    middleware::remove_orphaned_files,
+                ))
+                .layer(axum::middleware::from_fn_with_state(
+                    db.clone(),
+                    middleware::scope_isolates,
+                )),
+        )
+        .nest(
+            "/api/isolate_images",
+            Router::from(image_views::router(&db))
+                .merge(isolate_images::views::router(&db, &store))
+                .layer(axum::middleware::from_fn_with_state(
+                    (db.clone(), store.clone()),
+                    middleware::remove_orphaned_files,
+                ))
+                .layer(axum::middleware::from_fn_with_state(
+                    isolate_images::db::Column::IsolateId,
+                    middleware::scope_isolate_child::<isolate_images::db::Column>,
+                )),
+        )
+        .nest(
+            "/api/growth_tests",
+            Router::from(growth_views::router(&db))
+                .layer(axum::middleware::from_fn_with_state(
+                    growth_tests::db::Column::IsolateId,
+                    middleware::scope_isolate_child::<growth_tests::db::Column>,
+                )),
+        )
+        .nest(
+            "/api/culture_accessions",
+            Router::from(accession_views::router(&db))
+                .layer(axum::middleware::from_fn_with_state(
+                    culture_accessions::db::Column::IsolateId,
+                    middleware::scope_isolate_child::<culture_accessions::db::Column>,
+                )),
+        )
+        .nest(
+            "/api/marker_sequences",
+            Router::from(marker_views::router(&db))
+                .merge(marker_sequences::views::router(&db))
+                .layer(axum::middleware::from_fn_with_state(
+                    marker_sequences::db::Column::IsolateId,
+                    middleware::scope_isolate_child::<marker_sequences::db::Column>,
+                )),
         )
         .nest(
             "/api/dna",
@@ -212,9 +497,108 @@ pub async fn build_app_with_keycloak(db: DatabaseConnection) -> Router {
             Router::from(area_views::router(&db))
                 .layer(axum::middleware::from_fn(middleware::scope_areas)),
         )
+        .nest(
+            "/api/campaigns",
+            Router::from(campaign_views::router(&db))
+                .merge(campaigns::views::router(&db))
+                .layer(axum::middleware::from_fn(middleware::scope_campaigns)),
+        )
+        .nest(
+            "/api/parameters",
+            Router::from(param_views::router(&db))
+                .layer(axum::middleware::from_fn(middleware::scope_parameters)),
+        )
+        .nest(
+            "/api/parameter_values",
+            Router::from(pv_views::router(&db))
+                .layer(axum::middleware::from_fn(middleware::scope_parameter_values)),
+        )
+        .nest(
+            "/api/replicate_groups",
+            Router::from(rg_views::router(&db))
+                .merge(replicate_groups::views::router(&db))
+                .layer(axum::middleware::from_fn(middleware::scope_replicate_groups)),
+        )
+        .nest(
+            "/api/measurement_thresholds",
+            Router::from(threshold_views::router(&db))
+                .layer(axum::middleware::from_fn(middleware::scope_measurement_thresholds)),
+        )
+        .nest(
+            "/api/sample_types",
+            Router::from(sample_type_views::router(&db))
+                .layer(axum::middleware::from_fn(middleware::scope_sample_types)),
+        )
+        .nest(
+            "/api/sample_type_measurements",
+            Router::from(reserved_measurement_views::router(&db))
+                .layer(axum::middleware::from_fn(middleware::scope_sample_types)),
+        )
+        .nest(
+            "/api/storage_units",
+            Router::from(storage_unit_views::router(&db))
+                .merge(storage_units::views::router(&db))
+                .layer(axum::middleware::from_fn(middleware::admin_only)),
+        )
+        .nest(
+            "/api/storage_positions",
+            Router::from(position_views::router(&db))
+                .merge(storage_positions::views::router(&db))
+                .layer(axum::middleware::from_fn(middleware::admin_only)),
+        )
+        .nest(
+            "/api/material_requests",
+            material_requests::views::router(&db, FreezeThawLimit::default())
+                .layer(axum::middleware::from_fn(middleware::scope_material_requests)),
+        )
+        .nest(
+            "/api/withdrawals",
+            Router::from(withdrawal_views::router(&db))
+                .merge(withdrawals::views::router(&db))
+                .layer(axum::middleware::from_fn(middleware::admin_only)),
+        )
+        .nest(
+            "/api/custody_events",
+            Router::from(custody_views::router(&db))
+                .merge(custody_events::views::router(&db))
+                .layer(axum::middleware::from_fn(middleware::admin_only)),
+        )
+        .nest(
+            "/api/shipments",
+            Router::from(shipment_views::router(&db))
+                .merge(shipments::views::router(&db))
+                .layer(axum::middleware::from_fn(middleware::admin_only)),
+        )
+        .nest(
+            "/api/shipment_items",
+            Router::from(shipment_item_views::router(&db))
+                .layer(axum::middleware::from_fn(middleware::admin_only)),
+        )
+        .nest(
+            "/api/taxa",
+            Router::from(taxon_views::router(&db))
+                .merge(taxa::views::router(&db))
+                .layer(axum::middleware::from_fn(middleware::admin_only)),
+        )
+        .nest(
+            "/api/temperature_readings",
+            Router::from(temperature_views::router(&db))
+                .merge(temperature_readings::views::router(&db))
+                .layer(axum::middleware::from_fn(middleware::admin_only)),
+        )
+        .nest(
+            "/api/labels",
+            labels::views::router(&db).layer(axum::middleware::from_fn(middleware::admin_only)),
+        )
         .route(
             "/api/search",
-            get(crate::search::search).with_state(db.clone()),
+            routing::get(crate::search::search).with_state(db.clone()),
+        )
+        .route(
+            "/api/lookup",
+            routing::get(crate::lookup::lookup)
+                .with_state(db.clone())
+                .layer(axum::middleware::from_fn(middleware::scope_lookup)),
         )
         .layer(keycloak_pass_layer)
 }
@@ -222,6 +606,7 @@ pub async fn build_app_with_keycloak(db: DatabaseConnection) -> Router {
 /// Build app with scope middleware applied (simulates unauthenticated public access).
 /// No keycloak layer — ScopeCondition is always injected on every request.
 pub fn build_scoped_app_with_db(db: DatabaseConnection) -> Router {
+    let store = test_file_store();
     Router::new()
         .with_state(db.clone())
         .nest(
@@ -236,6 +621,7 @@ pub fn build_scoped_app_with_db(db: DatabaseConnection) -> Router {
             fr_views::router(&db)
                 .split_for_parts()
                 .0
+                .merge(field_records::views::router(&db))
                 .layer(axum::middleware::from_fn(middleware::scope_field_records)),
         )
         .nest(
@@ -250,13 +636,68 @@ pub fn build_scoped_app_with_db(db: DatabaseConnection) -> Router {
             iso_views::router(&db)
                 .split_for_parts()
                 .0
-                .layer(axum::middleware::from_fn(middleware::scope_isolates)),
+                .merge(isolates::views::router(&db, &store))
+                .layer(axum::middleware::from_fn_with_state(
+                    (db.clone(), store.clone()),
+                    middleware::remove_orphaned_files,
+                ))
+                .layer(axum::middleware::from_fn_with_state(
+                    db.clone(),
+                    middleware::scope_isolates,
+                )),
+        )
+        .nest(
+            "/api/isolate_images",
+            image_views::router(&db)
+                .split_for_parts()
+                .0
+                .merge(isolate_images::views::router(&db, &store))
+                .layer(axum::middleware::from_fn_with_state(
+                    (db.clone(), store.clone()),
+                    middleware::remove_orphaned_files,
+                ))
+                .layer(axum::middleware::from_fn_with_state(
+                    isolate_images::db::Column::IsolateId,
+                    middleware::scope_isolate_child::<isolate_images::db::Column>,
+                )),
+        )
+        .nest(
+            "/api/growth_tests",
+            growth_views::router(&db)
+                .split_for_parts()
+                .0
+                .layer(axum::middleware::from_fn_with_state(
+                    growth_tests::db::Column::IsolateId,
+                    middleware::scope_isolate_child::<growth_tests::db::Column>,
+                )),
+        )
+        .nest(
+            "/api/culture_accessions",
+            accession_views::router(&db)
+                .split_for_parts()
+                .0
+                .layer(axum::middleware::from_fn_with_state(
+                    culture_accessions::db::Column::IsolateId,
+                    middleware::scope_isolate_child::<culture_accessions::db::Column>,
+                )),
+        )
+        .nest(
+            "/api/marker_sequences",
+            marker_views::router(&db)
+                .split_for_parts()
+                .0
+                .merge(marker_sequences::views::router(&db))
+                .layer(axum::middleware::from_fn_with_state(
+                    marker_sequences::db::Column::IsolateId,
+                    middleware::scope_isolate_child::<marker_sequences::db::Column>,
+                )),
         )
         .nest(
             "/api/samples",
             samp_views::router(&db)
                 .split_for_parts()
                 .0
+                .merge(samples::views::router(&db))
                 .layer(axum::middleware::from_fn(middleware::scope_samples)),
         )
         .nest(
@@ -266,4 +707,269 @@ pub fn build_scoped_app_with_db(db: DatabaseConnection) -> Router {
                 .0
                 .layer(axum::middleware::from_fn(middleware::scope_areas)),
         )
+        .nest(
+            "/api/campaigns",
+            campaign_views::router(&db)
+                .split_for_parts()
+                .0
+                .merge(campaigns::views::router(&db))
+                .layer(axum::middleware::from_fn(middleware::scope_campaigns)),
+        )
+        .nest(
+            "/api/parameters",
+            param_views::router(&db)
+                .split_for_parts()
+                .0
+                .layer(axum::middleware::from_fn(middleware::scope_parameters)),
+        )
+        .nest(
+            "/api/parameter_values",
+            pv_views::router(&db)
+                .split_for_parts()
+                .0
+                .layer(axum::middleware::from_fn(middleware::scope_parameter_values)),
+        )
+        .nest(
+            "/api/replicate_groups",
+            rg_views::router(&db)
+                .split_for_parts()
+                .0
+                .merge(replicate_groups::views::router(&db))
+                .layer(axum::middleware::from_fn(middleware::scope_replicate_groups)),
+        )
+        .nest(
+            "/api/measurement_thresholds",
+            threshold_views::router(&db)
+                .split_for_parts()
+                .0
+                .layer(axum::middleware::from_fn(middleware::scope_measurement_thresholds)),
+        )
+        .nest(
+            "/api/sample_types",
+            sample_type_views::router(&db)
+                .split_for_parts()
+                .0
+                .layer(axum::middleware::from_fn(middleware::scope_sample_types)),
+        )
+        .nest(
+            "/api/sample_type_measurements",
+            reserved_measurement_views::router(&db)
+                .split_for_parts()
+                .0
+                .layer(axum::middleware::from_fn(middleware::scope_sample_types)),
+        )
+        .nest(
+            "/api/storage_units",
+            storage_unit_views::router(&db)
+                .split_for_parts()
+                .0
+                .merge(storage_units::views::router(&db))
+                .layer(axum::middleware::from_fn(middleware::admin_only)),
+        )
+        .nest(
+            "/api/storage_positions",
+            position_views::router(&db)
+                .split_for_parts()
+                .0
+                .merge(storage_positions::views::router(&db))
+                .layer(axum::middleware::from_fn(middleware::admin_only)),
+        )
+        .nest(
+            "/api/material_requests",
+            material_requests::views::router(&db, FreezeThawLimit::default())
+                .layer(axum::middleware::from_fn(middleware::scope_material_requests)),
+        )
+        .nest(
+            "/api/withdrawals",
+            withdrawal_views::router(&db)
+                .split_for_parts()
+                .0
+                .merge(withdrawals::views::router(&db))
+                .layer(axum::middleware::from_fn(middleware::admin_only)),
+        )
+        .nest(
+            "/api/custody_events",
+            custody_views::router(&db)
+                .split_for_parts()
+                .0
+                .merge(custody_events::views::router(&db))
+                .layer(axum::middleware::from_fn(middleware::admin_only)),
+        )
+        .nest(
+            "/api/shipments",
+            shipment_views::router(&db)
+                .split_for_parts()
+                .0
+                .merge(shipments::views::router(&db))
+                .layer(axum::middleware::from_fn(middleware::admin_only)),
+        )
+        .nest(
+            "/api/shipment_items",
+            shipment_item_views::router(&db)
+                .split_for_parts()
+                .0
+                .layer(axum::middleware::from_fn(middleware::admin_only)),
+        )
+        .nest(
+            "/api/taxa",
+            taxon_views::router(&db)
+                .split_for_parts()
+                .0
+                .merge(taxa::views::router(&db))
+                .layer(axum::middleware::from_fn(middleware::admin_only)),
+        )
+        .nest(
+            "/api/temperature_readings",
+            temperature_views::router(&db)
+                .split_for_parts()
+                .0
+                .merge(temperature_readings::views::router(&db))
+                .layer(axum::middleware::from_fn(middleware::admin_only)),
+        )
+        .nest(
+            "/api/labels",
+            labels::views::router(&db).layer(axum::middleware::from_fn(middleware::admin_only)),
+        )
+        .route(
+            "/api/search",
+            routing::get(crate::search::search).with_state(db.clone()),
+        )
+        .route(
+            "/api/lookup",
+            routing::get(crate::lookup::lookup)
+                .with_state(db.clone())
+                .layer(axum::middleware::from_fn(middleware::scope_lookup)),
+        )
+}
+
+/// A small PNG with real pixels, for uploads that have to decode.
+pub fn test_png() -> Vec<u8> {
+    let mut bytes = Vec::new();
+    image::RgbImage::from_pixel(40, 30, image::Rgb([200, 120, 40]))
+        .write_to(
+            &mut std::io::Cursor::new(&mut bytes),
+            image::ImageFormat::Png,
+        )
+        .unwrap();
+    bytes
+}
+
+/// Content type and body of a multipart form with `bytes` in the `image` field and
+/// the given text fields before it.
+pub fn image_upload(bytes: &[u8], fields: &[(&str, &str)]) -> (String, Vec<u8>) {
+    let boundary = "isolate-image";
+    let mut body = Vec::new();
+    for (name, value) in fields {
+        body.extend_from_slice(
+            format!(
+                "--{boundary}\r\nContent-Disposition: form-data; name=\"{name}\"\r\n\r\n{value}\r\n"
+            )
+            .as_bytes(),
+        );
+    }
+    body.extend_from_slice(
+        format!(
+            "--{boundary}\r\nContent-Disposition: form-data; name=\"image\"; filename=\"colony.png\"\r\n\r\n"
+        )
+        .as_bytes(),
+    );
+    body.extend_from_slice(bytes);
+    body.extend_from_slice(format!("\r\n--{boundary}--\r\n").as_bytes());
+    (format!("multipart/form-data; boundary={boundary}"), body)
+}
+
+/// Sends `payload` as JSON and returns the status with the JSON response body,
+/// `Null` when the body is empty or not JSON.
+pub async fn send(app: &Router, method: &str, uri: &str, payload: Value) -> (StatusCode, Value) {
+    let req = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("Content-Type", "application/json")
+        .body(Body::from(payload.to_string()))
+        .unwrap();
+    let resp = app.clone().oneshot(req).await.unwrap();
+    let status = resp.status();
+    let body = to_bytes(resp.into_body(), 1024 * 1024).await.unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+pub async fn get(app: &Router, uri: &str) -> (StatusCode, Value) {
+    let req = Request::builder()
+        .method("GET")
+        .uri(uri)
+        .body(Body::empty())
+        .unwrap();
+    let resp = app.clone().oneshot(req).await.unwrap();
+    let status = resp.status();
+    let body = to_bytes(resp.into_body(), 1024 * 1024).await.unwrap();
+    (status, serde_json::from_slice(&body).unwrap_or(Value::Null))
+}
+
+/// Sends `body` with the given headers and returns the whole response, for uploads
+/// and downloads that are not JSON.
+pub async fn send_raw(
+    app: &Router,
+    method: &str,
+    uri: &str,
+    headers: &[(&str, &str)],
+    body: Vec<u8>,
+) -> axum::response::Response {
+    let mut req = Request::builder().method(method).uri(uri);
+    for (name, value) in headers {
+        req = req.header(*name, *value);
+    }
+    app.clone()
+        .oneshot(req.body(Body::from(body)).unwrap())
+        .await
+        .unwrap()
+}
+
+/// The JSON body of `resp`, `Null` when it is empty or not JSON.
+pub async fn json_body(resp: axum::response::Response) -> Value {
+    let body = to_bytes(resp.into_body(), 1024 * 1024).await.unwrap();
+    serde_json::from_slice(&body).unwrap_or(Value::Null)
+}
+
+/// POSTs `payload`, asserting it was created, and returns the created row.
+pub async fn create_json(app: &Router, uri: &str, payload: Value) -> Value {
+    let (status, body) = send(app, "POST", uri, payload).await;
+    assert_eq!(status, StatusCode::CREATED, "POST {uri}: {body}");
+    body
+}
+
+/// POSTs `payload`, asserting it was created, and returns the new id.
+pub async fn create(app: &Router, uri: &str, payload: Value) -> String {
+    create_json(app, uri, payload).await["id"]
+        .as_str()
+        .unwrap()
+        .to_string()
+}
+
+/// A public site in the Alps.
+pub async fn create_site(app: &Router, name: &str) -> String {
+    create(
+        app,
+        "/api/sites",
+        json!({ "name": name, "latitude_4326": 46.1, "longitude_4326": 7.0, "elevation_metres": 2500.0 }),
+    )
+    .await
+}
+
+/// A public snow field record on `site_id`.
+pub async fn create_field_record(app: &Router, site_id: &str, name: &str) -> String {
+    create(
+        app,
+        "/api/field_records",
+        json!({ "name": name, "site_id": site_id, "sample_type": "Snow", "sampling_date": "2025-07-10" }),
+    )
+    .await
+}
+
+/// The `name` of each object in a JSON array.
+pub fn names(list: &Value) -> Vec<&str> {
+    list.as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["name"].as_str().unwrap())
+        .collect()
 }
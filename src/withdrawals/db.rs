@@ -0,0 +1,107 @@
+use chrono::{DateTime, Utc};
+use crudcrate::validation::{Validatable, ValidationError};
+use crudcrate::{CRUDResource, EntityToModels};
+use sea_orm::entity::prelude::*;
+use uuid::Uuid;
+
+#[derive(Clone, Debug, PartialEq, DeriveEntityModel, EntityToModels)]
+#[sea_orm(table_name = "withdrawals")]
+#[crudcrate(
+    generate_router,
+    api_struct = "Withdrawal",
+    name_singular = "withdrawal",
+    name_plural = "withdrawals",
+    description = "Consumption ledger of material taken out of samples and DNA extracts",
+    no_eq,
+    derive_partial_eq,
+    create::one::pre = crate::withdrawals::services::check_create,
+    create::many::pre = crate::withdrawals::services::check_create_many,
+    create::one::post = crate::withdrawals::services::update_availability,
+    create::many::post = crate::withdrawals::services::update_availabilities,
+    update::one::pre = crate::withdrawals::services::check_update,
+    update::many::pre = crate::withdrawals::services::check_update_many,
+    update::one::post = crate::withdrawals::services::update_availability,
+    update::many::post = crate::withdrawals::services::update_availabilities,
+    delete::one::body = crate::withdrawals::services::delete_withdrawal,
+    delete::many::body = crate::withdrawals::services::delete_withdrawals
+)]
+pub struct Model {
+    #[sea_orm(primary_key, auto_increment = false)]
+    #[crudcrate(primary_key, filterable, exclude(update, create), on_create = Uuid::new_v4())]
+    pub id: Uuid,
+    /// Exactly one of `sample_id` and `dna_id` is set.
+    #[crudcrate(sortable, filterable, exclude(update))]
+    pub sample_id: Option<Uuid>,
+    #[crudcrate(sortable, filterable, exclude(update))]
+    pub dna_id: Option<Uuid>,
+    /// In the sample's `quantity_unit`, or the unit of the extract's `volume`.
+    #[crudcrate(sortable, filterable)]
+    pub amount: f64,
+    #[crudcrate(sortable, filterable, fulltext)]
+    pub withdrawn_by: String,
+    #[crudcrate(fulltext)]
+    pub purpose: String,
+    #[crudcrate(sortable, filterable, exclude(create, update), on_create = chrono::Utc::now())]
+    pub withdrawn_at: DateTime<Utc>,
+}
+
+#[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
+pub enum Relation {
+    #[sea_orm(
+        belongs_to = "crate::samples::db::Entity",
+        from = "Column::SampleId",
+        to = "crate::samples::db::Column::Id"
+    )]
+    Sample,
+    #[sea_orm(
+        belongs_to = "crate::dna::db::Entity",
+        from = "Column::DnaId",
+        to = "crate::dna::db::Column::Id"
+    )]
+    Dna,
+}
+
+impl ActiveModelBehavior for ActiveModel {}
+
+fn validate_amount(amount: f64) -> Result<(), ValidationError> {
+    if !amount.is_finite() || amount <= 0.0 {
+        return Err(ValidationError::new("amount", "Must be greater than zero"));
+    }
+    Ok(())
+}
+
+fn validate_required(field: &str, value: &str) -> Result<(), ValidationError> {
+    if value.trim().is_empty() {
+        return Err(ValidationError::new(field, "Must not be blank"));
+    }
+    Ok(())
+}
+
+impl Validatable for WithdrawalCreate {
+    fn validate(&self) -> Result<(), ValidationError> {
+        if self.sample_id.is_some() == self.dna_id.is_some() {
+            return Err(ValidationError::new(
+                "sample_id",
+                "Withdraw from either one sample or one DNA extract",
+            ));
+        }
+        validate_amount(self.amount)?;
+        validate_required("withdrawn_by", &self.withdrawn_by)?;
+        validate_required("purpose", &self.purpose)
+    }
+}
+
+impl Validatable for WithdrawalUpdate {
+    fn validate(&self) -> Result<(), ValidationError> {
+        if let Some(Some(amount)) = self.amount {
+            validate_amount(amount)?;
+        }
+        if let Some(Some(withdrawn_by)) = &self.withdrawn_by {
+            validate_required("withdrawn_by", withdrawn_by)?;
+        }
+        if let Some(Some(purpose)) = &self.purpose {
+            validate_required("purpose", purpose)?;
+        }
+        Ok(())
+    }
+}
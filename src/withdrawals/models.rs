@@ -0,0 +1,25 @@
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+
+use super::db::Withdrawal;
+
+#[derive(Deserialize, IntoParams, Debug)]
+pub struct BalanceParams {
+    pub sample_id: Option<Uuid>,
+    pub dna_id: Option<Uuid>,
+}
+
+/// How much of a sample or DNA extract is left. `quantity` is the sample's quantity
+/// or the extract's volume; `remaining` is `None` when that is not recorded.
+#[derive(ToSchema, Serialize, Debug, Clone)]
+pub struct Balance {
+    pub quantity: Option<f64>,
+    pub unit: Option<String>,
+    /// Handed out to aliquots; always 0 for DNA.
+    pub aliquoted: f64,
+    pub withdrawn: f64,
+    pub remaining: Option<f64>,
+    /// The ledger for the item, newest first.
+    pub withdrawals: Vec<Withdrawal>,
+}
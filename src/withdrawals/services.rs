@@ -0,0 +1,288 @@
+use crudcrate::traits::MergeIntoActiveModel;
+use crudcrate::validation::ValidationError;
+use crudcrate::ApiError;
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, IntoActiveModel, Iterable,
+    ModelTrait, PaginatorTrait, QueryFilter, TryIntoModel,
+};
+use std::collections::{HashMap, HashSet};
+use uuid::Uuid;
+
+use super::db::{
+    ActiveModel, Column, Entity, Model, Withdrawal, WithdrawalCreate, WithdrawalUpdate,
+};
+use crate::common::batch;
+use crate::common::enums::MaterialRequestStatus;
+use crate::{dna, material_requests, samples};
+
+/// Slack for floating point sums, so withdrawing exactly what remains is allowed.
+const EPSILON: f64 = 1e-9;
+
+fn invalid(field: &str, message: String) -> ApiError {
+    ValidationError::new(field, message).into()
+}
+
+/// Total withdrawn from each sample, for samples with any withdrawals.
+pub async fn withdrawn_from_samples(
+    db: &DatabaseConnection,
+    sample_ids: Vec<Uuid>,
+) -> Result<HashMap<Uuid, f64>, ApiError> {
+    let mut totals = HashMap::new();
+    if sample_ids.is_empty() {
+        return Ok(totals);
+    }
+    for withdrawal in Entity::find()
+        .filter(Column::SampleId.is_in(sample_ids))
+        .all(db)
+        .await?
+    {
+        if let Some(sample_id) = withdrawal.sample_id {
+            *totals.entry(sample_id).or_insert(0.0) += withdrawal.amount;
+        }
+    }
+    Ok(totals)
+}
+
+/// Total withdrawn from one item, leaving out the withdrawal `except` (the one being
+/// edited).
+async fn withdrawn(
+    db: &DatabaseConnection,
+    column: Column,
+    id: Uuid,
+    except: Option<Uuid>,
+) -> Result<f64, ApiError> {
+    let mut query = Entity::find().filter(column.eq(id));
+    if let Some(except) = except {
+        query = query.filter(Column::Id.ne(except));
+    }
+    Ok(query.all(db).await?.iter().map(|w| w.amount).sum())
+}
+
+pub async fn withdrawn_from_sample(db: &DatabaseConnection, id: Uuid) -> Result<f64, ApiError> {
+    withdrawn(db, Column::SampleId, id, None).await
+}
+
+pub async fn withdrawn_from_dna(db: &DatabaseConnection, id: Uuid) -> Result<f64, ApiError> {
+    withdrawn(db, Column::DnaId, id, None).await
+}
+
+/// Quantity of a sample handed out to its aliquots.
+pub async fn aliquoted(db: &DatabaseConnection, sample_id: Uuid) -> Result<f64, ApiError> {
+    Ok(samples::db::Entity::find()
+        .filter(samples::db::Column::ParentSampleId.eq(sample_id))
+        .all(db)
+        .await?
+        .iter()
+        .filter_map(|aliquot| aliquot.quantity)
+        .sum())
+}
+
+/// The withdrawal must fit in what the item has left. `new` withdrawals also need
+/// the sample to still be available; corrections to old ones do not.
+async fn check_amount(
+    db: &DatabaseConnection,
+    withdrawal: &Model,
+    new: bool,
+) -> Result<(), ApiError> {
+    let (name, unit, available, drawn) = match (withdrawal.sample_id, withdrawal.dna_id) {
+        (Some(id), _) => {
+            let Some(sample) = samples::db::Entity::find_by_id(id).one(db).await? else {
+                return Err(invalid("sample_id", "Unknown sample".to_string()));
+            };
+            if new && !sample.is_available {
+                return Err(invalid(
+                    "sample_id",
+                    format!("Sample '{}' is not available", sample.name),
+                ));
+            }
+            let Some(quantity) = sample.quantity else {
+                return Err(invalid(
+                    "amount",
+                    format!("Sample '{}' has no recorded quantity", sample.name),
+                ));
+            };
+            (
+                sample.name,
+                sample.quantity_unit,
+                quantity - aliquoted(db, id).await?,
+                withdrawn(db, Column::SampleId, id, Some(withdrawal.id)).await?,
+            )
+        }
+        (None, Some(id)) => {
+            let Some(extract) = dna::db::Entity::find_by_id(id).one(db).await? else {
+                return Err(invalid("dna_id", "Unknown DNA extract".to_string()));
+            };
+            let Some(volume) = extract.volume else {
+                return Err(invalid(
+                    "amount",
+                    format!("DNA extract '{}' has no recorded volume", extract.name),
+                ));
+            };
+            (
+                extract.name,
+                None,
+                volume,
+                withdrawn(db, Column::DnaId, id, Some(withdrawal.id)).await?,
+            )
+        }
+        (None, None) => return Ok(()),
+    };
+
+    if drawn + withdrawal.amount > available + EPSILON {
+        let remaining = (available - drawn).max(0.0);
+        let unit = unit.map(|unit| format!(" {unit}")).unwrap_or_default();
+        return Err(invalid(
+            "amount",
+            format!("Exceeds the {remaining}{unit} remaining in '{name}'"),
+        ));
+    }
+    Ok(())
+}
+
+pub async fn check_create(
+    db: &DatabaseConnection,
+    data: &WithdrawalCreate,
+) -> Result<(), ApiError> {
+    let withdrawal = ActiveModel::from(data.clone()).try_into_model()?;
+    check_amount(db, &withdrawal, true).await
+}
+
+/// Items in one batch draw on the same stock, so each is checked with the earlier
+/// ones of the batch counted as already withdrawn.
+pub async fn check_create_many(
+    db: &DatabaseConnection,
+    data: &[WithdrawalCreate],
+) -> Result<(), ApiError> {
+    for (i, item) in data.iter().enumerate() {
+        let mut withdrawal = ActiveModel::from(item.clone()).try_into_model()?;
+        withdrawal.amount += data[..i]
+            .iter()
+            .filter(|earlier| {
+                (earlier.sample_id.is_some() && earlier.sample_id == item.sample_id)
+                    || (earlier.dna_id.is_some() && earlier.dna_id == item.dna_id)
+            })
+            .map(|earlier| earlier.amount)
+            .sum::<f64>();
+        check_amount(db, &withdrawal, true).await?;
+    }
+    Ok(())
+}
+
+pub async fn check_update(
+    db: &DatabaseConnection,
+    id: Uuid,
+    data: &WithdrawalUpdate,
+) -> Result<(), ApiError> {
+    let existing = Entity::find_by_id(id)
+        .one(db)
+        .await?
+        .ok_or_else(|| ApiError::not_found("withdrawal", Some(id.to_string())))?;
+    // Fields absent from the request come back `NotSet`; fill them from the stored row.
+    let mut merged = data
+        .clone()
+        .merge_into_activemodel(existing.clone().into_active_model())?;
+    for column in Column::iter() {
+        if merged.get(column).is_not_set() {
+            merged.set(column, existing.get(column));
+        }
+    }
+    check_amount(db, &merged.try_into_model()?, false).await
+}
+
+pub async fn check_update_many(
+    db: &DatabaseConnection,
+    updates: &[(Uuid, WithdrawalUpdate)],
+) -> Result<(), ApiError> {
+    for (id, data) in updates {
+        check_update(db, *id, data).await?;
+    }
+    Ok(())
+}
+
+/// Whether an approved or shipped material request holds the sample.
+async fn on_loan(db: &DatabaseConnection, sample_id: Uuid) -> Result<bool, ApiError> {
+    Ok(material_requests::db::Entity::find()
+        .filter(material_requests::db::Column::SampleId.eq(sample_id))
+        .filter(material_requests::db::Column::Status.is_in([
+            MaterialRequestStatus::Approved,
+            MaterialRequestStatus::Shipped,
+        ]))
+        .count(db)
+        .await?
+        > 0)
+}
+
+/// A sample with nothing left is no longer available, and one that a corrected or
+/// deleted withdrawal gives stock back to is available again unless a material
+/// request holds it. Its aliquots are separate tubes and keep their own
+/// availability.
+async fn recompute_availability(db: &DatabaseConnection, sample_id: Uuid) -> Result<(), ApiError> {
+    let Some(sample) = samples::db::Entity::find_by_id(sample_id).one(db).await? else {
+        return Ok(());
+    };
+    let Some(quantity) = sample.quantity else {
+        return Ok(());
+    };
+    let remaining =
+        quantity - aliquoted(db, sample_id).await? - withdrawn_from_sample(db, sample_id).await?;
+    let is_available = remaining > EPSILON && !on_loan(db, sample_id).await?;
+    if is_available != sample.is_available {
+        samples::db::Entity::update_many()
+            .col_expr(samples::db::Column::IsAvailable, Expr::value(is_available))
+            .filter(samples::db::Column::Id.eq(sample_id))
+            .exec(db)
+            .await?;
+    }
+    Ok(())
+}
+
+pub async fn update_availability(
+    db: &DatabaseConnection,
+    withdrawal: &Withdrawal,
+) -> Result<(), ApiError> {
+    match withdrawal.sample_id {
+        Some(sample_id) => recompute_availability(db, sample_id).await,
+        None => Ok(()),
+    }
+}
+
+pub async fn update_availabilities(
+    db: &DatabaseConnection,
+    withdrawals: &[Withdrawal],
+) -> Result<(), ApiError> {
+    let sample_ids: HashSet<Uuid> = withdrawals.iter().filter_map(|w| w.sample_id).collect();
+    for sample_id in sample_ids {
+        recompute_availability(db, sample_id).await?;
+    }
+    Ok(())
+}
+
+pub async fn delete_withdrawal(db: &DatabaseConnection, id: Uuid) -> Result<Uuid, ApiError> {
+    delete_withdrawals(db, vec![id])
+        .await?
+        .pop()
+        .ok_or_else(|| ApiError::not_found("withdrawal", Some(id.to_string())))
+}
+
+/// Deletes the withdrawals and gives what they drew back to their samples.
+pub async fn delete_withdrawals(
+    db: &DatabaseConnection,
+    ids: Vec<Uuid>,
+) -> Result<Vec<Uuid>, ApiError> {
+    batch::check_limit::<Withdrawal>("delete", ids.len())?;
+    let withdrawals = Entity::find().filter(Column::Id.is_in(ids)).all(db).await?;
+    if withdrawals.is_empty() {
+        return Ok(vec![]);
+    }
+    let deleted: Vec<Uuid> = withdrawals.iter().map(|w| w.id).collect();
+    Entity::delete_many()
+        .filter(Column::Id.is_in(deleted.clone()))
+        .exec(db)
+        .await?;
+    let sample_ids: HashSet<Uuid> = withdrawals.iter().filter_map(|w| w.sample_id).collect();
+    for sample_id in sample_ids {
+        recompute_availability(db, sample_id).await?;
+    }
+    Ok(deleted)
+}
@@ -0,0 +1,273 @@
+use axum::http::StatusCode;
+use serde_json::{json, Value};
+
+use crate::test_utils::{
+    build_app_with_db, build_scoped_app_with_db, create, create_field_record, create_site, get,
+    send, setup_sqlite_db,
+};
+
+async fn field_record(app: &axum::Router) -> String {
+    let site_id = create_site(app, "Glacier A").await;
+    create_field_record(app, &site_id, "FR-1").await
+}
+
+fn withdrawal(item: (&str, &str), amount: f64) -> Value {
+    json!({ item.0: item.1, "amount": amount, "withdrawn_by": "A. Keller", "purpose": "qPCR" })
+}
+
+/// Scenario: a 10 mL sample with a 4 mL aliquot, then withdrawals of 5, 2 and 1 mL.
+/// Expected behaviour: the 2 mL withdrawal is rejected, the last one uses the
+/// sample up and marks it unavailable, and the quantity can no longer drop below
+/// what has been drawn.
+#[tokio::test]
+async fn sample_withdrawals_draw_down_the_remaining_quantity() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db.clone());
+    let scoped = build_scoped_app_with_db(db);
+
+    let fr = field_record(&app).await;
+    let sample = create(
+        &app,
+        "/api/samples",
+        json!({ "name": "S-1", "field_record_id": fr, "quantity": 10.0, "quantity_unit": "mL" }),
+    )
+    .await;
+    let aliquot = create(
+        &app,
+        "/api/samples",
+        json!({
+            "name": "S-1a", "field_record_id": fr, "parent_sample_id": sample,
+            "quantity": 4.0, "quantity_unit": "mL"
+        }),
+    )
+    .await;
+
+    create(
+        &app,
+        "/api/withdrawals",
+        withdrawal(("sample_id", &sample), 5.0),
+    )
+    .await;
+    let (status, body) = send(
+        &app,
+        "POST",
+        "/api/withdrawals",
+        withdrawal(("sample_id", &sample), 2.0),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{body}");
+    let (status, _) = send(
+        &app,
+        "POST",
+        "/api/samples",
+        json!({
+            "name": "S-1b", "field_record_id": fr, "parent_sample_id": sample,
+            "quantity": 2.0, "quantity_unit": "mL"
+        }),
+    )
+    .await;
+    assert_eq!(
+        status,
+        StatusCode::UNPROCESSABLE_ENTITY,
+        "aliquots count withdrawals"
+    );
+
+    create(
+        &app,
+        "/api/withdrawals",
+        withdrawal(("sample_id", &sample), 1.0),
+    )
+    .await;
+    let (_, record) = get(&app, &format!("/api/samples/{sample}")).await;
+    assert_eq!(record["is_available"], false, "used up");
+    let (_, record) = get(&app, &format!("/api/samples/{aliquot}")).await;
+    assert_eq!(record["is_available"], true, "aliquot is its own tube");
+
+    let (status, balance) = get(
+        &app,
+        &format!("/api/withdrawals/balance?sample_id={sample}"),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK, "{balance}");
+    assert_eq!(balance["aliquoted"].as_f64().unwrap(), 4.0);
+    assert_eq!(balance["withdrawn"].as_f64().unwrap(), 6.0);
+    assert_eq!(balance["remaining"].as_f64().unwrap(), 0.0);
+    assert_eq!(balance["withdrawals"].as_array().unwrap().len(), 2);
+
+    let (_, tree) = get(&app, &format!("/api/samples/{sample}/aliquots")).await;
+    assert_eq!(tree["remaining_quantity"].as_f64().unwrap(), 0.0);
+
+    let (status, _) = send(
+        &app,
+        "PUT",
+        &format!("/api/samples/{sample}"),
+        json!({ "quantity": 9.0 }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+    let (status, _) = get(
+        &scoped,
+        &format!("/api/withdrawals/balance?sample_id={sample}"),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+}
+
+/// Scenario: a used-up sample whose withdrawals are corrected, deleted and redone.
+/// Expected behaviour: it is available again once stock is given back, and
+/// unavailable again when a batch correction uses it up.
+#[tokio::test]
+async fn corrected_withdrawals_give_the_stock_back() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db);
+
+    let fr = field_record(&app).await;
+    let sample = create(
+        &app,
+        "/api/samples",
+        json!({ "name": "S-2", "field_record_id": fr, "quantity": 10.0, "quantity_unit": "mL" }),
+    )
+    .await;
+    let first = create(
+        &app,
+        "/api/withdrawals",
+        withdrawal(("sample_id", &sample), 6.0),
+    )
+    .await;
+    let second = create(
+        &app,
+        "/api/withdrawals",
+        withdrawal(("sample_id", &sample), 4.0),
+    )
+    .await;
+    let available = |app| {
+        let sample = sample.clone();
+        async move { get(app, &format!("/api/samples/{sample}")).await.1["is_available"].clone() }
+    };
+    assert_eq!(available(&app).await, false, "used up");
+
+    let (status, _) = send(
+        &app,
+        "PUT",
+        &format!("/api/withdrawals/{second}"),
+        json!({ "amount": 3.0 }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(available(&app).await, true, "1 mL given back");
+
+    let (status, _) = send(
+        &app,
+        "PATCH",
+        "/api/withdrawals/batch",
+        json!([{ "id": second, "amount": 4.0 }]),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(available(&app).await, false, "used up again");
+
+    let (status, _) = send(
+        &app,
+        "DELETE",
+        &format!("/api/withdrawals/{first}"),
+        Value::Null,
+    )
+    .await;
+    assert!(status.is_success());
+    assert_eq!(available(&app).await, true, "withdrawal deleted");
+    let (_, remaining) = get(
+        &app,
+        &format!("/api/withdrawals/balance?sample_id={sample}"),
+    )
+    .await;
+    assert_eq!(remaining["remaining"].as_f64().unwrap(), 6.0);
+}
+
+#[tokio::test]
+async fn dna_withdrawals_need_a_volume_and_fit_in_it() {
+    let db = setup_sqlite_db().await;
+    let app = build_app_with_db(db);
+
+    let fr = field_record(&app).await;
+    let extract = create(
+        &app,
+        "/api/dna",
+        json!({ "name": "DNA-1", "field_record_id": fr }),
+    )
+    .await;
+
+    let (status, _) = send(
+        &app,
+        "POST",
+        "/api/withdrawals",
+        withdrawal(("dna_id", &extract), 5.0),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "no volume yet");
+
+    let (status, _) = send(
+        &app,
+        "PUT",
+        &format!("/api/dna/{extract}"),
+        json!({ "volume": 50.0 }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _) = send(
+        &app,
+        "POST",
+        "/api/withdrawals/batch",
+        json!([
+            withdrawal(("dna_id", &extract), 20.0),
+            withdrawal(("dna_id", &extract), 40.0),
+        ]),
+    )
+    .await;
+    assert_eq!(
+        status,
+        StatusCode::UNPROCESSABLE_ENTITY,
+        "batch shares the volume"
+    );
+
+    let first = create(
+        &app,
+        "/api/withdrawals",
+        withdrawal(("dna_id", &extract), 20.0),
+    )
+    .await;
+    create(
+        &app,
+        "/api/withdrawals",
+        withdrawal(("dna_id", &extract), 20.0),
+    )
+    .await;
+    let (status, _) = send(
+        &app,
+        "PUT",
+        &format!("/api/withdrawals/{first}"),
+        json!({ "amount": 35.0 }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    let (status, _) = send(
+        &app,
+        "PUT",
+        &format!("/api/dna/{extract}"),
+        json!({ "volume": 30.0 }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+
+    for payload in [
+        json!({ "dna_id": extract, "amount": -1.0, "withdrawn_by": "A. Keller", "purpose": "PCR" }),
+        json!({ "dna_id": extract, "amount": 1.0, "withdrawn_by": " ", "purpose": "PCR" }),
+    ] {
+        let (status, body) = send(&app, "POST", "/api/withdrawals", payload).await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "{body}");
+    }
+
+    let (_, balance) = get(&app, &format!("/api/withdrawals/balance?dna_id={extract}")).await;
+    assert_eq!(balance["remaining"].as_f64().unwrap(), 10.0);
+}
@@ -0,0 +1,76 @@
+use axum::extract::{Query, State};
+use axum::{routing::get, Json, Router};
+use crudcrate::ApiError;
+use sea_orm::{ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, QueryOrder};
+
+use super::db::{Column, Entity, Withdrawal};
+use super::models::{Balance, BalanceParams};
+use super::services::aliquoted;
+use crate::{dna, samples};
+
+/// Routes mounted next to the generated CRUD router under `/api/withdrawals`.
+pub fn router(db: &DatabaseConnection) -> Router {
+    Router::new()
+        .route("/balance", get(get_balance))
+        .with_state(db.clone())
+}
+
+#[utoipa::path(
+    get,
+    path = "/api/withdrawals/balance",
+    params(BalanceParams),
+    responses(
+        (status = OK, description = "Remaining amount and the withdrawals that used it", body = Balance),
+        (status = BAD_REQUEST, description = "Neither or both of sample_id and dna_id given"),
+        (status = NOT_FOUND, description = "Sample or DNA extract not found")
+    )
+)]
+pub async fn get_balance(
+    State(db): State<DatabaseConnection>,
+    Query(params): Query<BalanceParams>,
+) -> Result<Json<Balance>, ApiError> {
+    let (condition, quantity, unit, aliquoted) = match (params.sample_id, params.dna_id) {
+        (Some(id), None) => {
+            let sample = samples::db::Entity::find_by_id(id)
+                .one(&db)
+                .await?
+                .ok_or_else(|| ApiError::not_found("sample", Some(id.to_string())))?;
+            (
+                Column::SampleId.eq(id),
+                sample.quantity,
+                sample.quantity_unit,
+                aliquoted(&db, id).await?,
+            )
+        }
+        (None, Some(id)) => {
+            let extract = dna::db::Entity::find_by_id(id)
+                .one(&db)
+                .await?
+                .ok_or_else(|| ApiError::not_found("dna", Some(id.to_string())))?;
+            (Column::DnaId.eq(id), extract.volume, None, 0.0)
+        }
+        _ => {
+            return Err(ApiError::bad_request(
+                "Pass exactly one of sample_id and dna_id",
+            ))
+        }
+    };
+
+    let withdrawals: Vec<Withdrawal> = Entity::find()
+        .filter(condition)
+        .order_by_desc(Column::WithdrawnAt)
+        .all(&db)
+        .await?
+        .into_iter()
+        .map(Withdrawal::from)
+        .collect();
+    let withdrawn: f64 = withdrawals.iter().map(|w| w.amount).sum();
+    Ok(Json(Balance {
+        quantity,
+        unit,
+        aliquoted,
+        withdrawn,
+        remaining: quantity.map(|quantity| quantity - aliquoted - withdrawn),
+        withdrawals,
+    }))
+}